@@ -0,0 +1,141 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use typos::distance::cost::{Confusion, CostModel, Levenshtein};
+use typos::distance::path::PathMultiCost;
+use typos::distance::word::{self, EditDistance};
+use typos::distance::{
+    find_shortest_path_with_options, PathFindingAlgorithm, SearchOptions, ShardedWords,
+};
+
+// Counts allocations, so the expansion benchmarks can tell how many a search makes
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const SIZES: [usize; 4] = [1_000, 10_000, 100_000, 1_000_000];
+
+// Same words on every run, 4 to 9 letters long
+fn dictionary(size: usize) -> Vec<String> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    (0..size)
+        .map(|_| {
+            let length = 4 + (next() % 6) as usize;
+            (0..length)
+                .map(|_| (b'a' + (next() % 26) as u8) as char)
+                .collect()
+        })
+        .collect()
+}
+
+// The end word is one letter away from the start word, every expansion still scans the
+// whole dictionary so the time grows with its size
+fn search(c: &mut Criterion) {
+    let models: [(&str, Arc<dyn CostModel>); 2] = [
+        ("levenshtein", Arc::new(Levenshtein)),
+        ("ocr", Arc::new(Confusion::ocr())),
+    ];
+    for &size in &SIZES {
+        let mut words = dictionary(size);
+        let start = words[0].clone();
+        let stop = format!("{}s", start);
+        words.push(stop.clone());
+        let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+        let mut group = c.benchmark_group(format!("search/{}", size));
+        group.sample_size(10);
+        for (name, model) in &models {
+            for algorithm in &PathFindingAlgorithm::ALL {
+                let options = SearchOptions::new(*algorithm).with_cost_model(model.clone());
+                group.bench_function(BenchmarkId::new(*name, algorithm), |b| {
+                    b.iter(|| {
+                        find_shortest_path_with_options(&start, &stop, words.as_slice(), &options)
+                    })
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+// Searches expanding many words through an index, each expansion reusing the buffers of
+// the previous ones. Allocations per expanded word are printed before timing them.
+fn expansion(c: &mut Criterion) {
+    let mut words = dictionary(100_000);
+    words.extend(["cold", "cord", "card", "ward", "warm"].map(String::from));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let sharded = ShardedWords::new(&words);
+    let mut group = c.benchmark_group("expansion");
+    group.sample_size(10);
+    for algorithm in &PathFindingAlgorithm::ALL {
+        let options = SearchOptions::new(*algorithm).with_max_hop_distance(1);
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let (_, stats) = find_shortest_path_with_options("cold", "warm", &sharded, &options);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+        eprintln!(
+            "expansion/{}: {} allocations for {} words expanded",
+            algorithm, allocations, stats.expanded
+        );
+        group.bench_function(BenchmarkId::from_parameter(algorithm), |b| {
+            b.iter(|| find_shortest_path_with_options("cold", "warm", &sharded, &options))
+        });
+    }
+    group.finish();
+}
+
+fn cost_arithmetic(c: &mut Criterion) {
+    let hops: Vec<PathMultiCost<EditDistance>> = (0..64).map(|i| word::hop_cost(i % 12)).collect();
+    c.bench_function("cost/sum", |b| {
+        b.iter(|| {
+            black_box(&hops)
+                .iter()
+                .fold(word::hop_cost::<EditDistance>(0), |total, &hop| total + hop)
+        })
+    });
+    c.bench_function("cost/compare", |b| {
+        b.iter(|| black_box(&hops).iter().max().copied())
+    });
+    // Costs of paths getting longer, as found in the open set of a search
+    let mut totals: Vec<PathMultiCost<EditDistance>> = hops
+        .iter()
+        .scan(word::hop_cost(0), |total, &hop| {
+            *total = *total + hop;
+            Some(*total)
+        })
+        .collect();
+    totals.reverse();
+    c.bench_function("cost/sort", |b| {
+        b.iter_batched_ref(
+            || totals.clone(),
+            |totals| totals.sort_unstable(),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("cost/path_cost", |b| {
+        b.iter(|| word::path_cost(black_box("abracadabra"), black_box("abracadabrantesque")))
+    });
+}
+
+criterion_group!(benches, search, expansion, cost_arithmetic);
+criterion_main!(benches);
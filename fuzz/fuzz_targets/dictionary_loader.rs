@@ -0,0 +1,12 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use typos::words;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(list) = words::from_reader(data, &words::Policy::default()) {
+        for entry in &list.words {
+            assert!(!entry.is_empty());
+            assert_eq!(words::normalize(entry), *entry);
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use typos::distance::word;
+
+fuzz_target!(|words: (&str, &str)| {
+    let (w1, w2) = words;
+    let cost = word::path_cost(w1, w2);
+    let bound = word::edit_distance(w1, w2);
+    // The bound guides A*, it may never exceed the cost of the hop
+    assert!(bound <= cost);
+    assert_eq!(cost, word::path_cost(w2, w1));
+    assert_eq!(cost + word::path_cost(w1, w1), cost);
+});
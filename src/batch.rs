@@ -0,0 +1,114 @@
+use crate::matrix::quote;
+use std::io::{self, BufRead, Write};
+use typos::distance::cost::Unit;
+use typos::distance::{self, Dictionary, Interruption, PathResult, SearchOptions};
+
+/// Start and end words of a ladder to find
+pub type Pair = (String, String);
+
+/// Ladder found between the words of a pair, `None` when they are not connected
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub start: String,
+    pub end: String,
+    pub path: Option<PathResult>,
+}
+
+/// Pairs of `start,end` lines, without blank lines and `#` comments
+pub fn read_pairs(reader: impl BufRead) -> io::Result<Vec<Pair>> {
+    let mut pairs = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        match line.split_once(',') {
+            Some((start, end)) => pairs.push((start.trim().to_string(), end.trim().to_string())),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected start,end", number + 1),
+                ))
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Shortest ladder between the words of every pair, in the order of `pairs`
+pub fn solve<D: Dictionary + ?Sized>(
+    pairs: &[Pair],
+    words: &D,
+    options: &SearchOptions,
+) -> Result<Vec<Answer>, Interruption> {
+    pairs
+        .iter()
+        .map(|(start, end)| {
+            let (path, _) = distance::find_shortest_path_result(start, end, words, options);
+            Ok(Answer {
+                start: start.clone(),
+                end: end.clone(),
+                path: path?,
+            })
+        })
+        .collect()
+}
+
+/// Header line, then one line per pair with the number of hops, the cost, the words of
+/// the path and the letters each hop changes, those of unconnected pairs left empty
+pub fn write_csv(answers: &[Answer], mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "start,end,hops,cost,path,letters")?;
+    for answer in answers {
+        write!(writer, "{},{},", quote(&answer.start), quote(&answer.end))?;
+        match &answer.path {
+            Some(path) => {
+                let letters: Vec<String> = path
+                    .hops
+                    .iter()
+                    .map(|hop| hop.distance.to_string())
+                    .collect();
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    path.hops.len(),
+                    quote(&distance::describe_cost(&path.cost, Unit::Letter)),
+                    quote(&path.words.join("->")),
+                    letters.join(";")
+                )?
+            }
+            None => writeln!(writer, ",,,")?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typos::distance::PathFindingAlgorithm;
+
+    #[test]
+    fn pairs_are_solved_in_order() {
+        let pairs = read_pairs(&b"# from,to\ncold, warm\n\ncard,zzzz\n"[..]).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("cold".to_string(), "warm".to_string()),
+                ("card".to_string(), "zzzz".to_string())
+            ]
+        );
+        assert!(read_pairs(&b"cold warm\n"[..]).is_err());
+
+        let words = ["cold", "cord", "card", "ward", "warm", "zzzz"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let answers = solve(&pairs, &words[..], &options).unwrap();
+        let mut csv = Vec::new();
+        write_csv(&answers, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "start,end,hops,cost,path,letters\n\
+             cold,warm,4,4 1-letter mutations,cold->cord->card->ward->warm,1;1;1;1\n\
+             card,zzzz,,,,\n"
+        );
+    }
+}
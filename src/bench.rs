@@ -0,0 +1,280 @@
+use crate::random::Random;
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+use typos::distance::{self, PathFindingAlgorithm, SearchOptions, ShardedWords};
+
+/// Suites `typos bench` knows, the first one run unless told otherwise
+pub const SUITES: [&str; 2] = ["standard", "quick"];
+
+// Words of the bundled dictionary: 4 letters out of 8, about 1 in 3 of the 4096 words
+// possible, so every word has a dozen neighbours and most pairs are connected
+const ALPHABET: &[u8] = b"abcdefgh";
+const DICTIONARY_SIZE: usize = 1500;
+const DICTIONARY_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// Same words on every run and every machine, so that measures can be compared
+pub fn dictionary() -> Vec<String> {
+    let mut random = Random::new(DICTIONARY_SEED);
+    let every: Vec<String> = (0..ALPHABET.len().pow(4))
+        .map(|mut index| {
+            (0..4)
+                .map(|_| {
+                    let letter = ALPHABET[index % ALPHABET.len()] as char;
+                    index /= ALPHABET.len();
+                    letter
+                })
+                .collect()
+        })
+        .collect();
+    random
+        .sample(&every, DICTIONARY_SIZE)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// One search of a suite
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub start: String,
+    pub stop: String,
+    pub algorithm: PathFindingAlgorithm,
+    /// Searched through an index of hops of 1 letter rather than every word
+    pub indexed: bool,
+}
+
+impl Query {
+    /// What measures of the query are known by in results and baselines
+    pub fn name(&self) -> String {
+        let through = if self.indexed { "indexed" } else { "scan" };
+        format!(
+            "{} {} {}->{}",
+            self.algorithm, through, self.start, self.stop
+        )
+    }
+}
+
+/// Searches of the suite `name` through `words`, `None` for suites it does not know
+pub fn suite(name: &str, words: &[String]) -> Option<Vec<Query>> {
+    let (pairs, algorithms, scans): (usize, &[PathFindingAlgorithm], bool) = match name {
+        "standard" => (
+            4,
+            &[
+                PathFindingAlgorithm::Astar,
+                PathFindingAlgorithm::Dijkstra,
+                PathFindingAlgorithm::Idastar,
+                PathFindingAlgorithm::Fringe,
+                PathFindingAlgorithm::BoundedAstar,
+            ],
+            true,
+        ),
+        "quick" => (2, &[PathFindingAlgorithm::Astar], false),
+        _ => return None,
+    };
+    // Ends spread over the dictionary, always the same ones
+    let ends: Vec<(&String, &String)> = (0..pairs)
+        .map(|i| {
+            (
+                &words[i * 97 % words.len()],
+                &words[(i * 389 + 701) % words.len()],
+            )
+        })
+        .collect();
+    let mut queries = Vec::new();
+    for indexed in [true, false].iter().filter(|&&indexed| indexed || scans) {
+        for &algorithm in algorithms {
+            for (start, stop) in &ends {
+                queries.push(Query {
+                    start: start.to_string(),
+                    stop: stop.to_string(),
+                    algorithm,
+                    indexed: *indexed,
+                });
+            }
+        }
+    }
+    Some(queries)
+}
+
+/// What a query measured: its fastest run out of several, the words it expanded and the
+/// hops of the path found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measure {
+    pub name: String,
+    pub micros: u64,
+    pub expanded: usize,
+    pub hops: Option<usize>,
+}
+
+/// Run every query `repeats` times through `words`, calling `progress` after each one
+pub fn run(
+    queries: &[Query],
+    words: &[String],
+    repeats: usize,
+    mut progress: impl FnMut(&Measure),
+) -> Vec<Measure> {
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    let mut measures = Vec::new();
+    for query in queries {
+        let options = SearchOptions::new(query.algorithm);
+        let options = match query.indexed {
+            true => options.with_max_hop_distance(1),
+            false => options,
+        };
+        let mut fastest = Duration::MAX;
+        let mut outcome = (0, None);
+        for _ in 0..repeats.max(1) {
+            let started = Instant::now();
+            let (found, stats) = match query.indexed {
+                true => distance::find_shortest_path_with_options(
+                    &query.start,
+                    &query.stop,
+                    &indexed,
+                    &options,
+                ),
+                false => distance::find_shortest_path_with_options(
+                    &query.start,
+                    &query.stop,
+                    words.as_slice(),
+                    &options,
+                ),
+            };
+            fastest = fastest.min(started.elapsed());
+            let hops = found.ok().flatten().map(|(path, _)| path.len() - 1);
+            outcome = (stats.expanded, hops);
+        }
+        let measure = Measure {
+            name: query.name(),
+            micros: fastest.as_micros() as u64,
+            expanded: outcome.0,
+            hops: outcome.1,
+        };
+        progress(&measure);
+        measures.push(measure);
+    }
+    measures
+}
+
+/// The measures of `suite` as JSON, one query per line so that baselines diff well
+pub fn write_json(suite: &str, measures: &[Measure], mut writer: impl Write) -> io::Result<()> {
+    writeln!(writer, "{{\"suite\":\"{}\",\"queries\":[", suite)?;
+    for (i, measure) in measures.iter().enumerate() {
+        let hops = measure
+            .hops
+            .map_or_else(|| "null".to_string(), |hops| hops.to_string());
+        writeln!(
+            writer,
+            "{{\"name\":\"{}\",\"micros\":{},\"expanded\":{},\"hops\":{}}}{}",
+            measure.name,
+            measure.micros,
+            measure.expanded,
+            hops,
+            if i + 1 < measures.len() { "," } else { "" }
+        )?;
+    }
+    writeln!(writer, "]}}")
+}
+
+// Raw value of `"key":` in a line written by `write_json`, up to the next comma or brace
+fn field<'l>(line: &'l str, key: &str) -> Option<&'l str> {
+    let start = line.find(&format!("\"{}\":", key))? + key.len() + 3;
+    let rest = &line[start..];
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+/// Measures of a file `write_json` wrote
+pub fn read_json(reader: impl BufRead) -> io::Result<Vec<Measure>> {
+    let invalid = |line: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected measure {}", line),
+        )
+    };
+    let mut measures = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim_start().starts_with("{\"name\":") {
+            continue;
+        }
+        let number = |key| field(&line, key).and_then(|value| value.parse().ok());
+        let measure = (|| {
+            Some(Measure {
+                name: field(&line, "name")?.trim_matches('"').to_string(),
+                micros: number("micros")?,
+                expanded: number("expanded")? as usize,
+                hops: match field(&line, "hops")? {
+                    "null" => None,
+                    hops => Some(hops.parse().ok()?),
+                },
+            })
+        })();
+        measures.push(measure.ok_or_else(|| invalid(&line))?);
+    }
+    Ok(measures)
+}
+
+/// Queries measured worse than in `baseline`: slower by more than `tolerance`, a share of
+/// the time of the baseline, expanding more words or finding another number of hops.
+/// Queries missing from either side are left out.
+pub fn regressions(baseline: &[Measure], measures: &[Measure], tolerance: f64) -> Vec<String> {
+    let mut found = Vec::new();
+    for measure in measures {
+        let before = match baseline.iter().find(|before| before.name == measure.name) {
+            Some(before) => before,
+            None => continue,
+        };
+        if measure.micros as f64 > before.micros as f64 * (1.0 + tolerance) {
+            found.push(format!(
+                "{}: {}µs, {}µs in the baseline",
+                measure.name, measure.micros, before.micros
+            ));
+        }
+        if measure.expanded > before.expanded {
+            found.push(format!(
+                "{}: {} words expanded, {} in the baseline",
+                measure.name, measure.expanded, before.expanded
+            ));
+        }
+        if measure.hops != before.hops {
+            found.push(format!(
+                "{}: {:?} hops, {:?} in the baseline",
+                measure.name, measure.hops, before.hops
+            ));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_round_trip_and_compare() {
+        let words = dictionary();
+        assert_eq!(words.len(), DICTIONARY_SIZE);
+        assert_eq!(dictionary(), words);
+        assert!(suite("huge", &words).is_none());
+        assert_eq!(suite("standard", &words).unwrap().len(), 40);
+        let queries = suite("quick", &words).unwrap();
+        let measures = run(&queries, &words, 1, |_| ());
+        assert_eq!(measures.len(), 2);
+        assert!(measures.iter().all(|measure| measure.hops.is_some()));
+
+        let mut json = Vec::new();
+        write_json("quick", &measures, &mut json).unwrap();
+        let read = read_json(&json[..]).unwrap();
+        assert_eq!(read, measures);
+        assert!(regressions(&read, &measures, 0.0).is_empty());
+
+        let mut faster = read.clone();
+        faster[0].micros = 0;
+        faster[1].expanded -= 1;
+        let found = regressions(&faster, &measures, 0.5);
+        assert_eq!(found.len(), 1 + usize::from(measures[0].micros > 0));
+        assert!(found.last().unwrap().contains("words expanded"));
+        assert!(read_json(&b"{\"name\":\"astar\",\"micros\":1}\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// FNV-1a is used instead of the std hasher because its output must stay stable
+// across releases for the on-disk cache.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Stable fingerprint of a word list, sensitive to word order
+pub fn dictionary_hash<S: AsRef<str>>(words: &[S]) -> u64 {
+    words.iter().fold(FNV_OFFSET, |hash, word| {
+        fnv1a(fnv1a(hash, word.as_ref().as_bytes()), b"\n")
+    })
+}
+
+/// Stable fingerprint of the contents of a file, such as a cost matrix
+pub fn file_hash(path: impl AsRef<Path>) -> io::Result<u64> {
+    Ok(fnv1a(FNV_OFFSET, &fs::read(path)?))
+}
+
+/// Everything that can change the answer of a query
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub dictionary: u64,
+    pub start: String,
+    pub end: String,
+    pub options: String,
+}
+
+impl CacheKey {
+    fn describe(&self) -> String {
+        format!(
+            "{:016x} {} {} {}",
+            self.dictionary, self.start, self.end, self.options
+        )
+    }
+}
+
+/// A memoized answer: the rendered path and its cost
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedPath {
+    pub words: Vec<String>,
+    pub cost: String,
+}
+
+/// Least recently used cache holding at most `capacity` entries
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, V>,
+    recency: VecDeque<K>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    pub fn new(capacity: usize) -> LruCache<K, V> {
+        LruCache {
+            capacity,
+            entries: HashMap::new(),
+            recency: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.entries.get(key).cloned();
+        if value.is_some() {
+            self.touch(key);
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(key.clone(), value).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.recency.push_back(key);
+        if self.recency.len() > self.capacity {
+            if let Some(evicted) = self.recency.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(position) = self.recency.iter().position(|k| k == key) {
+            let key = self.recency.remove(position).unwrap();
+            self.recency.push_back(key);
+        }
+    }
+}
+
+/// Directory holding one file per memoized query
+pub struct DiskCache {
+    directory: PathBuf,
+}
+
+impl DiskCache {
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<DiskCache> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(DiskCache { directory })
+    }
+
+    fn file(&self, key: &CacheKey) -> PathBuf {
+        let description = key.describe();
+        self.directory.join(format!(
+            "{:016x}",
+            fnv1a(FNV_OFFSET, description.as_bytes())
+        ))
+    }
+
+    /// `Some(None)` means the query is known to have no path
+    pub fn get(&self, key: &CacheKey) -> Option<Option<CachedPath>> {
+        let content = fs::read_to_string(self.file(key)).ok()?;
+        let mut lines = content.lines();
+        if lines.next()? != key.describe() {
+            return None;
+        }
+        match (lines.next()?, lines.next()) {
+            ("none", None) => Some(None),
+            (words, Some(cost)) => Some(Some(CachedPath {
+                words: words.split("->").map(String::from).collect(),
+                cost: cost.to_string(),
+            })),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&self, key: &CacheKey, value: &Option<CachedPath>) -> io::Result<()> {
+        let body = match value {
+            Some(path) => format!("{}\n{}\n", path.words.join("->"), path.cost),
+            None => "none\n".to_string(),
+        };
+        fs::write(self.file(key), format!("{}\n{}", key.describe(), body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn key(start: &str) -> CacheKey {
+        CacheKey {
+            dictionary: dictionary_hash(&["cold", "warm"]),
+            start: start.to_string(),
+            end: "warm".to_string(),
+            options: "astar".to_string(),
+        }
+    }
+
+    #[test]
+    fn dictionary_hash_is_stable() {
+        assert_eq!(dictionary_hash::<&str>(&[]), FNV_OFFSET);
+        assert_eq!(
+            dictionary_hash(&["a", "b"]),
+            dictionary_hash(&["a".to_string(), "b".to_string()])
+        );
+        assert_ne!(dictionary_hash(&["ab"]), dictionary_hash(&["a", "b"]));
+        assert_ne!(dictionary_hash(&["a", "b"]), dictionary_hash(&["b", "a"]));
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&3), Some("three"));
+    }
+
+    #[test]
+    fn lru_with_zero_capacity_stores_nothing() {
+        let mut cache = LruCache::new(0);
+        cache.insert(1, "one");
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn disk_cache_round_trip() {
+        let directory = env::temp_dir().join(format!("typos-cache-test-{}", std::process::id()));
+        let cache = DiskCache::new(&directory).unwrap();
+        let path = Some(CachedPath {
+            words: vec!["cold".to_string(), "warm".to_string()],
+            cost: "1 4-letter mutation".to_string(),
+        });
+        assert_eq!(cache.get(&key("cold")), None);
+        cache.insert(&key("cold"), &path).unwrap();
+        cache.insert(&key("hot"), &None).unwrap();
+        assert_eq!(cache.get(&key("cold")), Some(path));
+        assert_eq!(cache.get(&key("hot")), Some(None));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,293 @@
+use crate::events::JsonString;
+use crate::pipe::Speller;
+use crate::tokenize::{self, Modes};
+use globset::GlobBuilder;
+use ignore::{WalkBuilder, WalkState};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Version of the JSON output, raised whenever a field changes meaning or goes away
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Word list of a project, found in the current directory or the closest parent having one
+pub const IGNORE_FILE: &str = ".typos-ignore";
+
+/// Lines containing this, usually in a comment, are not checked
+pub const IGNORE_LINE: &str = "typos:ignore-line";
+
+// Bytes at the start of a file searched for a NUL to tell binary files, as git does
+const BINARY_PROBE: usize = 8000;
+
+/// A word of a checked file missing from the dictionary
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub file: String,
+    /// Counted from 1
+    pub line: usize,
+    /// Counted from 1, in characters
+    pub column: usize,
+    pub token: String,
+    pub suggestions: Vec<String>,
+}
+
+/// `IGNORE_FILE` in `directory` or the closest of its parents, if any
+pub fn find_ignore_file(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|ancestor| ancestor.join(IGNORE_FILE))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Every unknown word of `text`, read from `file`, in order
+pub fn check(speller: &Speller, modes: &Modes, file: &str, text: &str) -> Vec<Finding> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut findings = Vec::new();
+    for token in tokenize::tokens(modes.for_path(file), file, text) {
+        if lines[token.line].contains(IGNORE_LINE) || speller.knows(&token.text) {
+            continue;
+        }
+        findings.push(Finding {
+            file: file.to_string(),
+            line: token.line + 1,
+            column: token.column + 1,
+            suggestions: speller
+                .suggestions(&token.text)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            token: token.text,
+        });
+    }
+    findings
+}
+
+/// Whether `bytes` look like the content of a binary file rather than text
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(BINARY_PROBE)].contains(&0)
+}
+
+fn is_glob(argument: &str) -> bool {
+    argument.contains(['*', '?', '[', '{']) && !Path::new(argument).exists()
+}
+
+// Directory to walk for a glob pattern, its components before the first wildcard
+fn glob_base(pattern: &str) -> PathBuf {
+    let base: PathBuf = Path::new(pattern)
+        .components()
+        .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+        .collect();
+    match base.as_os_str().is_empty() {
+        true => PathBuf::from("."),
+        false => base,
+    }
+}
+
+/// Findings in the file, the files under the directory or the files matching the glob
+/// pattern given as `argument`, sorted, along with the errors met. Directories are walked
+/// in parallel, without hidden files, files ignored by git or binary files
+pub fn check_path(speller: &Speller, modes: &Modes, argument: &str) -> (Vec<Finding>, Vec<String>) {
+    let (root, glob) = match is_glob(argument) {
+        // `*` stops at slashes as in shells, `**` crosses them
+        true => match GlobBuilder::new(argument).literal_separator(true).build() {
+            Ok(glob) => (glob_base(argument), Some(glob.compile_matcher())),
+            Err(error) => return (Vec::new(), vec![error.to_string()]),
+        },
+        false => (PathBuf::from(argument), None),
+    };
+    let findings = Mutex::new(Vec::new());
+    let errors = Mutex::new(Vec::new());
+    WalkBuilder::new(root).build_parallel().run(|| {
+        Box::new(|entry| {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => {
+                    errors.lock().unwrap().push(error.to_string());
+                    return WalkState::Continue;
+                }
+            };
+            let path = entry.path();
+            let selected = glob
+                .as_ref()
+                .is_none_or(|glob| glob.is_match(path.strip_prefix(".").unwrap_or(path)));
+            if !selected || !entry.file_type().is_some_and(|kind| kind.is_file()) {
+                return WalkState::Continue;
+            }
+            match std::fs::read(path) {
+                Ok(bytes) if is_binary(&bytes) => (),
+                Ok(bytes) => {
+                    let text = String::from_utf8_lossy(&bytes);
+                    let found = check(speller, modes, &path.display().to_string(), &text);
+                    findings.lock().unwrap().extend(found);
+                }
+                Err(error) => errors.lock().unwrap().push(format!(
+                    "cannot read {}: {}",
+                    path.display(),
+                    error
+                )),
+            }
+            WalkState::Continue
+        })
+    });
+    // Threads finish files in any order
+    let mut findings = findings.into_inner().unwrap();
+    findings.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+    let mut errors = errors.into_inner().unwrap();
+    errors.sort();
+    (findings, errors)
+}
+
+/// One `file:line:column: token` line per finding, as compilers report errors
+pub fn write_text(findings: &[Finding], mut output: impl Write) -> io::Result<()> {
+    for finding in findings {
+        write!(
+            output,
+            "{}:{}:{}: {}",
+            finding.file, finding.line, finding.column, finding.token
+        )?;
+        match finding.suggestions.is_empty() {
+            true => writeln!(output)?,
+            false => writeln!(
+                output,
+                " (did you mean {}?)",
+                finding.suggestions.join(", ")
+            )?,
+        }
+    }
+    Ok(())
+}
+
+/// A single JSON document listing the findings, whose schema only changes along with
+/// `SCHEMA_VERSION`
+pub fn write_json(findings: &[Finding], mut output: impl Write) -> io::Result<()> {
+    let findings: Vec<String> = findings
+        .iter()
+        .map(|finding| {
+            let suggestions: Vec<String> = finding
+                .suggestions
+                .iter()
+                .map(|suggestion| JsonString(suggestion).to_string())
+                .collect();
+            format!(
+                "{{\"file\":{},\"line\":{},\"column\":{},\"token\":{},\"suggestions\":[{}]}}",
+                JsonString(&finding.file),
+                finding.line,
+                finding.column,
+                JsonString(&finding.token),
+                suggestions.join(",")
+            )
+        })
+        .collect();
+    writeln!(
+        output,
+        "{{\"version\":{},\"findings\":[{}]}}",
+        SCHEMA_VERSION,
+        findings.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_unknown_words_where_they_are() {
+        let words = ["cold", "cord", "warm"];
+        let speller = Speller::new(&words);
+        let text = "Cold warm\n\n  colt, zzzz\nxyz // typos:ignore-line\n";
+        let findings = check(&speller, &Modes::default(), "notes \"1\".txt", text);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(
+            findings[0],
+            Finding {
+                file: "notes \"1\".txt".to_string(),
+                line: 3,
+                column: 3,
+                token: "colt".to_string(),
+                suggestions: vec!["cold".to_string(), "cord".to_string()],
+            }
+        );
+
+        let mut text = Vec::new();
+        write_text(&findings, &mut text).unwrap();
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "notes \"1\".txt:3:3: colt (did you mean cold, cord?)\nnotes \"1\".txt:3:9: zzzz\n"
+        );
+        let mut json = Vec::new();
+        write_json(&findings, &mut json).unwrap();
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            concat!(
+                r#"{"version":1,"findings":["#,
+                r#"{"file":"notes \"1\".txt","line":3,"column":3,"token":"colt","suggestions":["cold","cord"]},"#,
+                r#"{"file":"notes \"1\".txt","line":3,"column":9,"token":"zzzz","suggestions":[]}]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn walks_directories_and_globs() {
+        let root = std::env::temp_dir().join(format!("typos-check-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("docs/old")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("readme.md"), "colt\n").unwrap();
+        std::fs::write(root.join("debug.log"), "colt\n").unwrap();
+        std::fs::write(root.join("docs/a.md"), "cold\nwarm colt\n").unwrap();
+        std::fs::write(root.join("docs/old/b.md"), "zzzz\n").unwrap();
+        std::fs::write(root.join("docs/old/c.txt"), "colt\n").unwrap();
+        std::fs::write(root.join("docs/image.png"), b"colt\0\x89").unwrap();
+        let words = ["cold", "cord", "warm"];
+        let speller = Speller::new(&words);
+        let places = |argument: &Path| -> Vec<String> {
+            let (findings, errors) =
+                check_path(&speller, &Modes::default(), argument.to_str().unwrap());
+            assert!(errors.is_empty(), "{:?}", errors);
+            findings
+                .iter()
+                .map(|finding| {
+                    let file = Path::new(&finding.file).strip_prefix(&root).unwrap();
+                    format!("{}:{}", file.display(), finding.line)
+                })
+                .collect()
+        };
+        assert_eq!(
+            places(&root),
+            vec![
+                "docs/a.md:2",
+                "docs/old/b.md:1",
+                "docs/old/c.txt:1",
+                "readme.md:1"
+            ]
+        );
+        assert_eq!(
+            places(&root.join("**/*.md")),
+            vec!["docs/a.md:2", "docs/old/b.md:1", "readme.md:1"]
+        );
+        assert_eq!(places(&root.join("docs/*.md")), vec!["docs/a.md:2"]);
+        assert_eq!(places(&root.join("debug.log")), vec!["debug.log:1"]);
+        let (_, errors) = check_path(
+            &speller,
+            &Modes::default(),
+            root.join("missing").to_str().unwrap(),
+        );
+        assert_eq!(errors.len(), 1);
+
+        std::fs::write(root.join(IGNORE_FILE), "colt\n").unwrap();
+        assert_eq!(
+            find_ignore_file(&root.join("docs/old")),
+            Some(root.join(IGNORE_FILE))
+        );
+        // The closest one wins
+        std::fs::write(root.join("docs").join(IGNORE_FILE), "zzzz\n").unwrap();
+        assert_eq!(
+            find_ignore_file(&root.join("docs/old")),
+            Some(root.join("docs").join(IGNORE_FILE))
+        );
+        assert_eq!(find_ignore_file(&root), Some(root.join(IGNORE_FILE)));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
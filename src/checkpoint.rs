@@ -0,0 +1,81 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use typos::distance::SearchState;
+
+/// How long `--checkpoint-interval` is, such as `60s`, `5m` or `2h`, seconds without a unit
+pub fn parse_interval(interval: &str) -> Option<Duration> {
+    let (digits, unit) = match interval.char_indices().last()? {
+        (i, 's') => (&interval[..i], 1),
+        (i, 'm') => (&interval[..i], 60),
+        (i, 'h') => (&interval[..i], 60 * 60),
+        _ => (interval, 1),
+    };
+    let seconds = digits.parse::<u64>().ok()?.checked_mul(unit)?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Write `state` to `path` after a line telling which search it belongs to. The file is
+/// replaced at once, so a crash while saving leaves the previous checkpoint.
+pub fn save(path: &Path, fingerprint: &str, state: &SearchState) -> io::Result<()> {
+    let mut written = path.as_os_str().to_owned();
+    written.push(".tmp");
+    let mut writer = BufWriter::new(File::create(&written)?);
+    writeln!(writer, "{}", fingerprint)?;
+    state.write(&mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    fs::rename(&written, path)
+}
+
+/// State saved to `path` for the search `fingerprint` describes
+pub fn load(path: &Path, fingerprint: &str) -> io::Result<SearchState> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut saved = String::new();
+    reader.read_line(&mut saved)?;
+    if saved.trim_end_matches('\n') != fingerprint {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the checkpoint was saved by a search of other words, options or word list",
+        ));
+    }
+    SearchState::read(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typos::distance::{self, PathFindingAlgorithm, SearchOptions};
+
+    #[test]
+    fn checkpoints_only_resume_their_own_search() {
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let mut states = Vec::new();
+        let (result, _) = distance::find_shortest_path_checkpointed(
+            "cold",
+            "warm",
+            &words[..],
+            &options,
+            None,
+            Duration::ZERO,
+            &mut |state| states.push(state.clone()),
+        );
+        assert!(result.unwrap().is_some());
+        let state = states.pop().unwrap();
+
+        let path = std::env::temp_dir().join(format!("typos-checkpoint-{}", std::process::id()));
+        save(&path, "abc cold warm", &state).unwrap();
+        assert_eq!(load(&path, "abc cold warm").unwrap(), state);
+        assert!(load(&path, "abd cold warm").is_err());
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(parse_interval("60s"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_interval("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_interval("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_interval("soon"), None);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::random::Random;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use typos::distance::Dictionary;
+
+/// Names of the algorithms `typos cluster` groups words with, the first one used unless
+/// told otherwise
+pub const ALGORITHMS: [&str; 2] = ["label-propagation", "louvain"];
+
+// Rounds of label propagation before giving up on labels settling
+const MAX_ROUNDS: usize = 100;
+
+/// Neighbours of every word of `words` within `k` letters, by index in `words`
+pub fn adjacency<D: Dictionary + ?Sized>(
+    words: &[&str],
+    dictionary: &D,
+    k: usize,
+) -> Vec<Vec<usize>> {
+    let index: HashMap<&str, usize> = words
+        .iter()
+        .enumerate()
+        .map(|(i, &word)| (word, i))
+        .collect();
+    let mut neighbors = Vec::new();
+    words
+        .iter()
+        .map(|word| {
+            neighbors.clear();
+            dictionary.extend_neighbors(word, k, &mut neighbors);
+            let mut adjacent: Vec<usize> = neighbors
+                .iter()
+                .filter_map(|neighbor| index.get(neighbor).copied())
+                .collect();
+            adjacent.sort_unstable();
+            adjacent.dedup();
+            adjacent
+        })
+        .collect()
+}
+
+/// Every word takes the label most of its neighbours have, words visited in an order
+/// `random` shuffles every round, until no label changes. Ties go to the smallest label,
+/// so that a seed always gives the same clusters.
+pub fn label_propagation(adjacency: &[Vec<usize>], random: &mut Random) -> Vec<usize> {
+    let mut labels: Vec<usize> = (0..adjacency.len()).collect();
+    let mut order: Vec<usize> = (0..adjacency.len()).collect();
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for _ in 0..MAX_ROUNDS {
+        for i in (1..order.len()).rev() {
+            order.swap(i, random.below(i + 1));
+        }
+        let mut changed = false;
+        for &word in &order {
+            counts.clear();
+            for &neighbor in &adjacency[word] {
+                *counts.entry(labels[neighbor]).or_insert(0) += 1;
+            }
+            let best = counts
+                .iter()
+                .max_by(|(l1, c1), (l2, c2)| c1.cmp(c2).then(l2.cmp(l1)))
+                .map(|(&label, &count)| (label, count));
+            if let Some((label, count)) = best {
+                // Keeping a label as common as the best one stops words flipping forever
+                if label != labels[word] && counts.get(&labels[word]) != Some(&count) {
+                    labels[word] = label;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    labels
+}
+
+// Weighted hops of a graph whose nodes may be communities, a loop on a node weighing
+// twice the hops inside it
+type Weighted = Vec<Vec<(usize, f64)>>;
+
+// Moves every node to the community of a neighbour raising the modularity most until no
+// move raises it, returning the community of every node and whether any moved
+fn one_level(graph: &Weighted) -> (Vec<usize>, bool) {
+    let degrees: Vec<f64> = graph
+        .iter()
+        .map(|hops| hops.iter().map(|&(_, weight)| weight).sum())
+        .collect();
+    let total: f64 = degrees.iter().sum();
+    let mut community: Vec<usize> = (0..graph.len()).collect();
+    let mut totals = degrees.clone();
+    let mut links: HashMap<usize, f64> = HashMap::new();
+    let mut moved = false;
+    loop {
+        let mut improved = false;
+        for node in 0..graph.len() {
+            let current = community[node];
+            totals[current] -= degrees[node];
+            links.clear();
+            links.insert(current, 0.0);
+            for &(neighbor, weight) in graph[node].iter().filter(|&&(n, _)| n != node) {
+                *links.entry(community[neighbor]).or_insert(0.0) += weight;
+            }
+            let gain = |c: usize, weight: f64| weight - totals[c] * degrees[node] / total;
+            let mut best = (current, gain(current, links[&current]));
+            let mut candidates: Vec<(usize, f64)> = links.iter().map(|(&c, &w)| (c, w)).collect();
+            candidates.sort_unstable_by_key(|&(c, _)| c);
+            for (c, weight) in candidates {
+                let gained = gain(c, weight);
+                if gained > best.1 + 1e-12 {
+                    best = (c, gained);
+                }
+            }
+            totals[best.0] += degrees[node];
+            if best.0 != current {
+                community[node] = best.0;
+                improved = true;
+                moved = true;
+            }
+        }
+        if !improved {
+            return (community, moved);
+        }
+    }
+}
+
+// Communities numbered from 0 in the order of their first node
+fn renumber(labels: &mut [usize]) -> usize {
+    let mut numbers = HashMap::new();
+    for label in labels.iter_mut() {
+        let next = numbers.len();
+        *label = *numbers.entry(*label).or_insert(next);
+    }
+    numbers.len()
+}
+
+/// Communities raising the modularity of the graph as high as moving words between them
+/// gets, grouping the communities found into nodes and moving them again until none moves
+pub fn louvain(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let mut graph: Weighted = adjacency
+        .iter()
+        .map(|hops| hops.iter().map(|&neighbor| (neighbor, 1.0)).collect())
+        .collect();
+    let mut labels: Vec<usize> = (0..adjacency.len()).collect();
+    // Words without a hop give no modularity to gain
+    if graph.iter().all(Vec::is_empty) {
+        return labels;
+    }
+    loop {
+        let (mut community, moved) = one_level(&graph);
+        if !moved {
+            return labels;
+        }
+        let count = renumber(&mut community);
+        for label in labels.iter_mut() {
+            *label = community[*label];
+        }
+        let mut grouped: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+        for (node, hops) in graph.iter().enumerate() {
+            for &(neighbor, weight) in hops {
+                *grouped[community[node]]
+                    .entry(community[neighbor])
+                    .or_insert(0.0) += weight;
+            }
+        }
+        graph = grouped
+            .into_iter()
+            .map(|hops| {
+                let mut hops: Vec<(usize, f64)> = hops.into_iter().collect();
+                hops.sort_unstable_by_key(|&(neighbor, _)| neighbor);
+                hops
+            })
+            .collect();
+    }
+}
+
+/// Clusters of `labels` split into the parts hops connect inside them, so that a ladder
+/// within the cluster links any two of its words, numbered from the biggest one
+pub fn connected_clusters(adjacency: &[Vec<usize>], labels: &[usize]) -> Vec<usize> {
+    const UNSEEN: usize = usize::MAX;
+    let mut parts = vec![UNSEEN; labels.len()];
+    let mut sizes = Vec::new();
+    for first in 0..labels.len() {
+        if parts[first] != UNSEEN {
+            continue;
+        }
+        let part = sizes.len();
+        parts[first] = part;
+        let mut stack = vec![first];
+        let mut size = 0;
+        while let Some(word) = stack.pop() {
+            size += 1;
+            for &neighbor in &adjacency[word] {
+                if parts[neighbor] == UNSEEN && labels[neighbor] == labels[word] {
+                    parts[neighbor] = part;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    // Biggest first, ties in the order of their first word
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&part| std::cmp::Reverse(sizes[part]));
+    let mut rank = vec![0; sizes.len()];
+    for (number, part) in order.into_iter().enumerate() {
+        rank[part] = number;
+    }
+    parts.into_iter().map(|part| rank[part]).collect()
+}
+
+/// A `word<TAB>cluster` line per word, in the order of `words`
+pub fn write_assignments(
+    words: &[&str],
+    clusters: &[usize],
+    mut writer: impl Write,
+) -> io::Result<()> {
+    for (word, cluster) in words.iter().zip(clusters) {
+        writeln!(writer, "{}\t{}", word, cluster)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typos::distance::ShardedWords;
+
+    #[test]
+    fn ladders_apart_fall_in_clusters_apart() {
+        // Two groups of ladders a single ladder apart, and a word alone
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "worm", "word", "cork", "pork", "conk", "monk",
+            "mink", "pink", "pint", "mint", "mind", "mild", "wild", "wind", "kind", "zzzz",
+        ];
+        let indexed = ShardedWords::new(&words);
+        let adjacency = adjacency(&words, &indexed, 1);
+        assert_eq!(adjacency, super::adjacency(&words, &words[..], 1));
+        assert_eq!(adjacency[0], vec![1]);
+        for labels in [
+            louvain(&adjacency),
+            label_propagation(&adjacency, &mut Random::new(5)),
+        ] {
+            let clusters = connected_clusters(&adjacency, &labels);
+            let cluster = |word| clusters[words.iter().position(|&w| w == word).unwrap()];
+            assert_eq!(cluster("cold"), cluster("cord"));
+            assert_ne!(cluster("cold"), cluster("wild"));
+            assert_eq!(cluster("zzzz"), clusters.iter().max().copied().unwrap());
+            assert_eq!(
+                clusters.iter().filter(|&&c| c == cluster("zzzz")).count(),
+                1
+            );
+        }
+        assert_eq!(
+            label_propagation(&adjacency, &mut Random::new(5)),
+            label_propagation(&adjacency, &mut Random::new(5))
+        );
+        let mut written = Vec::new();
+        write_assignments(&words[..2], &[0, 0], &mut written).unwrap();
+        assert_eq!(written, b"cold\t0\ncord\t0\n");
+    }
+}
@@ -0,0 +1,239 @@
+use crate::batch::{Answer, Pair};
+use crate::matrix::Row;
+use std::io;
+
+/// Whether the file at `path` is to be read or written as Parquet rather than CSV
+pub fn is_parquet(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".parquet")
+}
+
+#[cfg(feature = "parquet-io")]
+mod parquet_io {
+    use super::*;
+    use crate::matrix;
+    use arrow_array::builder::{ListBuilder, StringBuilder, UInt32Builder};
+    use arrow_array::cast::AsArray;
+    use arrow_array::{ArrayRef, RecordBatch, StringArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::arrow::ArrowWriter;
+    use std::fs::File;
+    use std::sync::Arc;
+    use typos::distance::{self, cost::Unit};
+
+    fn invalid(error: impl std::error::Error + Send + Sync + 'static) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+
+    // Strings of the column `name`, whichever of the string types arrow has it is stored as
+    fn strings(batch: &RecordBatch, name: &str) -> io::Result<Vec<Option<String>>> {
+        let column = batch.column_by_name(name).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("no {} column", name))
+        })?;
+        let owned = |value: Option<&str>| value.map(str::to_string);
+        if let Some(column) = column.as_string_opt::<i32>() {
+            Ok(column.iter().map(owned).collect())
+        } else if let Some(column) = column.as_string_opt::<i64>() {
+            Ok(column.iter().map(owned).collect())
+        } else if let Some(column) = column.as_string_view_opt() {
+            Ok(column.iter().map(owned).collect())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the {} column does not hold strings", name),
+            ))
+        }
+    }
+
+    /// Pairs of the `start` and `end` columns of the Parquet file at `path`, other columns
+    /// ignored and rows missing either word skipped
+    pub fn read_pairs(path: &str) -> io::Result<Vec<Pair>> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+            .map_err(invalid)?
+            .build()
+            .map_err(invalid)?;
+        let mut pairs = Vec::new();
+        for batch in reader {
+            let batch = batch.map_err(invalid)?;
+            let starts = strings(&batch, "start")?;
+            let ends = strings(&batch, "end")?;
+            pairs.extend(starts.into_iter().zip(ends).filter_map(|pair| match pair {
+                (Some(start), Some(end)) => Some((start, end)),
+                _ => None,
+            }));
+        }
+        Ok(pairs)
+    }
+
+    fn write(path: &str, batch: RecordBatch) -> io::Result<()> {
+        let mut writer =
+            ArrowWriter::try_new(File::create(path)?, batch.schema(), None).map_err(invalid)?;
+        writer.write(&batch).map_err(invalid)?;
+        writer.close().map_err(invalid)?;
+        Ok(())
+    }
+
+    /// Write `answers` to the Parquet file at `path`, with the columns of the CSV output
+    /// but the words of the path and the letters of each hop as lists
+    pub fn write_answers(path: &str, answers: &[Answer]) -> io::Result<()> {
+        let mut hops = UInt32Builder::new();
+        let mut costs = StringBuilder::new();
+        let mut paths = ListBuilder::new(StringBuilder::new());
+        let mut letters = ListBuilder::new(UInt32Builder::new());
+        for answer in answers {
+            match &answer.path {
+                Some(path) => {
+                    hops.append_value(path.hops.len() as u32);
+                    costs.append_value(distance::describe_cost(&path.cost, Unit::Letter));
+                    paths.append_value(path.words.iter().map(Some));
+                    letters.append_value(path.hops.iter().map(|hop| Some(hop.distance as u32)));
+                }
+                None => {
+                    hops.append_null();
+                    costs.append_null();
+                    paths.append_null();
+                    letters.append_null();
+                }
+            }
+        }
+        let column = |words: Vec<&str>| Arc::new(StringArray::from(words)) as ArrayRef;
+        let batch = RecordBatch::try_from_iter([
+            (
+                "start",
+                column(answers.iter().map(|a| a.start.as_str()).collect()),
+            ),
+            (
+                "end",
+                column(answers.iter().map(|a| a.end.as_str()).collect()),
+            ),
+            ("hops", Arc::new(hops.finish()) as ArrayRef),
+            ("cost", Arc::new(costs.finish()) as ArrayRef),
+            ("path", Arc::new(paths.finish()) as ArrayRef),
+            ("letters", Arc::new(letters.finish()) as ArrayRef),
+        ])
+        .map_err(invalid)?;
+        write(path, batch)
+    }
+
+    /// Write `rows` to the Parquet file at `path`, one row per pair of `words` with the
+    /// number of hops of every size as a list, from 1-letter hops up, null when the words
+    /// are not connected
+    pub fn write_matrix(path: &str, words: &[&str], rows: &[Row]) -> io::Result<()> {
+        let mut starts = Vec::new();
+        let mut ends = Vec::new();
+        let mut mutations = ListBuilder::new(UInt32Builder::new());
+        for (start, row) in words.iter().zip(rows) {
+            for (end, cost) in words.iter().zip(row) {
+                starts.push(*start);
+                ends.push(*end);
+                match cost {
+                    Some(cost) => mutations.append_value(
+                        matrix::sizes(cost)
+                            .into_iter()
+                            .map(|count| Some(u32::from(count))),
+                    ),
+                    None => mutations.append_null(),
+                }
+            }
+        }
+        let batch = RecordBatch::try_from_iter([
+            ("start", Arc::new(StringArray::from(starts)) as ArrayRef),
+            ("end", Arc::new(StringArray::from(ends)) as ArrayRef),
+            ("mutations", Arc::new(mutations.finish()) as ArrayRef),
+        ])
+        .map_err(invalid)?;
+        write(path, batch)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::batch;
+        use arrow_array::{Array, ListArray, UInt32Array};
+        use typos::distance::{PathFindingAlgorithm, SearchOptions};
+
+        fn lists(batch: &RecordBatch, name: &str) -> Vec<Option<Vec<u32>>> {
+            let column: &ListArray = batch.column_by_name(name).unwrap().as_list();
+            (0..column.len())
+                .map(|i| {
+                    column.is_valid(i).then(|| {
+                        let values = column.value(i);
+                        let values: &UInt32Array = values.as_primitive();
+                        values.values().to_vec()
+                    })
+                })
+                .collect()
+        }
+
+        fn read(path: &str) -> RecordBatch {
+            let mut reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap())
+                .unwrap()
+                .build()
+                .unwrap();
+            reader.next().unwrap().unwrap()
+        }
+
+        #[test]
+        fn answers_round_trip_through_parquet() {
+            let directory = std::env::temp_dir();
+            let path = |name: &str| {
+                let path = directory.join(format!("typos-{}-{}.parquet", name, std::process::id()));
+                path.to_str().unwrap().to_string()
+            };
+            let words = ["cold", "cord", "card", "ward", "warm", "zzzz"];
+            let pairs = vec![
+                ("cold".to_string(), "warm".to_string()),
+                ("card".to_string(), "zzzz".to_string()),
+            ];
+            let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+            let answers = batch::solve(&pairs, &words[..], &options).unwrap();
+
+            let answered = path("answers");
+            write_answers(&answered, &answers).unwrap();
+            assert_eq!(read_pairs(&answered).unwrap(), pairs);
+            let written = read(&answered);
+            assert_eq!(
+                lists(&written, "letters"),
+                vec![Some(vec![1, 1, 1, 1]), None]
+            );
+            let hops: &UInt32Array = written.column_by_name("hops").unwrap().as_primitive();
+            assert_eq!(hops.iter().collect::<Vec<_>>(), vec![Some(4), None]);
+
+            let tabulated = path("matrix");
+            let rows = matrix::compute(&words[..2], &words[..], &options, |_| ());
+            write_matrix(&tabulated, &words[..2], &rows).unwrap();
+            assert_eq!(
+                lists(&read(&tabulated), "mutations"),
+                vec![Some(vec![]), Some(vec![1]), Some(vec![1]), Some(vec![])]
+            );
+            assert_eq!(read_pairs(&tabulated).unwrap().len(), 4);
+            std::fs::remove_file(answered).unwrap();
+            std::fs::remove_file(tabulated).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "parquet-io")]
+pub use parquet_io::{read_pairs, write_answers, write_matrix};
+
+#[cfg(not(feature = "parquet-io"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build cannot read or write Parquet files, build it with --features parquet-io",
+    )
+}
+
+#[cfg(not(feature = "parquet-io"))]
+pub fn read_pairs(_: &str) -> io::Result<Vec<Pair>> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "parquet-io"))]
+pub fn write_answers(_: &str, _: &[Answer]) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "parquet-io"))]
+pub fn write_matrix(_: &str, _: &[&str], _: &[Row]) -> io::Result<()> {
+    Err(unsupported())
+}
@@ -0,0 +1,273 @@
+use crate::hunspell::{self, Affixes};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+use typos::distance::prebuilt::PrebuiltGraph;
+use typos::distance::word;
+use typos::words;
+
+/// Ways of storing a dictionary a conversion reads or writes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// One word per line, optionally followed by a tab and a weight
+    Plain,
+    /// A `.dic` file, its affixes read from the `.aff` file next to it
+    Hunspell,
+    /// A finite state transducer set, as the fst crate writes them
+    Fst,
+    /// A graph file as build-graph writes them
+    Graph,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Plain, Format::Hunspell, Format::Fst, Format::Graph];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Plain => "plain",
+            Format::Hunspell => "hunspell",
+            Format::Fst => "fst",
+            Format::Graph => "graph",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Format, ()> {
+        Format::ALL
+            .iter()
+            .copied()
+            .find(|format| format.name() == s)
+            .ok_or(())
+    }
+}
+
+/// Words kept by a conversion, every bound being optional
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Fewest letters of a word kept
+    pub min_length: Option<usize>,
+    /// Most letters of a word kept
+    pub max_length: Option<usize>,
+    /// Only keep words made of letters, dropping phrases, numbers and punctuation
+    pub alphabetic: bool,
+    /// Alphabet, punctuation and digits allowed, as when loading word lists
+    pub policy: words::Policy,
+}
+
+impl Filter {
+    fn keeps(&self, word: &str) -> bool {
+        let length = word.chars().count();
+        self.min_length.is_none_or(|min| length >= min)
+            && self.max_length.is_none_or(|max| length <= max)
+            && (!self.alphabetic || word.chars().all(char::is_alphabetic))
+    }
+}
+
+/// Words of a dictionary on their way from a format to another, with their weights
+#[derive(Debug, Default, PartialEq)]
+pub struct Converted {
+    pub words: Vec<String>,
+    pub weights: HashMap<String, usize>,
+    /// Entries dropped as duplicates once normalized, or by the filter
+    pub dropped: usize,
+}
+
+#[cfg(feature = "fst")]
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(feature = "fst")]
+fn read_fst(filename: &Path) -> io::Result<Vec<String>> {
+    use fst::{IntoStreamer, Streamer};
+    let set = fst::Set::new(std::fs::read(filename)?).map_err(|e| invalid(e.to_string()))?;
+    let mut stream = set.into_stream();
+    let mut words = Vec::new();
+    while let Some(key) = stream.next() {
+        words.push(String::from_utf8(key.to_vec()).map_err(|e| invalid(e.to_string()))?);
+    }
+    Ok(words)
+}
+
+#[cfg(not(feature = "fst"))]
+fn read_fst(_: &Path) -> io::Result<Vec<String>> {
+    Err(without_fst())
+}
+
+#[cfg(feature = "fst")]
+fn write_fst(words: &[String], writer: impl Write) -> io::Result<()> {
+    // Sets hold their keys sorted by bytes, once each
+    let mut keys: Vec<&[u8]> = words.iter().map(|word| word.as_bytes()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    let mut builder = fst::SetBuilder::new(writer).map_err(|e| invalid(e.to_string()))?;
+    builder
+        .extend_iter(keys)
+        .map_err(|e| invalid(e.to_string()))?;
+    builder.finish().map_err(|e| invalid(e.to_string()))
+}
+
+#[cfg(not(feature = "fst"))]
+fn write_fst(_: &[String], _: impl Write) -> io::Result<()> {
+    Err(without_fst())
+}
+
+#[cfg(not(feature = "fst"))]
+fn without_fst() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build has no fst support, build it with --features fst",
+    )
+}
+
+/// Words of `filename` read as `format`. Hunspell affixes are read from `affixes`, or
+/// from the `.aff` file next to the `.dic` one when there is one.
+pub fn read(format: Format, filename: &Path, affixes: Option<&Path>) -> io::Result<Converted> {
+    let (words, weights) = match format {
+        // The policy of the filter applies to every format when normalizing
+        Format::Plain => {
+            let list = words::from_file(filename, &words::Policy::default())?;
+            (list.words, list.weights)
+        }
+        Format::Hunspell => {
+            let aff = filename.with_extension("aff");
+            let affixes = match affixes.or_else(|| Some(aff.as_path()).filter(|aff| aff.is_file()))
+            {
+                Some(aff) => Affixes::from_reader(BufReader::new(File::open(aff)?))?,
+                None => Affixes::default(),
+            };
+            let words = hunspell::words(BufReader::new(File::open(filename)?), &affixes)?;
+            (words, HashMap::new())
+        }
+        Format::Fst => (read_fst(filename)?, HashMap::new()),
+        Format::Graph => {
+            let graph = PrebuiltGraph::read(BufReader::new(File::open(filename)?))?;
+            (graph.words().to_vec(), HashMap::new())
+        }
+    };
+    Ok(Converted {
+        words,
+        weights,
+        dropped: 0,
+    })
+}
+
+/// Same words normalized as the searches read them, without duplicates, words too long
+/// to search or words `filter` drops
+pub fn normalize(converted: Converted, filter: &Filter) -> Converted {
+    let count = converted.words.len();
+    let mut words: Vec<String> = converted
+        .words
+        .iter()
+        .filter_map(|entry| filter.policy.apply(words::normalize(entry)).ok())
+        .filter(|word| word::check_length(word).is_ok() && filter.keeps(word))
+        .collect();
+    words::dedup(&mut words);
+    let kept: HashSet<&String> = words.iter().collect();
+    let weights = converted
+        .weights
+        .into_iter()
+        .filter(|(word, _)| kept.contains(word))
+        .collect();
+    Converted {
+        dropped: count - words.len(),
+        words,
+        weights,
+    }
+}
+
+/// Write `converted` to `writer` as `format`. Only plain lists keep weights, and
+/// hunspell files cannot be written since nothing tells which affixes words share.
+pub fn write(format: Format, converted: &Converted, mut writer: impl Write) -> io::Result<()> {
+    match format {
+        Format::Plain => {
+            converted
+                .words
+                .iter()
+                .try_for_each(|word| match converted.weights.get(word) {
+                    Some(weight) => writeln!(writer, "{}\t{}", word, weight),
+                    None => writeln!(writer, "{}", word),
+                })
+        }
+        Format::Hunspell => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "dictionaries cannot be converted to hunspell",
+        )),
+        Format::Fst => write_fst(&converted.words, writer),
+        Format::Graph => PrebuiltGraph::build(converted.words.clone()).write(writer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converted(words: &[&str]) -> Converted {
+        Converted {
+            words: words.iter().map(|word| word.to_string()).collect(),
+            weights: vec![("cold".to_string(), 3), ("warmer".to_string(), 2)]
+                .into_iter()
+                .collect(),
+            dropped: 0,
+        }
+    }
+
+    fn read_back(format: Format, converted: &Converted) -> Converted {
+        let path = std::env::temp_dir().join(format!(
+            "typos-convert-test-{}-{}",
+            std::process::id(),
+            format
+        ));
+        write(format, converted, File::create(&path).unwrap()).unwrap();
+        let read = read(format, &path, None).unwrap();
+        std::fs::remove_file(path).unwrap();
+        read
+    }
+
+    #[test]
+    fn words_are_normalized_and_filtered() {
+        let filter = Filter {
+            min_length: Some(3),
+            max_length: Some(4),
+            alphabetic: true,
+            ..Filter::default()
+        };
+        let normalized = normalize(
+            converted(&["Cold", "cold", "at", "warm", "warmer", "o'clock", "  Card "]),
+            &filter,
+        );
+        assert_eq!(normalized.words, vec!["cold", "warm", "card"]);
+        assert_eq!(normalized.dropped, 4);
+        assert_eq!(normalized.weights.get("cold"), Some(&3));
+        assert_eq!(normalized.weights.get("warmer"), None);
+        assert_eq!("graph".parse(), Ok(Format::Graph));
+        assert_eq!("aspell".parse::<Format>(), Err(()));
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        let words = converted(&["cold", "cord", "warm"]);
+        let plain = read_back(Format::Plain, &words);
+        assert_eq!(plain.words, words.words);
+        assert_eq!(plain.weights.get("cold"), Some(&3));
+        assert_eq!(read_back(Format::Graph, &words).words, words.words);
+        assert!(write(Format::Hunspell, &words, io::sink()).is_err());
+        #[cfg(feature = "fst")]
+        assert_eq!(read_back(Format::Fst, &words).words, words.words);
+        #[cfg(not(feature = "fst"))]
+        assert!(write(Format::Fst, &words, io::sink()).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::random::Random;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use typos::cache;
+use typos::distance::{self, Dictionary, Difficulty};
+
+/// Difficulty the puzzle aims for unless asked otherwise
+pub const DEFAULT_DIFFICULTY: u8 = 5;
+
+// Pairs tried before settling for the one the closest to the difficulty asked for
+const ATTEMPTS: usize = 64;
+
+/// Day of the calendar, counted from 1970-01-01
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Date(i64);
+
+impl Date {
+    /// Current day in UTC, so that everyone gets the same puzzle at the same time
+    pub fn today() -> Date {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        Date((now.as_secs() / 86400) as i64)
+    }
+
+    // Conversions from and to years, months and days by Howard Hinnant's algorithms
+    fn from_civil(year: i64, month: i64, day: i64) -> Date {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Date(era * 146_097 + day_of_era - 719_468)
+    }
+
+    fn civil(self) -> (i64, i64, i64) {
+        let days = self.0 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ();
+
+    /// Dates written `YYYY-MM-DD`
+    fn from_str(s: &str) -> Result<Date, ()> {
+        let parts: Vec<i64> = s
+            .splitn(3, '-')
+            .map(|part| part.parse().map_err(|_| ()))
+            .collect::<Result<_, ()>>()?;
+        let (year, month, day) = match parts[..] {
+            [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+                (year, month, day)
+            }
+            _ => return Err(()),
+        };
+        let date = Date::from_civil(year, month, day);
+        // Days past the end of the month, as 2023-02-30, come back as another date
+        match date.civil() == (year, month, day) {
+            true => Ok(date),
+            false => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.civil();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Puzzle of the day: a start and a stop word linked by a ladder of 1-letter hops, rated
+/// as close to `target` as found. The same date, word list and release always give the
+/// same puzzle. `None` when no two words of the list are linked.
+pub fn puzzle<'a, D: Dictionary + ?Sized>(
+    date: Date,
+    words: &[&'a str],
+    indexed: &'a D,
+    target: u8,
+) -> Option<(&'a str, &'a str, Difficulty)> {
+    if words.is_empty() {
+        return None;
+    }
+    let seed = cache::dictionary_hash(words) ^ (date.0 as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let mut random = Random::new(seed);
+    let mut best: Option<(&str, &str, Difficulty)> = None;
+    for _ in 0..ATTEMPTS {
+        let start = words[random.below(words.len())];
+        // Ratings mostly follow the hops, words about that far are the likeliest to fit
+        let reached = distance::find_ladder_hops(start, indexed, usize::from(target) + 2);
+        let likely: Vec<&str> = reached
+            .iter()
+            .filter(|&&(_, hops)| hops + 2 >= target as usize && hops <= target as usize)
+            .map(|&(word, _)| word)
+            .collect();
+        let candidates = match likely.is_empty() {
+            true => reached.iter().map(|&(word, _)| word).collect(),
+            false => likely,
+        };
+        if candidates.is_empty() {
+            continue;
+        }
+        let stop = candidates[random.below(candidates.len())];
+        let difficulty = distance::rate_difficulty(start, stop, indexed).unwrap();
+        let miss =
+            |difficulty: &Difficulty| (i32::from(difficulty.rating) - i32::from(target)).abs();
+        if best
+            .as_ref()
+            .is_none_or(|(_, _, best)| miss(&difficulty) < miss(best))
+        {
+            best = Some((start, stop, difficulty));
+        }
+        if best.as_ref().is_some_and(|(_, _, best)| miss(best) == 0) {
+            break;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_are_parsed_and_printed() {
+        let date: Date = "2024-06-01".parse().unwrap();
+        assert_eq!(date, Date(19875));
+        assert_eq!(date.to_string(), "2024-06-01");
+        assert_eq!("1970-01-01".parse(), Ok(Date(0)));
+        assert_eq!(Date(-1).to_string(), "1969-12-31");
+        assert_eq!(
+            "2024-02-29".parse::<Date>().unwrap().to_string(),
+            "2024-02-29"
+        );
+        for wrong in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-06",
+            "June 1st",
+            "2024-06-01-02",
+        ]
+        .iter()
+        {
+            assert_eq!(wrong.parse::<Date>(), Err(()), "{}", wrong);
+        }
+    }
+
+    #[test]
+    fn puzzles_are_repeatable() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "bold", "bord", "xyz",
+        ];
+        let date = "2024-06-01".parse().unwrap();
+        let (start, stop, difficulty) = puzzle(date, &words, &words[..], 5).unwrap();
+        assert_ne!(start, stop);
+        assert_eq!(
+            distance::rate_difficulty(start, stop, &words[..]),
+            Some(difficulty)
+        );
+        for _ in 0..3 {
+            let again = puzzle(date, &words, &words[..], 5).unwrap();
+            assert_eq!((again.0, again.1), (start, stop));
+        }
+        assert_eq!(
+            puzzle(date, &["cold", "xyz"], &["cold", "xyz"][..], 5),
+            None
+        );
+    }
+}
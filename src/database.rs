@@ -0,0 +1,77 @@
+use std::io;
+use std::path::Path;
+
+/// Entries of the rows `query` selects from the SQLite database at `path`: the first
+/// column, with the weight of the second if the query selects one. Rows whose entry is
+/// NULL are skipped, as are blank lines of text word lists.
+#[cfg(feature = "sqlite")]
+pub fn entries(path: &Path, query: &str) -> io::Result<Vec<(String, usize)>> {
+    use rusqlite::{Connection, OpenFlags};
+    let invalid = |error: rusqlite::Error| io::Error::new(io::ErrorKind::InvalidData, error);
+    // Read only, a query cannot change the lexicon it reads
+    let connection =
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(invalid)?;
+    let mut statement = connection.prepare(query).map_err(invalid)?;
+    let weighted = statement.column_count() > 1;
+    let mut rows = statement.query([]).map_err(invalid)?;
+    let mut entries = Vec::new();
+    while let Some(row) = rows.next().map_err(invalid)? {
+        let entry: Option<String> = row.get(0).map_err(invalid)?;
+        let weight: Option<i64> = match weighted {
+            true => row.get(1).map_err(invalid)?,
+            false => None,
+        };
+        if let Some(entry) = entry {
+            let weight = weight.map_or(0, |weight| weight.max(0) as usize);
+            entries.push((entry, weight));
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn entries(_: &Path, _: &str) -> io::Result<Vec<(String, usize)>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build cannot read SQLite databases, build it with --features sqlite",
+    ))
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+
+    #[test]
+    fn entries_come_from_the_query() {
+        let path = std::env::temp_dir().join(format!("typos-database-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE words (word TEXT, lang TEXT, frequency INTEGER);
+                 INSERT INTO words VALUES ('cold', 'en', 3), ('froid', 'fr', 1),
+                     ('warm', 'en', NULL), (NULL, 'en', 2);",
+            )
+            .unwrap();
+        drop(connection);
+
+        let english = entries(&path, "SELECT word FROM words WHERE lang = 'en'").unwrap();
+        assert_eq!(
+            english,
+            vec![("cold".to_string(), 0), ("warm".to_string(), 0)]
+        );
+        let weighted = entries(&path, "SELECT word, frequency FROM words ORDER BY word").unwrap();
+        assert_eq!(
+            weighted,
+            vec![
+                ("cold".to_string(), 3),
+                ("froid".to_string(), 1),
+                ("warm".to_string(), 0)
+            ]
+        );
+        assert!(entries(&path, "DELETE FROM words").is_err());
+        assert!(entries(&path, "SELECT word FROM lexicon").is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
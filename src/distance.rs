@@ -1,96 +1,1250 @@
-use crate::distance::path::PathMultiCost;
-use num_traits::Zero;
+use crate::distance::bounded::bytes_per_node;
+use crate::distance::cost::{CostModel, Levenshtein};
+use crate::distance::path::{Objective, PathMultiCost, MAX_DIMENSION};
+use num_traits::{Bounded, Zero};
 use pathfinding::directed::astar;
 use pathfinding::directed::dijkstra;
 use pathfinding::directed::fringe;
 use pathfinding::directed::idastar;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::hash::Hash;
 use std::str::FromStr;
+use std::sync::Arc;
 
-mod path;
-mod word;
+mod alternatives;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod bounded;
+mod checkpoint;
+mod consistency;
+mod control;
+pub mod cost;
+mod dictionary;
+mod difficulty;
+mod eccentricity;
+mod estimate;
+pub mod graph;
+mod heuristic;
+mod hint;
+mod messages;
+mod names;
+mod observer;
+mod pareto;
+pub mod path;
+pub mod prebuilt;
+mod pruning;
+mod registry;
+mod sampling;
+mod seeded;
+mod selection;
+mod shard;
+mod stem;
+mod suggest;
+mod symspell;
+mod transposition;
+mod trie;
+pub mod word;
 
-pub fn find_shortest_path<'a>(
+pub use crate::distance::alternatives::{find_disjoint_paths, Disjoint};
+#[cfg(feature = "async")]
+pub use crate::distance::asynchronous::{find_shortest_path_async, OwnedShortestPath};
+pub use crate::distance::checkpoint::{find_shortest_path_checkpointed, SearchState};
+pub use crate::distance::consistency::{
+    HeuristicChecker, HeuristicReport, HeuristicViolation, ADMISSIBILITY_SAMPLES, HEURISTIC_SAMPLES,
+};
+pub use crate::distance::control::{CancellationToken, Interruption, SearchControl};
+pub use crate::distance::dictionary::Dictionary;
+pub use crate::distance::difficulty::{find_ladder_hops, rate_difficulty, Difficulty};
+pub use crate::distance::eccentricity::{
+    find_costs_from, find_farthest_words, find_hops_from, find_partition, find_reachable,
+    Assignment, Farthest, Partition, WordCost,
+};
+pub use crate::distance::estimate::{
+    estimate_search, suggest_options, Estimate, COMPARISONS_PER_SECOND, INTRACTABLE,
+};
+use crate::distance::heuristic::Bound;
+pub use crate::distance::heuristic::{Heuristic, LANDMARKS};
+pub use crate::distance::hint::{find_hint, mask, Hint};
+pub use crate::distance::messages::Locale;
+pub use crate::distance::names::UnknownName;
+pub use crate::distance::observer::SearchObserver;
+pub use crate::distance::pareto::{find_pareto_paths, ParetoPath, Tradeoff};
+pub use crate::distance::pruning::{Pruning, PruningExplainer};
+pub use crate::distance::registry::{
+    register_algorithm, registered_algorithms, CostId, NodeId, SearchAlgorithm, SearchSpace,
+};
+pub use crate::distance::sampling::{sampling_confidence, Sampling, DEFAULT_SAMPLE_RATE};
+pub use crate::distance::seeded::search_with_seed;
+pub use crate::distance::selection::{select_algorithm, Selection, SMALL_DICTIONARY};
+pub use crate::distance::shard::ShardedWords;
+pub use crate::distance::stem::{fold_stems, StemLanguage};
+pub use crate::distance::suggest::suggest;
+pub use crate::distance::symspell::{SymSpellWords, DELETION_DEPTH};
+pub use crate::distance::transposition::DEFAULT_TRANSPOSITION_CAPACITY;
+pub use crate::distance::trie::TrieWords;
+
+/// Words visited from start to stop and the cost of the whole path
+pub type ShortestPath<'a> = (Vec<&'a str>, path::PathMultiCost<word::EditDistance>);
+
+/// A single hop of a path and what it adds to the cost of the whole path
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Hop {
+    /// Letters changed, as the cost model counts them
+    pub distance: usize,
+    pub cost: path::PathMultiCost<word::EditDistance>,
+}
+
+/// Owned words of a shortest path, its cost and the cost of each hop, ready to leave
+/// the search
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PathResult {
+    pub words: Vec<String>,
+    pub cost: path::PathMultiCost<word::EditDistance>,
+    /// `hops[i]` goes from `words[i]` to `words[i + 1]`
+    pub hops: Vec<Hop>,
+}
+
+impl PathResult {
+    /// Measure every hop of `path` with the cost model it was searched with
+    pub fn new((words, cost): ShortestPath, cost_model: &dyn CostModel) -> PathResult {
+        let hops = words
+            .windows(2)
+            .map(|hop| {
+                let distance = cost_model.distance(hop[0], hop[1]);
+                Hop {
+                    distance,
+                    cost: word::hop_cost(distance),
+                }
+            })
+            .collect();
+        PathResult {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            cost,
+            hops,
+        }
+    }
+}
+
+/// Counters collected while searching
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SearchStats {
+    /// Number of words whose successors were generated
+    pub expanded: usize,
+    /// Whether the cost returned reached the highest value its counters hold, and so
+    /// may be lower than the real one
+    pub saturated: bool,
+    /// Whether the search went from the end word back to the start word
+    pub backward: bool,
+    /// Expansions whose successors went into a buffer recycled from an earlier one
+    /// instead of a new allocation
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub recycled: usize,
+    /// Whether the algorithm asked for outgrew `SearchOptions::with_max_memory` and the
+    /// search was run again with bounded-astar, whose path may not be the shortest
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memory_fallback: bool,
+}
+
+/// End of the path a search starts from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Direction {
+    /// From the start word to the end word
+    #[default]
+    Forward,
+    /// From the end word back to the start word, through the same hops
+    Backward,
+    /// From whichever word has the fewest neighbours
+    Auto,
+}
+
+impl Direction {
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Forward => "forward",
+            Direction::Backward => "backward",
+            Direction::Auto => "auto",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Direction, ()> {
+        match s {
+            "forward" => Ok(Direction::Forward),
+            "backward" => Ok(Direction::Backward),
+            "auto" => Ok(Direction::Auto),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What a search does once a path costs more than its counters hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Saturation {
+    /// Fail with `Interruption::Saturated`
+    Error,
+    /// Keep the highest value and set `SearchStats::saturated`
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "saturate"))]
+    Flag,
+    /// Count with 64 bits while searching, only the returned cost is narrowed
+    Widen,
+}
+
+impl Saturation {
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Saturation::Error => "error",
+            Saturation::Flag => "saturate",
+            Saturation::Widen => "widen",
+        }
+    }
+}
+
+impl fmt::Display for Saturation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Saturation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Saturation, ()> {
+        match s {
+            "error" => Ok(Saturation::Error),
+            "saturate" => Ok(Saturation::Flag),
+            "widen" => Ok(Saturation::Widen),
+            _ => Err(()),
+        }
+    }
+}
+
+pub fn find_shortest_path<'a, D: Dictionary + ?Sized>(
     start: &'a str,
     stop: &str,
-    words: &'a [&str],
+    words: &'a D,
     algorithm: &PathFindingAlgorithm,
-) -> Option<(Vec<&'a str>, path::PathMultiCost<word::EditDistance>)> {
-    let get_successors = |&current_word: &&'a str| {
+) -> Option<ShortestPath<'a>> {
+    find_shortest_path_with_stats(start, stop, words, algorithm).0
+}
+
+pub fn find_shortest_path_with_stats<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &str,
+    words: &'a D,
+    algorithm: &PathFindingAlgorithm,
+) -> (Option<ShortestPath<'a>>, SearchStats) {
+    let (result, stats) =
+        find_shortest_path_with_control(start, stop, words, algorithm, &SearchControl::default());
+    (result.unwrap_or(None), stats)
+}
+
+/// Search that gives up once `control` reports a cancellation or an expired deadline
+pub fn find_shortest_path_with_control<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &str,
+    words: &'a D,
+    algorithm: &PathFindingAlgorithm,
+    control: &SearchControl,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let options = SearchOptions::new(*algorithm).with_control(control.clone());
+    find_shortest_path_with_options(start, stop, words, &options)
+}
+
+/// Called with every word a search expands and the cost of the cheapest path found to it
+/// from the word the search starts from
+pub type ExpandHook = Arc<dyn Fn(&str, PathMultiCost<word::EditDistance>) + Send + Sync>;
+
+/// Everything deciding how a search runs
+#[derive(Clone)]
+pub struct SearchOptions {
+    algorithm: PathFindingAlgorithm,
+    cost_model: Arc<dyn CostModel>,
+    heuristic: Heuristic,
+    control: SearchControl,
+    observer: Option<Arc<dyn SearchObserver>>,
+    on_expand: Option<ExpandHook>,
+    weights: Option<Arc<HashMap<String, usize>>>,
+    languages: Option<Arc<HashMap<String, Vec<String>>>>,
+    frequencies: Option<Arc<HashMap<String, u32>>>,
+    limits: Limits,
+    max_hops: Option<usize>,
+    max_hop_distance: Option<usize>,
+    monotone: bool,
+    sampling: Option<Sampling>,
+    objective: Objective,
+    saturation: Saturation,
+    direction: Direction,
+}
+
+/// Memory the algorithms keeping extra state may use
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    /// Words `idastar-tt` remembers the best cost of
+    transposition_capacity: usize,
+    /// Bytes `bounded-astar` keeps its nodes in, and the ones keeping every node they
+    /// reach may grow to before falling back to it
+    max_memory: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            transposition_capacity: DEFAULT_TRANSPOSITION_CAPACITY,
+            max_memory: usize::MAX,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Levenshtein distance without any limit
+    pub fn new(algorithm: PathFindingAlgorithm) -> SearchOptions {
+        SearchOptions {
+            algorithm,
+            cost_model: Arc::new(Levenshtein),
+            heuristic: Heuristic::default(),
+            control: SearchControl::default(),
+            observer: None,
+            on_expand: None,
+            weights: None,
+            languages: None,
+            frequencies: None,
+            limits: Limits::default(),
+            max_hops: None,
+            max_hop_distance: None,
+            monotone: false,
+            sampling: None,
+            objective: Objective::default(),
+            saturation: Saturation::default(),
+            direction: Direction::default(),
+        }
+    }
+
+    pub fn with_algorithm(mut self, algorithm: PathFindingAlgorithm) -> SearchOptions {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn with_cost_model(mut self, cost_model: Arc<dyn CostModel>) -> SearchOptions {
+        self.cost_model = cost_model;
+        self
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> SearchOptions {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_control(mut self, control: SearchControl) -> SearchOptions {
+        self.control = control;
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn SearchObserver>) -> SearchOptions {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Call `on_expand` with every word expanded and its cost from the start, e.g. to
+    /// animate a search or forward it to a channel. Costs are only tracked when set.
+    pub fn with_on_expand(
+        mut self,
+        on_expand: impl Fn(&str, PathMultiCost<word::EditDistance>) + Send + Sync + 'static,
+    ) -> SearchOptions {
+        self.on_expand = Some(Arc::new(on_expand));
+        self
+    }
+
+    /// Penalize entering the words listed by their weight, a penalty only telling apart
+    /// paths whose hops are the same
+    pub fn with_word_weights(mut self, weights: Arc<HashMap<String, usize>>) -> SearchOptions {
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Only take hops between words sharing one of the languages `languages` tags them
+    /// with, words it does not list belonging to every language
+    pub fn with_word_languages(
+        mut self,
+        languages: Arc<HashMap<String, Vec<String>>>,
+    ) -> SearchOptions {
+        self.languages = Some(languages);
+        self
+    }
+
+    fn share_language(&self, w1: &str, w2: &str) -> bool {
+        let languages = match &self.languages {
+            Some(languages) => languages,
+            None => return true,
+        };
+        match (languages.get(w1), languages.get(w2)) {
+            (Some(first), Some(second)) => first.iter().any(|language| second.contains(language)),
+            _ => true,
+        }
+    }
+
+    /// Prefer entering words used more often according to `frequencies`, as measured by
+    /// `word::rarity`, when hops and penalties leave paths tied
+    pub fn with_frequencies(mut self, frequencies: Arc<HashMap<String, u32>>) -> SearchOptions {
+        self.frequencies = Some(frequencies);
+        self
+    }
+
+    /// Number of words `idastar-tt` remembers the best cost of
+    pub fn with_transposition_capacity(mut self, capacity: usize) -> SearchOptions {
+        self.limits.transposition_capacity = capacity;
+        self
+    }
+
+    /// Bytes `bounded-astar` may keep its nodes in, the dictionary itself aside. Searches
+    /// with astar, fringe or dijkstra reaching more nodes than would fit start over with
+    /// bounded-astar, and tell so with `SearchStats::memory_fallback`.
+    pub fn with_max_memory(mut self, bytes: usize) -> SearchOptions {
+        self.limits.max_memory = bytes;
+        self
+    }
+
+    /// Longest path searched, in hops, no path is found beyond
+    pub fn with_max_hops(mut self, hops: usize) -> SearchOptions {
+        self.max_hops = Some(hops);
+        self
+    }
+
+    pub fn max_hops(&self) -> Option<usize> {
+        self.max_hops
+    }
+
+    /// Biggest single hop allowed, in letters changed as the cost model counts them
+    pub fn with_max_hop_distance(mut self, distance: usize) -> SearchOptions {
+        self.max_hop_distance = Some(distance);
+        self
+    }
+
+    pub fn max_hop_distance(&self) -> Option<usize> {
+        self.max_hop_distance
+    }
+
+    /// Only take hops getting strictly closer to the stop word, as the cost model measures
+    /// it; no path is found when every one has to go back at some point
+    pub fn with_monotone(mut self, monotone: bool) -> SearchOptions {
+        self.monotone = monotone;
+        self
+    }
+
+    /// Only go through the hops `sampling` keeps, hops leaving the start word or entering
+    /// the end word aside: much faster on huge word lists, but the path found may not be
+    /// the shortest, or no path found at all
+    pub fn with_sampling(mut self, sampling: Sampling) -> SearchOptions {
+        self.sampling = Some(sampling);
+        self
+    }
+
+    pub fn sampling(&self) -> Option<Sampling> {
+        self.sampling
+    }
+
+    /// What the shortest path minimizes first, the biggest hop unless told otherwise
+    pub fn with_objective(mut self, objective: Objective) -> SearchOptions {
+        self.objective = objective;
+        self
+    }
+
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    // Cost of a hop of `distance` as the objective compares it
+    pub(crate) fn hop_cost<U: word::HopCount>(&self, distance: usize) -> PathMultiCost<U> {
+        word::hop_cost(distance).with_objective(self.objective)
+    }
+
+    // Lower bound of the cost left from a lower bound of the 1-letter hops left, a hop
+    // possibly changing up to `max_hop_distance` letters when the number of hops comes first
+    pub(crate) fn cost_lower_bound<U: word::HopCount>(&self, letters: usize) -> PathMultiCost<U> {
+        let hops = match (self.objective, self.max_hop_distance) {
+            (Objective::FewestHops, Some(k)) if k > 0 => letters.div_ceil(k),
+            (Objective::FewestHops, _) => letters.min(1),
+            _ => letters,
+        };
+        word::hops_lower_bound(hops).with_objective(self.objective)
+    }
+
+    pub fn with_saturation(mut self, saturation: Saturation) -> SearchOptions {
+        self.saturation = saturation;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> SearchOptions {
+        self.direction = direction;
+        self
+    }
+
+    pub fn algorithm(&self) -> PathFindingAlgorithm {
+        self.algorithm
+    }
+
+    // Whether the search goes from `stop` back to `start`, `Auto` going from the word with
+    // the fewest neighbours within a hop
+    pub(crate) fn runs_backward<D: Dictionary + ?Sized>(
+        &self,
+        start: &str,
+        stop: &str,
+        words: &D,
+    ) -> bool {
+        match self.direction {
+            Direction::Forward => false,
+            Direction::Backward => true,
+            Direction::Auto => {
+                let radius = self.max_hop_distance.unwrap_or(1);
+                let degree = |word| words.neighbors_within(word, radius).len();
+                degree(stop) < degree(start)
+            }
+        }
+    }
+
+    /// Check that searches of these options over `words` only rely on hops costing the
+    /// same both ways with a cost model that does: algorithms may need it, and searches
+    /// going backward bound the cost left with it when the model claims it, which is
+    /// verified on a sample of hops.
+    pub fn check_symmetry<D: Dictionary + ?Sized>(
+        &self,
+        words: &D,
+    ) -> Result<(), cost::AsymmetricCosts> {
+        let cost_model = self.cost_model.as_ref();
+        let backward = self.direction != Direction::Forward && cost_model.symmetric();
+        match self.algorithm.needs_symmetric_costs() || backward {
+            true => cost::probe_symmetry(cost_model, words),
+            false => Ok(()),
+        }
+    }
+
+    // Lower bound of the hops left to the word the search goes to
+    pub(crate) fn prepare_bound<'a, D: Dictionary + ?Sized>(
+        &'a self,
+        words: &'a D,
+        start: &'a str,
+        stop: &'a str,
+        backward: bool,
+    ) -> Bound<'a> {
+        let cost_model = self.cost_model.as_ref();
+        let bound: Bound<'a> = match backward {
+            false => self.heuristic.prepare(cost_model, words, stop),
+            true if cost_model.symmetric() => self.heuristic.prepare(cost_model, words, start),
+            // Hops cost something else the other way round, nothing bounds them
+            true => Box::new(|_| 0),
+        };
+        let (origin, target) = match backward {
+            false => (start, stop),
+            true => (stop, start),
+        };
+        // Near the end word, letters apart understate the hops left: a word 2 letters
+        // away with no neighbour in common with it is 3 hops of 1 letter away
+        let depths = match self.max_hop_distance {
+            Some(k) if k > 0 && cost_model.bounded_by_edits() => {
+                heuristic::goal_depths(words, target, origin, k)
+            }
+            _ => None,
+        };
+        let (depths, k) = match (depths, self.max_hop_distance) {
+            (Some(depths), Some(k)) => (depths, k),
+            _ => return bound,
+        };
+        Box::new(move |word| {
+            let hops = depths.get(word).copied().unwrap_or(3);
+            bound(word).max(self.letters_for_hops(hops, k))
+        })
+    }
+
+    // Fewest letters `cost_lower_bound` turns into `hops` hops of up to `k` letters
+    fn letters_for_hops(&self, hops: usize, k: usize) -> usize {
+        match self.objective {
+            Objective::FewestHops if hops > 0 => (hops - 1) * k + 1,
+            _ => hops,
+        }
+    }
+}
+
+pub fn find_shortest_path_with_options<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &str,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let backward = options.runs_backward(start, stop, words);
+    let bound = options.prepare_bound(words, start, stop, backward);
+    search(
+        start,
+        stop,
+        words,
+        options,
+        backward,
+        &bound,
+        &|_, _| true,
+        None,
+    )
+}
+
+/// Same as `find_shortest_path_with_options`, ending on the first word `goal` accepts
+/// rather than on a given one, `start` itself if it does. Nothing tells how far these
+/// words are, so the search goes without a heuristic: the algorithm of `options` is
+/// ignored, this is always Dijkstra, forward and without `monotone`.
+pub fn find_shortest_path_to_match<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    goal: &dyn Fn(&str) -> bool,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let options = options
+        .clone()
+        .with_algorithm(PathFindingAlgorithm::Dijkstra)
+        .with_direction(Direction::Forward)
+        .with_monotone(false);
+    search(
+        start,
+        "",
+        words,
+        &options,
+        false,
+        &|_| 0,
+        &|_, _| true,
+        Some(goal),
+    )
+}
+
+/// Same as `find_shortest_path_with_options`, along with the cost of every hop
+pub fn find_shortest_path_result<D: Dictionary + ?Sized>(
+    start: &str,
+    stop: &str,
+    words: &D,
+    options: &SearchOptions,
+) -> (Result<Option<PathResult>, Interruption>, SearchStats) {
+    let (result, stats) = find_shortest_path_with_options(start, stop, words, options);
+    let cost_model = options.cost_model.as_ref();
+    let result = result.map(|found| found.map(|path| PathResult::new(path, cost_model)));
+    (result, stats)
+}
+
+// Search with a heuristic already prepared towards the word it goes to, only through the
+// hops `allowed` accepts. `backward` searches go from `stop` to `start` through the same
+// hops, the path returned always goes from `start` to `stop`. Forward searches given a
+// `goal` end on the first word it accepts instead of `stop`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn search<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &str,
+    words: &'a D,
+    options: &SearchOptions,
+    backward: bool,
+    bound: &dyn Fn(&str) -> usize,
+    allowed: &dyn Fn(&str, &str) -> bool,
+    goal: Option<&dyn Fn(&str) -> bool>,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    debug_assert!(
+        !backward || goal.is_none(),
+        "Goals only end forward searches"
+    );
+    let from = match backward {
+        false => Some(start),
+        true if start == stop => Some(start),
+        // Searches only end on words of the dictionary, `stop` must be one of them
+        true => words.iter().find(|&word| word == stop),
+    };
+    let ends = match from {
+        Some(from) if backward => Ends {
+            from,
+            to: start,
+            backward,
+            goal: None,
+        },
+        Some(from) => Ends {
+            from,
+            to: stop,
+            backward,
+            goal,
+        },
+        None => return (Ok(None), SearchStats::default()),
+    };
+    // Distances to `stop` are computed once per word, they are compared on every hop
+    let closeness: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+    let to_stop = |word: &str| {
+        if let Some(&distance) = closeness.borrow().get(word) {
+            return distance;
+        }
+        let distance = options.cost_model.distance(word, stop);
+        closeness.borrow_mut().insert(word.to_string(), distance);
+        distance
+    };
+    let same_language = |w1: &str, w2: &str| allowed(w1, w2) && options.share_language(w1, w2);
+    let allowed: &dyn Fn(&str, &str) -> bool = match options.languages {
+        Some(_) => &same_language,
+        None => allowed,
+    };
+    // Hops are always kept at both ends, the path found would otherwise rarely reach them
+    let sampled = |w1: &str, w2: &str| {
+        allowed(w1, w2)
+            && (w1 == start
+                || w2 == stop
+                || goal.is_some_and(|goal| goal(w2))
+                || options.sampling.is_none_or(|s| s.keeps(w1, w2)))
+    };
+    let allowed: &dyn Fn(&str, &str) -> bool = match options.sampling {
+        Some(_) => &sampled,
+        None => allowed,
+    };
+    let monotone = |w1: &str, w2: &str| allowed(w1, w2) && to_stop(w2) < to_stop(w1);
+    let allowed: &dyn Fn(&str, &str) -> bool = match options.monotone {
+        true => &monotone,
+        false => allowed,
+    };
+    let run = |options: &SearchOptions| match options.saturation {
+        Saturation::Widen => {
+            let (result, stats) = search_counting::<u64, D>(ends, words, options, bound, allowed);
+            let narrowed = result.map(|found| {
+                found.map(|(path, cost)| (path, cost.saturating_cast::<word::EditDistance>()))
+            });
+            (narrowed, stats)
+        }
+        _ => search_counting::<word::EditDistance, D>(ends, words, options, bound, allowed),
+    };
+    let (result, mut stats) = match run(options) {
+        // Gave up before running out of memory, what it expanded still counts
+        (Ok(_), given_up) if given_up.memory_fallback => {
+            let bounded = options
+                .clone()
+                .with_algorithm(PathFindingAlgorithm::BoundedAstar);
+            let (result, stats) = run(&bounded);
+            let stats = SearchStats {
+                expanded: given_up.expanded + stats.expanded,
+                recycled: given_up.recycled + stats.recycled,
+                memory_fallback: true,
+                ..stats
+            };
+            (result, stats)
+        }
+        ran => ran,
+    };
+    let result = result.map(|found| {
+        found.map(|(mut path, cost)| {
+            if backward {
+                path.reverse();
+            }
+            (path, cost)
+        })
+    });
+    stats.backward = backward;
+    stats.saturated = matches!(&result, Ok(Some((_, cost))) if cost.is_saturated());
+    match (options.saturation, stats.saturated) {
+        (Saturation::Error, true) => (Err(Interruption::Saturated), stats),
+        _ => (result, stats),
+    }
+}
+
+// Path found while counting hops with `U`
+type CountedPath<'a, U> = (Vec<&'a str>, PathMultiCost<U>);
+
+// Words a search goes from and to, `backward` when it follows the hops the other way round.
+// A `goal` ends the search on any word it accepts rather than on `to`.
+#[derive(Clone, Copy)]
+struct Ends<'a, 's> {
+    from: &'a str,
+    to: &'s str,
+    backward: bool,
+    goal: Option<&'s dyn Fn(&str) -> bool>,
+}
+
+// Successors handed to an algorithm, whose vector goes back to `pool` once it has been
+// gone through so that expanding a word does not allocate after the first few
+struct Recycled<'p, T> {
+    items: Vec<T>,
+    next: usize,
+    pool: &'p RefCell<Vec<Vec<T>>>,
+}
+
+impl<T: Copy> Iterator for Recycled<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let item = self.items.get(self.next).copied();
+        self.next += 1;
+        item
+    }
+}
+
+impl<T> Drop for Recycled<'_, T> {
+    fn drop(&mut self) {
+        let mut items = std::mem::take(&mut self.items);
+        items.clear();
+        self.pool.borrow_mut().push(items);
+    }
+}
+
+// Search counting hops with `U`, wider integers only overflow on longer paths
+fn search_counting<'a, U, D>(
+    ends: Ends<'a, '_>,
+    words: &'a D,
+    options: &SearchOptions,
+    bound: &dyn Fn(&str) -> usize,
+    allowed: &dyn Fn(&str, &str) -> bool,
+) -> (
+    Result<Option<CountedPath<'a, U>>, Interruption>,
+    SearchStats,
+)
+where
+    U: word::HopCount + Ord,
+    word::EditDistance: TryFrom<U>,
+    D: Dictionary + ?Sized,
+{
+    let control = &options.control;
+    let cost_model = options.cost_model.as_ref();
+    let heuristic = |&(word, _): &(&str, usize)| options.cost_lower_bound(bound(word));
+    let expanded = Cell::new(0);
+    let recycled = Cell::new(0);
+    let interruption = Cell::new(None);
+    // Nodes reached, only counted when they may not fit in memory. Beyond the budget the
+    // search gives up the way it does once interrupted, to start over with bounded-astar.
+    let guarded = options.limits.max_memory != usize::MAX && options.algorithm.keeps_every_node();
+    let node_budget =
+        options.limits.max_memory / bytes_per_node::<(&str, usize), PathMultiCost<U>>();
+    let mut discovered = HashSet::new();
+    let exceeded = Cell::new(false);
+    // Cheapest cost each word was reached at, only filled for `on_expand`
+    let reached: RefCell<HashMap<&'a str, PathMultiCost<U>>> = RefCell::new(HashMap::new());
+    let Ends {
+        from,
+        to,
+        backward,
+        goal,
+    } = ends;
+    // Hops keep their direction whichever end the search starts from
+    let hop = |current: &'a str, successor: &'a str| match backward {
+        false => (current, successor),
+        true => (successor, current),
+    };
+    // Nodes carry their number of hops from `from` only when it is limited, otherwise
+    // it stays 0 so that every word is a single node
+    let max_hops = options.max_hops;
+    let max_hop_distance = options.max_hop_distance;
+    // Every word once, the candidates of expansions no index narrows down. Only lists
+    // repeating words lead to the same successor twice, checked once up front.
+    let mut repeats = false;
+    let all: Vec<&'a str> = {
+        let mut seen = HashSet::new();
         words
             .iter()
-            .map(move |&successor| (successor, word::path_cost(current_word, &successor)))
+            .filter(|&word| {
+                let first = seen.insert(word);
+                repeats |= !first;
+                first
+            })
+            .collect()
     };
-
-    let heuristic = |word: &&str| word::edit_distance(word, stop);
-    let stop_condition = |word: &&str| *word == stop;
-    debug_assert!(stop_condition(&stop), "Stopping condition does not work");
-    match algorithm {
-        PathFindingAlgorithm::Astar => {
-            astar::astar(&start, get_successors, heuristic, stop_condition)
+    // Buffers reused by every expansion
+    let pool = RefCell::new(Vec::new());
+    let mut neighbors = Vec::new();
+    let mut seen = HashSet::new();
+    let mut reported = Vec::new();
+    let saturating = |value: usize| U::try_from(value).unwrap_or_else(|_| U::max_value());
+    let penalty = |word: &str| {
+        let weighted = match options.weights.as_ref().and_then(|w| w.get(word)) {
+            Some(&weight) => PathMultiCost::penalty_only(saturating(weight)),
+            None => PathMultiCost::zero(),
+        };
+        match &options.frequencies {
+            Some(frequencies) => {
+                weighted + PathMultiCost::rarity_only(saturating(word::rarity(word, frequencies)))
+            }
+            None => weighted,
+        }
+    };
+    let get_successors = |&(current_word, hops): &(&'a str, usize)| {
+        expanded.set(expanded.get() + 1);
+        if interruption.get().is_none() {
+            interruption.set(control.check(expanded.get()));
+        }
+        // Once interrupted, every node becomes a dead end so the algorithm drains quickly
+        let candidates: &[&'a str] = match (interruption.get(), max_hops, max_hop_distance) {
+            (Some(_), _, _) => &[],
+            _ if exceeded.get() => &[],
+            (None, Some(max_hops), _) if hops >= max_hops => &[],
+            // Indexed dictionaries find these without going through every word
+            (None, _, Some(k)) if cost_model.bounded_by_edits() => {
+                neighbors.clear();
+                words.extend_neighbors(current_word, k, &mut neighbors);
+                if repeats {
+                    seen.clear();
+                    neighbors.retain(|&neighbor| seen.insert(neighbor));
+                }
+                &neighbors
+            }
+            (None, _, _) => &all,
+        };
+        let mut successors: Vec<(&'a str, PathMultiCost<U>)> = match pool.borrow_mut().pop() {
+            Some(buffer) => {
+                recycled.set(recycled.get() + 1);
+                buffer
+            }
+            None => Vec::new(),
+        };
+        for &successor in candidates {
+            // A word leading to itself at no cost only makes the frontier bigger
+            if successor == current_word {
+                continue;
+            }
+            let (w1, w2) = hop(current_word, successor);
+            if !allowed(w1, w2) {
+                continue;
+            }
+            let distance = cost_model.distance(w1, w2);
+            if max_hop_distance.is_some_and(|k| distance > k) {
+                continue;
+            }
+            let cost = match (&options.weights, &options.frequencies) {
+                (None, None) => options.hop_cost(distance),
+                _ => options.hop_cost(distance) + penalty(w2),
+            };
+            successors.push((successor, cost));
+        }
+        if let Some(observer) = &options.observer {
+            reported.clear();
+            reported.extend(
+                successors
+                    .iter()
+                    .map(|&(successor, cost)| (successor, cost.saturating_cast())),
+            );
+            let estimate = heuristic(&(current_word, hops)).saturating_cast();
+            observer.expanded(current_word, estimate, &reported);
         }
-        PathFindingAlgorithm::Idastar => {
-            idastar::idastar(&start, get_successors, heuristic, stop_condition)
+        if let Some(on_expand) = &options.on_expand {
+            let mut reached = reached.borrow_mut();
+            let cost = reached
+                .get(current_word)
+                .copied()
+                .unwrap_or_else(PathMultiCost::zero);
+            on_expand(current_word, cost.saturating_cast());
+            for &(successor, hop) in &successors {
+                let through = cost + hop;
+                let known = reached.entry(successor).or_insert(through);
+                if through < *known {
+                    *known = through;
+                }
+            }
         }
-        PathFindingAlgorithm::Fringe => {
-            fringe::fringe(&start, get_successors, heuristic, stop_condition)
+        let depth = hops + usize::from(max_hops.is_some());
+        if guarded {
+            discovered.extend(successors.iter().map(|&(successor, _)| (successor, depth)));
+            exceeded.set(discovered.len() > node_budget);
         }
-        PathFindingAlgorithm::Dijkstra => {
-            dijkstra::dijkstra(&start, get_successors, stop_condition)
+        Recycled {
+            items: successors,
+            next: 0,
+            pool: &pool,
+        }
+        .map(move |(successor, cost)| ((successor, depth), cost))
+    };
+
+    let stop_condition = |&(word, _): &(&str, usize)| match goal {
+        Some(goal) => goal(word),
+        None => word == to,
+    };
+    debug_assert!(
+        goal.is_some() || stop_condition(&(to, 0)),
+        "Stopping condition does not work"
+    );
+    let result = run_algorithm(
+        options.algorithm,
+        &(from, 0),
+        get_successors,
+        heuristic,
+        stop_condition,
+        options.limits,
+    )
+    .map(|(path, cost)| (path.into_iter().map(|(word, _)| word).collect(), cost));
+    let stats = SearchStats {
+        expanded: expanded.get(),
+        saturated: false,
+        backward: false,
+        recycled: recycled.get(),
+        memory_fallback: exceeded.get(),
+    };
+    match interruption.get() {
+        Some(reason) => (Err(reason), stats),
+        None => (Ok(result), stats),
+    }
+}
+
+/// Run `algorithm` over any graph given by its successors, Dijkstra ignores `heuristic`
+pub(crate) fn run_algorithm<N, C, FN, IN, FH, FS>(
+    algorithm: PathFindingAlgorithm,
+    start: &N,
+    successors: FN,
+    heuristic: FH,
+    success: FS,
+    limits: Limits,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Bounded + Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    match algorithm {
+        PathFindingAlgorithm::Astar => astar::astar(start, successors, heuristic, success),
+        PathFindingAlgorithm::Idastar => idastar::idastar(start, successors, heuristic, success),
+        PathFindingAlgorithm::IdastarTt => transposition::idastar_tt(
+            start,
+            successors,
+            heuristic,
+            success,
+            limits.transposition_capacity,
+        ),
+        PathFindingAlgorithm::BoundedAstar => bounded::bounded_astar(
+            start,
+            successors,
+            heuristic,
+            success,
+            limits.max_memory / bytes_per_node::<N, C>(),
+        ),
+        PathFindingAlgorithm::Fringe => fringe::fringe(start, successors, heuristic, success),
+        PathFindingAlgorithm::Dijkstra => dijkstra::dijkstra(start, successors, success),
+        PathFindingAlgorithm::Registered(algorithm) => {
+            registry::run_registered(algorithm, start, successors, heuristic, success)
         }
     }
 }
 
 /// Pathfinding algorithm supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PathFindingAlgorithm {
     Astar,
     Fringe,
     Idastar,
+    IdastarTt,
+    BoundedAstar,
     Dijkstra,
+    /// Algorithm added with `register_algorithm`
+    Registered(&'static dyn SearchAlgorithm),
 }
 
-impl fmt::Display for PathFindingAlgorithm {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let name = match self {
+impl PathFindingAlgorithm {
+    pub const ALL: [PathFindingAlgorithm; 6] = [
+        PathFindingAlgorithm::Astar,
+        PathFindingAlgorithm::Fringe,
+        PathFindingAlgorithm::Idastar,
+        PathFindingAlgorithm::IdastarTt,
+        PathFindingAlgorithm::BoundedAstar,
+        PathFindingAlgorithm::Dijkstra,
+    ];
+
+    /// Other names `from_str` accepts
+    pub const ALIASES: [(&'static str, PathFindingAlgorithm); 4] = [
+        ("a*", PathFindingAlgorithm::Astar),
+        ("ida*", PathFindingAlgorithm::Idastar),
+        ("ida*-tt", PathFindingAlgorithm::IdastarTt),
+        ("bounded-a*", PathFindingAlgorithm::BoundedAstar),
+    ];
+
+    /// Built-in algorithms then the registered ones
+    pub fn all() -> Vec<PathFindingAlgorithm> {
+        PathFindingAlgorithm::ALL
+            .iter()
+            .copied()
+            .chain(
+                registered_algorithms()
+                    .into_iter()
+                    .map(PathFindingAlgorithm::Registered),
+            )
+            .collect()
+    }
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
             PathFindingAlgorithm::Astar => "astar",
             PathFindingAlgorithm::Fringe => "fringe",
             PathFindingAlgorithm::Idastar => "idastar",
+            PathFindingAlgorithm::IdastarTt => "idastar-tt",
+            PathFindingAlgorithm::BoundedAstar => "bounded-astar",
             PathFindingAlgorithm::Dijkstra => "dijkstra",
-        };
-        write!(f, "{}", name)
+            PathFindingAlgorithm::Registered(algorithm) => algorithm.name(),
+        }
+    }
+
+    /// Whether the algorithm is only exact when hops cost the same both ways. None of the
+    /// built-in ones is, since they search from a single end.
+    pub fn needs_symmetric_costs(self) -> bool {
+        match self {
+            PathFindingAlgorithm::Registered(algorithm) => algorithm.needs_symmetric_costs(),
+            _ => false,
+        }
+    }
+
+    // Whether the algorithm keeps every node it reaches until it ends
+    fn keeps_every_node(self) -> bool {
+        match self {
+            PathFindingAlgorithm::Registered(algorithm) => algorithm.keeps_every_node(),
+            algorithm => matches!(
+                algorithm,
+                PathFindingAlgorithm::Astar
+                    | PathFindingAlgorithm::Fringe
+                    | PathFindingAlgorithm::Dijkstra
+            ),
+        }
+    }
+}
+
+impl fmt::Display for PathFindingAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
     }
 }
 
 impl FromStr for PathFindingAlgorithm {
-    type Err = ();
+    type Err = UnknownName;
 
-    fn from_str(s: &str) -> Result<PathFindingAlgorithm, ()> {
-        match s {
-            "astar" => Ok(PathFindingAlgorithm::Astar),
-            "fringe" => Ok(PathFindingAlgorithm::Fringe),
-            "idastar" => Ok(PathFindingAlgorithm::Idastar),
-            "dijkstra" => Ok(PathFindingAlgorithm::Dijkstra),
-            _ => Err(()),
-        }
+    fn from_str(s: &str) -> Result<PathFindingAlgorithm, UnknownName> {
+        let names: Vec<_> = PathFindingAlgorithm::all()
+            .into_iter()
+            .map(|algorithm| (algorithm.name(), algorithm))
+            .collect();
+        names::find_name("algorithm", s, &names, &PathFindingAlgorithm::ALIASES)
+    }
+}
+
+// Written as their names, so registered algorithms read back too
+#[cfg(feature = "serde")]
+impl Serialize for PathFindingAlgorithm {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PathFindingAlgorithm {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Number of mutations of a path by size, counted in `unit`s
+pub fn describe_cost<U: Display + Zero + PartialEq + Copy>(
+    cost: &PathMultiCost<U>,
+    unit: cost::Unit,
+) -> String {
+    describe_cost_in(cost, unit, Locale::En)
+}
+
+/// Same as `describe_cost`, in the language of `locale`
+pub fn describe_cost_in<U: Display + Zero + PartialEq + Copy>(
+    cost: &PathMultiCost<U>,
+    unit: cost::Unit,
+    locale: Locale,
+) -> String {
+    match cost.get_cost().as_slice() {
+        [] => locale.no_mutation().to_string(),
+        cost => cost
+            .iter()
+            .rev()
+            .map(|&(v, count)| match count {
+                MAX_DIMENSION => locale.big_mutations(v, unit),
+                _ => locale.mutations(v, count, unit),
+            })
+            .collect::<Vec<String>>()
+            .join(" + "),
     }
 }
 
 // Display number of letter-changes from a path between two words.
 impl<U: Display + Zero + PartialEq + Copy> Display for PathMultiCost<U> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.get_cost().as_slice() {
-            [] => write!(f, "0 mutation"),
-            cost => write!(
-                f,
-                "{}",
-                cost.iter()
-                    .rev()
-                    .map(|(v, count)| format!("{} {}-letter mutation", v, count))
-                    .collect::<Vec<String>>()
-                    .join(" + ")
-            ),
+        write!(f, "{}", describe_cost(self, cost::Unit::Letter))
+    }
+}
+
+// Sizes of mutations spelled out, as in "2 one-letter mutations"
+const SPELLED_SIZES: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Reads back what `Display` prints, such as `2 1-letter mutations + 1 3-letter mutation`
+/// or `0 mutations`. Sizes may be spelled out, `2 one-letter mutations`, and mutations of
+/// words count as the ones of letters do.
+impl<U: word::HopCount> FromStr for PathMultiCost<U> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<PathMultiCost<U>, ()> {
+        let s = s.trim();
+        if s == Locale::En.no_mutation() {
+            return Ok(PathMultiCost::zero());
+        }
+        s.split(" + ")
+            .try_fold(PathMultiCost::zero(), |cost, term| {
+                Ok(cost + mutations(term)?)
+            })
+    }
+}
+
+// One term of a cost, `2 1-letter mutations` or `25 letters changed by mutations of 20+ letters`
+fn mutations<U: word::HopCount>(term: &str) -> Result<PathMultiCost<U>, ()> {
+    let number = |value: &str| value.parse::<usize>().map_err(|_| ());
+    let value = |value: usize| U::try_from(value).map_err(|_| ());
+    let noun = |noun: &str| matches!(noun, "letter" | "letters" | "word" | "words");
+    match term.split_whitespace().collect::<Vec<_>>()[..] {
+        [count, size, "mutation" | "mutations"] => {
+            let count = number(count)?;
+            let (size, unit) = size.split_once('-').ok_or(())?;
+            if !noun(unit) {
+                return Err(());
+            }
+            let size = match SPELLED_SIZES.iter().position(|&spelled| spelled == size) {
+                Some(index) => index + 1,
+                None => number(size)?,
+            };
+            match size {
+                0 => Err(()),
+                // Letters changed by mutations too big to count apart, as `hop_cost` does
+                size if size > word::OVERFLOW_DIMENSION => Ok(PathMultiCost::new(
+                    value(count.checked_mul(size).ok_or(())?)?,
+                    word::OVERFLOW_DIMENSION,
+                )),
+                size => Ok(PathMultiCost::new(value(count)?, size - 1)),
+            }
+        }
+        [changed, changed_noun, "changed", "by", "mutations", "of", big, big_noun]
+            if noun(changed_noun) && noun(big_noun) && big == format!("{}+", MAX_DIMENSION) =>
+        {
+            Ok(PathMultiCost::new(
+                value(number(changed)?)?,
+                word::OVERFLOW_DIMENSION,
+            ))
         }
+        _ => Err(()),
     }
 }
 
@@ -126,6 +1280,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn words_longer_than_max_dimension() {
+        let start = "a".repeat(30);
+        let shorter = "a".repeat(25);
+        test_compare(
+            &start,
+            "bbbbb",
+            vec![&shorter],
+            (vec![&start, &shorter, "bbbbb"], vec![(25, 20), (1, 5)]),
+        );
+        let (_, cost) = find_shortest_path(
+            &start,
+            "bbbbb",
+            &["bbbbb", &shorter][..],
+            &PathFindingAlgorithm::Astar,
+        )
+        .unwrap();
+        assert_eq!(
+            cost.to_string(),
+            "1 5-letter mutation + 25 letters changed by mutations of 20+ letters"
+        );
+        assert_eq!(cost.to_string().parse(), Ok(cost));
+    }
+
+    #[test]
+    fn costs_are_read_back() {
+        let cost = word::path_cost("cold", "cord") + word::path_cost("cord", "scored");
+        assert_eq!(
+            cost.to_string(),
+            "1 1-letter mutation + 1 2-letter mutation"
+        );
+        assert_eq!(cost.to_string().parse(), Ok(cost));
+        let two: PathMultiCost<word::EditDistance> = "2 one-letter mutations".parse().unwrap();
+        assert_eq!(two, word::hops_lower_bound(2));
+        assert_eq!(" 0 mutations ".parse(), Ok(PathMultiCost::<u64>::zero()));
+        assert_eq!(
+            "1 25-word mutation".parse(),
+            Ok(word::hop_cost::<word::EditDistance>(25))
+        );
+        for invalid in &[
+            "",
+            "2",
+            "2 mutations",
+            "2 0-letter mutations",
+            "x 1-letter mutation",
+        ] {
+            assert_eq!(
+                invalid.parse::<PathMultiCost<u64>>(),
+                Err(()),
+                "{}",
+                invalid
+            );
+        }
+    }
+
     #[test]
     fn integration() {
         test_compare(
@@ -139,6 +1348,300 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stats_count_expansions() {
+        let words = vec!["banana", "banane"];
+        let (result, stats) = find_shortest_path_with_stats(
+            "banane",
+            "banana",
+            words.as_slice(),
+            &PathFindingAlgorithm::Dijkstra,
+        );
+        assert!(result.is_some());
+        assert_eq!(stats.expanded, 1);
+    }
+
+    #[test]
+    fn cancelled_search_reports_interruption() {
+        let words = vec!["banana", "banane", "banan", "banon"];
+        let token = CancellationToken::new();
+        token.cancel();
+        let control = SearchControl::new()
+            .with_cancellation(token)
+            .with_check_interval(1);
+        let (result, stats) = find_shortest_path_with_control(
+            "banane",
+            "banana",
+            words.as_slice(),
+            &PathFindingAlgorithm::Astar,
+            &control,
+        );
+        assert_eq!(result, Err(Interruption::Cancelled));
+        assert_eq!(stats.expanded, 1);
+    }
+
+    #[test]
+    fn observer_sees_every_expansion() {
+        struct Recorder(std::sync::Mutex<Vec<(String, usize)>>);
+        impl SearchObserver for Recorder {
+            fn expanded(
+                &self,
+                word: &str,
+                _heuristic: PathMultiCost<word::EditDistance>,
+                successors: &[(&str, PathMultiCost<word::EditDistance>)],
+            ) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((word.to_string(), successors.len()));
+            }
+        }
+        let recorder = Arc::new(Recorder(Default::default()));
+        let options =
+            SearchOptions::new(PathFindingAlgorithm::Astar).with_observer(recorder.clone());
+        let words = vec!["banana", "banane"];
+        let (result, stats) =
+            find_shortest_path_with_options("banane", "banana", words.as_slice(), &options);
+        assert!(result.unwrap().is_some());
+        assert_eq!(*recorder.0.lock().unwrap(), vec![("banane".to_string(), 1)]);
+        assert_eq!(stats.expanded, 1);
+    }
+
+    #[test]
+    fn successor_buffers_are_recycled() {
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let (result, stats) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        assert!(result.unwrap().is_some());
+        // Only the first expansion needs a buffer of its own
+        assert_eq!(stats.recycled, stats.expanded - 1);
+    }
+
+    #[test]
+    fn searches_outgrowing_memory_fall_back_to_bounded_astar() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "bold", "gold", "hold", "mold", "told", "colt",
+        ];
+        let node = bytes_per_node::<(&str, usize), PathMultiCost<word::EditDistance>>();
+        for algorithm in &[
+            PathFindingAlgorithm::Astar,
+            PathFindingAlgorithm::Fringe,
+            PathFindingAlgorithm::Dijkstra,
+        ] {
+            let options = SearchOptions::new(*algorithm).with_max_hop_distance(1);
+            let (result, stats) =
+                find_shortest_path_with_options("cold", "warm", &words[..], &options);
+            assert_eq!(result.unwrap().unwrap().0.len(), 5);
+            assert!(!stats.memory_fallback);
+            // The neighbours of the start word alone do not fit
+            let options = options.with_max_memory(6 * node);
+            let (result, stats) =
+                find_shortest_path_with_options("cold", "warm", &words[..], &options);
+            let (path, _) = result.unwrap().unwrap();
+            assert_eq!(
+                path,
+                vec!["cold", "cord", "card", "ward", "warm"],
+                "{}",
+                algorithm
+            );
+            assert!(stats.memory_fallback, "{}", algorithm);
+        }
+        // Nothing to fall back from
+        let options = SearchOptions::new(PathFindingAlgorithm::Idastar)
+            .with_max_hop_distance(1)
+            .with_max_memory(node);
+        let (_, stats) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        assert!(!stats.memory_fallback);
+    }
+
+    #[test]
+    fn on_expand_gets_costs_from_the_start() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let options =
+            SearchOptions::new(PathFindingAlgorithm::Astar).with_on_expand(move |word, cost| {
+                recorder.lock().unwrap().push((word.to_string(), cost))
+            });
+        let words = vec!["cold", "cord", "card", "ward", "warm"];
+        let (result, _) =
+            find_shortest_path_with_options("cold", "warm", words.as_slice(), &options);
+        assert!(result.unwrap().is_some());
+        // Every hop changes a single letter, the last word is reached without expanding it
+        let expected: Vec<(String, PathMultiCost<word::EditDistance>)> = words[..4]
+            .iter()
+            .enumerate()
+            .map(|(hops, word)| {
+                (
+                    word.to_string(),
+                    PathMultiCost::new(hops as word::EditDistance, 0),
+                )
+            })
+            .collect();
+        assert_eq!(*seen.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn repeated_words_do_not_add_expansions() {
+        let unique = vec!["cold", "cord", "card", "ward", "warm", "wart", "bold"];
+        let repeated: Vec<&str> = unique.iter().flat_map(|&word| [word, word, word]).collect();
+        // A weak bound and hop-counted nodes used to let self-loops pad the path with
+        // copies of the start word, and plain IDA* expanded over 200000 nodes here
+        for algorithm in PathFindingAlgorithm::ALL {
+            let options = SearchOptions::new(algorithm)
+                .with_heuristic(Heuristic::LengthDiff)
+                .with_max_hops(6);
+            let expansions = |words: &[&str]| {
+                let (result, stats) =
+                    find_shortest_path_with_options("cold", "warm", words, &options);
+                assert_eq!(result.unwrap().unwrap().0.len(), 5);
+                stats.expanded
+            };
+            assert_eq!(expansions(&repeated), expansions(&unique), "{}", algorithm);
+            assert!(expansions(&unique) < 40, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn weights_break_ties_between_paths() {
+        let words = vec!["cold", "bold", "cord", "bord"];
+        let search = |weights: &[(&str, usize)], direction| {
+            let weights = weights.iter().map(|&(w, n)| (w.to_string(), n)).collect();
+            let options = SearchOptions::new(PathFindingAlgorithm::Astar)
+                .with_word_weights(Arc::new(weights))
+                .with_direction(direction);
+            let (result, _) =
+                find_shortest_path_with_options("cold", "bord", words.as_slice(), &options);
+            result.unwrap().unwrap()
+        };
+        for direction in [Direction::Forward, Direction::Backward] {
+            let (path, cost) = search(&[("bold", 3)], direction);
+            assert_eq!(path, vec!["cold", "cord", "bord"]);
+            assert_eq!(cost.penalty(), 0);
+            let (path, cost) = search(&[("cord", 3), ("bold", 1), ("bord", 2)], direction);
+            assert_eq!(path, vec!["cold", "bold", "bord"]);
+            assert_eq!(cost.penalty(), 3);
+        }
+    }
+
+    #[test]
+    fn frequencies_break_ties_after_weights() {
+        let words = vec!["cold", "bold", "cord", "bord"];
+        let frequencies: HashMap<String, u32> = [("bold", 1000), ("cord", 10), ("bord", 1)]
+            .iter()
+            .map(|&(word, count)| (word.to_string(), count))
+            .collect();
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar)
+            .with_frequencies(Arc::new(frequencies.clone()));
+        for direction in [Direction::Forward, Direction::Backward] {
+            let options = options.clone().with_direction(direction);
+            let (result, _) =
+                find_shortest_path_with_options("cold", "bord", words.as_slice(), &options);
+            let (path, cost) = result.unwrap().unwrap();
+            assert_eq!(path, vec!["cold", "bold", "bord"]);
+            assert_eq!(cost.rarity(), 22 + 31);
+            assert_eq!(
+                cost,
+                word::path_cost_with_frequencies("cold", "bold", &frequencies)
+                    + word::path_cost_with_frequencies("bold", "bord", &frequencies)
+            );
+            // Weights still come first
+            let weights = std::iter::once(("bold".to_string(), 1)).collect();
+            let options = options.with_word_weights(Arc::new(weights));
+            let (result, _) =
+                find_shortest_path_with_options("cold", "bord", words.as_slice(), &options);
+            assert_eq!(result.unwrap().unwrap().0, vec!["cold", "cord", "bord"]);
+        }
+    }
+
+    #[test]
+    fn ocr_cost_model_prefers_confusions() {
+        let words = vec!["c1ear", "dear", "clean", "clear"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar)
+            .with_cost_model(Arc::new(cost::Confusion::ocr()));
+        let (result, _) =
+            find_shortest_path_with_options("clear", "dear", words.as_slice(), &options);
+        let (path, cost) = result.unwrap().unwrap();
+        assert_eq!(path, vec!["clear", "dear"]);
+        assert_eq!(cost.get_cost(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn token_unit_builds_phrase_ladders() {
+        let words = vec![
+            "the cat sat",
+            "the dog sat",
+            "a dog sat",
+            "the dog sat down",
+        ];
+        let options =
+            SearchOptions::new(PathFindingAlgorithm::Astar).with_cost_model(Arc::new(cost::Tokens));
+        let (result, _) =
+            find_shortest_path_with_options("the cat sat", "a dog sat", words.as_slice(), &options);
+        let (path, cost) = result.unwrap().unwrap();
+        assert_eq!(path, vec!["the cat sat", "the dog sat", "a dog sat"]);
+        assert_eq!(
+            describe_cost(&cost, cost::Unit::Token),
+            "2 1-word mutations"
+        );
+        assert_eq!(
+            describe_cost_in(&cost, cost::Unit::Token, Locale::Fr),
+            "2 mutations de 1 mot"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn results_serialize_to_json() {
+        let (path, stats) = find_shortest_path_with_stats(
+            "banane",
+            "banana",
+            &["banana", "banane"][..],
+            &PathFindingAlgorithm::Astar,
+        );
+        let result = PathResult::new(path.unwrap(), &Levenshtein);
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            r#"{"words":["banane","banana"],"cost":[1],"hops":[{"distance":1,"cost":[1]}]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&stats).unwrap(),
+            r#"{"expanded":1,"saturated":false,"backward":false,"recycled":0,"memory_fallback":false}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PathFindingAlgorithm>(r#""IDA*""#).unwrap(),
+            PathFindingAlgorithm::Idastar
+        );
+    }
+
+    #[test]
+    fn every_heuristic_keeps_paths_optimal() {
+        let words = ["warm", "cold", "cord", "card", "ward", "wart", "swarm"];
+        let dijkstra = SearchOptions::new(PathFindingAlgorithm::Dijkstra);
+        for heuristic in &[
+            "edit-distance",
+            "length-diff",
+            "alt",
+            "max(alt,edit-distance)",
+        ] {
+            let options = SearchOptions::new(PathFindingAlgorithm::Astar)
+                .with_heuristic(Heuristic::from_str(heuristic).unwrap());
+            for (start, stop) in &[("cold", "warm"), ("cold", "swarm"), ("swarm", "cold")] {
+                let (expected, _) =
+                    find_shortest_path_with_options(start, stop, &words[..], &dijkstra);
+                let (result, _) =
+                    find_shortest_path_with_options(start, stop, &words[..], &options);
+                assert_eq!(
+                    result.unwrap().map(|(_, cost)| cost),
+                    expected.unwrap().map(|(_, cost)| cost),
+                    "{} from {} to {}",
+                    heuristic,
+                    start,
+                    stop
+                );
+            }
+        }
+    }
+
     #[test]
     // heuristic function h is admissible
     // path cost will always be bigger than the edit_distance
@@ -167,6 +1670,8 @@ mod tests {
             PathFindingAlgorithm::Astar,
             PathFindingAlgorithm::Fringe,
             PathFindingAlgorithm::Idastar,
+            PathFindingAlgorithm::IdastarTt,
+            PathFindingAlgorithm::BoundedAstar,
             PathFindingAlgorithm::Dijkstra,
         ]
         .iter()
@@ -180,4 +1685,374 @@ mod tests {
             },
         )
     }
+
+    #[test]
+    fn max_hops_prunes_longer_paths() {
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        for algorithm in &PathFindingAlgorithm::ALL {
+            let options = SearchOptions::new(*algorithm).with_max_hops(4);
+            let (result, _) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+            assert_eq!(result.unwrap().unwrap().0.len(), 5, "{}", algorithm);
+            let options = SearchOptions::new(*algorithm).with_max_hops(2);
+            let (result, _) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+            // Fewer but bigger hops are needed
+            let (path, cost) = result.unwrap().unwrap();
+            assert!(path.len() <= 3, "{}", algorithm);
+            assert!(
+                cost > word::hop_cost(1) + word::hop_cost(1),
+                "{}",
+                algorithm
+            );
+            let options = SearchOptions::new(*algorithm).with_max_hops(0);
+            let (result, _) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+            assert_eq!(result.unwrap(), None, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn goals_end_on_the_first_word_they_accept() {
+        let words = ["cold", "cord", "card", "ward", "warm", "worm"];
+        let indexed = ShardedWords::new(&words);
+        let goal = |word: &str| word.starts_with("war");
+        // Whatever algorithm is asked for, nothing guides the search to these words
+        let options = SearchOptions::new(PathFindingAlgorithm::Idastar).with_max_hop_distance(1);
+        let (found, _) = find_shortest_path_to_match("cold", &goal, &indexed, &options);
+        let (path, cost) = found.unwrap().unwrap();
+        assert_eq!(path, vec!["cold", "cord", "card", "ward"]);
+        assert_eq!(cost.get_cost(), vec![(3, 1)]);
+        let (found, _) = find_shortest_path_to_match("warm", &goal, &words[..], &options);
+        assert_eq!(found.unwrap().unwrap().0, vec!["warm"]);
+        let (found, _) = find_shortest_path_to_match("cold", &|w| w == "zzzz", &indexed, &options);
+        assert_eq!(found.unwrap(), None);
+    }
+
+    #[test]
+    fn objectives_pick_different_paths() {
+        let words = [
+            "aaaa", "bbaa", "bbbb", "caaa", "ccaa", "ccba", "ccbb", "cbbb",
+        ];
+        let expected = [
+            (
+                Objective::SmallestMaxMutation,
+                "aaaa caaa ccaa ccba ccbb cbbb bbbb",
+            ),
+            (Objective::FewestHops, "aaaa bbaa bbbb"),
+            (Objective::FewestLetters, "aaaa bbaa bbbb"),
+        ];
+        for algorithm in &PathFindingAlgorithm::ALL {
+            for &(objective, path) in &expected {
+                let options = SearchOptions::new(*algorithm)
+                    .with_max_hop_distance(2)
+                    .with_objective(objective);
+                let (found, _) =
+                    find_shortest_path_with_options("aaaa", "bbbb", &words[..], &options);
+                let (found, cost) = found.unwrap().unwrap();
+                assert_eq!(found.join(" "), path, "{} {}", algorithm, objective);
+                assert_eq!(cost.objective(), objective);
+            }
+        }
+        // Without a limit, a single hop is the fewest whatever it changes
+        let options =
+            SearchOptions::new(PathFindingAlgorithm::Astar).with_objective(Objective::FewestHops);
+        let (found, _) = find_shortest_path_with_options("aaaa", "ccbb", &words[..], &options);
+        assert_eq!(found.unwrap().unwrap().0, vec!["aaaa", "ccbb"]);
+    }
+
+    #[test]
+    fn monotone_paths_never_go_back() {
+        // "ac" is as far from "bb" as "aa" is
+        let mut words = vec!["aa", "ac", "bc", "bb"];
+        for algorithm in &PathFindingAlgorithm::ALL {
+            let options = SearchOptions::new(*algorithm).with_max_hop_distance(1);
+            let search = |options: &SearchOptions, words: &[&str]| {
+                let (found, _) = find_shortest_path_with_options("aa", "bb", words, options);
+                found.unwrap().map(|(path, _)| path.join(" "))
+            };
+            assert_eq!(search(&options, &words), Some("aa ac bc bb".to_string()));
+            let options = options.with_monotone(true);
+            assert_eq!(search(&options, &words), None, "{}", algorithm);
+            let backward = options.clone().with_direction(Direction::Backward);
+            assert_eq!(search(&backward, &words), None, "{}", algorithm);
+            words.push("ba");
+            assert_eq!(search(&options, &words), Some("aa ba bb".to_string()));
+            assert_eq!(search(&backward, &words), Some("aa ba bb".to_string()));
+            words.pop();
+        }
+    }
+
+    #[test]
+    fn bounds_count_the_hops_left_near_the_end() {
+        // "aacc" is 2 letters from "cccc" but shares no neighbour with it
+        let words = ["aaaa", "caaa", "ccaa", "ccca", "cccc", "aaac", "aacc"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let bound = options.prepare_bound(&words[..], "aaaa", "cccc", false);
+        let bounds: Vec<usize> = ["cccc", "ccca", "ccaa", "aacc", "aaaa"]
+            .iter()
+            .map(|word| bound(word))
+            .collect();
+        assert_eq!(bounds, vec![0, 1, 2, 3, 4]);
+        // Backward searches count the hops back to the start word
+        let bound = options.prepare_bound(&words[..], "aaaa", "cccc", true);
+        assert_eq!((bound("aaaa"), bound("ccaa"), bound("aacc")), (0, 2, 2));
+        let (result, _) = find_shortest_path_with_options("aaaa", "cccc", &words[..], &options);
+        assert_eq!(result.unwrap().unwrap().0.len(), 5);
+    }
+
+    #[test]
+    fn hops_stay_within_a_language() {
+        // "card" and "ward" only meet in French
+        let words = ["cold", "cord", "card", "ward", "warm", "wall"];
+        let tags = [
+            ("cold", "en"),
+            ("cord", "en"),
+            ("card", "en"),
+            ("ward", "fr"),
+            ("warm", "en"),
+        ];
+        let languages: HashMap<String, Vec<String>> = tags
+            .iter()
+            .map(|&(word, language)| (word.to_string(), vec![language.to_string()]))
+            .collect();
+        for algorithm in &PathFindingAlgorithm::ALL {
+            let options = SearchOptions::new(*algorithm).with_max_hop_distance(1);
+            let search = |options: &SearchOptions| {
+                let (found, _) =
+                    find_shortest_path_with_options("cold", "warm", &words[..], options);
+                found.unwrap().map(|(path, _)| path.join(" "))
+            };
+            assert!(search(&options).is_some(), "{}", algorithm);
+            let options = options.with_word_languages(Arc::new(languages.clone()));
+            assert_eq!(search(&options), None, "{}", algorithm);
+            let mut bridged = languages.clone();
+            bridged.insert("ward".to_string(), vec!["fr".to_string(), "en".to_string()]);
+            let options = options.with_word_languages(Arc::new(bridged));
+            assert_eq!(
+                search(&options),
+                Some("cold cord card ward warm".to_string()),
+                "{}",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn max_hop_distance_keeps_hops_small() {
+        let words = ["cold", "cord", "card", "ward", "warm", "wxyz"];
+        let sharded = ShardedWords::new(&words);
+        for algorithm in &PathFindingAlgorithm::ALL {
+            let options = SearchOptions::new(*algorithm).with_max_hop_distance(1);
+            let (result, _) = find_shortest_path_with_options("cold", "warm", &sharded, &options);
+            assert_eq!(result.unwrap().unwrap().0.len(), 5, "{}", algorithm);
+            let (result, _) = find_shortest_path_with_options("cold", "wxyz", &sharded, &options);
+            assert_eq!(result.unwrap(), None, "{}", algorithm);
+            // Models the index does not apply to scan, ordinary OCR edits weigh 2
+            let options = options
+                .with_cost_model(Arc::new(cost::Confusion::ocr()))
+                .with_max_hop_distance(cost::CONFUSION_EDIT_WEIGHT);
+            let (result, _) = find_shortest_path_with_options("cold", "warm", &sharded, &options);
+            assert_eq!(result.unwrap().unwrap().0.len(), 5, "{}", algorithm);
+        }
+    }
+
+    #[test]
+    fn backward_searches_find_the_same_paths() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "worm", "wore", "bold",
+        ];
+        for algorithm in &PathFindingAlgorithm::ALL {
+            for model in [
+                Arc::new(Levenshtein) as Arc<dyn CostModel>,
+                Arc::new(cost::Confusion::ocr()),
+            ] {
+                let forward = SearchOptions::new(*algorithm).with_cost_model(model);
+                let backward = forward.clone().with_direction(Direction::Backward);
+                let (expected, stats) =
+                    find_shortest_path_with_options("cold", "warm", &words[..], &forward);
+                assert!(!stats.backward);
+                let (result, stats) =
+                    find_shortest_path_with_options("cold", "warm", &words[..], &backward);
+                assert!(stats.backward);
+                let (path, cost) = result.unwrap().unwrap();
+                assert_eq!(cost, expected.unwrap().unwrap().1, "{}", algorithm);
+                assert_eq!(path.first(), Some(&"cold"), "{}", algorithm);
+                assert_eq!(path.last(), Some(&"warm"), "{}", algorithm);
+            }
+            let backward = SearchOptions::new(*algorithm).with_direction(Direction::Backward);
+            let (result, _) =
+                find_shortest_path_with_options("cold", "snow", &words[..], &backward);
+            assert_eq!(result.unwrap(), None);
+        }
+    }
+
+    #[test]
+    fn algorithms_are_parsed_whatever_their_case() {
+        assert_eq!("AStar".parse(), Ok(PathFindingAlgorithm::Astar));
+        assert_eq!("ida*".parse(), Ok(PathFindingAlgorithm::Idastar));
+        assert_eq!(" IDA*-TT".parse(), Ok(PathFindingAlgorithm::IdastarTt));
+        let error = "bfs".parse::<PathFindingAlgorithm>().unwrap_err();
+        assert_eq!(error.given, "bfs");
+        assert!(error.expected.starts_with(&["astar", "fringe"]));
+        assert!(error
+            .to_string()
+            .starts_with("unknown algorithm bfs, expected one of astar"));
+        assert_eq!("Phonetic".parse(), Ok(cost::CostModelName::Phonetic));
+        assert_eq!(
+            "levenstein"
+                .parse::<cost::CostModelName>()
+                .unwrap_err()
+                .to_string(),
+            "unknown cost model levenstein, expected one of levenshtein, ocr, phonetic"
+        );
+    }
+
+    #[test]
+    fn auto_direction_starts_from_the_fewest_neighbours() {
+        let words = ["cold", "cord", "bold", "hold", "told", "warm", "ward"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar)
+            .with_direction(Direction::Auto)
+            .with_max_hop_distance(1);
+        let (_, stats) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        assert!(stats.backward);
+        let (_, stats) = find_shortest_path_with_options("warm", "cold", &words[..], &options);
+        assert!(!stats.backward);
+        assert_eq!("auto".parse(), Ok(Direction::Auto));
+    }
+
+    #[test]
+    fn results_detail_every_hop() {
+        let words = ["cold", "cord", "card", "ward", "warm", "wxyz"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let (result, _) = find_shortest_path_result("cold", "warm", &words[..], &options);
+        let result = result.unwrap().unwrap();
+        assert_eq!(result.hops.len(), 4);
+        assert!(result.hops.iter().all(|hop| hop.distance == 1));
+        let total = result
+            .hops
+            .iter()
+            .fold(PathMultiCost::zero(), |total, hop| total + hop.cost);
+        assert_eq!(total, result.cost);
+        let (result, _) = find_shortest_path_result("warm", "wxyz", &words[..], &options);
+        assert_eq!(result.unwrap().unwrap().hops[0].distance, 3);
+        let (result, _) = find_shortest_path_result("warm", "warm", &words[..], &options);
+        assert!(result.unwrap().unwrap().hops.is_empty());
+    }
+
+    // Hops too big for the overflow dimension of an `EditDistance`
+    struct Huge;
+
+    impl CostModel for Huge {
+        fn distance(&self, w1: &str, w2: &str) -> usize {
+            match (w1, w2) {
+                _ if w1 == w2 => 0,
+                ("a", "c") | ("c", "a") => 200_000,
+                _ => 40_000,
+            }
+        }
+
+        fn heuristic(&self, _word: &str, _stop: &str) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn saturation_policies() {
+        let words = ["a", "b", "c"];
+        let options = |saturation| {
+            SearchOptions::new(PathFindingAlgorithm::Astar)
+                .with_cost_model(Arc::new(Huge))
+                .with_saturation(saturation)
+        };
+        let (result, stats) =
+            find_shortest_path_with_options("a", "c", &words[..], &options(Saturation::Flag));
+        assert!(result.unwrap().unwrap().1.is_saturated());
+        assert!(stats.saturated);
+        // Only 64 bits tell the path through "b" is cheaper
+        let (result, stats) =
+            find_shortest_path_with_options("a", "c", &words[..], &options(Saturation::Widen));
+        assert_eq!(result.unwrap().unwrap().0, vec!["a", "b", "c"]);
+        assert!(stats.saturated);
+        let (result, _) =
+            find_shortest_path_with_options("a", "c", &words[..], &options(Saturation::Error));
+        assert_eq!(result, Err(Interruption::Saturated));
+        let (result, stats) =
+            find_shortest_path_with_options("a", "b", &words[..], &options(Saturation::Error));
+        assert!(result.unwrap().is_some());
+        assert!(!stats.saturated);
+        assert_eq!("widen".parse(), Ok(Saturation::Widen));
+        assert_eq!(Saturation::Flag.to_string(), "saturate");
+    }
+
+    extern crate quickcheck;
+
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    // A few short words over a tiny alphabet, so that many paths tie and hops vary in size
+    #[derive(Debug, Clone)]
+    struct SmallDictionary {
+        words: Vec<String>,
+        start: usize,
+        stop: usize,
+    }
+
+    impl SmallDictionary {
+        fn new(words: Vec<String>, start: usize, stop: usize) -> SmallDictionary {
+            let count = words.len();
+            SmallDictionary {
+                words,
+                start: start % count,
+                stop: stop % count,
+            }
+        }
+    }
+
+    impl Arbitrary for SmallDictionary {
+        fn arbitrary<G: Gen>(g: &mut G) -> SmallDictionary {
+            let count = 1 + usize::from(u8::arbitrary(g)) % 8;
+            let words = (0..count)
+                .map(|_| {
+                    let length = 1 + usize::from(u8::arbitrary(g)) % 5;
+                    (0..length)
+                        .map(|_| ['a', 'b', 'c'][usize::from(u8::arbitrary(g)) % 3])
+                        .collect()
+                })
+                .collect();
+            SmallDictionary::new(words, usize::arbitrary(g), usize::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = SmallDictionary>> {
+            let (start, stop) = (self.start, self.stop);
+            Box::new(
+                self.words
+                    .shrink()
+                    .filter(|words| !words.is_empty())
+                    .map(move |words| SmallDictionary::new(words, start, stop)),
+            )
+        }
+    }
+
+    quickcheck! {
+        fn algorithms_agree_on_optimal_paths_prop(dictionary: SmallDictionary) -> bool {
+            let words: Vec<&str> = dictionary.words.iter().map(AsRef::as_ref).collect();
+            let (start, stop) = (words[dictionary.start], words[dictionary.stop]);
+            let (_, optimal) =
+                find_shortest_path(start, stop, words.as_slice(), &PathFindingAlgorithm::Dijkstra)
+                    .unwrap();
+            PathFindingAlgorithm::ALL.iter().all(|algorithm| {
+                match find_shortest_path(start, stop, words.as_slice(), algorithm) {
+                    Some((path, cost)) => {
+                        let total = path
+                            .windows(2)
+                            .map(|hop| word::path_cost(hop[0], hop[1]))
+                            .fold(PathMultiCost::zero(), |total, hop| total + hop);
+                        path.first() == Some(&start)
+                            && path.last() == Some(&stop)
+                            && path.iter().all(|word| words.contains(word))
+                            && total == cost
+                            && cost == optimal
+                    }
+                    None => false,
+                }
+            })
+        }
+    }
 }
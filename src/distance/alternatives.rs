@@ -0,0 +1,128 @@
+use crate::distance::{search, Interruption, SearchOptions, SearchStats, ShortestPath};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// What alternative paths may not share with the ones found before them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disjoint {
+    /// No word but the start and end ones
+    Node,
+    /// No hop between the same two words, in either direction
+    Edge,
+}
+
+impl fmt::Display for Disjoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Disjoint::Node => write!(f, "node"),
+            Disjoint::Edge => write!(f, "edge"),
+        }
+    }
+}
+
+impl FromStr for Disjoint {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Disjoint, ()> {
+        match s {
+            "node" => Ok(Disjoint::Node),
+            "edge" => Ok(Disjoint::Edge),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Up to `count` paths from `start` to `stop`, each the shortest one avoiding what the
+/// previous ones went through. Fewer are returned once no other path remains.
+pub fn find_disjoint_paths<'a>(
+    start: &'a str,
+    stop: &str,
+    words: &'a [&str],
+    options: &SearchOptions,
+    count: usize,
+    disjoint: Disjoint,
+) -> (Result<Vec<ShortestPath<'a>>, Interruption>, SearchStats) {
+    // Removing words or hops only makes paths longer, the heuristic stays a lower bound
+    let backward = options.runs_backward(start, stop, words);
+    let bound = options.prepare_bound(words, start, stop, backward);
+    let mut paths: Vec<ShortestPath<'a>> = vec![];
+    let mut stats = SearchStats::default();
+    let mut used_words: HashSet<&str> = HashSet::new();
+    let mut used_hops: HashSet<(&str, &str)> = HashSet::new();
+    while paths.len() < count {
+        let allowed = |from: &str, to: &str| {
+            // Even node-disjoint paths may not repeat a direct hop from start to stop
+            !used_hops.contains(&(from, to))
+                && (disjoint == Disjoint::Edge || to == stop || !used_words.contains(to))
+        };
+        let (result, run) = search(
+            start, stop, words, options, backward, &bound, &allowed, None,
+        );
+        stats.expanded += run.expanded;
+        stats.recycled += run.recycled;
+        stats.memory_fallback |= run.memory_fallback;
+        stats.saturated |= run.saturated;
+        stats.backward = backward;
+        let path = match result {
+            Ok(Some(path)) => path,
+            Ok(None) => break,
+            Err(interruption) => return (Err(interruption), stats),
+        };
+        let (path_words, _) = &path;
+        if path_words.len() < 2 {
+            // `start` is `stop`, there is nothing to avoid
+            paths.push(path);
+            break;
+        }
+        for hop in path_words.windows(2) {
+            used_hops.insert((hop[0], hop[1]));
+            used_hops.insert((hop[1], hop[0]));
+        }
+        used_words.extend(&path_words[1..path_words.len() - 1]);
+        paths.push(path);
+    }
+    (Ok(paths), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::PathFindingAlgorithm;
+
+    const WORDS: [&str; 9] = [
+        "warm", "cord", "card", "ward", "cold", "bold", "bald", "bard", "wore",
+    ];
+
+    fn paths(count: usize, disjoint: Disjoint) -> Vec<Vec<&'static str>> {
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let (paths, _) = find_disjoint_paths("cold", "warm", &WORDS, &options, count, disjoint);
+        paths.unwrap().into_iter().map(|(words, _)| words).collect()
+    }
+
+    #[test]
+    fn node_disjoint_paths_share_no_word() {
+        let paths = paths(3, Disjoint::Node);
+        assert_eq!(paths[0], vec!["cold", "cord", "card", "ward", "warm"]);
+        for (i, first) in paths.iter().enumerate() {
+            for second in &paths[i + 1..] {
+                assert!(first[1..first.len() - 1]
+                    .iter()
+                    .all(|word| !second.contains(word)));
+            }
+        }
+    }
+
+    #[test]
+    fn edge_disjoint_paths_share_no_hop() {
+        let paths = paths(3, Disjoint::Edge);
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[1], vec!["cold", "bold", "bald", "bard", "warm"]);
+        let hops: Vec<&[&str]> = paths.iter().flat_map(|path| path.windows(2)).collect();
+        for (i, hop) in hops.iter().enumerate() {
+            assert!(hops[i + 1..]
+                .iter()
+                .all(|other| other != hop && (other[1], other[0]) != (hop[0], hop[1])));
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{
+    find_shortest_path_with_options, CancellationToken, Interruption, SearchOptions,
+};
+use std::panic;
+use std::sync::Arc;
+
+/// Same as `ShortestPath` but owning its words so it can leave the blocking pool
+pub type OwnedShortestPath = (Vec<String>, PathMultiCost<EditDistance>);
+
+// Cancels the search when the future awaiting it is dropped
+struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Run the search on tokio's blocking pool; dropping the future cancels the search
+pub async fn find_shortest_path_async(
+    start: String,
+    stop: String,
+    words: Arc<Vec<String>>,
+    options: SearchOptions,
+) -> Result<Option<OwnedShortestPath>, Interruption> {
+    cancelled_on_drop(start, stop, words, options, CancellationToken::new()).await
+}
+
+// The search of `find_shortest_path_async`, `token` being cancelled when the future is dropped
+async fn cancelled_on_drop(
+    start: String,
+    stop: String,
+    words: Arc<Vec<String>>,
+    options: SearchOptions,
+    token: CancellationToken,
+) -> Result<Option<OwnedShortestPath>, Interruption> {
+    let guard = CancelOnDrop(token.clone());
+    let control = options.control.clone().with_cancellation(token);
+    let options = options.with_control(control);
+    let task = tokio::task::spawn_blocking(move || {
+        let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+        let (result, _) =
+            find_shortest_path_with_options(&start, &stop, words.as_slice(), &options);
+        result.map(|path| {
+            path.map(|(path, cost)| (path.iter().map(|w| w.to_string()).collect(), cost))
+        })
+    });
+    let result = match task.await {
+        Ok(result) => result,
+        Err(error) => panic::resume_unwind(error.into_panic()),
+    };
+    drop(guard);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{PathFindingAlgorithm, SearchObserver};
+    use std::future::Future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Poll;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn search_on_blocking_pool() {
+        let words = Arc::new(vec!["banana".to_string(), "banane".to_string()]);
+        let (path, cost) = block_on(find_shortest_path_async(
+            "banane".to_string(),
+            "banana".to_string(),
+            words,
+            SearchOptions::new(PathFindingAlgorithm::Astar),
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(path, vec!["banane", "banana"]);
+        assert_eq!(cost.get_cost(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn dropping_the_guard_cancels() {
+        let token = CancellationToken::new();
+        drop(CancelOnDrop(token.clone()));
+        assert!(token.is_cancelled());
+    }
+
+    // Counts expansions leading somewhere, slowly enough for the search to be dropped
+    // midway. Once cancelled, the words left are drained without successors.
+    #[derive(Default)]
+    struct Slow(AtomicUsize);
+
+    impl SearchObserver for Slow {
+        fn expanded(
+            &self,
+            _: &str,
+            _: PathMultiCost<EditDistance>,
+            successors: &[(&str, PathMultiCost<EditDistance>)],
+        ) {
+            if !successors.is_empty() {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    #[test]
+    fn dropping_the_future_cancels_the_search() {
+        // No path ends on zzzzzz, every one of the words would be expanded
+        let words: Vec<String> = (0..2_000).map(|i| format!("w{:05}", i)).collect();
+        let count = words.len();
+        let slow = Arc::new(Slow::default());
+        let options =
+            SearchOptions::new(PathFindingAlgorithm::Dijkstra).with_observer(slow.clone());
+        let token = CancellationToken::new();
+        block_on(async {
+            let mut search = Box::pin(cancelled_on_drop(
+                "w00000".to_string(),
+                "zzzzzz".to_string(),
+                Arc::new(words),
+                options,
+                token.clone(),
+            ));
+            // Polled once to hand the search to the blocking pool
+            let pending = std::future::poll_fn(|cx| Poll::Ready(search.as_mut().poll(cx))).await;
+            assert!(pending.is_pending());
+            while slow.0.load(Ordering::SeqCst) == 0 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            drop(search);
+        });
+        assert!(token.is_cancelled());
+        // The blocking task holds the other reference to the observer until it ends
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Arc::strong_count(&slow) > 1 {
+            assert!(Instant::now() < deadline, "the search went on");
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(slow.0.load(Ordering::SeqCst) < count);
+    }
+}
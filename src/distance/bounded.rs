@@ -0,0 +1,222 @@
+use num_traits::Zero;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+use std::mem;
+
+// A node kept in memory, with the entry it was reached from
+struct Entry<N, C> {
+    node: N,
+    parent: Option<usize>,
+    cost: C,
+    // Key it is queued with while open
+    open: Option<Key<C>>,
+    children: usize,
+}
+
+/// Rough number of bytes every node kept in memory takes, collections overhead included
+pub fn bytes_per_node<N, C>() -> usize {
+    // Entries live both in a map by id and in a map by node, open ones in a set too
+    2 * (mem::size_of::<Entry<N, C>>()
+        + mem::size_of::<(N, usize)>()
+        + mem::size_of::<(Key<C>, usize)>())
+}
+
+// Lowest estimate first, ties go to the deepest node to reach a goal with fewer nodes around
+type Key<C> = (C, Reverse<C>);
+
+struct Memory<N, C> {
+    entries: HashMap<usize, Entry<N, C>>,
+    ids: HashMap<N, usize>,
+    open: BTreeSet<(Key<C>, usize)>,
+    next_id: usize,
+}
+
+impl<N: Eq + Hash + Clone, C: Ord + Copy> Memory<N, C> {
+    fn path(&self, mut id: usize) -> Vec<N> {
+        let mut path = vec![];
+        loop {
+            let entry = &self.entries[&id];
+            path.push(entry.node.clone());
+            match entry.parent {
+                Some(parent) => id = parent,
+                None => break,
+            }
+        }
+        path.reverse();
+        path
+    }
+
+    fn open(&mut self, id: usize, estimate: C) {
+        let entry = self.entries.get_mut(&id).unwrap();
+        let key = (estimate, Reverse(entry.cost));
+        if let Some(previous) = entry.open.replace(key) {
+            self.open.remove(&(previous, id));
+        }
+        self.open.insert((key, id));
+    }
+
+    fn set_parent(&mut self, id: usize, parent: usize) {
+        if let Some(previous) = self.entries.get_mut(&id).unwrap().parent.replace(parent) {
+            self.entries.get_mut(&previous).unwrap().children -= 1;
+        }
+        self.entries.get_mut(&parent).unwrap().children += 1;
+    }
+
+    // Forget the open node with the worst estimate among those no other node was reached from
+    fn evict(&mut self) -> bool {
+        let worst = self
+            .open
+            .iter()
+            .rev()
+            .find(|(_, id)| self.entries[id].children == 0 && self.entries[id].parent.is_some())
+            .copied();
+        match worst {
+            Some((key, id)) => {
+                self.open.remove(&(key, id));
+                let entry = self.entries.remove(&id).unwrap();
+                self.ids.remove(&entry.node);
+                if let Some(parent) = entry.parent {
+                    self.entries.get_mut(&parent).unwrap().children -= 1;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A* keeping at most `capacity` nodes in memory. Once full, the open nodes with the worst
+/// estimates are forgotten, so the path is only guaranteed to be the shortest when nothing
+/// had to be forgotten. When memory runs out altogether, the cheapest path reaching a
+/// successful node so far is returned.
+pub fn bounded_astar<N, C, FN, IN, FH, FS>(
+    start: &N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+    capacity: usize,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let mut memory = Memory {
+        entries: HashMap::new(),
+        ids: HashMap::new(),
+        open: BTreeSet::new(),
+        next_id: 1,
+    };
+    memory.entries.insert(
+        0,
+        Entry {
+            node: start.clone(),
+            parent: None,
+            cost: C::zero(),
+            open: None,
+            children: 0,
+        },
+    );
+    memory.ids.insert(start.clone(), 0);
+    memory.open(0, heuristic(start));
+    let mut best: Option<(Vec<N>, C)> = None;
+    while let Some((key, id)) = memory.open.iter().next().copied() {
+        memory.open.remove(&(key, id));
+        memory.entries.get_mut(&id).unwrap().open = None;
+        let (node, cost) = {
+            let entry = &memory.entries[&id];
+            (entry.node.clone(), entry.cost)
+        };
+        if success(&node) {
+            return Some((memory.path(id), cost));
+        }
+        for (successor, hop) in successors(&node) {
+            let cost = cost + hop;
+            let successor_id = match memory.ids.get(&successor) {
+                Some(&known) if memory.entries[&known].cost <= cost => continue,
+                Some(&known) => {
+                    memory.entries.get_mut(&known).unwrap().cost = cost;
+                    known
+                }
+                None => {
+                    let new = memory.next_id;
+                    memory.next_id += 1;
+                    memory.entries.insert(
+                        new,
+                        Entry {
+                            node: successor.clone(),
+                            parent: None,
+                            cost,
+                            open: None,
+                            children: 0,
+                        },
+                    );
+                    memory.ids.insert(successor.clone(), new);
+                    new
+                }
+            };
+            memory.set_parent(successor_id, id);
+            if success(&successor) && best.as_ref().is_none_or(|(_, best)| cost < *best) {
+                best = Some((memory.path(successor_id), cost));
+            }
+            let estimate = cost + heuristic(&successor);
+            memory.open(successor_id, estimate);
+            while memory.entries.len() > capacity {
+                if !memory.evict() {
+                    return best;
+                }
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinding::directed::astar::astar;
+
+    fn grid(&(x, y): &(u32, u32)) -> Vec<((u32, u32), u32)> {
+        let mut neighbours = vec![((x + 1, y), 1), ((x, y + 1), 1)];
+        if x > 0 {
+            neighbours.push(((x - 1, y), 1));
+        }
+        if y > 0 {
+            neighbours.push(((x, y - 1), 1));
+        }
+        neighbours
+    }
+
+    fn distance(&(x, y): &(u32, u32)) -> u32 {
+        x.abs_diff(6) + y.abs_diff(4)
+    }
+
+    #[test]
+    fn same_as_astar_with_enough_memory() {
+        let expected = astar(&(0, 0), grid, distance, |&node| node == (6, 4)).unwrap();
+        let (path, cost) =
+            bounded_astar(&(0, 0), grid, distance, |&node| node == (6, 4), 1 << 20).unwrap();
+        assert_eq!(cost, expected.1);
+        assert_eq!(path.len(), expected.0.len());
+    }
+
+    #[test]
+    fn finds_a_path_with_little_memory() {
+        let (path, cost) =
+            bounded_astar(&(0, 0), grid, distance, |&node| node == (6, 4), 16).unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(6, 4)));
+    }
+
+    #[test]
+    fn gives_up_when_memory_runs_out() {
+        // Without heuristic every node is as promising, the goal is never reached
+        let result = bounded_astar(&(0, 0), grid, |_| 0, |&node| node == (60, 40), 8);
+        assert_eq!(result, None);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{Interruption, SearchOptions, SearchStats, ShortestPath};
+use num_traits::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "typos-checkpoint 1";
+
+/// Where a search stood when it was saved: the cheapest cost known to every word it
+/// reached, the word each was reached from and the words already expanded. The open
+/// words are the reached ones not expanded yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchState {
+    start: String,
+    stop: String,
+    reached: HashMap<String, (PathMultiCost<EditDistance>, Option<String>)>,
+    closed: HashSet<String>,
+    expanded: usize,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl SearchState {
+    /// Start and end words of the search
+    pub fn ends(&self) -> (&str, &str) {
+        (&self.start, &self.stop)
+    }
+
+    /// Words expanded before the state was saved
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Number of words reached, expanded or not
+    pub fn reached(&self) -> usize {
+        self.reached.len()
+    }
+
+    /// Write the state as text, one line per word reached
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        writeln!(writer, "ends\t{}\t{}", self.start, self.stop)?;
+        writeln!(writer, "expanded\t{}", self.expanded)?;
+        let mut words: Vec<&String> = self.reached.keys().collect();
+        words.sort_unstable();
+        for word in words {
+            let (cost, parent) = &self.reached[word];
+            let values: Vec<String> = cost.as_slice().iter().map(u16::to_string).collect();
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}",
+                word,
+                parent.as_deref().unwrap_or(""),
+                match self.closed.contains(word) {
+                    true => "closed",
+                    false => "open",
+                },
+                values.join(",")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Read a state `write` wrote
+    pub fn read(reader: impl BufRead) -> io::Result<SearchState> {
+        let mut lines = reader.lines();
+        let mut next = || {
+            lines
+                .next()
+                .unwrap_or_else(|| Err(invalid("checkpoint cut short".to_string())))
+        };
+        if next()? != HEADER {
+            return Err(invalid("not a checkpoint of a search".to_string()));
+        }
+        let ends = next()?;
+        let (start, stop) = match ends.split('\t').collect::<Vec<_>>()[..] {
+            ["ends", start, stop] => (start.to_string(), stop.to_string()),
+            _ => return Err(invalid(format!("bad ends line {:?}", ends))),
+        };
+        let expanded = next()?;
+        let expanded = match expanded.split_once('\t') {
+            Some(("expanded", count)) => count.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| invalid(format!("bad expanded line {:?}", expanded)))?;
+        let mut state = SearchState {
+            start,
+            stop,
+            reached: HashMap::new(),
+            closed: HashSet::new(),
+            expanded,
+        };
+        for line in lines {
+            let line = line?;
+            let bad = || invalid(format!("bad word line {:?}", line));
+            let (word, parent, status, values) = match line.split('\t').collect::<Vec<_>>()[..] {
+                [word, parent, status, values] => (word, parent, status, values),
+                _ => return Err(bad()),
+            };
+            let values = values
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<EditDistance>, _>>()
+                .map_err(|_| bad())?;
+            let parent = Some(parent.to_string()).filter(|_| word != state.start);
+            let cost = PathMultiCost::from_slice(&values);
+            state.reached.insert(word.to_string(), (cost, parent));
+            match status {
+                "closed" => state.closed.insert(word.to_string()),
+                "open" => false,
+                _ => return Err(bad()),
+            };
+        }
+        Ok(state)
+    }
+}
+
+/// Shortest path from `start` to `stop` as A* finds it, going on from `resume` when given.
+/// Every `interval` and when interrupted, `save` gets the state the search is in, to go
+/// on with later. Hops longer than the hop limit of `options` are skipped; its algorithm,
+/// direction and word weights are ignored.
+pub fn find_shortest_path_checkpointed<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+    resume: Option<&SearchState>,
+    interval: Duration,
+    save: &mut dyn FnMut(&SearchState),
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let cost_model = options.cost_model.as_ref();
+    let bound = options.heuristic.prepare(cost_model, words, stop);
+    let heuristic = |word: &str| options.cost_lower_bound::<EditDistance>(bound(word));
+    let mut stats = SearchStats::default();
+    let mut reached: HashMap<&'a str, (PathMultiCost<EditDistance>, Option<&'a str>)> =
+        HashMap::new();
+    let mut closed: HashSet<&'a str> = HashSet::new();
+    match resume {
+        Some(state) => {
+            // Words of the state borrowed from the dictionary, like those found later
+            let listed: HashMap<&str, &'a str> = words
+                .iter()
+                .chain([start, stop])
+                .map(|word| (word, word))
+                .collect();
+            for (word, (cost, parent)) in &state.reached {
+                if let Some(&word) = listed.get(word.as_str()) {
+                    let parent = parent.as_deref().and_then(|p| listed.get(p).copied());
+                    reached.insert(word, (cost.with_objective(options.objective), parent));
+                }
+            }
+            closed.extend(state.closed.iter().filter_map(|w| listed.get(w.as_str())));
+            stats.expanded = state.expanded;
+        }
+        None => {
+            reached.insert(start, (PathMultiCost::zero(), None));
+        }
+    }
+    let mut open: BinaryHeap<_> = reached
+        .iter()
+        .filter(|(word, _)| !closed.contains(*word))
+        .map(|(&word, &(cost, _))| Reverse((cost + heuristic(word), cost, word)))
+        .collect();
+    let snapshot = |reached: &HashMap<&str, (PathMultiCost<EditDistance>, Option<&str>)>,
+                    closed: &HashSet<&str>,
+                    expanded| SearchState {
+        start: start.to_string(),
+        stop: stop.to_string(),
+        reached: reached
+            .iter()
+            .map(|(&word, &(cost, parent))| (word.to_string(), (cost, parent.map(str::to_string))))
+            .collect(),
+        closed: closed.iter().map(|word| word.to_string()).collect(),
+        expanded,
+    };
+    let mut saved = Instant::now();
+    let mut candidates = Vec::new();
+    while let Some(Reverse((_, cost, current))) = open.pop() {
+        // Queued again since at a lower cost, or expanded already
+        if reached[current].0 < cost || closed.contains(current) {
+            continue;
+        }
+        if current == stop {
+            let mut path = vec![stop];
+            while let Some(parent) = reached[path[path.len() - 1]].1 {
+                path.push(parent);
+            }
+            path.reverse();
+            stats.saturated = cost.is_saturated();
+            return (Ok(Some((path, cost))), stats);
+        }
+        if let Some(reason) = options.control.check(stats.expanded + 1) {
+            save(&snapshot(&reached, &closed, stats.expanded));
+            return (Err(reason), stats);
+        }
+        if saved.elapsed() >= interval {
+            save(&snapshot(&reached, &closed, stats.expanded));
+            saved = Instant::now();
+        }
+        stats.expanded += 1;
+        closed.insert(current);
+        candidates.clear();
+        match options.max_hop_distance {
+            Some(k) if cost_model.bounded_by_edits() => {
+                words.extend_neighbors(current, k, &mut candidates)
+            }
+            _ => candidates.extend(words.iter()),
+        }
+        for &next in &candidates {
+            let distance = cost_model.distance(current, next);
+            if next == current || options.max_hop_distance.is_some_and(|k| distance > k) {
+                continue;
+            }
+            let through = cost + options.hop_cost(distance);
+            if reached
+                .get(next)
+                .is_some_and(|&(known, _)| known <= through)
+            {
+                continue;
+            }
+            // Reopened when reached cheaper, which only weaker heuristics allow
+            closed.remove(next);
+            reached.insert(next, (through, Some(current)));
+            open.push(Reverse((through + heuristic(next), through, next)));
+        }
+    }
+    (Ok(None), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{
+        find_shortest_path_with_options, CancellationToken, PathFindingAlgorithm, SearchControl,
+    };
+
+    #[test]
+    fn searches_go_on_from_where_they_were_saved() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "word", "worm", "bold",
+        ];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let (expected, _) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        let expected = expected.unwrap().unwrap();
+
+        // Saved before every expansion, then cancelled after three of them
+        let token = CancellationToken::new();
+        let control = SearchControl::new()
+            .with_cancellation(token.clone())
+            .with_check_interval(1);
+        let stopped = options.clone().with_control(control);
+        let mut saves = Vec::new();
+        let (result, stats) = find_shortest_path_checkpointed(
+            "cold",
+            "warm",
+            &words[..],
+            &stopped,
+            None,
+            Duration::ZERO,
+            &mut |state| {
+                saves.push(state.clone());
+                if saves.len() == 3 {
+                    token.cancel();
+                }
+            },
+        );
+        assert_eq!(result, Err(Interruption::Cancelled));
+        assert_eq!((stats.expanded, saves.len()), (3, 4));
+        let state = saves.pop().unwrap();
+        assert_eq!(state.expanded(), 3);
+        assert_eq!(state.ends(), ("cold", "warm"));
+
+        let mut text = Vec::new();
+        state.write(&mut text).unwrap();
+        let read = SearchState::read(&text[..]).unwrap();
+        assert_eq!(read, state);
+
+        let (result, stats) = find_shortest_path_checkpointed(
+            "cold",
+            "warm",
+            &words[..],
+            &options,
+            Some(&read),
+            Duration::ZERO,
+            &mut |state| saves.push(state.clone()),
+        );
+        let (path, cost) = result.unwrap().unwrap();
+        assert_eq!((path.len(), cost), (expected.0.len(), expected.1));
+        assert!(stats.expanded > 3);
+        // One save before every expansion, over both runs
+        assert_eq!(saves.len(), stats.expanded);
+        assert!(SearchState::read(&b"typos-checkpoint 1\nends\tcold\n"[..]).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::observer::SearchObserver;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{
+    find_shortest_path_with_options, Direction, PathFindingAlgorithm, SearchOptions, SearchStats,
+};
+use std::fmt;
+use std::sync::Mutex;
+
+/// Expansions every one of which is sampled before sampling gets sparser
+pub const HEURISTIC_SAMPLES: usize = 1000;
+
+/// Sampled words whose exact cost left is searched for, each costing a search
+pub const ADMISSIBILITY_SAMPLES: usize = 20;
+
+/// A heuristic value proven wrong by the costs of the search
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeuristicViolation {
+    /// The value of `word` exceeds the cost of the hop to `next` plus the value of `next`,
+    /// so a word may be expanded before its cheapest path is known
+    Inconsistent {
+        word: String,
+        next: String,
+        heuristic: PathMultiCost<EditDistance>,
+        hop: PathMultiCost<EditDistance>,
+        next_heuristic: PathMultiCost<EditDistance>,
+    },
+    /// The value of `word` exceeds the cost of the cheapest path left from it, so the
+    /// path returned may not be the shortest
+    Inadmissible {
+        word: String,
+        heuristic: PathMultiCost<EditDistance>,
+        cost_left: PathMultiCost<EditDistance>,
+    },
+}
+
+impl fmt::Display for HeuristicViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeuristicViolation::Inconsistent {
+                word,
+                next,
+                heuristic,
+                hop,
+                next_heuristic,
+            } => write!(
+                f,
+                "inconsistent: {} is estimated at {}, more than the {} of the hop to {} plus the {} it is estimated at",
+                word, heuristic, hop, next, next_heuristic
+            ),
+            HeuristicViolation::Inadmissible {
+                word,
+                heuristic,
+                cost_left,
+            } => write!(
+                f,
+                "inadmissible: {} is estimated at {}, more than the {} of the cheapest path left",
+                word, heuristic, cost_left
+            ),
+        }
+    }
+}
+
+/// What a check went through and what it found wrong
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeuristicReport {
+    /// Expansions whose hops were checked
+    pub expansions: usize,
+    /// Words whose exact cost left was compared with their value
+    pub searched: usize,
+    pub violations: Vec<HeuristicViolation>,
+}
+
+// An expansion sampled: the word, its heuristic value and its successors with their hops
+type Sample = (
+    String,
+    PathMultiCost<EditDistance>,
+    Vec<(String, PathMultiCost<EditDistance>)>,
+);
+
+/// Observer of a search sampling its expansions to check afterwards that the heuristic
+/// never overestimates: the first `HEURISTIC_SAMPLES` expansions, then those whose rank
+/// is a multiple of it
+#[derive(Default)]
+pub struct HeuristicChecker {
+    expanded: Mutex<(usize, Vec<Sample>)>,
+}
+
+impl HeuristicChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the expansions sampled from the search observed, with `options` from `start`
+    /// to `stop` through `words`: the value of every word against its hops, and that of
+    /// up to `ADMISSIBILITY_SAMPLES` words against a search of the cost left from them
+    pub fn check<D: Dictionary + ?Sized>(
+        &self,
+        start: &str,
+        stop: &str,
+        words: &D,
+        options: &SearchOptions,
+        stats: &SearchStats,
+    ) -> HeuristicReport {
+        let (_, samples) = &*self.expanded.lock().unwrap();
+        let bound = options.prepare_bound(words, start, stop, stats.backward);
+        let estimate = |word: &str| options.cost_lower_bound::<EditDistance>(bound(word));
+        let mut violations = Vec::new();
+        for (word, heuristic, successors) in samples {
+            for (next, hop) in successors {
+                let next_heuristic = estimate(next);
+                if *heuristic > *hop + next_heuristic {
+                    violations.push(HeuristicViolation::Inconsistent {
+                        word: word.clone(),
+                        next: next.clone(),
+                        heuristic: *heuristic,
+                        hop: *hop,
+                        next_heuristic,
+                    });
+                }
+            }
+        }
+        // Exact costs left, with nothing guiding the search nor observing it
+        let exact = SearchOptions {
+            observer: None,
+            ..options.clone()
+        }
+        .with_algorithm(PathFindingAlgorithm::Dijkstra)
+        .with_direction(Direction::Forward);
+        let step = samples.len().div_ceil(ADMISSIBILITY_SAMPLES).max(1);
+        let mut searched = 0;
+        for (word, heuristic, _) in samples.iter().step_by(step) {
+            // Backward searches estimate the cost of the path from the start word
+            let (from, to) = match stats.backward {
+                false => (word.as_str(), stop),
+                true => (start, word.as_str()),
+            };
+            if let (Ok(Some((_, cost_left))), _) =
+                find_shortest_path_with_options(from, to, words, &exact)
+            {
+                searched += 1;
+                if *heuristic > cost_left {
+                    violations.push(HeuristicViolation::Inadmissible {
+                        word: word.clone(),
+                        heuristic: *heuristic,
+                        cost_left,
+                    });
+                }
+            }
+        }
+        HeuristicReport {
+            expansions: samples.len(),
+            searched,
+            violations,
+        }
+    }
+}
+
+impl SearchObserver for HeuristicChecker {
+    fn expanded(
+        &self,
+        word: &str,
+        heuristic: PathMultiCost<EditDistance>,
+        successors: &[(&str, PathMultiCost<EditDistance>)],
+    ) {
+        let (count, samples) = &mut *self.expanded.lock().unwrap();
+        *count += 1;
+        if *count <= HEURISTIC_SAMPLES || count.is_multiple_of(HEURISTIC_SAMPLES) {
+            let successors = successors
+                .iter()
+                .map(|&(next, hop)| (next.to_string(), hop))
+                .collect();
+            samples.push((word.to_string(), heuristic, successors));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::cost::{CostModel, Levenshtein};
+    use std::sync::Arc;
+
+    // Levenshtein distances, but three times as many hops left as there are
+    struct Tripled;
+
+    impl CostModel for Tripled {
+        fn distance(&self, w1: &str, w2: &str) -> usize {
+            Levenshtein.distance(w1, w2)
+        }
+
+        fn heuristic(&self, word: &str, stop: &str) -> usize {
+            Levenshtein.heuristic(word, stop) * 3
+        }
+
+        fn bounded_by_edits(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn overestimating_heuristics_are_caught() {
+        let words = ["cold", "cord", "card", "ward", "warm", "bold"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let checker = Arc::new(HeuristicChecker::new());
+        let observed = options.clone().with_observer(checker.clone());
+        let (_, stats) = find_shortest_path_with_options("cold", "warm", &words[..], &observed);
+        let report = checker.check("cold", "warm", &words[..], &options, &stats);
+        assert_eq!(report.expansions, stats.expanded);
+        assert!(report.searched > 0);
+        assert_eq!(report.violations, vec![]);
+
+        let tripled = options.clone().with_cost_model(Arc::new(Tripled));
+        let checker = Arc::new(HeuristicChecker::new());
+        let observed = tripled.clone().with_observer(checker.clone());
+        let (_, stats) = find_shortest_path_with_options("cold", "warm", &words[..], &observed);
+        let report = checker.check("cold", "warm", &words[..], &tripled, &stats);
+        assert!(report
+            .violations
+            .iter()
+            .any(|violation| matches!(violation, HeuristicViolation::Inconsistent { .. })));
+        assert!(report
+            .violations
+            .contains(&HeuristicViolation::Inadmissible {
+                word: "cold".to_string(),
+                heuristic: options.cost_lower_bound(12),
+                cost_left: options.cost_lower_bound(4),
+            }));
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Flag shared between a running search and the code that may abort it
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Why a search stopped before reaching a conclusion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interruption {
+    Cancelled,
+    DeadlineExceeded,
+    /// The cost of the path found no longer fits its counters, with `Saturation::Error`
+    Saturated,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Interruption::Cancelled => write!(f, "search cancelled"),
+            Interruption::DeadlineExceeded => write!(f, "search deadline exceeded"),
+            Interruption::Saturated => write!(f, "path cost overflowed its counters"),
+        }
+    }
+}
+
+/// Limits checked every `check_interval` expansions while searching
+#[derive(Debug, Clone)]
+pub struct SearchControl {
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    check_interval: usize,
+}
+
+impl Default for SearchControl {
+    fn default() -> SearchControl {
+        SearchControl {
+            cancellation: None,
+            deadline: None,
+            check_interval: 64,
+        }
+    }
+}
+
+impl SearchControl {
+    pub fn new() -> SearchControl {
+        SearchControl::default()
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> SearchControl {
+        self.cancellation = Some(token);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> SearchControl {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> SearchControl {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn with_check_interval(mut self, check_interval: usize) -> SearchControl {
+        self.check_interval = check_interval.max(1);
+        self
+    }
+
+    /// Looks at the limits on the first expansion, then once every `check_interval` expansions
+    pub(crate) fn check(&self, expanded: usize) -> Option<Interruption> {
+        if !expanded
+            .saturating_sub(1)
+            .is_multiple_of(self.check_interval)
+        {
+            return None;
+        }
+        if self.cancellation.as_ref().is_some_and(|t| t.is_cancelled()) {
+            return Some(Interruption::Cancelled);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Some(Interruption::DeadlineExceeded),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_shared_between_clones() {
+        let token = CancellationToken::new();
+        let control = SearchControl::new()
+            .with_cancellation(token.clone())
+            .with_check_interval(1);
+        assert_eq!(control.check(1), None);
+        token.cancel();
+        assert_eq!(control.check(2), Some(Interruption::Cancelled));
+    }
+
+    #[test]
+    fn deadline_is_checked_at_interval() {
+        let control = SearchControl::new()
+            .with_deadline(Instant::now())
+            .with_check_interval(10);
+        assert_eq!(control.check(1), Some(Interruption::DeadlineExceeded));
+        assert_eq!(control.check(3), None);
+        assert_eq!(control.check(11), Some(Interruption::DeadlineExceeded));
+    }
+}
@@ -0,0 +1,660 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::names::{self, UnknownName};
+use crate::distance::word::{
+    levenshtein, matrix_alignment, matrix_edit_distance, token_edit_distance,
+    weighted_edit_distance, Edit, EditWeights, Substitution,
+};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+#[cfg(feature = "scripting")]
+mod script;
+
+#[cfg(feature = "scripting")]
+pub use crate::distance::cost::script::ScriptCost;
+
+/// Measure of how many letters a hop between two words changes
+pub trait CostModel: Send + Sync {
+    /// Letters changed between `w1` and `w2`, 0 only when both are identical
+    fn distance(&self, w1: &str, w2: &str) -> usize;
+
+    /// Lower bound of the number of 1-letter hops needed to go from `word` to `stop`.
+    /// Must never exceed the real number of hops for the search to stay optimal.
+    fn heuristic(&self, word: &str, stop: &str) -> usize;
+
+    /// Weaker lower bound than `heuristic`, from the lengths of both words only.
+    /// 0 is always admissible, the default for models unable to do better.
+    fn length_heuristic(&self, _word: &str, _stop: &str) -> usize {
+        0
+    }
+
+    /// Whether `distance` is never below the Levenshtein distance, so that the words
+    /// within a distance are among those `Dictionary::neighbors_within` returns
+    fn bounded_by_edits(&self) -> bool {
+        false
+    }
+
+    /// Whether a hop costs the same both ways, so that bounds towards the start word are
+    /// computed as those towards the end word
+    fn symmetric(&self) -> bool {
+        false
+    }
+}
+
+// Hops between consecutive words compared both ways by `probe_symmetry`, those of a
+// sorted list often differing by a letter or two as the hops of searches do
+const SYMMETRY_PROBES: usize = 1000;
+
+/// Error of relying on hops costing the same both ways with a cost model where they do not
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsymmetricCosts {
+    /// The model does not claim that hops cost the same both ways
+    Undeclared,
+    /// The model claims so, but going `from` `to` costs `there` and coming back `back`
+    Hop {
+        from: String,
+        to: String,
+        there: usize,
+        back: usize,
+    },
+}
+
+impl fmt::Display for AsymmetricCosts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AsymmetricCosts::Undeclared => {
+                write!(f, "the cost model may cost hops differently both ways")
+            }
+            AsymmetricCosts::Hop {
+                from,
+                to,
+                there,
+                back,
+            } => write!(
+                f,
+                "the cost model claims hops cost the same both ways, but {} to {} costs {} and {} to {} costs {}",
+                from, to, there, to, from, back
+            ),
+        }
+    }
+}
+
+impl Error for AsymmetricCosts {}
+
+/// Check that `model` costs hops the same both ways, as it claims with `symmetric`, on
+/// hops between consecutive words of `words`. Probing cannot prove a model symmetric,
+/// only catch one that is not.
+pub fn probe_symmetry<D: Dictionary + ?Sized>(
+    model: &dyn CostModel,
+    words: &D,
+) -> Result<(), AsymmetricCosts> {
+    if !model.symmetric() {
+        return Err(AsymmetricCosts::Undeclared);
+    }
+    let asymmetric = words
+        .iter()
+        .zip(words.iter().skip(1))
+        .take(SYMMETRY_PROBES)
+        .map(|(from, to)| (from, to, model.distance(from, to), model.distance(to, from)))
+        .find(|(_, _, there, back)| there != back);
+    match asymmetric {
+        Some((from, to, there, back)) => Err(AsymmetricCosts::Hop {
+            from: from.to_string(),
+            to: to.to_string(),
+            there,
+            back,
+        }),
+        None => Ok(()),
+    }
+}
+
+// Every edit changes the length by at most one
+fn length_difference(word: &str, stop: &str) -> usize {
+    let (word, stop) = (word.chars().count(), stop.chars().count());
+    word.max(stop) - word.min(stop)
+}
+
+/// Cost models selectable from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostModelName {
+    Levenshtein,
+    Ocr,
+    Phonetic,
+}
+
+impl CostModelName {
+    pub const ALL: [CostModelName; 3] = [
+        CostModelName::Levenshtein,
+        CostModelName::Ocr,
+        CostModelName::Phonetic,
+    ];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            CostModelName::Levenshtein => "levenshtein",
+            CostModelName::Ocr => "ocr",
+            CostModelName::Phonetic => "phonetic",
+        }
+    }
+}
+
+impl fmt::Display for CostModelName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CostModelName {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<CostModelName, UnknownName> {
+        let names = CostModelName::ALL.map(|model| (model.name(), model));
+        names::find_name("cost model", s, &names, &[])
+    }
+}
+
+/// What a single edit changes: a letter of a word or a word of a phrase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Letter,
+    Token,
+}
+
+impl Unit {
+    pub const ALL: [Unit; 2] = [Unit::Letter, Unit::Token];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Letter => "letter",
+            Unit::Token => "token",
+        }
+    }
+
+    /// Name of the unit when describing a mutation
+    pub fn noun(self) -> &'static str {
+        match self {
+            Unit::Letter => "letter",
+            Unit::Token => "word",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Unit, ()> {
+        match s {
+            "letter" => Ok(Unit::Letter),
+            "token" => Ok(Unit::Token),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Levenshtein distance over the whitespace-separated words of phrases
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tokens;
+
+impl CostModel for Tokens {
+    fn distance(&self, w1: &str, w2: &str) -> usize {
+        token_edit_distance(w1, w2)
+    }
+
+    fn heuristic(&self, word: &str, stop: &str) -> usize {
+        token_edit_distance(word, stop)
+    }
+
+    fn length_heuristic(&self, word: &str, stop: &str) -> usize {
+        let (word, stop) = (word.split_whitespace(), stop.split_whitespace());
+        let (word, stop) = (word.count(), stop.count());
+        word.max(stop) - word.min(stop)
+    }
+
+    fn symmetric(&self) -> bool {
+        true
+    }
+}
+
+/// Plain Levenshtein distance, every edit counts as one letter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Levenshtein;
+
+impl CostModel for Levenshtein {
+    fn distance(&self, w1: &str, w2: &str) -> usize {
+        levenshtein(w1, w2)
+    }
+
+    fn heuristic(&self, word: &str, stop: &str) -> usize {
+        levenshtein(word, stop)
+    }
+
+    fn length_heuristic(&self, word: &str, stop: &str) -> usize {
+        length_difference(word, stop)
+    }
+
+    fn bounded_by_edits(&self) -> bool {
+        true
+    }
+
+    fn symmetric(&self) -> bool {
+        true
+    }
+}
+
+/// Weight of an ordinary edit for `Confusion`, confusable groups of letters weigh 1
+pub const CONFUSION_EDIT_WEIGHT: usize = 2;
+
+/// Letters commonly mistaken for each other by OCR engines, lowercase since words are folded
+const OCR_CONFUSIONS: [(&str, &str); 16] = [
+    ("l", "1"),
+    ("i", "1"),
+    ("i", "l"),
+    ("o", "0"),
+    ("s", "5"),
+    ("b", "8"),
+    ("z", "2"),
+    ("g", "9"),
+    ("e", "c"),
+    ("u", "v"),
+    ("rn", "m"),
+    ("nn", "m"),
+    ("cl", "d"),
+    ("vv", "w"),
+    ("ri", "n"),
+    ("li", "h"),
+];
+
+/// Letters sharing a Soundex code, plus vowels which Soundex drops altogether
+const SOUNDEX_CLASSES: [&str; 6] = ["bfpv", "cgjkqsxz", "dt", "mn", "aeiouy", "l"];
+
+/// Spellings Metaphone reduces to the same sound
+const PHONETIC_DIGRAPHS: [(&str, &str); 12] = [
+    ("ph", "f"),
+    ("gh", "f"),
+    ("ck", "k"),
+    ("kn", "n"),
+    ("gn", "n"),
+    ("wr", "r"),
+    ("wh", "w"),
+    ("mb", "m"),
+    ("th", "t"),
+    ("qu", "kw"),
+    ("x", "ks"),
+    ("sch", "sk"),
+];
+
+/// Edit distance where confusable groups of letters are cheaper to swap than ordinary edits.
+/// Ordinary edits weigh `CONFUSION_EDIT_WEIGHT`, so a 1-letter typo is reported as a 2-letter mutation
+/// and a confusion as a 1-letter one.
+#[derive(Debug, Clone)]
+pub struct Confusion {
+    substitutions: Vec<Substitution>,
+    // Most Levenshtein edits a hop of weight 1 can account for
+    max_edits_per_unit: usize,
+}
+
+impl Confusion {
+    /// Built-in table of OCR confusions (l↔1, o↔0, rn↔m, …)
+    pub fn ocr() -> Confusion {
+        Confusion::new(
+            OCR_CONFUSIONS
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string(), 1)),
+        )
+    }
+
+    /// Letters of the same Soundex class and Metaphone-equivalent spellings (ph↔f, ck↔k, …)
+    pub fn phonetic() -> Confusion {
+        let classes = SOUNDEX_CLASSES.iter().flat_map(|class| {
+            let letters: Vec<char> = class.chars().collect();
+            (0..letters.len()).flat_map(move |i| {
+                let letters = letters.clone();
+                (i + 1..letters.len())
+                    .map(move |j| (letters[i].to_string(), letters[j].to_string(), 1))
+            })
+        });
+        let digraphs = PHONETIC_DIGRAPHS
+            .iter()
+            .map(|(from, to)| (from.to_string(), to.to_string(), 1));
+        Confusion::new(classes.chain(digraphs))
+    }
+
+    /// Confusions apply in both directions
+    pub fn new(confusions: impl IntoIterator<Item = (String, String, usize)>) -> Confusion {
+        let mut substitutions = Vec::new();
+        for (from, to, weight) in confusions {
+            let from: Vec<char> = from.chars().collect();
+            let to: Vec<char> = to.chars().collect();
+            substitutions.push(Substitution {
+                from: from.clone(),
+                to: to.clone(),
+                weight,
+            });
+            substitutions.push(Substitution {
+                from: to,
+                to: from,
+                weight,
+            });
+        }
+        let max_edits_per_unit = substitutions
+            .iter()
+            .filter(|s| s.weight == 1)
+            .map(|s| s.from.len().max(s.to.len()))
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        Confusion {
+            substitutions,
+            max_edits_per_unit,
+        }
+    }
+
+    /// Read `from,to[,weight]` lines, weight defaults to 1 and must be 1 or 2
+    pub fn from_csv(reader: impl BufRead) -> io::Result<Confusion> {
+        let mut confusions = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, reason),
+                )
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let weight = match fields.get(2) {
+                None => 1,
+                Some(weight) => weight
+                    .parse()
+                    .map_err(|_| invalid("weight is not a number"))?,
+            };
+            match fields.as_slice() {
+                [from, to, ..] if !from.is_empty() && !to.is_empty() && fields.len() <= 3 => {
+                    if weight == 0 || weight > CONFUSION_EDIT_WEIGHT {
+                        return Err(invalid("weight must be 1 or 2"));
+                    }
+                    confusions.push((from.to_lowercase(), to.to_lowercase(), weight))
+                }
+                _ => return Err(invalid("expected from,to[,weight]")),
+            }
+        }
+        Ok(Confusion::new(confusions))
+    }
+}
+
+impl CostModel for Confusion {
+    fn distance(&self, w1: &str, w2: &str) -> usize {
+        weighted_edit_distance(w1, w2, CONFUSION_EDIT_WEIGHT, &self.substitutions)
+    }
+
+    // Hops weighing more than 1 outrank any number of 1-letter hops, and a hop of weight 1
+    // is a single confusion changing at most `max_edits_per_unit` letters in the Levenshtein sense
+    fn heuristic(&self, word: &str, stop: &str) -> usize {
+        let edits = levenshtein(word, stop);
+        edits.div_ceil(self.max_edits_per_unit)
+    }
+
+    fn length_heuristic(&self, word: &str, stop: &str) -> usize {
+        length_difference(word, stop).div_ceil(self.max_edits_per_unit)
+    }
+
+    // Confusions apply in both directions with the same weight
+    fn symmetric(&self) -> bool {
+        true
+    }
+}
+
+/// Edit distance weighted per letter by a user-supplied matrix
+#[derive(Debug, Clone)]
+pub struct CostMatrix {
+    weights: EditWeights,
+}
+
+impl CostMatrix {
+    pub fn new(weights: EditWeights) -> CostMatrix {
+        CostMatrix { weights }
+    }
+
+    /// Read `from,to,weight` lines: an empty `from` is an insertion, an empty `to` a deletion
+    /// and `*` sets the default weight of the operation, e.g. `*,*,2` for substitutions.
+    /// Lines starting with `#` are comments. Weights are whole numbers of at least 1.
+    pub fn from_csv(reader: impl BufRead) -> io::Result<CostMatrix> {
+        let mut weights = EditWeights::default();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, reason),
+                )
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let (from, to, weight) = match fields.as_slice() {
+                [from, to, weight] => (*from, *to, *weight),
+                _ => return Err(invalid("expected from,to,weight")),
+            };
+            let weight: usize = weight
+                .parse()
+                .map_err(|_| invalid("weight is not a number"))?;
+            if weight == 0 {
+                return Err(invalid("weight must be at least 1"));
+            }
+            let letter = |field: &str| {
+                let mut chars = field.chars().flat_map(char::to_lowercase);
+                match (chars.next(), chars.next()) {
+                    (Some(letter), None) => Ok(letter),
+                    _ => Err(invalid("expected a single letter, * or nothing")),
+                }
+            };
+            match (from, to) {
+                ("", "") => return Err(invalid("from and to cannot both be empty")),
+                ("", "*") => weights.default_insertion = weight,
+                ("*", "") => weights.default_deletion = weight,
+                ("*", "*") => weights.default_substitution = weight,
+                ("", to) => {
+                    weights.insertions.insert(letter(to)?, weight);
+                }
+                (from, "") => {
+                    weights.deletions.insert(letter(from)?, weight);
+                }
+                (from, to) => {
+                    weights
+                        .substitutions
+                        .insert((letter(from)?, letter(to)?), weight);
+                }
+            }
+        }
+        Ok(CostMatrix::new(weights))
+    }
+
+    /// Cheapest edits turning `w1` into `w2` with the weights of this matrix
+    pub fn alignment(&self, w1: &str, w2: &str) -> Vec<Edit> {
+        matrix_alignment(w1, w2, &self.weights)
+    }
+}
+
+impl CostModel for CostMatrix {
+    fn distance(&self, w1: &str, w2: &str) -> usize {
+        matrix_edit_distance(w1, w2, &self.weights)
+    }
+
+    // Every operation weighs at least 1, so a hop weighing 1 is a single edit and
+    // Levenshtein counts the minimum number of such hops. When even the lightest
+    // operation weighs more, no hop can weigh 1 and only 0 stays a lower bound.
+    fn heuristic(&self, word: &str, stop: &str) -> usize {
+        match self.weights.min_weight() {
+            1 => levenshtein(word, stop),
+            _ => 0,
+        }
+    }
+
+    fn length_heuristic(&self, word: &str, stop: &str) -> usize {
+        match self.weights.min_weight() {
+            1 => length_difference(word, stop),
+            _ => 0,
+        }
+    }
+
+    // Every operation weighing at least 1, a path of weight n makes at least n edits
+    fn bounded_by_edits(&self) -> bool {
+        true
+    }
+
+    fn symmetric(&self) -> bool {
+        self.weights.symmetric()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ocr_confusions_are_cheaper() {
+        let ocr = Confusion::ocr();
+        assert_eq!(ocr.distance("modern", "modem"), 1);
+        assert_eq!(ocr.distance("modem", "modern"), 1);
+        assert_eq!(ocr.distance("hello", "he11o"), 2);
+        assert_eq!(ocr.distance("hello", "hallo"), 2);
+        assert_eq!(ocr.distance("hello", "hello"), 0);
+    }
+
+    #[test]
+    fn ocr_heuristic_is_admissible() {
+        let ocr = Confusion::ocr();
+        assert_eq!(ocr.heuristic("modern", "modem"), 1);
+        assert!(ocr.heuristic("clown", "down") <= ocr.distance("clown", "down"));
+    }
+
+    #[test]
+    fn phonetic_confusions_are_cheaper() {
+        let phonetic = Confusion::phonetic();
+        assert_eq!(phonetic.distance("phone", "fone"), 1);
+        assert_eq!(phonetic.distance("cat", "kat"), 1);
+        assert_eq!(phonetic.distance("bat", "bad"), 1);
+        assert_eq!(phonetic.distance("bat", "bit"), 1);
+        assert_eq!(phonetic.distance("bat", "rat"), 2);
+        assert_eq!(phonetic.heuristic("school", "skool"), 1);
+    }
+
+    #[test]
+    fn confusions_from_csv() {
+        let csv = "ph,f\n\nc, k ,2\n";
+        let model = Confusion::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(model.distance("phone", "fone"), 1);
+        assert_eq!(model.distance("cat", "kat"), 2);
+        assert!(Confusion::from_csv("a,b,3".as_bytes()).is_err());
+        assert!(Confusion::from_csv("a".as_bytes()).is_err());
+        assert!(Confusion::from_csv("a,b,x".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn tokens_count_whole_words() {
+        assert_eq!(Tokens.distance("the cat sat", "the bat sat"), 1);
+        assert_eq!(Tokens.distance("the cat", "the cat sat down"), 2);
+        assert_eq!(Unit::from_str("token"), Ok(Unit::Token));
+        assert_eq!(Unit::Token.noun(), "word");
+    }
+
+    #[test]
+    fn cost_matrix_from_csv() {
+        let csv = "# vowels are cheap\na,e,1\n*,*,3\n,*,2\nh,,1\n";
+        let matrix = CostMatrix::from_csv(csv.as_bytes()).unwrap();
+        assert_eq!(matrix.distance("cat", "cet"), 1);
+        assert_eq!(matrix.distance("cat", "cot"), 3);
+        assert_eq!(matrix.distance("cat", "cats"), 2);
+        assert_eq!(matrix.distance("chat", "cat"), 1);
+        assert!(CostMatrix::from_csv("a,b".as_bytes()).is_err());
+        assert!(CostMatrix::from_csv("a,b,0".as_bytes()).is_err());
+        assert!(CostMatrix::from_csv("ab,c,1".as_bytes()).is_err());
+        assert!(CostMatrix::from_csv(",,1".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn cost_matrix_symmetry_is_detected() {
+        let symmetric = CostMatrix::from_csv("a,e,1\ne,a,1\nh,,2\n,h,2\n".as_bytes()).unwrap();
+        assert!(symmetric.symmetric());
+        let words = ["cat", "cet", "chat", "hat"];
+        assert_eq!(probe_symmetry(&symmetric, &words[..]), Ok(()));
+        for csv in ["a,e,2\n", "h,,2\n", ",*,2\n"] {
+            let asymmetric = CostMatrix::from_csv(csv.as_bytes()).unwrap();
+            assert!(!asymmetric.symmetric(), "{}", csv);
+            assert_eq!(
+                probe_symmetry(&asymmetric, &words[..]),
+                Err(AsymmetricCosts::Undeclared)
+            );
+        }
+        assert!(Confusion::ocr().symmetric());
+    }
+
+    #[test]
+    fn false_symmetry_claims_are_caught() {
+        // Going down the alphabet costs more
+        struct Downhill;
+
+        impl CostModel for Downhill {
+            fn distance(&self, w1: &str, w2: &str) -> usize {
+                match w1.cmp(w2) {
+                    std::cmp::Ordering::Less => 1,
+                    std::cmp::Ordering::Equal => 0,
+                    std::cmp::Ordering::Greater => 2,
+                }
+            }
+
+            fn heuristic(&self, _word: &str, _stop: &str) -> usize {
+                0
+            }
+
+            fn symmetric(&self) -> bool {
+                true
+            }
+        }
+
+        let error = probe_symmetry(&Downhill, &["cat", "cot"][..]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the cost model claims hops cost the same both ways, but cat to cot costs 1 and cot to cat costs 2"
+        );
+        assert_eq!(probe_symmetry(&Levenshtein, &["cat", "cot"][..]), Ok(()));
+    }
+
+    #[test]
+    fn cost_matrix_heuristic_without_unit_weight() {
+        let matrix = CostMatrix::from_csv("*,*,2\n,*,2\n*,,2".as_bytes()).unwrap();
+        assert_eq!(matrix.distance("cat", "cot"), 2);
+        assert_eq!(matrix.heuristic("cat", "cot"), 0);
+    }
+
+    use quickcheck::quickcheck;
+
+    quickcheck! {
+        fn ocr_heuristic_prop(a: String, b: String) -> bool {
+            let ocr = Confusion::ocr();
+            ocr.heuristic(&a, &b) <= ocr.distance(&a, &b)
+        }
+        fn phonetic_heuristic_prop(a: String, b: String) -> bool {
+            let phonetic = Confusion::phonetic();
+            phonetic.heuristic(&a, &b) <= phonetic.distance(&a, &b)
+        }
+        fn length_heuristic_prop(a: String, b: String) -> bool {
+            let ocr = Confusion::ocr();
+            Levenshtein.length_heuristic(&a, &b) <= Levenshtein.heuristic(&a, &b)
+                && ocr.length_heuristic(&a, &b) <= ocr.heuristic(&a, &b)
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::distance::cost::CostModel;
+use rhai::{CallFnOptions, Engine, Scope, AST};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+// Memoized costs are dropped all at once past this size to bound memory usage
+const CACHE_CAPACITY: usize = 1 << 20;
+
+/// Cost computed by a Rhai script defining `fn cost(w1, w2)`
+pub struct ScriptCost {
+    engine: Engine,
+    ast: AST,
+    cache: Mutex<HashMap<(String, String), usize>>,
+}
+
+impl ScriptCost {
+    pub fn new(source: &str) -> io::Result<ScriptCost> {
+        let engine = sandboxed_engine();
+        let ast = engine
+            .compile(source)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == "cost" && function.params.len() == 2)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "script must define fn cost(w1, w2)",
+            ));
+        }
+        Ok(ScriptCost {
+            engine,
+            ast,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<ScriptCost> {
+        ScriptCost::new(&fs::read_to_string(path)?)
+    }
+
+    fn evaluate(&self, w1: &str, w2: &str) -> usize {
+        let options = CallFnOptions::new().eval_ast(false);
+        let result = self.engine.call_fn_with_options::<rhai::INT>(
+            options,
+            &mut Scope::new(),
+            &self.ast,
+            "cost",
+            (w1.to_string(), w2.to_string()),
+        );
+        // A failing or negative cost makes the hop as heavy as possible rather than aborting the search
+        match result {
+            Ok(cost) if cost >= 0 => (cost as usize).max(1),
+            _ => usize::MAX,
+        }
+    }
+}
+
+// No module loading, no output and bounded work so a script cannot touch the
+// system or hang the search
+fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_modules(0)
+        .set_max_operations(100_000)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(4096)
+        .set_max_array_size(4096)
+        .set_max_map_size(4096)
+        .on_print(|_| ())
+        .on_debug(|_, _, _| ());
+    engine
+}
+
+impl CostModel for ScriptCost {
+    fn distance(&self, w1: &str, w2: &str) -> usize {
+        if w1 == w2 {
+            return 0;
+        }
+        let key = (w1.to_string(), w2.to_string());
+        if let Some(cost) = self.cache.lock().unwrap().get(&key) {
+            return *cost;
+        }
+        let cost = self.evaluate(w1, w2);
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(key, cost);
+        cost
+    }
+
+    // Nothing is known about the script, 0 is the only safe bound
+    fn heuristic(&self, _word: &str, _stop: &str) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_defines_cost() {
+        let script =
+            ScriptCost::new("fn cost(w1, w2) { if w1.len() == w2.len() { 1 } else { 3 } }")
+                .unwrap();
+        assert_eq!(script.distance("cat", "cat"), 0);
+        assert_eq!(script.distance("cat", "cot"), 1);
+        assert_eq!(script.distance("cat", "cats"), 3);
+        assert_eq!(script.cache.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn script_without_cost_is_rejected() {
+        assert!(ScriptCost::new("fn other(w1, w2) { 1 }").is_err());
+        assert!(ScriptCost::new("fn cost(w1) { 1 }").is_err());
+        assert!(ScriptCost::new("fn cost(w1, w2) {").is_err());
+    }
+
+    #[test]
+    fn runaway_script_is_stopped() {
+        let script = ScriptCost::new("fn cost(w1, w2) { loop {} }").unwrap();
+        assert_eq!(script.distance("cat", "cot"), usize::MAX);
+        let script = ScriptCost::new("fn cost(w1, w2) { -1 }").unwrap();
+        assert_eq!(script.distance("cat", "cot"), usize::MAX);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::distance::cost::{CostModel, Levenshtein};
+
+/// Words a search goes through, whatever holds them
+pub trait Dictionary {
+    /// Every word, always in the same order
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_>;
+
+    fn contains(&self, word: &str) -> bool {
+        self.iter().any(|candidate| candidate == word)
+    }
+
+    /// Words other than `word` at most `k` letters away from it. Scans every word unless
+    /// the backend indexes them better.
+    fn neighbors_within(&self, word: &str, k: usize) -> Vec<&str> {
+        let mut neighbors = Vec::new();
+        self.extend_neighbors(word, k, &mut neighbors);
+        neighbors
+    }
+
+    /// Appends the words `neighbors_within` returns to `neighbors`, so searches can reuse
+    /// the same buffer for every word they expand
+    fn extend_neighbors<'d>(&'d self, word: &str, k: usize, neighbors: &mut Vec<&'d str>) {
+        neighbors.extend(
+            self.iter().filter(|&candidate| {
+                candidate != word && Levenshtein.distance(word, candidate) <= k
+            }),
+        );
+    }
+}
+
+impl Dictionary for [&str] {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(<[&str]>::iter(self).copied())
+    }
+}
+
+impl Dictionary for [String] {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(<[String]>::iter(self).map(String::as_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vectors_scan_their_words() {
+        let words = &["cold", "cord", "card", "colder"][..];
+        assert!(Dictionary::contains(words, "card"));
+        assert!(!Dictionary::contains(words, "warm"));
+        assert_eq!(words.neighbors_within("cold", 1), vec!["cord"]);
+        assert_eq!(
+            words.neighbors_within("cold", 2),
+            vec!["cord", "card", "colder"]
+        );
+        let owned: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        assert_eq!(Dictionary::iter(owned.as_slice()).count(), 4);
+        let mut neighbors = vec!["warm"];
+        words.extend_neighbors("cold", 1, &mut neighbors);
+        assert_eq!(neighbors, vec!["warm", "cord"]);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::messages::Locale;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// How hard finding a shortest ladder of 1-letter hops between two words is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    /// Hops of the shortest ladders
+    pub hops: usize,
+    /// Words 1 letter away from the words of the shortest ladders, on average: the
+    /// choices faced at every step
+    pub branching: f64,
+    /// Number of different shortest ladders, saturating at `u64::MAX`
+    pub optimal_paths: u64,
+    /// From 1, the easiest, to 10
+    pub rating: u8,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/10 ({}, {:.1} choices per step, {})",
+            self.rating,
+            Locale::En.count(self.hops, "hop", "hops"),
+            self.branching,
+            Locale::En.count(self.optimal_paths, "shortest ladder", "shortest ladders")
+        )
+    }
+}
+
+// Hops from `from` to every word at most as far as `to`, or at most `limit` hops away
+// when there is none, with the number of shortest ladders leading to each word. Words
+// one layer further may be listed too.
+pub(crate) fn layers<'a, D: Dictionary + ?Sized>(
+    from: &'a str,
+    to: Option<&str>,
+    mut limit: usize,
+    words: &'a D,
+) -> HashMap<&'a str, (usize, u64)> {
+    let mut reached = HashMap::new();
+    reached.insert(from, (0, 1u64));
+    let mut queue = VecDeque::from(vec![from]);
+    let mut neighbors = Vec::new();
+    while let Some(word) = queue.pop_front() {
+        let (hops, paths) = reached[word];
+        // Words are taken layer by layer, every ladder to `to` is counted by now
+        if to == Some(word) {
+            limit = hops;
+        }
+        if hops >= limit {
+            continue;
+        }
+        neighbors.clear();
+        words.extend_neighbors(word, 1, &mut neighbors);
+        for &next in &neighbors {
+            match reached.get_mut(next) {
+                Some((next_hops, next_paths)) if *next_hops == hops + 1 => {
+                    *next_paths = next_paths.saturating_add(paths)
+                }
+                Some(_) => (),
+                None => {
+                    reached.insert(next, (hops + 1, paths));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    reached
+}
+
+/// Difficulty of going from `start` to `stop` by changing a letter at a time, `None` when
+/// no ladder links them. Long ladders are hard, more so with many words to choose from at
+/// every step, and less so when many ladders are as short.
+pub fn rate_difficulty<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &'a str,
+    words: &'a D,
+) -> Option<Difficulty> {
+    let forward = layers(start, Some(stop), usize::MAX, words);
+    let &(hops, optimal_paths) = forward.get(stop)?;
+    let backward = layers(stop, Some(start), hops, words);
+    // Words of some shortest ladder, but the last one where no choice is left
+    let choices: Vec<usize> = forward
+        .iter()
+        .filter(|(word, &(from_start, _))| {
+            from_start < hops
+                && backward
+                    .get(*word)
+                    .is_some_and(|&(to_stop, _)| from_start + to_stop == hops)
+        })
+        .map(|(word, _)| words.neighbors_within(word, 1).len())
+        .collect();
+    let branching = match choices.len() {
+        0 => 0.0,
+        count => choices.iter().sum::<usize>() as f64 / count as f64,
+    };
+    let score = hops as f64 + branching.max(1.0).log2() - (optimal_paths as f64).log2() / 2.0;
+    Some(Difficulty {
+        hops,
+        branching,
+        optimal_paths,
+        rating: score.round().clamp(1.0, 10.0) as u8,
+    })
+}
+
+/// Every word a ladder of at most `max_hops` 1-letter hops leads to from `start`, with
+/// the hops of the shortest one, in alphabetical order
+pub fn find_ladder_hops<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    words: &'a D,
+    max_hops: usize,
+) -> Vec<(&'a str, usize)> {
+    let mut reached: Vec<(&str, usize)> = layers(start, None, max_hops, words)
+        .into_iter()
+        .filter(|&(word, (hops, _))| word != start && hops <= max_hops)
+        .map(|(word, (hops, _))| (word, hops))
+        .collect();
+    reached.sort_unstable();
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ladders_are_rated() {
+        let words = ["cold", "cord", "card", "ward", "warm", "bold", "bord"];
+        let difficulty = rate_difficulty("cold", "warm", &words[..]).unwrap();
+        assert_eq!(difficulty.hops, 4);
+        assert_eq!(difficulty.optimal_paths, 1);
+        // cold has cord and bold, cord has cold, card and bord, card and ward have 2
+        assert_eq!(difficulty.branching, 2.25);
+        assert_eq!(difficulty.rating, 5);
+        assert_eq!(
+            difficulty.to_string(),
+            "5/10 (4 hops, 2.2 choices per step, 1 shortest ladder)"
+        );
+
+        // Through bold or through cord
+        let difficulty = rate_difficulty("cold", "bord", &words[..]).unwrap();
+        assert_eq!((difficulty.hops, difficulty.optimal_paths), (2, 2));
+        let difficulty = rate_difficulty("cold", "cold", &words[..]).unwrap();
+        assert_eq!((difficulty.hops, difficulty.rating), (0, 1));
+        assert_eq!(rate_difficulty("cold", "zzzz", &words[..]), None);
+        assert_eq!(
+            find_ladder_hops("ward", &words[..], usize::MAX),
+            vec![
+                ("bold", 4),
+                ("bord", 3),
+                ("card", 1),
+                ("cold", 3),
+                ("cord", 2),
+                ("warm", 1)
+            ]
+        );
+        assert_eq!(find_ladder_hops("ward", &words[..], 1).len(), 2);
+    }
+}
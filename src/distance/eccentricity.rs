@@ -0,0 +1,383 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{Interruption, SearchOptions, SearchStats};
+use num_traits::Zero;
+use pathfinding::directed::dijkstra::{build_path, dijkstra_all};
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Words the most expensive to reach from a given one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Farthest<'a> {
+    /// Shortest path to each of the farthest words
+    pub paths: Vec<Vec<&'a str>>,
+    /// Cost of every one of these paths
+    pub cost: PathMultiCost<EditDistance>,
+    /// Words of the dictionary no path leads to
+    pub unreachable: usize,
+}
+
+// Every word reached from `start` with the word it was reached from and its cost
+type Reached<'a> = HashMap<&'a str, (&'a str, PathMultiCost<EditDistance>)>;
+
+/// A word with the cost of the shortest path leading to it
+pub type WordCost<'a> = (&'a str, PathMultiCost<EditDistance>);
+
+/// Seed a word is the closest to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Assignment<'a> {
+    pub word: &'a str,
+    pub seed: &'a str,
+    /// Cost of the shortest path from the seed to the word
+    pub cost: PathMultiCost<EditDistance>,
+}
+
+/// Words of a dictionary split by the seed they are the closest to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partition<'a> {
+    /// Every word some seed leads to, in the order of the dictionary
+    pub assignments: Vec<Assignment<'a>>,
+    /// Words no seed leads to
+    pub unreachable: Vec<&'a str>,
+}
+
+// Words one hop away from `current` and the cost of that hop, `max_hop_distance` letters
+// at most if `options` sets it
+fn hops_from<'a, D: Dictionary + ?Sized>(
+    current: &str,
+    words: &'a D,
+    options: &SearchOptions,
+    candidates: &mut Vec<&'a str>,
+) -> Vec<(&'a str, PathMultiCost<EditDistance>)> {
+    let cost_model = options.cost_model.as_ref();
+    candidates.clear();
+    match options.max_hop_distance {
+        Some(k) if cost_model.bounded_by_edits() => words.extend_neighbors(current, k, candidates),
+        _ => candidates.extend(words.iter()),
+    }
+    candidates
+        .iter()
+        .filter_map(|&next| {
+            let distance = cost_model.distance(current, next);
+            match next == current || options.max_hop_distance.is_some_and(|k| distance > k) {
+                true => None,
+                false => Some((next, options.hop_cost(distance))),
+            }
+        })
+        .collect()
+}
+
+// Dijkstra from `start` to every word of the dictionary at once, through hops of
+// `max_hop_distance` letters at most if `options` sets it
+fn reach<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<Reached<'a>, Interruption>, SearchStats) {
+    let expanded = Cell::new(0);
+    let interruption = Cell::new(None);
+    let mut candidates = Vec::new();
+    let reached = dijkstra_all(&start, |&current: &&'a str| {
+        expanded.set(expanded.get() + 1);
+        if interruption.get().is_none() {
+            interruption.set(options.control.check(expanded.get()));
+        }
+        match interruption.get() {
+            Some(_) => Vec::new(),
+            None => hops_from(current, words, options, &mut candidates),
+        }
+    });
+    let stats = SearchStats {
+        expanded: expanded.get(),
+        saturated: reached.values().any(|(_, cost)| cost.is_saturated()),
+        backward: false,
+        recycled: 0,
+        memory_fallback: false,
+    };
+    match interruption.get() {
+        Some(reason) => (Err(reason), stats),
+        None => (Ok(reached), stats),
+    }
+}
+
+/// Cost of the shortest path from `start` to every word it leads to, through hops of
+/// `max_hop_distance` letters at most if `options` sets it, looked up in an index when
+/// `words` is one.
+/// The algorithm of `options` is ignored, this is always Dijkstra.
+pub fn find_costs_from<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (
+    Result<HashMap<&'a str, PathMultiCost<EditDistance>>, Interruption>,
+    SearchStats,
+) {
+    let (reached, stats) = reach(start, words, options);
+    let costs = reached.map(|reached| {
+        reached
+            .into_iter()
+            .map(|(word, (_, cost))| (word, cost))
+            .collect()
+    });
+    (costs, stats)
+}
+
+/// Hops of the cheapest path from `start` to every word it leads to, counted along the
+/// path: costs lump hops of `MAX_DIMENSION` letters or more together by their letters.
+/// The algorithm of `options` is ignored, this is always Dijkstra.
+pub fn find_hops_from<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<HashMap<&'a str, usize>, Interruption>, SearchStats) {
+    let (reached, stats) = reach(start, words, options);
+    let hops = reached.map(|reached| {
+        reached
+            .keys()
+            .map(|&word| (word, build_path(&word, &reached).len() - 1))
+            .collect()
+    });
+    (hops, stats)
+}
+
+/// Search from `start` to every word of the dictionary at once and keep the farthest ones.
+/// The algorithm of `options` is ignored, this is always Dijkstra.
+pub fn find_farthest_words<'a>(
+    start: &'a str,
+    words: &'a [&str],
+    options: &SearchOptions,
+) -> (Result<Farthest<'a>, Interruption>, SearchStats) {
+    let (reached, stats) = reach(start, words, options);
+    let reached = match reached {
+        Ok(reached) => reached,
+        Err(reason) => return (Err(reason), stats),
+    };
+    let cost = reached
+        .values()
+        .map(|&(_, cost)| cost)
+        .max()
+        .unwrap_or_else(PathMultiCost::zero);
+    let mut farthest: Vec<&str> = reached
+        .iter()
+        .filter(|(_, &(_, reached_cost))| reached_cost == cost)
+        .map(|(&word, _)| word)
+        .collect();
+    farthest.sort_unstable();
+    let dictionary: HashSet<&str> = words.iter().copied().filter(|&w| w != start).collect();
+    let unreachable = dictionary
+        .iter()
+        .filter(|word| !reached.contains_key(*word))
+        .count();
+    let paths = farthest
+        .iter()
+        .map(|word| build_path(word, &reached))
+        .collect();
+    (
+        Ok(Farthest {
+            paths,
+            cost,
+            unreachable,
+        }),
+        stats,
+    )
+}
+
+/// Every word other than `start` whose shortest path from it costs at most `budget`,
+/// cheapest first then in alphabetical order. Hops are limited to `max_hop_distance`
+/// letters if `options` sets it, its algorithm is ignored: this is always Dijkstra,
+/// stopped at the budget.
+pub fn find_reachable<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    words: &'a D,
+    budget: PathMultiCost<EditDistance>,
+    options: &SearchOptions,
+) -> (Result<Vec<WordCost<'a>>, Interruption>, SearchStats) {
+    let mut stats = SearchStats::default();
+    let mut best: HashMap<&'a str, PathMultiCost<EditDistance>> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    best.insert(start, PathMultiCost::zero());
+    open.push(Reverse((PathMultiCost::zero(), start)));
+    let mut candidates = Vec::new();
+    while let Some(Reverse((cost, current))) = open.pop() {
+        // Queued again since at a lower cost
+        if best[current] < cost {
+            continue;
+        }
+        stats.expanded += 1;
+        if let Some(reason) = options.control.check(stats.expanded) {
+            return (Err(reason), stats);
+        }
+        for (next, hop) in hops_from(current, words, options, &mut candidates) {
+            let through = cost + hop;
+            if through > budget || best.get(next).is_some_and(|&known| known <= through) {
+                continue;
+            }
+            best.insert(next, through);
+            open.push(Reverse((through, next)));
+        }
+    }
+    let mut reachable: Vec<WordCost<'a>> = best
+        .into_iter()
+        .filter(|&(word, _)| word != start)
+        .collect();
+    reachable.sort_unstable_by(|(w1, c1), (w2, c2)| c1.cmp(c2).then(w1.cmp(w2)));
+    stats.saturated = reachable.iter().any(|(_, cost)| cost.is_saturated());
+    (Ok(reachable), stats)
+}
+
+/// Assign every word of `words` to the seed the cheapest path leads from, searching from
+/// all of them at once. Ties go to the seed listed first. Hops are limited to
+/// `max_hop_distance` letters if `options` sets it, its algorithm is ignored: this is
+/// always Dijkstra.
+pub fn find_partition<'a, D: Dictionary + ?Sized>(
+    seeds: &[&'a str],
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<Partition<'a>, Interruption>, SearchStats) {
+    let mut stats = SearchStats::default();
+    // Cheapest cost known to every word reached, with the position of its seed
+    let mut best: HashMap<&'a str, (PathMultiCost<EditDistance>, usize)> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    for (position, &seed) in seeds.iter().enumerate() {
+        if !best.contains_key(seed) {
+            best.insert(seed, (PathMultiCost::zero(), position));
+            open.push(Reverse((PathMultiCost::zero(), position, seed)));
+        }
+    }
+    let mut candidates = Vec::new();
+    while let Some(Reverse((cost, position, current))) = open.pop() {
+        // Queued again since at a lower cost or from an earlier seed
+        if best[current] != (cost, position) {
+            continue;
+        }
+        stats.expanded += 1;
+        if let Some(reason) = options.control.check(stats.expanded) {
+            return (Err(reason), stats);
+        }
+        for (next, hop) in hops_from(current, words, options, &mut candidates) {
+            let through = (cost + hop, position);
+            if best.get(next).is_some_and(|&known| known <= through) {
+                continue;
+            }
+            best.insert(next, through);
+            open.push(Reverse((through.0, position, next)));
+        }
+    }
+    let mut partition = Partition {
+        assignments: Vec::new(),
+        unreachable: Vec::new(),
+    };
+    for word in words.iter() {
+        match best.get(word) {
+            Some(&(cost, position)) => partition.assignments.push(Assignment {
+                word,
+                seed: seeds[position],
+                cost,
+            }),
+            None => partition.unreachable.push(word),
+        }
+    }
+    stats.saturated = partition
+        .assignments
+        .iter()
+        .any(|assignment| assignment.cost.is_saturated());
+    (Ok(partition), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::PathFindingAlgorithm;
+
+    #[test]
+    fn farthest_words_and_their_paths() {
+        let words = ["cold", "cord", "card", "ward", "warm", "word"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Dijkstra);
+        let (farthest, stats) = find_farthest_words("cold", &words, &options);
+        let farthest = farthest.unwrap();
+        assert_eq!(
+            farthest.paths,
+            vec![vec!["cold", "cord", "card", "ward", "warm"]]
+        );
+        assert_eq!(farthest.cost.get_cost(), vec![(4, 1)]);
+        assert_eq!(farthest.unreachable, 0);
+        assert_eq!(stats.expanded, words.len());
+        let (costs, _) = find_costs_from("cold", &words[..], &options);
+        let costs = costs.unwrap();
+        assert_eq!(costs.len(), words.len() - 1);
+        assert_eq!(costs["card"].get_cost(), vec![(2, 1)]);
+        let words = ["cold", "cord", "zzzz"];
+        let (costs, _) = find_costs_from("cold", &words[..], &options.with_max_hop_distance(1));
+        assert_eq!(costs.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn words_within_a_budget() {
+        let words = ["cold", "cord", "card", "ward", "warm", "word", "scold"];
+        let reachable = |budget: &str, options: &SearchOptions| {
+            let (reachable, _) =
+                find_reachable("cold", &words[..], budget.parse().unwrap(), options);
+            let reachable = reachable.unwrap();
+            reachable
+                .into_iter()
+                .map(|(word, _)| word)
+                .collect::<Vec<_>>()
+        };
+        let options = SearchOptions::new(PathFindingAlgorithm::Dijkstra);
+        assert_eq!(
+            reachable("2 one-letter mutations", &options),
+            vec!["cord", "scold", "card", "word"]
+        );
+        assert_eq!(reachable("0 mutations", &options), Vec::<&str>::new());
+        // Any 1-letter hops are cheaper than a single 2-letter one
+        assert_eq!(reachable("1 2-letter mutation", &options).len(), 6);
+        let options = options.with_max_hop_distance(1);
+        assert_eq!(reachable("1 2-letter mutation", &options).len(), 6);
+        assert_eq!(
+            reachable("1 1-letter mutation", &options),
+            vec!["cord", "scold"]
+        );
+    }
+
+    #[test]
+    fn words_go_to_their_closest_seed() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "word", "bold", "zzzz",
+        ];
+        let options = SearchOptions::new(PathFindingAlgorithm::Dijkstra).with_max_hop_distance(1);
+        let (partition, stats) = find_partition(&["cold", "warm", "cold"], &words[..], &options);
+        let partition = partition.unwrap();
+        let seeds: Vec<(&str, &str)> = partition
+            .assignments
+            .iter()
+            .map(|assignment| (assignment.word, assignment.seed))
+            .collect();
+        // card is two hops from both, the first seed wins
+        assert_eq!(
+            seeds,
+            vec![
+                ("cold", "cold"),
+                ("cord", "cold"),
+                ("card", "cold"),
+                ("ward", "warm"),
+                ("warm", "warm"),
+                ("word", "cold"),
+                ("bold", "cold"),
+            ]
+        );
+        assert_eq!(partition.assignments[2].cost.get_cost(), vec![(2, 1)]);
+        assert_eq!(partition.unreachable, vec!["zzzz"]);
+        assert_eq!(stats.expanded, 7);
+    }
+
+    #[test]
+    fn lonely_word() {
+        let options = SearchOptions::new(PathFindingAlgorithm::Dijkstra);
+        let (farthest, _) = find_farthest_words("cold", &["cold"], &options);
+        let farthest = farthest.unwrap();
+        assert!(farthest.paths.is_empty());
+        assert_eq!(farthest.unreachable, 0);
+    }
+}
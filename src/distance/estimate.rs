@@ -0,0 +1,193 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::{Direction, PathFindingAlgorithm, SearchOptions};
+use std::fmt;
+use std::time::Duration;
+
+/// Words a search compares per second, roughly, turning comparisons into time
+pub const COMPARISONS_PER_SECOND: f64 = 1e7;
+
+/// Searches expected to run longer than this are worth a warning
+pub const INTRACTABLE: Duration = Duration::from_secs(10 * 60);
+
+/// What a search is expected to go through, measured before running it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub words: usize,
+    /// Letters a hop may change, or without a limit the fewest letters both ends are
+    /// from other words, 1 at least
+    pub radius: usize,
+    /// Neighbours within `radius` letters of each end
+    pub start_degree: usize,
+    pub stop_degree: usize,
+    /// Letters between both ends as the cost model counts them: the fewest hops needed
+    /// times `radius`, and what the heuristic starts from
+    pub gap: usize,
+    /// Words expanded, `algorithm` repeating expansions or not
+    pub expanded: f64,
+    /// Pairs of words compared, against every word without an index
+    pub comparisons: f64,
+    pub algorithm: PathFindingAlgorithm,
+}
+
+impl Estimate {
+    /// Time the comparisons take at `COMPARISONS_PER_SECOND`
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64((self.comparisons / COMPARISONS_PER_SECOND).min(u64::MAX as f64))
+    }
+
+    pub fn intractable(&self) -> bool {
+        self.duration() > INTRACTABLE
+    }
+}
+
+// Rounded to a unit a person reads at a glance
+fn human(duration: Duration) -> String {
+    match duration.as_secs() {
+        0 => "under a second".to_string(),
+        1 => "1 second".to_string(),
+        seconds @ 2..=119 => format!("{} seconds", seconds),
+        seconds @ 120..=7199 => format!("{} minutes", seconds / 60),
+        seconds @ 7200..=172_799 => format!("{} hours", seconds / 3600),
+        seconds => format!("{} days", seconds / 86400),
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} letters apart with {} and {} neighbours within {} letter{}, {} may expand about {:.0} of {} words and make {:.0} comparisons, {}",
+            self.gap,
+            self.start_degree,
+            self.stop_degree,
+            self.radius,
+            if self.radius == 1 { "" } else { "s" },
+            self.algorithm,
+            self.expanded,
+            self.words,
+            self.comparisons,
+            human(self.duration())
+        )
+    }
+}
+
+/// Expected cost of searching from `start` to `stop` through `words` with `options`.
+/// Searches expand the words of a tree of `b^d` leaves, `b` being the neighbours of the ends on average and
+/// `d` the hops needed, up to every word for algorithms expanding a word at most once;
+/// every expansion compares the word with its neighbours in an index, or with every word.
+pub fn estimate_search<D: Dictionary + ?Sized>(
+    start: &str,
+    stop: &str,
+    words: &D,
+    options: &SearchOptions,
+) -> Estimate {
+    let size = words.iter().count();
+    let gap = options.cost_model.distance(start, stop);
+    let degree = |word, radius| words.neighbors_within(word, radius).len();
+    let mut radius = options.max_hop_distance().unwrap_or(1);
+    let (mut start_degree, mut stop_degree) = (degree(start, radius), degree(stop, radius));
+    // Without a limit, ends far from every word leave through bigger hops
+    while options.max_hop_distance().is_none()
+        && (start_degree == 0 || stop_degree == 0)
+        && radius < gap
+    {
+        radius += 1;
+        start_degree = degree(start, radius);
+        stop_degree = degree(stop, radius);
+    }
+    let hops = gap.div_ceil(radius) as i32;
+    let branching = match options.max_hop_distance() {
+        // Paths with bigger hops only come up once every cheaper one was tried, through
+        // any word
+        None if radius > 1 => size as f64,
+        _ => ((start_degree + stop_degree) as f64 / 2.0).max(1.0),
+    };
+    // Sampled searches only go on through some of the successors
+    let branching = match options.sampling() {
+        Some(sampling) => (branching * sampling.rate).max(1.0),
+        None => branching,
+    };
+    // Every layer of the tree of hops up to the end word
+    let tree = match branching {
+        b if b > 1.0 => (b.powi(hops + 1) - 1.0) / (b - 1.0),
+        _ => hops as f64 + 1.0,
+    };
+    let expanded = match options.algorithm() {
+        // Iterative deepening expands words again at every bound
+        PathFindingAlgorithm::Idastar | PathFindingAlgorithm::Fringe => tree,
+        PathFindingAlgorithm::IdastarTt => tree.min(size as f64 * hops as f64),
+        _ => tree.min(size as f64),
+    };
+    let indexed = options.max_hop_distance().is_some() && options.cost_model.bounded_by_edits();
+    let compared = if indexed { branching } else { size as f64 };
+    Estimate {
+        words: size,
+        radius,
+        start_degree,
+        stop_degree,
+        gap,
+        expanded,
+        comparisons: expanded * compared,
+        algorithm: options.algorithm(),
+    }
+}
+
+/// Options that would make a search of `estimate` with `options` cheaper, as flags with
+/// what they change
+pub fn suggest_options(estimate: &Estimate, options: &SearchOptions) -> Vec<String> {
+    let mut suggestions = Vec::new();
+    if options.max_hop_distance().is_none() {
+        suggestions.push(format!(
+            "--max-hop-distance {} compares every word with its neighbours in an index instead of with every word",
+            estimate.radius
+        ));
+    }
+    if estimate.expanded > estimate.words as f64 {
+        suggestions.push("astar as the algorithm expands every word at most once".to_string());
+    }
+    if options.direction == Direction::Forward && estimate.stop_degree < estimate.start_degree {
+        suggestions.push(
+            "--auto-direction searches back from the end word, which has fewer neighbours"
+                .to_string(),
+        );
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::ShardedWords;
+
+    #[test]
+    fn far_words_without_an_index_look_intractable() {
+        let words: Vec<String> = (0..100_000).map(|i| format!("w{:06}", i)).collect();
+        let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let estimate = estimate_search("w000000", "w099999", &words[..], &options);
+        assert_eq!((estimate.gap, estimate.words), (5, 100_000));
+        // 1 letter away from 9 others at every position but the first digit, always 0
+        assert_eq!((estimate.start_degree, estimate.stop_degree), (45, 45));
+        assert_eq!(estimate.expanded, 100_000.0);
+        assert!(estimate.intractable());
+        let suggestions = suggest_options(&estimate, &options);
+        assert!(suggestions[0].starts_with("--max-hop-distance 1"));
+
+        let indexed = ShardedWords::new(&words);
+        let options = options.with_max_hop_distance(1);
+        let estimate = estimate_search("w000000", "w099999", &indexed, &options);
+        assert!(!estimate.intractable());
+        assert_eq!(
+            estimate.to_string(),
+            "5 letters apart with 45 and 45 neighbours within 1 letter, astar may expand about 100000 of 100000 words and make 4500000 comparisons, under a second"
+        );
+
+        let deepening = SearchOptions::new(PathFindingAlgorithm::Idastar).with_max_hop_distance(1);
+        let estimate = estimate_search("w000000", "w099999", &indexed, &deepening);
+        assert!(estimate.intractable());
+        assert_eq!(
+            suggest_options(&estimate, &deepening),
+            vec!["astar as the algorithm expands every word at most once"]
+        );
+    }
+}
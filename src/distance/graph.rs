@@ -0,0 +1,158 @@
+use crate::distance::path::{PathMultiCost, MAX_DIMENSION};
+use crate::distance::{run_algorithm, Limits, PathFindingAlgorithm};
+use num_traits::Zero;
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+/// Weight of an edge or a path, one value per criterion
+pub type GraphCost = PathMultiCost<u32>;
+
+/// Directed graph read from a list of weighted edges
+#[derive(Debug, Default)]
+pub struct Graph {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    edges: Vec<Vec<(usize, GraphCost)>>,
+    criteria: usize,
+}
+
+impl Graph {
+    /// Read `from,to,weight[,weight…]` lines, all with the same number of weights.
+    /// When several weights are given the first one matters most, the next ones
+    /// only break ties. Lines starting with `#` are comments.
+    pub fn from_csv(reader: impl BufRead) -> io::Result<Graph> {
+        let mut graph = Graph::default();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", number + 1, reason),
+                )
+            };
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() < 3 || fields.len() - 2 > MAX_DIMENSION {
+                return Err(invalid(&format!(
+                    "expected from,to and between 1 and {} weights",
+                    MAX_DIMENSION
+                )));
+            }
+            let weights = fields[2..]
+                .iter()
+                .map(|weight| weight.parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(|_| invalid("weight is not a number"))?;
+            match graph.criteria {
+                0 => graph.criteria = weights.len(),
+                criteria if criteria != weights.len() => {
+                    return Err(invalid(&format!("expected {} weights", criteria)))
+                }
+                _ => (),
+            }
+            let from = graph.node(fields[0]);
+            let to = graph.node(fields[1]);
+            graph.edges[from].push((to, PathMultiCost::from_slice(&weights)));
+        }
+        Ok(graph)
+    }
+
+    fn node(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        self.names.push(name.to_string());
+        self.edges.push(Vec::new());
+        self.indices.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    /// Number of weights on every edge
+    pub fn criteria(&self) -> usize {
+        self.criteria
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Cheapest path from `start` to `stop`, `None` when either is unknown or unreachable
+    pub fn shortest_path(
+        &self,
+        start: &str,
+        stop: &str,
+        algorithm: PathFindingAlgorithm,
+    ) -> Option<(Vec<&str>, GraphCost)> {
+        let start = *self.indices.get(start)?;
+        let stop = *self.indices.get(stop)?;
+        // Edge weights carry no geometry, so the zero heuristic is the only admissible one
+        let (path, cost) = run_algorithm(
+            algorithm,
+            &start,
+            |&node| self.edges[node].iter().copied(),
+            |_| GraphCost::zero(),
+            |&node| node == stop,
+            Limits::default(),
+        )?;
+        Some((
+            path.into_iter()
+                .map(|node| self.names[node].as_str())
+                .collect(),
+            cost,
+        ))
+    }
+
+    /// Weights of `cost`, one per criterion
+    pub fn weights<'a>(&self, cost: &'a GraphCost) -> &'a [u32] {
+        &cost.as_slice()[..self.criteria]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EDGES: &str = "# from,to,time,tolls\na,b,1,5\nb,d,1,5\na,c,1,0\nc,d,1,0\na,d,3,0\n";
+
+    #[test]
+    fn first_criterion_wins_then_ties_are_broken() {
+        let graph = Graph::from_csv(EDGES.as_bytes()).unwrap();
+        assert_eq!(graph.node_count(), 4);
+        [
+            PathFindingAlgorithm::Astar,
+            PathFindingAlgorithm::Fringe,
+            PathFindingAlgorithm::Idastar,
+            PathFindingAlgorithm::IdastarTt,
+            PathFindingAlgorithm::BoundedAstar,
+            PathFindingAlgorithm::Dijkstra,
+        ]
+        .iter()
+        .for_each(|&algorithm| {
+            let (path, cost) = graph.shortest_path("a", "d", algorithm).unwrap();
+            assert_eq!(path, vec!["a", "c", "d"]);
+            assert_eq!(graph.weights(&cost), &[2, 0]);
+        });
+    }
+
+    #[test]
+    fn edges_are_directed() {
+        let graph = Graph::from_csv(EDGES.as_bytes()).unwrap();
+        assert_eq!(
+            graph.shortest_path("d", "a", PathFindingAlgorithm::Astar),
+            None
+        );
+        assert_eq!(
+            graph.shortest_path("a", "z", PathFindingAlgorithm::Astar),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_edges_are_rejected() {
+        assert!(Graph::from_csv("a,b".as_bytes()).is_err());
+        assert!(Graph::from_csv("a,b,x".as_bytes()).is_err());
+        assert!(Graph::from_csv("a,b,1\nb,c,1,2".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::distance::cost::{CostModel, Levenshtein};
+use crate::distance::dictionary::Dictionary;
+use crate::distance::names::UnknownName;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of landmark words `Heuristic::Alt` measures hops from
+pub const LANDMARKS: usize = 4;
+
+/// Words within a hop of the end word past which `goal_depths` gives up, their own
+/// neighbours taking a scan each in word lists without an index
+pub const GOAL_NEIGHBOURS_LIMIT: usize = 1024;
+
+/// Lower bound of the number of 1-letter hops left, guiding every algorithm but Dijkstra
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Heuristic {
+    /// The cost model's own bound, derived from the edit distance between both words
+    #[default]
+    EditDistance,
+    /// Difference of lengths only, weaker but cheaper
+    LengthDiff,
+    /// Triangle inequality over hops from a few landmark words, computed through the
+    /// whole dictionary before searching: quadratic, meant for small dictionaries
+    Alt,
+    /// Largest of several bounds, still a lower bound since each one is
+    Max(Vec<Heuristic>),
+}
+
+// A boxed bound of the number of 1-letter hops from a word to the stop word
+pub(crate) type Bound<'a> = Box<dyn Fn(&str) -> usize + 'a>;
+
+impl Heuristic {
+    /// Precompute whatever the heuristic needs to evaluate the words of a search
+    pub(crate) fn prepare<'a, D: Dictionary + ?Sized>(
+        &self,
+        cost_model: &'a dyn CostModel,
+        words: &'a D,
+        stop: &'a str,
+    ) -> Bound<'a> {
+        match self {
+            Heuristic::EditDistance => Box::new(move |word| cost_model.heuristic(word, stop)),
+            Heuristic::LengthDiff => Box::new(move |word| cost_model.length_heuristic(word, stop)),
+            Heuristic::Alt => alt(cost_model, words, stop),
+            Heuristic::Max(heuristics) => {
+                let bounds: Vec<Bound<'a>> = heuristics
+                    .iter()
+                    .map(|heuristic| heuristic.prepare(cost_model, words, stop))
+                    .collect();
+                Box::new(move |word| bounds.iter().map(|bound| bound(word)).max().unwrap_or(0))
+            }
+        }
+    }
+}
+
+// A path made of 1-letter hops from `word` to `stop` followed by the shortest one from a
+// landmark to `word` is a path from the landmark to `stop`, hence
+// hops(word, stop) >= hops(landmark, stop) - hops(landmark, word)
+fn alt<'a, D: Dictionary + ?Sized>(
+    cost_model: &'a dyn CostModel,
+    words: &'a D,
+    stop: &'a str,
+) -> Bound<'a> {
+    let words: Vec<&'a str> = words.iter().collect();
+    let mut landmarks: Vec<&'a str> = (0..LANDMARKS)
+        .filter(|_| !words.is_empty())
+        .map(|i| words[i * words.len() / LANDMARKS])
+        .collect();
+    landmarks.dedup();
+    let hops: Vec<HashMap<&'a str, usize>> = landmarks
+        .iter()
+        .map(|landmark| unit_hops_from(landmark, &words, cost_model))
+        .collect();
+    let unreachable = words.len();
+    Box::new(move |word| {
+        hops.iter()
+            .map(|hops| match (hops.get(stop), hops.get(word)) {
+                (Some(to_stop), Some(to_word)) => to_stop.saturating_sub(*to_word),
+                // No 1-letter hops lead from `word` to `stop`, a heavier hop outranks any bound
+                (None, Some(_)) => unreachable,
+                _ => 0,
+            })
+            .max()
+            .unwrap_or(0)
+    })
+}
+
+// Breadth-first search through hops changing a single letter
+fn unit_hops_from<'a>(
+    landmark: &'a str,
+    words: &[&'a str],
+    cost_model: &dyn CostModel,
+) -> HashMap<&'a str, usize> {
+    let mut hops = HashMap::new();
+    hops.insert(landmark, 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(landmark);
+    while let Some(current) = queue.pop_front() {
+        let next = hops[current] + 1;
+        for &word in words {
+            if !hops.contains_key(word) && cost_model.distance(current, word) == 1 {
+                hops.insert(word, next);
+                queue.push_back(word);
+            }
+        }
+    }
+    hops
+}
+
+/// Fewest hops of at most `k` letters from the words of `words`, and `origin`, to `stop`,
+/// those further than 2 hops away left out; `None` when `stop` has more than
+/// `GOAL_NEIGHBOURS_LIMIT` neighbours. Words within `k` letters of `stop`, then of those,
+/// are intersected with the dictionary as `Dictionary::neighbors_within` does; every hop
+/// of a cost model bounded by edits is among them, so no word is given more hops than
+/// it needs.
+pub(crate) fn goal_depths<'a, D: Dictionary + ?Sized>(
+    words: &'a D,
+    stop: &'a str,
+    origin: &'a str,
+    k: usize,
+) -> Option<HashMap<&'a str, usize>> {
+    let near = words.neighbors_within(stop, k);
+    if near.len() > GOAL_NEIGHBOURS_LIMIT {
+        return None;
+    }
+    let mut depths: HashMap<&'a str, usize> = near.iter().map(|&word| (word, 1)).collect();
+    let mut neighbors = Vec::new();
+    for &word in &near {
+        neighbors.clear();
+        words.extend_neighbors(word, k, &mut neighbors);
+        for &next in &neighbors {
+            depths.entry(next).or_insert(2);
+        }
+    }
+    // The word searched from may be missing from the dictionary
+    if !depths.contains_key(origin) {
+        let within = |word: &str| Levenshtein.distance(origin, word) <= k;
+        if within(stop) {
+            depths.insert(origin, 1);
+        } else if near.iter().any(|&word| within(word)) {
+            depths.insert(origin, 2);
+        }
+    }
+    depths.insert(stop, 0);
+    Some(depths)
+}
+
+impl fmt::Display for Heuristic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Heuristic::EditDistance => write!(f, "edit-distance"),
+            Heuristic::LengthDiff => write!(f, "length-diff"),
+            Heuristic::Alt => write!(f, "alt"),
+            Heuristic::Max(heuristics) => {
+                let names: Vec<String> = heuristics.iter().map(|h| h.to_string()).collect();
+                write!(f, "max({})", names.join(","))
+            }
+        }
+    }
+}
+
+impl FromStr for Heuristic {
+    type Err = UnknownName;
+
+    fn from_str(s: &str) -> Result<Heuristic, UnknownName> {
+        let unknown = || UnknownName {
+            kind: "heuristic",
+            given: s.to_string(),
+            expected: vec!["edit-distance", "length-diff", "alt", "max(h1,h2,..)"],
+        };
+        match s.trim().to_lowercase().as_str() {
+            "edit-distance" => Ok(Heuristic::EditDistance),
+            "length-diff" => Ok(Heuristic::LengthDiff),
+            "alt" => Ok(Heuristic::Alt),
+            s if s.starts_with("max(") && s.ends_with(')') => {
+                let heuristics = split_arguments(&s[4..s.len() - 1])
+                    .map_err(|_| unknown())?
+                    .into_iter()
+                    .map(Heuristic::from_str)
+                    .collect::<Result<Vec<Heuristic>, UnknownName>>()?;
+                if heuristics.is_empty() {
+                    Err(unknown())
+                } else {
+                    Ok(Heuristic::Max(heuristics))
+                }
+            }
+            _ => Err(unknown()),
+        }
+    }
+}
+
+// Split on the commas outside of nested parentheses
+fn split_arguments(s: &str) -> Result<Vec<&str>, ()> {
+    let mut arguments = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or(())?,
+            ',' if depth == 0 => {
+                arguments.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if !s.trim().is_empty() {
+        arguments.push(&s[start..]);
+    }
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::cost::Levenshtein;
+
+    #[test]
+    fn parse_and_display() {
+        let heuristic = Heuristic::from_str("max(alt, max(length-diff,edit-distance))").unwrap();
+        assert_eq!(
+            heuristic,
+            Heuristic::Max(vec![
+                Heuristic::Alt,
+                Heuristic::Max(vec![Heuristic::LengthDiff, Heuristic::EditDistance])
+            ])
+        );
+        assert_eq!(
+            heuristic.to_string(),
+            "max(alt,max(length-diff,edit-distance))"
+        );
+        assert!(Heuristic::from_str("max()").is_err());
+        assert!(Heuristic::from_str("max(alt))").is_err());
+        assert!(Heuristic::from_str("min(alt)").is_err());
+        assert_eq!(
+            Heuristic::from_str("MAX(Alt, Length-Diff)"),
+            Ok(Heuristic::Max(vec![Heuristic::Alt, Heuristic::LengthDiff]))
+        );
+        assert_eq!(
+            Heuristic::from_str("max(alt,nearest)").unwrap_err().to_string(),
+            "unknown heuristic nearest, expected one of edit-distance, length-diff, alt, max(h1,h2,..)"
+        );
+    }
+
+    #[test]
+    fn alt_counts_hops_through_the_dictionary() {
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        let bound = Heuristic::Alt.prepare(&Levenshtein, &words[..], "warm");
+        // "cold" is the first landmark and 4 hops away from "warm"
+        assert_eq!(bound("cold"), 4);
+        assert_eq!(bound("card"), 2);
+        assert_eq!(bound("warm"), 0);
+    }
+
+    #[test]
+    fn goal_depths_count_hops_near_the_end() {
+        let words = ["cold", "cord", "card", "ward", "warm", "worm"];
+        let depths = goal_depths(&words[..], "warm", "bold", 1).unwrap();
+        assert_eq!(depths["warm"], 0);
+        assert_eq!((depths["ward"], depths["worm"]), (1, 1));
+        assert_eq!(depths["card"], 2);
+        assert!(!depths.contains_key("cord") && !depths.contains_key("bold"));
+        let depths = goal_depths(&words[..], "warm", "wars", 1).unwrap();
+        assert_eq!(depths["wars"], 1);
+        let depths = goal_depths(&words[..], "warm", "wore", 1).unwrap();
+        assert_eq!(depths["wore"], 2);
+    }
+
+    #[test]
+    fn max_takes_the_strongest_bound() {
+        let words = ["abc"];
+        let max = Heuristic::from_str("max(length-diff,edit-distance)").unwrap();
+        let bound = max.prepare(&Levenshtein, &words[..], "abc");
+        assert_eq!(bound("xyz"), 3);
+        assert_eq!(bound("abcde"), 2);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::difficulty::layers;
+use crate::distance::word::{alignment, Edit};
+
+/// Best word to play next in a ladder of 1-letter hops
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint<'a> {
+    pub next: &'a str,
+    /// `next` with the letters to change blanked with `_`, telling where to change the
+    /// current word without telling into what
+    pub masked: String,
+    /// Hops still needed after `next` to reach the stop word
+    pub hops_left: usize,
+}
+
+/// `next` with the letters not kept from `current` replaced by `_`
+pub fn mask(current: &str, next: &str) -> String {
+    alignment(current, next)
+        .into_iter()
+        .filter_map(|edit| match edit {
+            Edit::Keep { letter, .. } => Some(letter),
+            Edit::Substitute { .. } | Edit::Insert { .. } => Some('_'),
+            Edit::Delete { .. } => None,
+        })
+        .collect()
+}
+
+/// Word of a shortest ladder from `current` to `stop` to play after `current`, the first
+/// in alphabetical order when several are as good. `None` when `current` already is
+/// `stop` or no ladder leads there.
+pub fn find_hint<'a, D: Dictionary + ?Sized>(
+    current: &str,
+    stop: &'a str,
+    words: &'a D,
+) -> Option<Hint<'a>> {
+    if current == stop {
+        return None;
+    }
+    // Searched from the stop word, every neighbour of `current` gets its hops left
+    let reached = layers(stop, Some(current), usize::MAX, words);
+    let &(hops, _) = reached.get(current)?;
+    let next = words
+        .neighbors_within(current, 1)
+        .into_iter()
+        .filter(|next| reached.get(next).is_some_and(|&(left, _)| left + 1 == hops))
+        .min()?;
+    Some(Hint {
+        next,
+        masked: mask(current, next),
+        hops_left: hops - 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints_follow_a_shortest_ladder() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "bold", "bord", "wars",
+        ];
+        let hint = find_hint("cord", "warm", &words[..]).unwrap();
+        assert_eq!(
+            hint,
+            Hint {
+                next: "card",
+                masked: "c_rd".to_string(),
+                hops_left: 2
+            }
+        );
+        // Back on track from a detour
+        assert_eq!(find_hint("bord", "warm", &words[..]).unwrap().next, "cord");
+        assert_eq!(find_hint("warm", "warm", &words[..]), None);
+        assert_eq!(find_hint("cold", "zzzz", &words[..]), None);
+        assert_eq!(mask("ward", "wards"), "ward_");
+        assert_eq!(mask("wards", "ward"), "ward");
+    }
+}
@@ -0,0 +1,254 @@
+use crate::distance::cost::Unit;
+use crate::distance::path::MAX_DIMENSION;
+use std::env;
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Language the results are described in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+// How many of something, only telling singular from plural apart
+#[derive(Clone, Copy, PartialEq)]
+enum Number {
+    Singular,
+    Plural,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Fr];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Locale of the first of `LC_ALL`, `LC_MESSAGES` and `LANG` set, English when none
+    /// is or when its language is not supported
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map_or(Locale::En, |value| Locale::from_posix(&value))
+    }
+
+    // A POSIX locale such as fr_FR.UTF-8, only its language matters
+    fn from_posix(value: &str) -> Locale {
+        let language = value.split(['_', '.', '@', '-']).next().unwrap_or("");
+        language.parse().unwrap_or(Locale::En)
+    }
+
+    // French counts 0 as singular, English as plural
+    fn number(self, count: impl Display) -> Number {
+        match (self, count.to_string().as_str()) {
+            (_, "1") | (Locale::Fr, "0") => Number::Singular,
+            _ => Number::Plural,
+        }
+    }
+
+    fn noun(self, unit: Unit, number: Number) -> &'static str {
+        match (self, unit, number) {
+            (Locale::En, Unit::Letter, Number::Singular) => "letter",
+            (Locale::En, Unit::Letter, Number::Plural) => "letters",
+            (Locale::En, Unit::Token, Number::Singular) => "word",
+            (Locale::En, Unit::Token, Number::Plural) => "words",
+            (Locale::Fr, Unit::Letter, Number::Singular) => "lettre",
+            (Locale::Fr, Unit::Letter, Number::Plural) => "lettres",
+            (Locale::Fr, Unit::Token, Number::Singular) => "mot",
+            (Locale::Fr, Unit::Token, Number::Plural) => "mots",
+        }
+    }
+
+    // "changed", agreeing with the French noun it follows
+    fn changed(self, unit: Unit, number: Number) -> &'static str {
+        match (self, unit, number) {
+            (Locale::En, _, _) => "changed",
+            (Locale::Fr, Unit::Letter, Number::Singular) => "changée",
+            (Locale::Fr, Unit::Letter, Number::Plural) => "changées",
+            (Locale::Fr, Unit::Token, Number::Singular) => "changé",
+            (Locale::Fr, Unit::Token, Number::Plural) => "changés",
+        }
+    }
+
+    /// `count` followed by `singular` or `plural`, whichever agrees with it, e.g. "1 word"
+    pub fn count(self, count: impl Display + Copy, singular: &str, plural: &str) -> String {
+        match self.number(count) {
+            Number::Singular => format!("{} {}", count, singular),
+            Number::Plural => format!("{} {}", count, plural),
+        }
+    }
+
+    /// A path made of no mutation at all
+    pub fn no_mutation(self) -> &'static str {
+        match self {
+            Locale::En => "0 mutations",
+            Locale::Fr => "aucune mutation",
+        }
+    }
+
+    /// `count` mutations changing `size` units each
+    pub fn mutations(self, count: impl Display + Copy, size: usize, unit: Unit) -> String {
+        let plural = if self.number(count) == Number::Plural {
+            "s"
+        } else {
+            ""
+        };
+        match self {
+            Locale::En => format!(
+                "{} {}-{} mutation{}",
+                count,
+                size,
+                self.noun(unit, Number::Singular),
+                plural
+            ),
+            Locale::Fr => format!(
+                "{} mutation{} de {} {}",
+                count,
+                plural,
+                size,
+                self.noun(unit, self.number(size))
+            ),
+        }
+    }
+
+    /// `count` units changed by mutations of `MAX_DIMENSION` units or more
+    pub fn big_mutations(self, count: impl Display + Copy, unit: Unit) -> String {
+        let number = self.number(count);
+        let (noun, changed) = (self.noun(unit, number), self.changed(unit, number));
+        let big = self.noun(unit, Number::Plural);
+        match self {
+            Locale::En => format!(
+                "{} {} {} by mutations of {}+ {}",
+                count, noun, changed, MAX_DIMENSION, big
+            ),
+            Locale::Fr => format!(
+                "{} {} {} par des mutations de {} {} ou plus",
+                count, noun, changed, MAX_DIMENSION, big
+            ),
+        }
+    }
+
+    /// A single hop changing `count` units
+    pub fn units_changed(self, count: usize, unit: Unit) -> String {
+        let number = self.number(count);
+        format!(
+            "{} {} {}",
+            count,
+            self.noun(unit, number),
+            self.changed(unit, number)
+        )
+    }
+
+    /// Outcome of a search: the words of the path, how long finding it took, and its cost
+    pub fn path_found(self, path: &str, took: Option<Duration>, cost: &str) -> String {
+        match (self, took) {
+            (Locale::En, Some(took)) => {
+                format!(
+                    "Shortest path found in {:?}: {} (achieved in {})",
+                    took, path, cost
+                )
+            }
+            (Locale::En, None) => {
+                format!(
+                    "Shortest path found in cache: {} (achieved in {})",
+                    path, cost
+                )
+            }
+            (Locale::Fr, Some(took)) => format!(
+                "Plus court chemin trouvé en {:?} : {} (obtenu en {})",
+                took, path, cost
+            ),
+            (Locale::Fr, None) => format!(
+                "Plus court chemin trouvé en cache : {} (obtenu en {})",
+                path, cost
+            ),
+        }
+    }
+}
+
+impl Locale {
+    /// Outcome of a search finding nothing, `max_hops` when it limited the path
+    pub fn no_path(self, max_hops: Option<usize>) -> String {
+        match (self, max_hops) {
+            (Locale::En, Some(hops)) => format!("No path within {} hops", hops),
+            (Locale::En, None) => "No path found, something went wrong ?".to_string(),
+            (Locale::Fr, Some(hops)) => format!("Aucun chemin en {} sauts au plus", hops),
+            (Locale::Fr, None) => {
+                "Aucun chemin trouvé, quelque chose s'est mal passé ?".to_string()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Locale, ()> {
+        match s {
+            "en" => Ok(Locale::En),
+            "fr" => Ok(Locale::Fr),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plurals_in_every_locale() {
+        assert_eq!(
+            Locale::En.mutations(1, 1, Unit::Letter),
+            "1 1-letter mutation"
+        );
+        assert_eq!(
+            Locale::En.mutations(4, 1, Unit::Letter),
+            "4 1-letter mutations"
+        );
+        assert_eq!(
+            Locale::Fr.mutations(1, 2, Unit::Letter),
+            "1 mutation de 2 lettres"
+        );
+        assert_eq!(
+            Locale::Fr.mutations(4, 1, Unit::Token),
+            "4 mutations de 1 mot"
+        );
+        assert_eq!(
+            Locale::Fr.big_mutations(1, Unit::Letter),
+            "1 lettre changée par des mutations de 20 lettres ou plus"
+        );
+        assert_eq!(Locale::En.units_changed(3, Unit::Token), "3 words changed");
+        assert_eq!(Locale::En.count(0, "word", "words"), "0 words");
+        assert_eq!(Locale::Fr.count(0, "mot", "mots"), "0 mot");
+        assert_eq!(
+            Locale::Fr.units_changed(0, Unit::Letter),
+            "0 lettre changée"
+        );
+    }
+
+    #[test]
+    fn locales_from_the_environment() {
+        assert_eq!(Locale::from_posix("fr_FR.UTF-8"), Locale::Fr);
+        assert_eq!(Locale::from_posix("fr"), Locale::Fr);
+        assert_eq!(Locale::from_posix("en_US"), Locale::En);
+        assert_eq!(Locale::from_posix("C"), Locale::En);
+        assert_eq!(Locale::from_posix("de_DE@euro"), Locale::En);
+    }
+}
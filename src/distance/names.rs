@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error of parsing a name none of the accepted ones matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownName {
+    /// What the name was for, such as `algorithm`
+    pub kind: &'static str,
+    pub given: String,
+    /// Names accepted, aliases left out
+    pub expected: Vec<&'static str>,
+}
+
+impl fmt::Display for UnknownName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown {} {}, expected one of {}",
+            self.kind,
+            self.given,
+            self.expected.join(", ")
+        )
+    }
+}
+
+impl Error for UnknownName {}
+
+/// Value named `s` among `names` then `aliases`, whatever its case and surrounding spaces
+pub(crate) fn find_name<T: Copy>(
+    kind: &'static str,
+    s: &str,
+    names: &[(&'static str, T)],
+    aliases: &[(&'static str, T)],
+) -> Result<T, UnknownName> {
+    let wanted = s.trim().to_lowercase();
+    names
+        .iter()
+        .chain(aliases)
+        .find(|(name, _)| name.to_lowercase() == wanted)
+        .map(|&(_, value)| value)
+        .ok_or_else(|| UnknownName {
+            kind,
+            given: s.to_string(),
+            expected: names.iter().map(|&(name, _)| name).collect(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_found_whatever_their_case() {
+        let names = [("astar", 1), ("dijkstra", 2)];
+        assert_eq!(
+            find_name("algorithm", " AStar ", &names, &[("a*", 1)]),
+            Ok(1)
+        );
+        assert_eq!(find_name("algorithm", "A*", &names, &[("a*", 1)]), Ok(1));
+        let error = find_name("algorithm", "bfs", &names, &[("a*", 1)]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown algorithm bfs, expected one of astar, dijkstra"
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+
+/// Receives the progress of a running search, e.g. to display it
+pub trait SearchObserver: Send + Sync {
+    /// `word` is being expanded: its heuristic value and every word it leads to
+    /// with the cost of the hop
+    fn expanded(
+        &self,
+        word: &str,
+        heuristic: PathMultiCost<EditDistance>,
+        successors: &[(&str, PathMultiCost<EditDistance>)],
+    );
+}
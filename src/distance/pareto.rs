@@ -0,0 +1,194 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::{Interruption, SearchOptions, SearchStats};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+/// Costs of a path a Pareto search trades off against each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Tradeoff {
+    pub hops: usize,
+    /// Distance of the biggest hop
+    pub largest_hop: usize,
+    /// Distance of all the hops together
+    pub total: usize,
+}
+
+impl Tradeoff {
+    // At least as good in every cost, paths this good make the other one useless
+    fn covers(self, other: Tradeoff) -> bool {
+        self.hops <= other.hops
+            && self.largest_hop <= other.largest_hop
+            && self.total <= other.total
+    }
+
+    // Cheapest a path could get going on from a word `left` letters away from the stop word
+    fn at_best(self, left: usize) -> Tradeoff {
+        match left {
+            0 => self,
+            left => Tradeoff {
+                hops: self.hops + 1,
+                largest_hop: self.largest_hop.max(1),
+                total: self.total + left,
+            },
+        }
+    }
+
+    fn then(self, distance: usize) -> Tradeoff {
+        Tradeoff {
+            hops: self.hops + 1,
+            largest_hop: self.largest_hop.max(distance),
+            total: self.total + distance,
+        }
+    }
+}
+
+impl fmt::Display for Tradeoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} hops, largest {}, {} in total",
+            self.hops, self.largest_hop, self.total
+        )
+    }
+}
+
+/// Path no other one beats in every cost at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoPath<'a> {
+    pub words: Vec<&'a str>,
+    pub tradeoff: Tradeoff,
+}
+
+// A way of reaching a word, with the label it was extended from
+struct Label<'a> {
+    word: &'a str,
+    tradeoff: Tradeoff,
+    parent: Option<usize>,
+}
+
+/// Every path from `start` to `stop` worth taking when fewer hops, smaller hops and fewer
+/// letters changed all matter: none of them is beaten in all three by another path. One
+/// path is kept per tradeoff, in increasing number of hops. The algorithm of `options` is
+/// ignored, this is always a multi-objective label-setting search; its cost model, control,
+/// `max_hops` and `max_hop_distance` are used.
+pub fn find_pareto_paths<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &str,
+    words: &'a D,
+    options: &SearchOptions,
+) -> (Result<Vec<ParetoPath<'a>>, Interruption>, SearchStats) {
+    let cost_model = options.cost_model.as_ref();
+    let mut labels = vec![Label {
+        word: start,
+        tradeoff: Tradeoff::default(),
+        parent: None,
+    }];
+    // Labels come out in lexicographic order, and every hop adds a hop, so no label popped
+    // later is as good in every cost as one popped before: popped labels are final
+    let mut queue = BinaryHeap::new();
+    queue.push(Reverse((Tradeoff::default(), 0)));
+    let mut settled: HashMap<&str, Vec<Tradeoff>> = HashMap::new();
+    let mut found = Vec::new();
+    let mut stats = SearchStats::default();
+    let covered = |settled: &HashMap<&str, Vec<Tradeoff>>, word: &str, tradeoff: Tradeoff| {
+        settled
+            .get(word)
+            .is_some_and(|known| known.iter().any(|known| known.covers(tradeoff)))
+    };
+    while let Some(Reverse((tradeoff, index))) = queue.pop() {
+        let word = labels[index].word;
+        // Paths to `stop` bound every other one, extending a path never makes it cheaper
+        if covered(&settled, word, tradeoff) || covered(&settled, stop, tradeoff) {
+            continue;
+        }
+        settled.entry(word).or_default().push(tradeoff);
+        if word == stop {
+            found.push(index);
+            continue;
+        }
+        stats.expanded += 1;
+        if let Some(reason) = options.control.check(stats.expanded) {
+            return (Err(reason), stats);
+        }
+        if options
+            .max_hops
+            .is_some_and(|max_hops| tradeoff.hops >= max_hops)
+        {
+            continue;
+        }
+        let candidates: Vec<&'a str> = match options.max_hop_distance {
+            Some(k) if cost_model.bounded_by_edits() => words.neighbors_within(word, k),
+            _ => words.iter().collect(),
+        };
+        for successor in candidates {
+            if successor == word {
+                continue;
+            }
+            let distance = cost_model.distance(word, successor);
+            if options.max_hop_distance.is_some_and(|k| distance > k) {
+                continue;
+            }
+            let next = tradeoff.then(distance);
+            // The heuristic bounds the letters left to change, as for the other searches
+            let at_best = next.at_best(cost_model.heuristic(successor, stop));
+            if covered(&settled, successor, next) || covered(&settled, stop, at_best) {
+                continue;
+            }
+            labels.push(Label {
+                word: successor,
+                tradeoff: next,
+                parent: Some(index),
+            });
+            queue.push(Reverse((next, labels.len() - 1)));
+        }
+    }
+    let paths = found
+        .into_iter()
+        .map(|index| {
+            let mut words = Vec::new();
+            let mut current = Some(index);
+            while let Some(index) = current {
+                words.push(labels[index].word);
+                current = labels[index].parent;
+            }
+            words.reverse();
+            ParetoPath {
+                words,
+                tradeoff: labels[index].tradeoff,
+            }
+        })
+        .collect();
+    (Ok(paths), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::PathFindingAlgorithm;
+
+    #[test]
+    fn frontier_trades_hops_for_smaller_hops() {
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let (paths, _) = find_pareto_paths("cold", "warm", &words[..], &options);
+        let paths: Vec<String> = paths
+            .unwrap()
+            .iter()
+            .map(|path| format!("{} ({})", path.words.join("->"), path.tradeoff))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "cold->warm (1 hops, largest 4, 4 in total)",
+                "cold->card->warm (2 hops, largest 2, 4 in total)",
+                "cold->cord->card->ward->warm (4 hops, largest 1, 4 in total)",
+            ]
+        );
+        let options = options.with_max_hops(3);
+        let (paths, _) = find_pareto_paths("cold", "warm", &words[..], &options);
+        assert_eq!(paths.unwrap().len(), 2);
+        let (paths, _) = find_pareto_paths("cold", "cold", &words[..], &options);
+        assert_eq!(paths.unwrap()[0].words, vec!["cold"]);
+    }
+}
@@ -1,6 +1,11 @@
 use num_traits::{Bounded, CheckedAdd, Zero};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::{min, Ord, Ordering};
+use std::convert::TryFrom;
+use std::fmt;
 use std::ops::Add;
+use std::str::FromStr;
 
 /*
 In order to derive the Copy trait required by pathfinding api we use fixed size array for storage
@@ -8,10 +13,89 @@ Increasing this value with increase the memory required by the program but will
 */
 pub const MAX_DIMENSION: usize = 20;
 
+/// What costs are compared on first, the hops by size deciding between costs equal there.
+/// Variants are ordered as listed, which decides between the objectives of two costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Objective {
+    /// Fewest hops of the biggest size, then of the next size and so on
+    #[default]
+    SmallestMaxMutation,
+    /// Fewest hops, hops of `MAX_DIMENSION` letters or more counting once per
+    /// `MAX_DIMENSION` letters
+    FewestHops,
+    /// Fewest letters changed along the whole path
+    FewestLetters,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 3] = [
+        Objective::SmallestMaxMutation,
+        Objective::FewestHops,
+        Objective::FewestLetters,
+    ];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Objective::SmallestMaxMutation => "smallest-max-mutation",
+            Objective::FewestHops => "fewest-hops",
+            Objective::FewestLetters => "fewest-letters",
+        }
+    }
+
+    // Value compared before the hops by size, the same for every cost by default
+    fn key<U: Copy + Into<u64>>(self, data: &[U; MAX_DIMENSION]) -> u64 {
+        let (overflow, sizes) = (data[0].into(), &data[1..]);
+        // `sizes` go from `MAX_DIMENSION - 1` letters down to 1
+        let by_size = sizes
+            .iter()
+            .zip((1..MAX_DIMENSION as u64).rev())
+            .map(|(&count, size)| (count.into(), size));
+        match self {
+            Objective::SmallestMaxMutation => 0,
+            Objective::FewestHops => by_size
+                .map(|(count, _)| count)
+                .fold(overflow.div_ceil(MAX_DIMENSION as u64), u64::saturating_add),
+            Objective::FewestLetters => by_size
+                .map(|(count, size)| count.saturating_mul(size))
+                .fold(overflow, u64::saturating_add),
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Objective {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Objective, ()> {
+        match s {
+            "smallest-max-mutation" => Ok(Objective::SmallestMaxMutation),
+            "fewest-hops" => Ok(Objective::FewestHops),
+            "fewest-letters" => Ok(Objective::FewestLetters),
+            _ => Err(()),
+        }
+    }
+}
+
 /// A metric data for Path that supports different layers
 #[derive(Debug, Copy, Clone, Eq)]
 pub struct PathMultiCost<U> {
     data: [U; MAX_DIMENSION],
+    // Bit i set when data[i] may not be zero. Most costs only use a few dimensions, with
+    // the `sparse-costs` feature sums and comparisons skip the others.
+    #[cfg(feature = "sparse-costs")]
+    lanes: u32,
+    // Least significant, only tells apart costs whose hops are the same
+    penalty: U,
+    // Less significant still, only tells apart costs whose penalties are the same too
+    rarity: U,
+    // How this cost compares to others, left out of equality
+    objective: Objective,
 }
 
 impl<U: Zero + PartialEq + Copy> PathMultiCost<U> {
@@ -29,41 +113,192 @@ impl<U: Zero + Copy> PathMultiCost<U> {
     pub fn new(cost: U, dimension: usize) -> PathMultiCost<U> {
         let mut data = [U::zero(); MAX_DIMENSION];
         data[min(MAX_DIMENSION - 1, MAX_DIMENSION - 1 - dimension)] = cost;
-        return PathMultiCost { data };
+        PathMultiCost {
+            #[cfg(feature = "sparse-costs")]
+            lanes: occupied(&data),
+            data,
+            penalty: U::zero(),
+            rarity: U::zero(),
+            objective: Objective::default(),
+        }
+    }
+
+    /// Cost of nothing but a penalty, weighing less than any hop
+    pub fn penalty_only(penalty: U) -> PathMultiCost<U> {
+        PathMultiCost {
+            penalty,
+            ..PathMultiCost::new(U::zero(), 0)
+        }
+    }
+
+    /// Cost of nothing but a rarity, weighing less than any hop and any penalty
+    pub fn rarity_only(rarity: U) -> PathMultiCost<U> {
+        PathMultiCost {
+            rarity,
+            ..PathMultiCost::new(U::zero(), 0)
+        }
+    }
+
+    /// Penalties summed along a path, e.g. for entering weighted words
+    pub fn penalty(&self) -> U {
+        self.penalty
+    }
+
+    /// Rarities summed along a path, e.g. of the words entered according to how seldom
+    /// they are used
+    pub fn rarity(&self) -> U {
+        self.rarity
+    }
+
+    /// Same cost compared to others for `objective`. Costs compared together should all
+    /// have the same, those with the default one take the other's: sums keep it.
+    pub fn with_objective(mut self, objective: Objective) -> PathMultiCost<U> {
+        self.objective = objective;
+        self
+    }
+}
+
+impl<U> PathMultiCost<U> {
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    // Objective two costs are compared and added with, whichever comes first. Costs of
+    // `Objective::default()`, such as zero, take the one of the other cost; costs of two
+    // other objectives, which a search never mixes, the one listed last.
+    fn shared_objective(&self, other: &Self) -> Objective {
+        self.objective.max(other.objective)
+    }
+
+    // Dimensions of `self` or `other` that may not be zero, from the most significant one
+    // to the least significant one with all the ones between, which loops go through
+    // faster than through set bits
+    #[cfg(feature = "sparse-costs")]
+    fn lanes(&self, other: &Self) -> std::ops::Range<usize> {
+        match self.shared_lanes(other) {
+            0 => 0..0,
+            lanes => lanes.trailing_zeros() as usize..(u32::BITS - lanes.leading_zeros()) as usize,
+        }
+    }
+
+    #[cfg(feature = "sparse-costs")]
+    fn shared_lanes(&self, other: &Self) -> u32 {
+        self.lanes | other.lanes
+    }
+
+    #[cfg(not(feature = "sparse-costs"))]
+    fn lanes(&self, _other: &Self) -> std::ops::Range<usize> {
+        0..MAX_DIMENSION
+    }
+}
+
+#[cfg(feature = "sparse-costs")]
+fn occupied<U: Zero>(data: &[U; MAX_DIMENSION]) -> u32 {
+    data.iter()
+        .enumerate()
+        .filter(|(_, u)| !u.is_zero())
+        .fold(0, |lanes, (i, _)| lanes | 1 << i)
+}
+
+impl<U: Zero + Copy> PathMultiCost<U> {
+    /// Cost whose values are listed from the most to the least significant
+    pub fn from_slice(costs: &[U]) -> PathMultiCost<U> {
+        let mut data = [U::zero(); MAX_DIMENSION];
+        data[..costs.len()].copy_from_slice(costs);
+        PathMultiCost {
+            #[cfg(feature = "sparse-costs")]
+            lanes: occupied(&data),
+            data,
+            penalty: U::zero(),
+            rarity: U::zero(),
+            objective: Objective::default(),
+        }
+    }
+
+    /// Values from the most to the least significant, as given to `from_slice`
+    pub fn as_slice(&self) -> &[U] {
+        &self.data
+    }
+}
+
+impl<U: Bounded + PartialEq + Copy> PathMultiCost<U> {
+    /// Whether a value reached the highest its counter holds, sums stop growing there
+    pub fn is_saturated(&self) -> bool {
+        self.data
+            .iter()
+            .chain([&self.penalty, &self.rarity])
+            .any(|&u| u == U::max_value())
+    }
+
+    /// Same cost counted with another integer, values too high for it become its highest
+    pub fn saturating_cast<V: Bounded + Zero + Copy + TryFrom<U>>(self) -> PathMultiCost<V> {
+        let mut data = [V::zero(); MAX_DIMENSION];
+        let cast = |u: U| V::try_from(u).unwrap_or_else(|_| V::max_value());
+        for (narrow, &u) in data.iter_mut().zip(&self.data) {
+            *narrow = cast(u);
+        }
+        PathMultiCost {
+            data,
+            // Casting saturates, values stay zero only when they were
+            #[cfg(feature = "sparse-costs")]
+            lanes: self.lanes,
+            penalty: cast(self.penalty),
+            rarity: cast(self.rarity),
+            objective: self.objective,
+        }
     }
 }
 
 impl<U: Bounded + Copy + Zero> Bounded for PathMultiCost<U> {
     fn min_value() -> Self {
-        return PathMultiCost::new(U::min_value(), 0);
+        PathMultiCost::new(U::min_value(), 0)
     }
     fn max_value() -> Self {
-        return PathMultiCost::new(U::max_value(), MAX_DIMENSION - 1);
+        PathMultiCost {
+            penalty: U::max_value(),
+            rarity: U::max_value(),
+            ..PathMultiCost::new(U::max_value(), MAX_DIMENSION - 1)
+        }
     }
 }
 
 impl<U: PartialEq> PartialEq for PathMultiCost<U> {
     fn eq(&self, other: &Self) -> bool {
-        self.data == other.data
+        self.data == other.data && self.penalty == other.penalty && self.rarity == other.rarity
     }
 }
 
-impl<U: Ord> Ord for PathMultiCost<U> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        for kv in self.data.iter().zip(other.data.iter()) {
-            match kv.0.cmp(kv.1) {
+impl<U: Ord + Copy + Into<u64>> PathMultiCost<U> {
+    /// Order of `self` and `other` as `objective` compares them, whatever objectives they
+    /// carry. `Ord` and `PartialOrd` both compare with the objective they share.
+    pub fn compare_with(&self, other: &Self, objective: Objective) -> Ordering {
+        let key = objective.key(&self.data).cmp(&objective.key(&other.data));
+        if key != Ordering::Equal {
+            return key;
+        }
+        let lanes = self.lanes(other);
+        for (a, b) in self.data[lanes.clone()].iter().zip(&other.data[lanes]) {
+            match a.cmp(b) {
                 Ordering::Equal => (),
                 Ordering::Greater => return Ordering::Greater,
                 Ordering::Less => return Ordering::Less,
             }
         }
-        return Ordering::Equal;
+        self.penalty
+            .cmp(&other.penalty)
+            .then(self.rarity.cmp(&other.rarity))
     }
 }
 
-impl<U: PartialOrd> PartialOrd for PathMultiCost<U> {
+impl<U: Ord + Copy + Into<u64>> Ord for PathMultiCost<U> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare_with(other, self.shared_objective(other))
+    }
+}
+
+impl<U: Ord + Copy + Into<u64>> PartialOrd for PathMultiCost<U> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        return self.data.partial_cmp(&other.data);
+        Some(self.cmp(other))
     }
 }
 
@@ -74,27 +309,72 @@ impl<U: Zero + Copy + CheckedAdd + Bounded> Add for PathMultiCost<U> {
     where
         U: CheckedAdd,
     {
+        let sum = |a: U, b: U| a.checked_add(&b).unwrap_or_else(U::max_value);
         let mut array = self.data;
-        rhs.data
-            .iter()
-            .enumerate()
-            .for_each(|(i, e)| match e.checked_add(&array[i]) {
-                None => array[i] = U::max_value(),
-                Some(s) => array[i] = s,
-            });
-        return PathMultiCost::<U> { data: array };
+        // Every dimension at once, the compiler turns this loop into vector additions
+        for (a, &b) in array.iter_mut().zip(&rhs.data) {
+            *a = sum(*a, b);
+        }
+        PathMultiCost::<U> {
+            data: array,
+            #[cfg(feature = "sparse-costs")]
+            lanes: self.shared_lanes(&rhs),
+            penalty: sum(self.penalty, rhs.penalty),
+            rarity: sum(self.rarity, rhs.rarity),
+            objective: self.shared_objective(&rhs),
+        }
     }
 }
 
 impl<U: Zero + Copy + Bounded + CheckedAdd> Zero for PathMultiCost<U> {
     fn zero() -> Self {
-        return PathMultiCost::new(U::zero(), 0);
+        PathMultiCost::new(U::zero(), 0)
     }
     fn is_zero(&self) -> bool {
-        self.data
+        self.data[self.lanes(self)].iter().all(|u| u.is_zero())
+            && self.penalty.is_zero()
+            && self.rarity.is_zero()
+    }
+}
+
+// Serialized as hop counts from the 1-letter dimension up, trailing zeros trimmed:
+// [2, 0, 1] is two 1-letter mutations and one 3-letter mutation. The penalty and rarity are left
+// out, results describe paths by their hops.
+#[cfg(feature = "serde")]
+impl<U: Serialize + Zero + PartialEq + Copy> Serialize for PathMultiCost<U> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let highest = self
+            .data
+            .iter()
+            .position(|u| *u != U::zero())
+            .unwrap_or(MAX_DIMENSION);
+        serializer.collect_seq(self.data[highest..].iter().rev())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, U: Deserialize<'de> + Zero + Copy> Deserialize<'de> for PathMultiCost<U> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let counts = Vec::<U>::deserialize(deserializer)?;
+        if counts.len() > MAX_DIMENSION {
+            return Err(de::Error::invalid_length(
+                counts.len(),
+                &"at most MAX_DIMENSION hop counts",
+            ));
+        }
+        let mut data = [U::zero(); MAX_DIMENSION];
+        counts
             .iter()
-            .rev() //reverse to speedup because it's more likely that the last elements are not empty
-            .all(|u| u.is_zero())
+            .enumerate()
+            .for_each(|(dimension, count)| data[MAX_DIMENSION - 1 - dimension] = *count);
+        Ok(PathMultiCost {
+            #[cfg(feature = "sparse-costs")]
+            lanes: occupied(&data),
+            data,
+            penalty: U::zero(),
+            rarity: U::zero(),
+            objective: Objective::default(),
+        })
     }
 }
 
@@ -107,6 +387,34 @@ mod tests {
         assert_eq!(cost(&[1, 5, 3]).get_cost(), vec![(1, 3), (5, 2), (3, 1)])
     }
 
+    #[test]
+    fn objectives_order_costs() {
+        // Two 2-letter hops against five 1-letter ones
+        let (big, small) = (cost(&[2, 0]), cost(&[0, 5]));
+        assert!(big > small);
+        let big = big.with_objective(Objective::FewestHops);
+        assert!(big < small.with_objective(Objective::FewestHops));
+        // The objective of a cost carries over to the ones it is compared with or added to
+        assert!(big < small);
+        assert!(small > big);
+        assert_eq!((small + big).objective(), Objective::FewestHops);
+        // Whichever is compared to the other
+        let (hops, letters_of) = (big, small.with_objective(Objective::FewestLetters));
+        assert_eq!(hops.cmp(&letters_of), letters_of.cmp(&hops).reverse());
+        assert_eq!(
+            hops.compare_with(&letters_of, Objective::SmallestMaxMutation),
+            Ordering::Greater
+        );
+        let letters = |c: PathMultiCost<u8>| c.with_objective(Objective::FewestLetters);
+        assert!(letters(cost(&[2, 0])) < letters(cost(&[0, 5])));
+        assert!(letters(cost(&[2, 0])) > letters(cost(&[0, 4])));
+        // Equal letters fall back to the hops by size
+        assert!(letters(cost(&[1, 0])) > letters(cost(&[0, 2])));
+        for objective in Objective::ALL {
+            assert_eq!(objective.name().parse(), Ok(objective));
+        }
+    }
+
     #[test]
     fn zero() {
         assert!(cost(&[0, 0, 0, 0, 0]).is_zero());
@@ -156,6 +464,44 @@ mod tests {
         assert!(cost(&[3, 0, 0]) > cost(&[2, 71, 88]));
     }
 
+    #[test]
+    fn penalty_only_breaks_ties() {
+        let penalized = cost(&[0, 1]) + PathMultiCost::penalty_only(5);
+        assert!(penalized > cost(&[0, 1]));
+        assert!(penalized < cost(&[0, 2]));
+        assert!(penalized < cost(&[1, 0]));
+        assert_eq!((penalized + penalized).penalty(), 10);
+        assert_eq!(penalized.get_cost(), cost(&[0, 1]).get_cost());
+        assert!(!PathMultiCost::<u8>::penalty_only(1).is_zero());
+        // Rarities only decide between costs whose penalties are the same
+        let rare = penalized + PathMultiCost::rarity_only(9);
+        assert!(rare > penalized);
+        assert!(rare < cost(&[0, 1]) + PathMultiCost::penalty_only(6));
+        assert_eq!((rare + rare).rarity(), 18);
+        assert!((PathMultiCost::<u8>::rarity_only(u8::MAX)).is_saturated());
+    }
+
+    #[test]
+    fn from_slice_most_significant_first() {
+        let a = PathMultiCost::from_slice(&[1u8, 0, 9]);
+        assert!(a > PathMultiCost::from_slice(&[0, 5, 0]));
+        assert_eq!(&a.as_slice()[..3], &[1, 0, 9]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let a = PathMultiCost::new(2u8, 0) + PathMultiCost::new(1, 2);
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, "[2,0,1]");
+        assert_eq!(serde_json::from_str::<PathMultiCost<u8>>(&json).unwrap(), a);
+        assert_eq!(
+            serde_json::to_string(&PathMultiCost::<u8>::zero()).unwrap(),
+            "[]"
+        );
+        assert!(serde_json::from_str::<PathMultiCost<u8>>(&format!("{:?}", [1; 21])).is_err());
+    }
+
     #[test]
     fn subadditivity() {
         //f(x+y)<=f(x)+f(y)
@@ -163,17 +509,24 @@ mod tests {
         assert!(cost(&[3, 5, 4]) <= cost(&[2, 3, 1]) + cost(&[1, 2, 3]));
     }
 
+    #[test]
+    fn saturation() {
+        let max = PathMultiCost::<u16>::new(u16::MAX, 0);
+        assert!((max + PathMultiCost::new(1, 0)).is_saturated());
+        assert!(!PathMultiCost::<u16>::new(1, 3).is_saturated());
+        let wide = PathMultiCost::<u64>::new(70_000, 2) + PathMultiCost::new(3, 0);
+        let narrow: PathMultiCost<u16> = wide.saturating_cast();
+        assert_eq!(narrow.get_cost(), vec![(u16::MAX, 3), (3, 1)]);
+        assert!(narrow.is_saturated());
+    }
+
     extern crate quickcheck;
 
     use quickcheck::empty_shrinker;
     use quickcheck::quickcheck;
 
     fn from_vec<U: Zero + Copy>(v: Vec<U>) -> PathMultiCost<U> {
-        let mut array = [U::zero(); MAX_DIMENSION];
-        v.iter().take(MAX_DIMENSION).enumerate().for_each(|(i, u)| {
-            array[i] = *u;
-        });
-        return PathMultiCost { data: array };
+        PathMultiCost::from_slice(&v[..v.len().min(MAX_DIMENSION)])
     }
 
     impl<U: quickcheck::Arbitrary + Copy + Zero + Copy + Bounded + CheckedAdd> quickcheck::Arbitrary
@@ -181,10 +534,10 @@ mod tests {
     {
         fn arbitrary<G: quickcheck::Gen>(g: &mut G) -> PathMultiCost<U> {
             let input: Vec<U> = quickcheck::Arbitrary::arbitrary(g);
-            return from_vec(input);
+            from_vec(input)
         }
 
-        fn shrink(&self) -> Box<Iterator<Item = Self>> {
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
             match self.is_zero() {
                 true => empty_shrinker(),
                 false => Box::new(self.data.to_vec().shrink().map(from_vec)),
@@ -231,6 +584,7 @@ mod tests {
         }
 
         //total ordering
+        #[allow(clippy::double_comparisons)]
         fn antisymmetry_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>) -> bool {
             if a >= b && a <= b { a == b } else { true }
         }
@@ -249,18 +603,52 @@ mod tests {
             a <= a
         }
 
+        // Sums compare as the same values given directly, whichever dimensions they use
+        fn sum_compares_as_its_values_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, c: PathMultiCost<u8>) -> bool {
+            let sum = a + b;
+            let given = PathMultiCost::from_slice(sum.as_slice());
+            sum.cmp(&c) == given.cmp(&c) && sum.is_zero() == given.is_zero()
+        }
+
+        // Both orders are the same one, with any objectives
+        fn partial_cmp_is_cmp_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, x: u8, y: u8) -> bool {
+            let (a, b) = (a.with_objective(objective(x)), b.with_objective(objective(y)));
+            a.partial_cmp(&b) == Some(a.cmp(&b)) && (a == b) == (a.cmp(&b) == Ordering::Equal)
+        }
+
+        fn antisymmetry_across_objectives_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, x: u8, y: u8) -> bool {
+            let (a, b) = (a.with_objective(objective(x)), b.with_objective(objective(y)));
+            a.cmp(&b) == b.cmp(&a).reverse() && (a + b).objective() == (b + a).objective()
+        }
+
+        fn transitivity_within_an_objective_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, c: PathMultiCost<u8>, x: u8) -> bool {
+            let o = objective(x);
+            let (a, b, c) = (a.with_objective(o), b.with_objective(o), c.with_objective(o));
+            if a <= b && b <= c { a <= c } else { true }
+        }
+
+        fn compare_with_ignores_carried_objectives_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, x: u8, y: u8, z: u8) -> bool {
+            let o = objective(z);
+            a.with_objective(objective(x)).compare_with(&b.with_objective(objective(y)), o)
+                == a.with_objective(o).cmp(&b.with_objective(o))
+        }
+
         //PathMultiCost<u8> is isotone
          fn isotone_prop(a: PathMultiCost<u8>, b: PathMultiCost<u8>, c: PathMultiCost<u8>) -> bool {
             if a <= b {a + c <= b + c && c + a <= c + b } else {true}
         }
     }
 
+    fn objective(n: u8) -> Objective {
+        Objective::ALL[n as usize % Objective::ALL.len()]
+    }
+
     fn cost(input: &[u8]) -> PathMultiCost<u8> {
         let mut data = [0; MAX_DIMENSION];
         input
             .iter()
             .enumerate()
-            .for_each(|(i, _)| (data[MAX_DIMENSION - i - 1] = input[input.len() - i - 1]));
-        return PathMultiCost { data };
+            .for_each(|(i, _)| data[MAX_DIMENSION - i - 1] = input[input.len() - i - 1]);
+        PathMultiCost::from_slice(&data)
     }
 }
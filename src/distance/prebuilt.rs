@@ -0,0 +1,802 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::prebuilt::format::{Decoder, Encoder, Tag};
+use crate::distance::symspell::{Deletions, DELETION_DEPTH};
+use crate::distance::word::{hops_lower_bound, levenshtein, EditDistance};
+use crate::distance::ShortestPath;
+use pathfinding::directed::astar::astar;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::io::{self, BufRead, Read, Write};
+use std::mem;
+use std::thread;
+
+mod format;
+
+pub use crate::distance::prebuilt::format::VERSION;
+
+const WORDS: Tag = *b"WRDS";
+const NEIGHBOURS: Tag = *b"ADJC";
+const LANDMARKS: Tag = *b"LMRK";
+const BUCKETS: Tag = *b"BCKT";
+const FREQUENCIES: Tag = *b"FREQ";
+const DELETIONS: Tag = *b"DELS";
+
+// Hops from a landmark to a word it does not reach
+const UNREACHABLE: u32 = u32::MAX;
+
+/// Words linked by the hops changing a single letter, computed once and saved to a file
+/// along with optional data speeding up the searches run over it
+#[derive(Debug, Default, PartialEq)]
+pub struct PrebuiltGraph {
+    words: Vec<String>,
+    indices: HashMap<String, u32>,
+    neighbours: Vec<Vec<u32>>,
+    // Landmark word and the hops from it to every word
+    landmarks: Vec<(u32, Vec<u32>)>,
+    // Words by number of letters
+    buckets: Option<BTreeMap<u32, Vec<u32>>>,
+    frequencies: Option<Vec<u32>>,
+    deletions: Option<Deletions>,
+}
+
+fn length(word: &str) -> u32 {
+    word.chars().count() as u32
+}
+
+fn by_length(words: &[String]) -> BTreeMap<u32, Vec<u32>> {
+    let mut buckets: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for (index, word) in words.iter().enumerate() {
+        buckets.entry(length(word)).or_default().push(index as u32);
+    }
+    buckets
+}
+
+// Words one letter apart from `word` among those whose lengths differ by at most one letter
+fn links_among<'a>(
+    word: &'a str,
+    words: &'a [String],
+    buckets: &'a BTreeMap<u32, Vec<u32>>,
+) -> impl Iterator<Item = u32> + 'a {
+    let length = length(word);
+    (length.saturating_sub(1)..=length + 1)
+        .filter_map(move |length| buckets.get(&length))
+        .flatten()
+        .copied()
+        .filter(move |&other| levenshtein(word, &words[other as usize]) == 1)
+}
+
+/// Read `word,count` lines, lines starting with `#` are comments
+pub fn frequencies_from_csv(reader: impl BufRead) -> io::Result<HashMap<String, u32>> {
+    let mut frequencies = HashMap::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let count = line
+            .rsplit_once(',')
+            .and_then(|(word, count)| Some((word, count.trim().parse::<u32>().ok()?)));
+        match count {
+            Some((word, count)) => frequencies.insert(word.trim().to_string(), count),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: expected word,count", number + 1),
+                ))
+            }
+        };
+    }
+    Ok(frequencies)
+}
+
+impl PrebuiltGraph {
+    /// Link every pair of words one letter apart, comparing only words whose lengths differ
+    /// by at most one letter. Duplicated words are kept once.
+    pub fn build(mut words: Vec<String>) -> PrebuiltGraph {
+        words.sort();
+        words.dedup();
+        let buckets = by_length(&words);
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk = words.len().div_ceil(threads).max(1);
+        let neighbours = thread::scope(|scope| {
+            let handles: Vec<_> = words
+                .chunks(chunk)
+                .map(|chunk| {
+                    let (words, buckets) = (&words, &buckets);
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|word| links_among(word, words, buckets).collect())
+                            .collect::<Vec<Vec<u32>>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+        let mut graph = PrebuiltGraph {
+            words,
+            neighbours,
+            ..PrebuiltGraph::default()
+        };
+        graph.reindex();
+        graph
+    }
+
+    fn reindex(&mut self) {
+        self.indices = self
+            .words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| (word.clone(), index as u32))
+            .collect();
+    }
+
+    /// Add and remove words without linking the whole list again, only the words added are
+    /// compared to the others. Words added have no frequency. Landmarks and deletions, when
+    /// stored, are computed again. Returns how many words were added and removed.
+    pub fn update(&mut self, added: Vec<String>, removed: &[String]) -> (usize, usize) {
+        let landmarks = self.landmarks.len();
+        let removed = self.remove(removed);
+        let added = self.add(added);
+        if landmarks > 0 {
+            *self = mem::take(self).with_landmarks(landmarks);
+        }
+        if self.deletions.is_some() {
+            *self = mem::take(self).with_deletions();
+        }
+        (added, removed)
+    }
+
+    // Landmarks and deletions are left stale, `update` computes them again
+    fn remove(&mut self, removed: &[String]) -> usize {
+        let gone: HashSet<u32> = removed
+            .iter()
+            .filter_map(|word| self.indices.get(word))
+            .copied()
+            .collect();
+        if gone.is_empty() {
+            return 0;
+        }
+        // Index of every word kept once the others are gone
+        let mut next = 0;
+        let renumbered: Vec<Option<u32>> = (0..self.words.len() as u32)
+            .map(|index| {
+                if gone.contains(&index) {
+                    return None;
+                }
+                next += 1;
+                Some(next - 1)
+            })
+            .collect();
+        let renumber = |indices: &[u32]| -> Vec<u32> {
+            indices
+                .iter()
+                .filter_map(|&index| renumbered[index as usize])
+                .collect()
+        };
+        fn kept<T>(items: Vec<T>, renumbered: &[Option<u32>]) -> Vec<T> {
+            items
+                .into_iter()
+                .zip(renumbered)
+                .filter(|(_, index)| index.is_some())
+                .map(|(item, _)| item)
+                .collect()
+        }
+        self.words = kept(mem::take(&mut self.words), &renumbered);
+        self.neighbours = kept(mem::take(&mut self.neighbours), &renumbered)
+            .iter()
+            .map(|neighbours| renumber(neighbours))
+            .collect();
+        if let Some(buckets) = &mut self.buckets {
+            buckets
+                .values_mut()
+                .for_each(|words| *words = renumber(words));
+            buckets.retain(|_, words| !words.is_empty());
+        }
+        if let Some(frequencies) = &mut self.frequencies {
+            *frequencies = kept(mem::take(frequencies), &renumbered);
+        }
+        self.landmarks.clear();
+        self.reindex();
+        gone.len()
+    }
+
+    fn add(&mut self, added: Vec<String>) -> usize {
+        let first = self.words.len();
+        for word in added {
+            if self.indices.contains_key(&word) {
+                continue;
+            }
+            let index = self.words.len() as u32;
+            if let Some(buckets) = &mut self.buckets {
+                buckets.entry(length(&word)).or_default().push(index);
+            }
+            if let Some(frequencies) = &mut self.frequencies {
+                frequencies.push(0);
+            }
+            self.indices.insert(word.clone(), index);
+            self.words.push(word);
+            self.neighbours.push(Vec::new());
+        }
+        let buckets = by_length(&self.words);
+        for new in first..self.words.len() {
+            let links: Vec<u32> = links_among(&self.words[new], &self.words, &buckets)
+                .filter(|&other| other as usize != new)
+                .collect();
+            // Words added link to each other when their own links are computed
+            for &other in links.iter().filter(|&&other| (other as usize) < first) {
+                self.neighbours[other as usize].push(new as u32);
+            }
+            self.neighbours[new] = links;
+        }
+        self.words.len() - first
+    }
+
+    /// Store the hops from `count` landmarks, each one the word the farthest from those
+    /// picked before it, for the searches to bound the hops left by triangle inequality
+    pub fn with_landmarks(mut self, count: usize) -> PrebuiltGraph {
+        self.landmarks.clear();
+        if self.words.is_empty() {
+            return self;
+        }
+        // The first landmark is the word the farthest from an arbitrary one
+        let mut closest = self.hops_from(0);
+        for _ in 0..count {
+            let landmark = (0..self.words.len())
+                .filter(|&word| closest[word] != UNREACHABLE)
+                .max_by_key(|&word| (closest[word], std::cmp::Reverse(word)))
+                .unwrap() as u32;
+            if self.landmarks.iter().any(|&(known, _)| known == landmark) {
+                break;
+            }
+            let hops = self.hops_from(landmark);
+            for (closest, &hops) in closest.iter_mut().zip(&hops) {
+                *closest = (*closest).min(hops);
+            }
+            self.landmarks.push((landmark, hops));
+        }
+        self
+    }
+
+    /// Store the words by number of letters, to link words missing from the graph quickly
+    pub fn with_buckets(mut self) -> PrebuiltGraph {
+        self.buckets = Some(by_length(&self.words));
+        self
+    }
+
+    /// Store the deletions of every word, to find the words up to 2 letters away quickly
+    pub fn with_deletions(mut self) -> PrebuiltGraph {
+        let words = self.words.iter().map(String::as_str);
+        self.deletions = Some(Deletions::new(words, DELETION_DEPTH));
+        self
+    }
+
+    /// Store how often every word is used, words missing from `frequencies` count 0
+    pub fn with_frequencies(mut self, frequencies: &HashMap<String, u32>) -> PrebuiltGraph {
+        self.frequencies = Some(
+            self.words
+                .iter()
+                .map(|word| frequencies.get(word).copied().unwrap_or(0))
+                .collect(),
+        );
+        self
+    }
+
+    // Breadth-first search through the whole graph
+    fn hops_from(&self, start: u32) -> Vec<u32> {
+        let mut hops = vec![UNREACHABLE; self.words.len()];
+        hops[start as usize] = 0;
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(current) = queue.pop_front() {
+            let next = hops[current as usize] + 1;
+            for &word in &self.neighbours[current as usize] {
+                if hops[word as usize] == UNREACHABLE {
+                    hops[word as usize] = next;
+                    queue.push_back(word);
+                }
+            }
+        }
+        hops
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Every word, in the order they are stored in
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Number of hops between two words, counted once for both directions
+    pub fn edge_count(&self) -> usize {
+        self.neighbours.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    pub fn landmark_count(&self) -> usize {
+        self.landmarks.len()
+    }
+
+    pub fn has_buckets(&self) -> bool {
+        self.buckets.is_some()
+    }
+
+    pub fn has_deletions(&self) -> bool {
+        self.deletions.is_some()
+    }
+
+    /// Letters deleted at most from the words to index them, when the graph stores deletions
+    pub fn deletion_depth(&self) -> Option<usize> {
+        self.deletions.as_ref().map(Deletions::depth)
+    }
+
+    pub fn has_frequencies(&self) -> bool {
+        self.frequencies.is_some()
+    }
+
+    /// Bytes the graph takes in memory once read, leaving out what allocators and hash
+    /// tables keep spare
+    pub fn memory_estimate(&self) -> usize {
+        let string = |s: &String| mem::size_of::<String>() + s.capacity();
+        let list = |v: &Vec<u32>| mem::size_of::<Vec<u32>>() + v.capacity() * 4;
+        let words: usize = self.words.iter().map(string).sum();
+        let indices: usize = self.indices.keys().map(|word| string(word) + 4).sum();
+        let neighbours: usize = self.neighbours.iter().map(list).sum();
+        let landmarks: usize = self.landmarks.iter().map(|(_, hops)| 4 + list(hops)).sum();
+        let buckets: usize = self
+            .buckets
+            .iter()
+            .flatten()
+            .map(|(_, w)| 4 + list(w))
+            .sum();
+        let frequencies = self.frequencies.as_ref().map_or(0, |f| f.capacity() * 4);
+        let deletions: usize = self
+            .deletions
+            .iter()
+            .flat_map(|deletions| deletions.variants())
+            .map(|(variant, words)| string(variant) + list(words))
+            .sum();
+        mem::size_of::<PrebuiltGraph>()
+            + words
+            + indices
+            + neighbours
+            + landmarks
+            + buckets
+            + frequencies
+            + deletions
+    }
+
+    /// How often `word` is used, when the graph stores frequencies and knows the word
+    pub fn frequency(&self, word: &str) -> Option<u32> {
+        let index = *self.indices.get(word)?;
+        Some(self.frequencies.as_ref()?[index as usize])
+    }
+
+    // Words of the graph one letter away from a word it may not contain
+    fn links(&self, word: &str) -> Vec<u32> {
+        if let Some(&index) = self.indices.get(word) {
+            return self.neighbours[index as usize].clone();
+        }
+        match &self.buckets {
+            Some(buckets) => links_among(word, &self.words, buckets).collect(),
+            None => (0..self.words.len() as u32)
+                .filter(|&other| levenshtein(word, &self.words[other as usize]) == 1)
+                .collect(),
+        }
+    }
+
+    /// Shortest path of 1-letter hops through the graph, `start` and `stop` do not need to
+    /// belong to it. Landmarks, when stored, tighten the edit distance heuristic.
+    pub fn shortest_path<'a>(&'a self, start: &'a str, stop: &'a str) -> Option<ShortestPath<'a>> {
+        if start == stop {
+            return Some((vec![start], hops_lower_bound(0)));
+        }
+        // Words of the graph are numbered first, then `start` and `stop` when outside of it
+        let outside_start = self.words.len();
+        let outside_stop = outside_start + 1;
+        let start_node = self
+            .indices
+            .get(start)
+            .map_or(outside_start, |&i| i as usize);
+        let stop_node = self.indices.get(stop).map_or(outside_stop, |&i| i as usize);
+        let start_links = self.links(start);
+        let stop_links = self.links(stop);
+        let word = |node: usize| match node {
+            node if node == outside_start => start,
+            node if node == outside_stop => stop,
+            node => self.words[node].as_str(),
+        };
+        let successors = |&node: &usize| -> Vec<(usize, usize)> {
+            let mut successors: Vec<(usize, usize)> = match node {
+                node if node == outside_start => &start_links,
+                node if node == outside_stop => &stop_links,
+                node => &self.neighbours[node],
+            }
+            .iter()
+            .map(|&word| (word as usize, 1))
+            .collect();
+            if stop_node == outside_stop && stop_links.contains(&(node as u32)) {
+                successors.push((outside_stop, 1));
+            }
+            if node == outside_start && stop_node == outside_stop && levenshtein(start, stop) == 1 {
+                successors.push((outside_stop, 1));
+            }
+            successors
+        };
+        let heuristic = |&node: &usize| {
+            let bound = levenshtein(word(node), stop);
+            if node >= outside_start || stop_node == outside_stop {
+                return bound;
+            }
+            self.landmarks
+                .iter()
+                .map(|(_, hops)| match (hops[stop_node], hops[node]) {
+                    // No hop leads from the word to `stop`, the search will find it out
+                    (UNREACHABLE, _) | (_, UNREACHABLE) => 0,
+                    (to_stop, to_word) => to_stop.saturating_sub(to_word) as usize,
+                })
+                .fold(bound, usize::max)
+        };
+        let (path, hops) = astar(&start_node, successors, heuristic, |&node| {
+            node == stop_node
+        })?;
+        let cost: PathMultiCost<EditDistance> = hops_lower_bound(hops);
+        Some((path.into_iter().map(word).collect(), cost))
+    }
+
+    /// Write the graph and every optional section it holds
+    pub fn write(&self, writer: impl Write) -> io::Result<()> {
+        let mut sections = Vec::new();
+        let mut words = Encoder::default();
+        words.u32(self.words.len() as u32);
+        self.words.iter().for_each(|word| words.str(word));
+        sections.push((WORDS, words.into_bytes()));
+        let mut neighbours = Encoder::default();
+        self.neighbours.iter().for_each(|n| neighbours.u32s(n));
+        sections.push((NEIGHBOURS, neighbours.into_bytes()));
+        if !self.landmarks.is_empty() {
+            let mut landmarks = Encoder::default();
+            landmarks.u32(self.landmarks.len() as u32);
+            for (landmark, hops) in &self.landmarks {
+                landmarks.u32(*landmark);
+                landmarks.u32s(hops);
+            }
+            sections.push((LANDMARKS, landmarks.into_bytes()));
+        }
+        if let Some(buckets) = &self.buckets {
+            let mut encoder = Encoder::default();
+            encoder.u32(buckets.len() as u32);
+            for (length, words) in buckets {
+                encoder.u32(*length);
+                encoder.u32s(words);
+            }
+            sections.push((BUCKETS, encoder.into_bytes()));
+        }
+        if let Some(frequencies) = &self.frequencies {
+            let mut encoder = Encoder::default();
+            encoder.u32s(frequencies);
+            sections.push((FREQUENCIES, encoder.into_bytes()));
+        }
+        if let Some(deletions) = &self.deletions {
+            let mut encoder = Encoder::default();
+            encoder.u32(deletions.depth() as u32);
+            encoder.u32(deletions.variants().len() as u32);
+            for (variant, words) in deletions.variants() {
+                encoder.str(variant);
+                encoder.u32s(words);
+            }
+            sections.push((DELETIONS, encoder.into_bytes()));
+        }
+        format::write(writer, &sections)
+    }
+
+    /// Version of a graph file, which may be older than the one `read` accepts
+    pub fn version(bytes: &[u8]) -> io::Result<u32> {
+        format::read_any(bytes).map(|(version, _)| version)
+    }
+
+    /// A graph file of an older version written again as the current one, or `None` if
+    /// it already is. Sections keep their content, only their framing changed so far.
+    pub fn migrate(bytes: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let (version, sections) = format::read_any(bytes)?;
+        if version == VERSION {
+            return Ok(None);
+        }
+        let sections: Vec<(Tag, Vec<u8>)> = sections
+            .into_iter()
+            .map(|(tag, section)| (tag, section.to_vec()))
+            .collect();
+        let mut migrated = Vec::new();
+        format::write(&mut migrated, &sections)?;
+        // Files only get replaced by graphs this release can read
+        PrebuiltGraph::read(migrated.as_slice())?;
+        Ok(Some(migrated))
+    }
+
+    /// Read a whole graph file at once. Sections this release does not know are skipped.
+    pub fn read(mut reader: impl Read) -> io::Result<PrebuiltGraph> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let mut graph = PrebuiltGraph::default();
+        for (tag, section) in format::read(&bytes)? {
+            let mut decoder = Decoder::new(section);
+            match tag {
+                WORDS => {
+                    let count = decoder.u32()?;
+                    graph.words = (0..count)
+                        .map(|_| decoder.str().map(str::to_string))
+                        .collect::<io::Result<_>>()?;
+                }
+                NEIGHBOURS => {
+                    while !decoder.is_empty() {
+                        graph.neighbours.push(decoder.u32s()?);
+                    }
+                }
+                LANDMARKS => {
+                    let count = decoder.u32()?;
+                    for _ in 0..count {
+                        graph.landmarks.push((decoder.u32()?, decoder.u32s()?));
+                    }
+                }
+                BUCKETS => {
+                    let count = decoder.u32()?;
+                    let mut buckets = BTreeMap::new();
+                    for _ in 0..count {
+                        buckets.insert(decoder.u32()?, decoder.u32s()?);
+                    }
+                    graph.buckets = Some(buckets);
+                }
+                FREQUENCIES => graph.frequencies = Some(decoder.u32s()?),
+                DELETIONS => {
+                    let depth = decoder.u32()? as usize;
+                    let count = decoder.u32()?;
+                    let mut variants = BTreeMap::new();
+                    for _ in 0..count {
+                        variants.insert(decoder.str()?.to_string(), decoder.u32s()?);
+                    }
+                    graph.deletions = Some(Deletions::from_parts(depth, variants));
+                }
+                _ => (),
+            }
+        }
+        graph.check()?;
+        graph.reindex();
+        Ok(graph)
+    }
+
+    // Every index must point to a word, searches index without checking
+    fn check(&self) -> io::Result<()> {
+        let count = self.words.len();
+        let valid = |indices: &[u32]| indices.iter().all(|&i| (i as usize) < count);
+        let consistent = self.neighbours.len() == count
+            && self.neighbours.iter().all(|n| valid(n))
+            && self
+                .landmarks
+                .iter()
+                .all(|(landmark, hops)| (*landmark as usize) < count && hops.len() == count)
+            && self
+                .buckets
+                .iter()
+                .flat_map(BTreeMap::values)
+                .all(|words| valid(words))
+            && self.frequencies.as_ref().is_none_or(|f| f.len() == count)
+            && self
+                .deletions
+                .iter()
+                .flat_map(|deletions| deletions.variants().values())
+                .all(|words| valid(words));
+        if consistent {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "graph file sections do not match its words",
+            ))
+        }
+    }
+}
+
+impl Dictionary for PrebuiltGraph {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.words.iter().map(String::as_str))
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.indices.contains_key(word)
+    }
+
+    // Hops of a single letter are stored, farther words are looked up among the deletions
+    // when stored, scanned for otherwise
+    fn extend_neighbors<'d>(&'d self, word: &str, k: usize, neighbors: &mut Vec<&'d str>) {
+        let candidates = match k {
+            0 => return,
+            // Links of stored words are read in place rather than copied
+            1 => match self.indices.get(word) {
+                Some(&index) => {
+                    return neighbors.extend(
+                        self.neighbours[index as usize]
+                            .iter()
+                            .map(|&other| self.words[other as usize].as_str()),
+                    )
+                }
+                None => {
+                    return neighbors.extend(
+                        self.links(word)
+                            .into_iter()
+                            .map(|other| self.words[other as usize].as_str()),
+                    )
+                }
+            },
+            k => self
+                .deletions
+                .as_ref()
+                .and_then(|deletions| deletions.candidates(word, k))
+                .unwrap_or_else(|| (0..self.words.len() as u32).collect()),
+        };
+        neighbors.extend(
+            candidates
+                .into_iter()
+                .map(|index| self.words[index as usize].as_str())
+                .filter(|&other| other != word && levenshtein(word, other) <= k),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<String> {
+        [
+            "cold", "cord", "card", "ward", "warm", "word", "cold", "lonely",
+        ]
+        .iter()
+        .map(|w| w.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn round_trip_with_every_section() {
+        let frequencies = frequencies_from_csv("# counts\ncold,12\nwarm, 3\n".as_bytes()).unwrap();
+        let graph = PrebuiltGraph::build(words())
+            .with_landmarks(2)
+            .with_buckets()
+            .with_frequencies(&frequencies)
+            .with_deletions();
+        assert_eq!(graph.word_count(), 7);
+        assert_eq!(graph.landmark_count(), 2);
+        let mut file = Vec::new();
+        graph.write(&mut file).unwrap();
+        let read = PrebuiltGraph::read(file.as_slice()).unwrap();
+        assert_eq!(read, graph);
+        assert_eq!(read.frequency("cold"), Some(12));
+        assert_eq!(read.frequency("card"), Some(0));
+        assert_eq!(read.frequency("snow"), None);
+    }
+
+    #[test]
+    fn landmarks_keep_paths_shortest() {
+        let plain = PrebuiltGraph::build(words());
+        let fast = PrebuiltGraph::build(words())
+            .with_landmarks(3)
+            .with_buckets();
+        for graph in &[plain, fast] {
+            let (path, cost) = graph.shortest_path("cold", "warm").unwrap();
+            assert_eq!(path.len(), 5);
+            assert_eq!(cost, hops_lower_bound(4));
+            assert_eq!(graph.shortest_path("cold", "lonely"), None);
+        }
+    }
+
+    #[test]
+    fn words_outside_of_the_graph() {
+        let graph = PrebuiltGraph::build(words()).with_buckets();
+        let (path, _) = graph.shortest_path("bold", "worm").unwrap();
+        assert_eq!(path.first(), Some(&"bold"));
+        assert_eq!(path.last(), Some(&"worm"));
+        assert_eq!(path.len(), 5);
+        assert_eq!(graph.shortest_path("zzzz", "cold"), None);
+    }
+
+    // Words linked to every word, whatever their indices
+    fn links(graph: &PrebuiltGraph) -> BTreeMap<&str, Vec<&str>> {
+        graph
+            .words
+            .iter()
+            .zip(&graph.neighbours)
+            .map(|(word, neighbours)| {
+                let mut neighbours: Vec<&str> = neighbours
+                    .iter()
+                    .map(|&n| graph.words[n as usize].as_str())
+                    .collect();
+                neighbours.sort();
+                (word.as_str(), neighbours)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn updates_match_a_fresh_build() {
+        let frequencies = frequencies_from_csv("warm,3\nward,2\n".as_bytes()).unwrap();
+        let mut graph = PrebuiltGraph::build(words())
+            .with_landmarks(2)
+            .with_buckets()
+            .with_frequencies(&frequencies)
+            .with_deletions();
+        let added = vec!["worm".to_string(), "wore".to_string(), "cold".to_string()];
+        let removed = vec!["card".to_string(), "snow".to_string()];
+        assert_eq!(graph.update(added, &removed), (2, 1));
+        let mut expected = words();
+        expected.retain(|word| word != "card");
+        expected.extend(vec!["worm".to_string(), "wore".to_string()]);
+        let fresh = PrebuiltGraph::build(expected).with_landmarks(2);
+        assert_eq!(links(&graph), links(&fresh));
+        let mut near = graph.neighbors_within("word", 2);
+        near.sort_unstable();
+        assert_eq!(near, fresh.neighbors_within("word", 2));
+        assert_eq!(graph.landmark_count(), 2);
+        assert!(graph.has_frequencies());
+        assert_eq!(graph.deletion_depth(), Some(DELETION_DEPTH));
+        assert!(graph.memory_estimate() > PrebuiltGraph::build(words()).memory_estimate());
+        assert_eq!(graph.frequency("warm"), Some(3));
+        assert_eq!(graph.frequency("worm"), Some(0));
+        let (path, _) = graph.shortest_path("cold", "warm").unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(!path.contains(&"card"));
+        let mut file = Vec::new();
+        graph.write(&mut file).unwrap();
+        assert_eq!(PrebuiltGraph::read(file.as_slice()).unwrap(), graph);
+    }
+
+    #[test]
+    fn searches_run_over_the_graph_as_a_dictionary() {
+        let graph = PrebuiltGraph::build(words()).with_buckets();
+        assert!(graph.contains("ward"));
+        assert!(!graph.contains("worm"));
+        let mut neighbours = graph.neighbors_within("worm", 1);
+        neighbours.sort_unstable();
+        assert_eq!(neighbours, vec!["warm", "word"]);
+        assert_eq!(graph.neighbors_within("cold", 2).len(), 3);
+        let indexed = PrebuiltGraph::build(words()).with_deletions();
+        assert_eq!(
+            indexed.neighbors_within("cold", 2),
+            graph.neighbors_within("cold", 2)
+        );
+        let (path, _) = crate::distance::find_shortest_path(
+            "cold",
+            "warm",
+            &graph,
+            &crate::distance::PathFindingAlgorithm::Astar,
+        )
+        .unwrap();
+        assert_eq!(path.len(), 5);
+    }
+
+    #[test]
+    fn old_files_are_migrated() {
+        let graph = PrebuiltGraph::build(words()).with_buckets();
+        let mut current = Vec::new();
+        graph.write(&mut current).unwrap();
+        let sections: Vec<(Tag, Vec<u8>)> = format::read(&current)
+            .unwrap()
+            .into_iter()
+            .map(|(tag, section)| (tag, section.to_vec()))
+            .collect();
+        let old = format::write_version_1(&sections);
+        assert_eq!(PrebuiltGraph::version(&old).unwrap(), 1);
+        assert!(PrebuiltGraph::read(old.as_slice()).is_err());
+        let migrated = PrebuiltGraph::migrate(&old).unwrap().unwrap();
+        assert_eq!(migrated, current);
+        assert_eq!(PrebuiltGraph::migrate(&migrated).unwrap(), None);
+    }
+
+    #[test]
+    fn inconsistent_files_are_rejected() {
+        let mut file = Vec::new();
+        format::write(&mut file, &[(NEIGHBOURS, vec![1, 0, 0, 0, 7, 0, 0, 0])]).unwrap();
+        assert!(PrebuiltGraph::read(file.as_slice()).is_err());
+    }
+}
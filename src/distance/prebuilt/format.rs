@@ -0,0 +1,281 @@
+use std::convert::TryInto;
+use std::io::{self, Write};
+
+// Header: magic, version, flags, number of sections, checksum of everything after the
+// header. Every section then starts with its tag, its length and its own checksum.
+const MAGIC: &[u8; 8] = b"TYPOSGRF";
+const HEADER_LENGTH: usize = 28;
+
+// Version 1 had no flags and no checksums of sections, its files are still read to be
+// migrated
+const V1_HEADER_LENGTH: usize = 24;
+
+/// Version of the layout written by this release
+pub const VERSION: u32 = 2;
+
+// Features a file needs its reader to understand, none yet. Readers refuse files with
+// flags they do not know rather than misread them; sections they do not know, which
+// older releases can do without, are skipped instead.
+const KNOWN_FLAGS: u32 = 0;
+
+/// Four letters naming the content of a section
+pub type Tag = [u8; 4];
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+// FNV-1a, stable across releases unlike the std hasher
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Little-endian encoding of a section
+#[derive(Default)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+    }
+
+    /// Length first, then every value
+    pub fn u32s(&mut self, values: &[u32]) {
+        self.u32(values.len() as u32);
+        values.iter().for_each(|&value| self.u32(value));
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads back what an `Encoder` wrote, failing on truncated data
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Decoder<'a> {
+        Decoder { bytes }
+    }
+
+    fn take(&mut self, length: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < length {
+            return Err(invalid("truncated graph file"));
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub fn str(&mut self) -> io::Result<&'a str> {
+        let length = self.u32()? as usize;
+        std::str::from_utf8(self.take(length)?).map_err(|_| invalid("word is not UTF-8"))
+    }
+
+    pub fn u32s(&mut self) -> io::Result<Vec<u32>> {
+        let length = self.u32()? as usize;
+        (0..length).map(|_| self.u32()).collect()
+    }
+}
+
+/// Header followed by every section, each one prefixed by its tag, length and checksum
+pub fn write(mut writer: impl Write, sections: &[(Tag, Vec<u8>)]) -> io::Result<()> {
+    let mut body = Vec::new();
+    for (tag, bytes) in sections {
+        body.extend_from_slice(tag);
+        body.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        body.extend_from_slice(&checksum(bytes).to_le_bytes());
+        body.extend_from_slice(bytes);
+    }
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&KNOWN_FLAGS.to_le_bytes())?;
+    writer.write_all(&(sections.len() as u32).to_le_bytes())?;
+    writer.write_all(&checksum(&body).to_le_bytes())?;
+    writer.write_all(&body)
+}
+
+/// Sections of a file by tag, in the order they were written
+pub type Sections<'b> = Vec<(Tag, &'b [u8])>;
+
+/// Version of a file of this version or an older one, and its sections split without
+/// copying them once the header and checksums are checked
+pub fn read_any(bytes: &[u8]) -> io::Result<(u32, Sections<'_>)> {
+    if bytes.len() < V1_HEADER_LENGTH || &bytes[..8] != MAGIC {
+        return Err(invalid("not a typos graph file"));
+    }
+    let mut header = Decoder::new(&bytes[8..]);
+    let version = header.u32()?;
+    let header_length = match version {
+        1 => V1_HEADER_LENGTH,
+        VERSION => HEADER_LENGTH,
+        version if version > VERSION => {
+            return Err(invalid(&format!(
+            "graph file version {} was written by a newer release, this one reads up to version {}",
+            version, VERSION
+        )))
+        }
+        version => {
+            return Err(invalid(&format!(
+                "graph file version {} is not supported",
+                version
+            )))
+        }
+    };
+    if version != 1 {
+        let unknown = header.u32()? & !KNOWN_FLAGS;
+        if unknown != 0 {
+            return Err(invalid(&format!(
+                "graph file needs features this release does not have (flags {:#x})",
+                unknown
+            )));
+        }
+    }
+    let count = header.u32()?;
+    let body = &bytes[header_length.min(bytes.len())..];
+    if header.u64()? != checksum(body) {
+        return Err(invalid("graph file is corrupted, checksum mismatch"));
+    }
+    let mut body = Decoder::new(body);
+    let sections = (0..count)
+        .map(|_| {
+            let tag: Tag = body.take(4)?.try_into().unwrap();
+            let length = body.u64()? as usize;
+            let expected = match version {
+                1 => None,
+                _ => Some(body.u64()?),
+            };
+            let section = body.take(length)?;
+            if expected.is_some_and(|expected| expected != checksum(section)) {
+                return Err(invalid(&format!(
+                    "section {} of the graph file is corrupted, checksum mismatch",
+                    String::from_utf8_lossy(&tag)
+                )));
+            }
+            Ok((tag, section))
+        })
+        .collect::<io::Result<_>>()?;
+    Ok((version, sections))
+}
+
+/// Same sections laid out as version 1 wrote them, to test migrations
+#[cfg(test)]
+pub fn write_version_1(sections: &[(Tag, Vec<u8>)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (tag, bytes) in sections {
+        body.extend_from_slice(tag);
+        body.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+        body.extend_from_slice(bytes);
+    }
+    let mut file = MAGIC.to_vec();
+    file.extend_from_slice(&1u32.to_le_bytes());
+    file.extend_from_slice(&(sections.len() as u32).to_le_bytes());
+    file.extend_from_slice(&checksum(&body).to_le_bytes());
+    file.extend_from_slice(&body);
+    file
+}
+
+/// Sections of a file of the current version, older ones being refused until migrated
+pub fn read(bytes: &[u8]) -> io::Result<Sections<'_>> {
+    let (version, sections) = read_any(bytes)?;
+    if version != VERSION {
+        return Err(invalid(&format!(
+            "graph file version {} is older than version {}, upgrade it with `typos migrate-graph`",
+            version, VERSION
+        )));
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_round_trip() {
+        let mut encoder = Encoder::default();
+        encoder.str("café");
+        encoder.u32s(&[1, 2, 3]);
+        let mut file = Vec::new();
+        write(&mut file, &[(*b"TEST", encoder.into_bytes())]).unwrap();
+        let sections = read(&file).unwrap();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(&sections[0].0, b"TEST");
+        let mut decoder = Decoder::new(sections[0].1);
+        assert_eq!(decoder.str().unwrap(), "café");
+        assert_eq!(decoder.u32s().unwrap(), vec![1, 2, 3]);
+        assert!(decoder.u32().is_err());
+    }
+
+    #[test]
+    fn corruption_is_detected() {
+        let mut file = Vec::new();
+        write(&mut file, &[(*b"TEST", vec![1, 2, 3])]).unwrap();
+        let last = file.len() - 1;
+        file[last] ^= 1;
+        assert!(read(&file).unwrap_err().to_string().contains("checksum"));
+        assert!(read(b"hello").is_err());
+    }
+
+    #[test]
+    fn versions_and_flags_are_checked() {
+        let sections = [(*b"TEST", vec![1, 2, 3])];
+        let old = write_version_1(&sections);
+        let (version, read_back) = read_any(&old).unwrap();
+        assert_eq!(version, 1);
+        assert_eq!(read_back, vec![(*b"TEST", &[1u8, 2, 3][..])]);
+        assert!(read(&old)
+            .unwrap_err()
+            .to_string()
+            .contains("migrate-graph"));
+
+        let mut file = Vec::new();
+        write(&mut file, &sections).unwrap();
+        assert_eq!(read_any(&file).unwrap().0, VERSION);
+        let mut newer = file.clone();
+        newer[8] = VERSION as u8 + 1;
+        assert!(read(&newer)
+            .unwrap_err()
+            .to_string()
+            .contains("newer release"));
+        let mut flagged = file.clone();
+        flagged[12] = 4;
+        assert!(read(&flagged)
+            .unwrap_err()
+            .to_string()
+            .contains("flags 0x4"));
+        // A section and the checksum of the whole body both damaged the same way
+        let mut damaged = file;
+        let last = damaged.len() - 1;
+        damaged[last] ^= 1;
+        let body = checksum(&damaged[HEADER_LENGTH..]).to_le_bytes();
+        damaged[20..HEADER_LENGTH].copy_from_slice(&body);
+        assert!(read(&damaged)
+            .unwrap_err()
+            .to_string()
+            .contains("section TEST"));
+    }
+}
@@ -0,0 +1,300 @@
+use crate::distance::cost::CostModel;
+use crate::distance::dictionary::Dictionary;
+use crate::distance::observer::SearchObserver;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{Interruption, SearchOptions, SearchStats, ShortestPath};
+use num_traits::Zero;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Why a search did not go through a word
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pruning {
+    /// The word is on the path found
+    OnPath,
+    NotListed,
+    /// Expanded at `cost` from the word the search started from, without leading anywhere
+    /// cheaper than the path found
+    Expanded {
+        cost: PathMultiCost<EditDistance>,
+    },
+    /// Reached at `cost`, but the search ended before its `estimate` of a path through it
+    /// came up, being no cheaper than the path found if any
+    Queued {
+        cost: PathMultiCost<EditDistance>,
+        estimate: PathMultiCost<EditDistance>,
+        path: Option<PathMultiCost<EditDistance>>,
+    },
+    /// The search stopped before expanding the word
+    Interrupted(Interruption),
+    /// Nodes outgrew the memory allowed, bounded-astar searched again and may have dropped it
+    MemoryFallback,
+    /// Every word expanded is more than `limit` letters longer or shorter, so no hop of at
+    /// most `limit` letters leads to it and indexes never look it up
+    LengthBucket {
+        limit: usize,
+    },
+    /// The closest word expanded, `nearest`, is `distance` letters away, more than `limit`
+    HopLimit {
+        nearest: String,
+        distance: usize,
+        limit: usize,
+    },
+    /// Words a hop away were only expanded after `limit` hops
+    MaxHops {
+        limit: usize,
+    },
+    /// No hop leading to it got closer to the end word
+    Monotone,
+    /// The search ended before expanding a word close enough to it
+    Unreached,
+}
+
+impl fmt::Display for Pruning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pruning::OnPath => write!(f, "on the path found"),
+            Pruning::NotListed => write!(f, "not in the word list"),
+            Pruning::Expanded { cost } => write!(
+                f,
+                "expanded at a cost of {}, leading nowhere cheaper than the path found",
+                cost
+            ),
+            Pruning::Queued {
+                cost,
+                estimate,
+                path: Some(path),
+            } => write!(
+                f,
+                "reached at a cost of {}, but a path through it costs at least {}, no less than the {} of the path found",
+                cost, estimate, path
+            ),
+            Pruning::Queued { cost, .. } => write!(
+                f,
+                "reached at a cost of {}, but the search ended before expanding it",
+                cost
+            ),
+            Pruning::Interrupted(reason) => write!(f, "not expanded before the {}", reason),
+            Pruning::MemoryFallback => write!(
+                f,
+                "not expanded, the search outgrew --max-memory and bounded-astar may have dropped it"
+            ),
+            Pruning::LengthBucket { limit } => write!(
+                f,
+                "every word expanded is longer or shorter by more than {} letter{}, indexes never looked it up",
+                limit,
+                if *limit == 1 { "" } else { "s" }
+            ),
+            Pruning::HopLimit {
+                nearest,
+                distance,
+                limit,
+            } => write!(
+                f,
+                "the closest word expanded, {}, is {} letters away, more than the {} a hop may change",
+                nearest, distance, limit
+            ),
+            Pruning::MaxHops { limit } => write!(
+                f,
+                "the words a hop away were only expanded after the {} hops allowed",
+                limit
+            ),
+            Pruning::Monotone => write!(f, "no hop leading to it gets closer to the end word"),
+            Pruning::Unreached => write!(f, "the search ended before getting close to it"),
+        }
+    }
+}
+
+// What a search went through, as far as the words watched are concerned
+#[derive(Default)]
+struct Explored {
+    // Cheapest cost every word was reached at
+    reached: HashMap<String, PathMultiCost<EditDistance>>,
+    expanded: HashMap<String, PathMultiCost<EditDistance>>,
+    // Closest word expanded to each word watched, with its distance
+    nearest: Vec<Option<(String, usize)>>,
+    // Smallest difference of length between each word watched and a word expanded
+    closest_length: Vec<Option<usize>>,
+}
+
+/// Observer of a search telling afterwards why it did not go through some words
+pub struct PruningExplainer {
+    watched: Vec<String>,
+    cost_model: Arc<dyn CostModel>,
+    explored: Mutex<Explored>,
+}
+
+impl PruningExplainer {
+    /// Explainer of the words `watched` for searches costing hops with the model of `options`
+    pub fn new(watched: impl IntoIterator<Item = String>, options: &SearchOptions) -> Self {
+        let watched: Vec<String> = watched.into_iter().collect();
+        let explored = Explored {
+            nearest: vec![None; watched.len()],
+            closest_length: vec![None; watched.len()],
+            ..Explored::default()
+        };
+        PruningExplainer {
+            watched,
+            cost_model: options.cost_model.clone(),
+            explored: Mutex::new(explored),
+        }
+    }
+
+    /// Why the search observed, with `options` from `start` to `stop` through `words`,
+    /// did not go through each word watched, in the order they were given
+    pub fn explain<D: Dictionary + ?Sized>(
+        &self,
+        start: &str,
+        stop: &str,
+        words: &D,
+        options: &SearchOptions,
+        result: &Result<Option<ShortestPath>, Interruption>,
+        stats: &SearchStats,
+    ) -> Vec<(&str, Pruning)> {
+        let explored = self.explored.lock().unwrap();
+        let bound = options.prepare_bound(words, start, stop, stats.backward);
+        let path = match result {
+            Ok(Some((path, cost))) => Some((path, *cost)),
+            _ => None,
+        };
+        self.watched
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let listed = word == start || word == stop || words.iter().any(|w| w == word);
+                let reason = if path.is_some_and(|(path, _)| path.contains(&word.as_str())) {
+                    Pruning::OnPath
+                } else if !listed {
+                    Pruning::NotListed
+                } else if let Some(&cost) = explored.expanded.get(word) {
+                    Pruning::Expanded { cost }
+                } else if let Err(reason) = result {
+                    Pruning::Interrupted(*reason)
+                } else if stats.memory_fallback {
+                    Pruning::MemoryFallback
+                } else if let Some(&cost) = explored.reached.get(word) {
+                    Pruning::Queued {
+                        cost,
+                        estimate: cost + options.cost_lower_bound(bound(word)),
+                        path: path.map(|(_, cost)| cost),
+                    }
+                } else {
+                    let limit = options.max_hop_distance;
+                    match (limit, &explored.nearest[i], explored.closest_length[i]) {
+                        (Some(limit), _, Some(length)) if length > limit => {
+                            Pruning::LengthBucket { limit }
+                        }
+                        (Some(limit), Some((nearest, distance)), _) if *distance > limit => {
+                            Pruning::HopLimit {
+                                nearest: nearest.clone(),
+                                distance: *distance,
+                                limit,
+                            }
+                        }
+                        (_, None, _) => Pruning::Unreached,
+                        _ if options.monotone => Pruning::Monotone,
+                        _ => match options.max_hops {
+                            Some(limit) => Pruning::MaxHops { limit },
+                            None => Pruning::Unreached,
+                        },
+                    }
+                };
+                (word.as_str(), reason)
+            })
+            .collect()
+    }
+}
+
+impl SearchObserver for PruningExplainer {
+    fn expanded(
+        &self,
+        word: &str,
+        _heuristic: PathMultiCost<EditDistance>,
+        successors: &[(&str, PathMultiCost<EditDistance>)],
+    ) {
+        let mut explored = self.explored.lock().unwrap();
+        let Explored {
+            reached,
+            expanded,
+            nearest,
+            closest_length,
+        } = &mut *explored;
+        // The first word expanded is the one the search starts from
+        let cost = reached
+            .get(word)
+            .copied()
+            .unwrap_or_else(PathMultiCost::zero);
+        let known = expanded.entry(word.to_string()).or_insert(cost);
+        *known = (*known).min(cost);
+        for &(successor, hop) in successors {
+            let through = cost + hop;
+            match reached.get_mut(successor) {
+                Some(known) => *known = (*known).min(through),
+                None => {
+                    reached.insert(successor.to_string(), through);
+                }
+            }
+        }
+        let length = word.chars().count();
+        for (i, watched) in self.watched.iter().enumerate() {
+            let distance = self.cost_model.distance(word, watched);
+            if nearest[i].as_ref().is_none_or(|(_, d)| distance < *d) {
+                nearest[i] = Some((word.to_string(), distance));
+            }
+            let difference = length.abs_diff(watched.chars().count());
+            closest_length[i] = Some(closest_length[i].map_or(difference, |d| d.min(difference)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{find_shortest_path_with_options, PathFindingAlgorithm};
+
+    #[test]
+    fn pruned_words_are_explained() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "bold", "zzzz", "colder",
+        ];
+        let watched = ["cord", "bold", "zzzz", "colder", "qqqq"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let explainer = Arc::new(PruningExplainer::new(
+            watched.iter().map(|word| word.to_string()),
+            &options,
+        ));
+        let observed = options.clone().with_observer(explainer.clone());
+        let (result, stats) =
+            find_shortest_path_with_options("cold", "warm", &words[..], &observed);
+        let reasons = explainer.explain("cold", "warm", &words[..], &options, &result, &stats);
+        assert_eq!(reasons[0], ("cord", Pruning::OnPath));
+        // bold is as far from warm as cold is
+        assert_eq!(
+            reasons[1].1.to_string(),
+            "reached at a cost of 1 1-letter mutation, but a path through it costs at least \
+             5 1-letter mutations, no less than the 4 1-letter mutations of the path found"
+        );
+        assert_eq!(
+            reasons[2..],
+            [
+                (
+                    "zzzz",
+                    Pruning::HopLimit {
+                        nearest: "cold".to_string(),
+                        distance: 4,
+                        limit: 1,
+                    }
+                ),
+                ("colder", Pruning::LengthBucket { limit: 1 }),
+                ("qqqq", Pruning::NotListed),
+            ]
+        );
+        assert!(matches!(reasons[1].1, Pruning::Queued { .. }));
+        assert_eq!(
+            reasons[3].1.to_string(),
+            "every word expanded is longer or shorter by more than 1 letter, indexes never looked it up"
+        );
+    }
+}
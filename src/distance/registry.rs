@@ -0,0 +1,303 @@
+use crate::distance::PathFindingAlgorithm;
+use num_traits::Zero;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::RwLock;
+
+/// Node of a `SearchSpace`, the start being `NodeId(0)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub usize);
+
+/// Cost of a `SearchSpace`, only meaningful to the space that made it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CostId(usize);
+
+/// Graph a registered algorithm searches, whatever the nodes and costs of the search
+/// calling it. Costs are compared and summed by the space, which remembers every cost
+/// it hands out until the search ends.
+pub trait SearchSpace {
+    fn start(&self) -> NodeId;
+
+    /// Appends the successors of `node` to `successors`, with the cost of each hop
+    fn successors(&mut self, node: NodeId, successors: &mut Vec<(NodeId, CostId)>);
+
+    /// Lower bound of the cost from `node` to the goal
+    fn heuristic(&mut self, node: NodeId) -> CostId;
+
+    fn is_goal(&mut self, node: NodeId) -> bool;
+
+    fn zero(&mut self) -> CostId;
+
+    fn add(&mut self, a: CostId, b: CostId) -> CostId;
+
+    fn compare(&self, a: CostId, b: CostId) -> Ordering;
+}
+
+/// Pathfinding algorithm added to the built-in ones with `register_algorithm`
+pub trait SearchAlgorithm: Send + Sync {
+    /// Name `--algorithm` and `PathFindingAlgorithm::from_str` accept
+    fn name(&self) -> &'static str;
+
+    /// Cheapest path from the start to a goal and its cost, starting with the start
+    fn search(&self, space: &mut dyn SearchSpace) -> Option<(Vec<NodeId>, CostId)>;
+
+    /// Whether the algorithm keeps every node it reaches, so `--max-memory` has it go
+    /// on with bounded-astar once they no longer fit
+    fn keeps_every_node(&self) -> bool {
+        false
+    }
+
+    /// Whether the algorithm is only exact when hops cost the same both ways, as when it
+    /// also searches from the goal or compares estimates between any two nodes.
+    /// `SearchOptions::check_symmetry` then refuses cost models that do not.
+    fn needs_symmetric_costs(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Debug for dyn SearchAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Names are unique among the registered algorithms
+impl PartialEq for dyn SearchAlgorithm {
+    fn eq(&self, other: &dyn SearchAlgorithm) -> bool {
+        self.name() == other.name()
+    }
+}
+
+impl Eq for dyn SearchAlgorithm {}
+
+static REGISTERED: RwLock<Vec<&'static dyn SearchAlgorithm>> = RwLock::new(Vec::new());
+
+/// Make `algorithm` available to searches by its name, for the whole process. Returns
+/// `None` when an algorithm, built in or registered, already has that name.
+pub fn register_algorithm(algorithm: &'static dyn SearchAlgorithm) -> Option<PathFindingAlgorithm> {
+    let mut registered = REGISTERED.write().unwrap();
+    let taken = PathFindingAlgorithm::ALL
+        .iter()
+        .map(|builtin| builtin.name())
+        .chain(registered.iter().map(|other| other.name()))
+        .any(|name| name == algorithm.name());
+    if taken {
+        return None;
+    }
+    registered.push(algorithm);
+    Some(PathFindingAlgorithm::Registered(algorithm))
+}
+
+/// Algorithms registered so far, in the order they were
+pub fn registered_algorithms() -> Vec<&'static dyn SearchAlgorithm> {
+    REGISTERED.read().unwrap().clone()
+}
+
+// Search of the caller behind ids, nodes and costs being interned as they show up
+struct Erased<N, C, FN, FH, FS> {
+    nodes: Vec<N>,
+    ids: HashMap<N, usize>,
+    costs: Vec<C>,
+    successors: FN,
+    heuristic: FH,
+    success: FS,
+}
+
+impl<N: Eq + Hash + Clone, C, FN, FH, FS> Erased<N, C, FN, FH, FS> {
+    fn node(&mut self, node: N) -> NodeId {
+        let next = self.nodes.len();
+        let id = *self.ids.entry(node.clone()).or_insert(next);
+        if id == next {
+            self.nodes.push(node);
+        }
+        NodeId(id)
+    }
+
+    fn cost(&mut self, cost: C) -> CostId {
+        self.costs.push(cost);
+        CostId(self.costs.len() - 1)
+    }
+}
+
+impl<N, C, FN, IN, FH, FS> SearchSpace for Erased<N, C, FN, FH, FS>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    fn start(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    fn successors(&mut self, node: NodeId, successors: &mut Vec<(NodeId, CostId)>) {
+        let node = self.nodes[node.0].clone();
+        for (successor, cost) in (self.successors)(&node) {
+            let successor = self.node(successor);
+            successors.push((successor, self.cost(cost)));
+        }
+    }
+
+    fn heuristic(&mut self, node: NodeId) -> CostId {
+        let estimate = (self.heuristic)(&self.nodes[node.0]);
+        self.cost(estimate)
+    }
+
+    fn is_goal(&mut self, node: NodeId) -> bool {
+        (self.success)(&self.nodes[node.0])
+    }
+
+    fn zero(&mut self) -> CostId {
+        self.cost(C::zero())
+    }
+
+    fn add(&mut self, a: CostId, b: CostId) -> CostId {
+        let sum = self.costs[a.0] + self.costs[b.0];
+        self.cost(sum)
+    }
+
+    fn compare(&self, a: CostId, b: CostId) -> Ordering {
+        self.costs[a.0].cmp(&self.costs[b.0])
+    }
+}
+
+/// Run a registered `algorithm` as `run_algorithm` runs the built-in ones
+pub(crate) fn run_registered<N, C, FN, IN, FH, FS>(
+    algorithm: &dyn SearchAlgorithm,
+    start: &N,
+    successors: FN,
+    heuristic: FH,
+    success: FS,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let mut space = Erased {
+        nodes: Vec::new(),
+        ids: HashMap::new(),
+        costs: Vec::new(),
+        successors,
+        heuristic,
+        success,
+    };
+    space.node(start.clone());
+    let (path, cost) = algorithm.search(&mut space)?;
+    let cost = space.costs[cost.0];
+    Some((
+        path.into_iter()
+            .map(|node| space.nodes[node.0].clone())
+            .collect(),
+        cost,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::cost::{AsymmetricCosts, CostMatrix};
+    use crate::distance::{find_shortest_path, find_shortest_path_with_options, SearchOptions};
+    use std::sync::Arc;
+
+    // Uniform cost search keeping its frontier in a plain vector
+    struct Naive;
+
+    impl SearchAlgorithm for Naive {
+        fn name(&self) -> &'static str {
+            "naive"
+        }
+
+        fn search(&self, space: &mut dyn SearchSpace) -> Option<(Vec<NodeId>, CostId)> {
+            let zero = space.zero();
+            let mut frontier = vec![(vec![space.start()], zero)];
+            let mut done = Vec::new();
+            let mut successors = Vec::new();
+            loop {
+                let cheapest = (0..frontier.len())
+                    .min_by(|&a, &b| space.compare(frontier[a].1, frontier[b].1))?;
+                let (path, cost) = frontier.swap_remove(cheapest);
+                let node = *path.last().unwrap();
+                if space.is_goal(node) {
+                    return Some((path, cost));
+                }
+                if done.contains(&node) {
+                    continue;
+                }
+                done.push(node);
+                successors.clear();
+                space.successors(node, &mut successors);
+                for &(successor, hop) in &successors {
+                    let mut longer = path.clone();
+                    longer.push(successor);
+                    frontier.push((longer, space.add(cost, hop)));
+                }
+            }
+        }
+    }
+
+    // Naive search claiming to need hops costing the same both ways
+    struct Symmetric;
+
+    impl SearchAlgorithm for Symmetric {
+        fn name(&self) -> &'static str {
+            "symmetric-naive"
+        }
+
+        fn search(&self, space: &mut dyn SearchSpace) -> Option<(Vec<NodeId>, CostId)> {
+            Naive.search(space)
+        }
+
+        fn needs_symmetric_costs(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn algorithms_may_need_symmetric_costs() {
+        let symmetric = register_algorithm(&Symmetric).unwrap();
+        assert!(symmetric.needs_symmetric_costs());
+        assert!(!PathFindingAlgorithm::Astar.needs_symmetric_costs());
+        let words = ["cat", "cot", "cots"];
+        let options = SearchOptions::new(symmetric);
+        assert_eq!(options.check_symmetry(&words[..]), Ok(()));
+        let matrix = CostMatrix::from_csv(",*,2".as_bytes()).unwrap();
+        let options = options.with_cost_model(Arc::new(matrix));
+        assert_eq!(
+            options.check_symmetry(&words[..]),
+            Err(AsymmetricCosts::Undeclared)
+        );
+        // Searches of a single end are exact whatever the model
+        let options = options.with_algorithm(PathFindingAlgorithm::Astar);
+        assert_eq!(options.check_symmetry(&words[..]), Ok(()));
+    }
+
+    #[test]
+    fn registered_algorithms_are_found_by_name() {
+        let naive = register_algorithm(&Naive).unwrap();
+        assert_eq!(register_algorithm(&Naive), None);
+        assert_eq!("naive".parse(), Ok(naive));
+        assert_eq!(naive.name(), "naive");
+        assert!(PathFindingAlgorithm::all().contains(&naive));
+        assert!(registered_algorithms().iter().any(|a| a.name() == "naive"));
+
+        let words = ["cold", "cord", "card", "ward", "warm", "worm", "word"];
+        let expected = find_shortest_path("cold", "warm", &words[..], &PathFindingAlgorithm::Astar);
+        let (path, cost) = find_shortest_path("cold", "warm", &words[..], &naive).unwrap();
+        // Both ladders through cord and word are as short
+        assert_eq!((path.len(), cost), (5, expected.unwrap().1));
+        assert_eq!(path[1], "cord");
+        let options = SearchOptions::new(naive)
+            .with_max_hop_distance(1)
+            .with_max_hops(3);
+        let (path, _) = find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        assert_eq!(path, Ok(None));
+    }
+}
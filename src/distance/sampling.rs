@@ -0,0 +1,100 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::SearchOptions;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Share of the successors an approximate search goes through unless told otherwise
+pub const DEFAULT_SAMPLE_RATE: f64 = 0.1;
+
+/// Hops an approximate search keeps: each one is kept or dropped by a hash of its words
+/// and `seed`, the same way every time it comes up, about `rate` of them being kept
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    pub rate: f64,
+    pub seed: u64,
+}
+
+impl Sampling {
+    pub fn new(rate: f64, seed: u64) -> Sampling {
+        Sampling { rate, seed }
+    }
+
+    /// Whether the hop from `w1` to `w2` is kept
+    pub fn keeps(&self, w1: &str, w2: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        (self.seed, w1, w2).hash(&mut hasher);
+        (hasher.finish() as f64) < self.rate * u64::MAX as f64
+    }
+}
+
+/// How sure the path of `hops` hops at `cost` found from `start` to `stop` with `options`
+/// is a shortest one: certain when it costs what the heuristic bounds the cost of any path
+/// to, otherwise the chance sampling kept every hop of a path as long, hops into the end
+/// word being always kept. Searches without sampling are always certain.
+pub fn sampling_confidence<D: Dictionary + ?Sized>(
+    start: &str,
+    stop: &str,
+    words: &D,
+    options: &SearchOptions,
+    cost: &PathMultiCost<EditDistance>,
+    hops: usize,
+) -> f64 {
+    let sampling = match options.sampling {
+        Some(sampling) => sampling,
+        None => return 1.0,
+    };
+    let bound = options.prepare_bound(words, start, stop, false);
+    if *cost <= options.cost_lower_bound(bound(start)) {
+        return 1.0;
+    }
+    let sampled = hops.saturating_sub(1).min(i32::MAX as usize) as i32;
+    sampling.rate.clamp(0.0, 1.0).powi(sampled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{find_shortest_path_with_options, PathFindingAlgorithm};
+
+    #[test]
+    fn sampled_searches_keep_a_share_of_hops() {
+        let sampling = Sampling::new(0.3, 42);
+        let words: Vec<String> = (0..10_000).map(|i| format!("w{}", i)).collect();
+        let kept = words
+            .iter()
+            .filter(|word| sampling.keeps("cold", word))
+            .count();
+        assert!((2500..3500).contains(&kept), "{}", kept);
+        let again = words
+            .iter()
+            .filter(|word| sampling.keeps("cold", word))
+            .count();
+        assert_eq!(kept, again);
+        let reseeded = Sampling::new(0.3, 7);
+        assert!(words
+            .iter()
+            .any(|word| sampling.keeps("cold", word) != reseeded.keeps("cold", word)));
+
+        let words = ["cold", "cord", "card", "ward", "warm"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let search = |options: &SearchOptions| {
+            let (found, _) = find_shortest_path_with_options("cold", "warm", &words[..], options);
+            found.unwrap()
+        };
+        let (path, cost) = search(&options.clone().with_sampling(Sampling::new(1.0, 1))).unwrap();
+        assert_eq!(path.len(), 5);
+        let confidence = |options: &SearchOptions| {
+            sampling_confidence("cold", "warm", &words[..], options, &cost, 4)
+        };
+        assert_eq!(confidence(&options), 1.0);
+        // The ladder costs what letters apart the words are, no path can do better
+        assert_eq!(
+            confidence(&options.clone().with_sampling(Sampling::new(0.5, 1))),
+            1.0
+        );
+        // Only the hops leaving the start word and entering the end word are left
+        assert_eq!(search(&options.with_sampling(Sampling::new(0.0, 1))), None);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{Interruption, SearchOptions, SearchStats, ShortestPath};
+use num_traits::Zero;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// Cost of `previous` through `words` as `options` cost hops, if it still goes from `start`
+// to `stop` through listed words with hops `options` allow
+fn seed_cost<D: Dictionary + ?Sized>(
+    start: &str,
+    stop: &str,
+    words: &D,
+    options: &SearchOptions,
+    previous: &[&str],
+) -> Option<PathMultiCost<EditDistance>> {
+    if previous.first() != Some(&start) || previous.last() != Some(&stop) {
+        return None;
+    }
+    let listed: HashSet<&str> = words.iter().collect();
+    if !previous[1..].iter().all(|word| listed.contains(word)) {
+        return None;
+    }
+    let cost_model = options.cost_model.as_ref();
+    previous
+        .windows(2)
+        .try_fold(PathMultiCost::zero(), |cost, hop| {
+            let distance = cost_model.distance(hop[0], hop[1]);
+            match options.max_hop_distance {
+                Some(k) if distance > k => None,
+                _ => Some(cost + options.hop_cost(distance)),
+            }
+        })
+}
+
+/// Shortest path from `start` to `stop` as A* finds it, starting from `previous`, a path
+/// found earlier between the same words. While it is still a path of `words`, its cost
+/// bounds the search: words costing more are never queued, and the search stops with
+/// `previous` as soon as nothing cheaper is left. A stale path, through a word removed
+/// since, leaves the search unbounded. The algorithm, the direction, the hop limit and
+/// the word weights of `options` are ignored.
+pub fn search_with_seed<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+    previous: &[&str],
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let cost_model = options.cost_model.as_ref();
+    let bound = options.heuristic.prepare(cost_model, words, stop);
+    let heuristic = |word: &str| options.cost_lower_bound::<EditDistance>(bound(word));
+    let seed = seed_cost(start, stop, words, options, previous);
+    let mut stats = SearchStats::default();
+    let mut reached: HashMap<&'a str, (PathMultiCost<EditDistance>, Option<&'a str>)> =
+        HashMap::new();
+    reached.insert(start, (PathMultiCost::zero(), None));
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(start), PathMultiCost::zero(), start)));
+    let mut candidates = Vec::new();
+    while let Some(Reverse((estimate, cost, current))) = open.pop() {
+        // Queued again since at a lower cost
+        if reached[current].0 < cost {
+            continue;
+        }
+        if current == stop {
+            let mut path = vec![stop];
+            while let Some(parent) = reached[path[path.len() - 1]].1 {
+                path.push(parent);
+            }
+            path.reverse();
+            stats.saturated = cost.is_saturated();
+            return (Ok(Some((path, cost))), stats);
+        }
+        if let Some(seed) = seed.filter(|&seed| estimate >= seed) {
+            // Nothing left is cheaper than the seed, which goes through listed words only
+            let listed: HashMap<&str, &'a str> = words
+                .iter()
+                .chain([start, stop])
+                .map(|word| (word, word))
+                .collect();
+            let path = previous.iter().map(|word| listed[word]).collect();
+            stats.saturated = seed.is_saturated();
+            return (Ok(Some((path, seed))), stats);
+        }
+        stats.expanded += 1;
+        if let Some(reason) = options.control.check(stats.expanded) {
+            return (Err(reason), stats);
+        }
+        candidates.clear();
+        match options.max_hop_distance {
+            Some(k) if cost_model.bounded_by_edits() => {
+                words.extend_neighbors(current, k, &mut candidates)
+            }
+            _ => candidates.extend(words.iter()),
+        }
+        for &next in &candidates {
+            let distance = cost_model.distance(current, next);
+            if next == current || options.max_hop_distance.is_some_and(|k| distance > k) {
+                continue;
+            }
+            let through = cost + options.hop_cost(distance);
+            if reached
+                .get(next)
+                .is_some_and(|&(known, _)| known <= through)
+            {
+                continue;
+            }
+            let estimate = through + heuristic(next);
+            // Never popped before the search ends with the seed, `stop` included
+            if seed.is_some_and(|seed| estimate >= seed) {
+                continue;
+            }
+            reached.insert(next, (through, Some(current)));
+            open.push(Reverse((estimate, through, next)));
+        }
+    }
+    (Ok(None), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{find_shortest_path_with_options, PathFindingAlgorithm};
+
+    #[test]
+    fn known_paths_cut_searches_short() {
+        let words = [
+            "cold", "cord", "card", "ward", "warm", "word", "worm", "bold", "wold", "wald",
+        ];
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_hop_distance(1);
+        let (expected, unseeded) =
+            find_shortest_path_with_options("cold", "warm", &words[..], &options);
+        let (path, cost) = expected.unwrap().unwrap();
+
+        let (result, stats) = search_with_seed("cold", "warm", &words[..], &options, &path);
+        assert_eq!(result, Ok(Some((path.clone(), cost))));
+        assert!(stats.expanded < unseeded.expanded);
+
+        // A seed through a word no longer listed does not bound anything
+        let fewer: Vec<&str> = words.iter().copied().filter(|&w| w != path[2]).collect();
+        let (result, _) = search_with_seed("cold", "warm", &fewer[..], &options, &path);
+        let (found, found_cost) = result.unwrap().unwrap();
+        assert_eq!((found.len(), found_cost), (path.len(), cost));
+        assert!(!found.contains(&path[2]));
+
+        let (result, _) = search_with_seed("cold", "warm", &words[..2], &options, &path);
+        assert_eq!(result, Ok(None));
+    }
+}
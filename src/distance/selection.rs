@@ -0,0 +1,123 @@
+use crate::distance::bounded::bytes_per_node;
+use crate::distance::dictionary::Dictionary;
+use crate::distance::path::PathMultiCost;
+use crate::distance::word::EditDistance;
+use crate::distance::{PathFindingAlgorithm, SearchOptions};
+
+/// Below this many words, a search visits so little that guiding it costs more than it saves
+pub const SMALL_DICTIONARY: usize = 2_000;
+
+/// Algorithm picked for a search, with the reasons for the choice in the order they were
+/// considered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    pub algorithm: PathFindingAlgorithm,
+    pub reasons: Vec<String>,
+}
+
+/// Pick the algorithm likely to be the fastest from the size of `words`, the neighbourhoods
+/// of both ends and the limits of `options`, ignoring the algorithm `options` asks for
+pub fn select_algorithm<D: Dictionary + ?Sized>(
+    start: &str,
+    stop: &str,
+    words: &D,
+    options: &SearchOptions,
+) -> Selection {
+    let size = words.iter().count();
+    let radius = options.max_hop_distance().unwrap_or(1);
+    let degree = |word| words.neighbors_within(word, radius).len();
+    let (start_degree, stop_degree) = (degree(start), degree(stop));
+    let mut reasons = vec![
+        format!("{} words in the dictionary", size),
+        format!(
+            "{} has {} neighbours and {} has {} within {} letter{}",
+            start,
+            start_degree,
+            stop,
+            stop_degree,
+            radius,
+            if radius == 1 { "" } else { "s" }
+        ),
+    ];
+    let backward = options.runs_backward(start, stop, words);
+    // Same estimate as the one bounded-astar caps its nodes with
+    let needed =
+        size.saturating_mul(bytes_per_node::<(&str, usize), PathMultiCost<EditDistance>>());
+    let (algorithm, reason) = if size < SMALL_DICTIONARY {
+        (
+            PathFindingAlgorithm::Dijkstra,
+            format!(
+                "fewer than {} words, a heuristic would not pay off",
+                SMALL_DICTIONARY
+            ),
+        )
+    } else if start_degree == 0 || stop_degree == 0 {
+        (
+            PathFindingAlgorithm::Dijkstra,
+            "an end has no neighbour, the search stops right away".to_string(),
+        )
+    } else if backward && !options.cost_model.symmetric() {
+        (
+            PathFindingAlgorithm::Dijkstra,
+            "searching backward with an asymmetric cost model, nothing bounds the cost left"
+                .to_string(),
+        )
+    } else if needed > options.limits.max_memory {
+        (
+            PathFindingAlgorithm::BoundedAstar,
+            format!(
+                "visiting every word may take {} bytes, more than the {} allowed",
+                needed, options.limits.max_memory
+            ),
+        )
+    } else {
+        // Measured faster than the iterative deepening ones even on paths of a few hops
+        (
+            PathFindingAlgorithm::Astar,
+            "the cost left to the end guides the search through a large dictionary".to_string(),
+        )
+    };
+    reasons.push(reason);
+    Selection { algorithm, reasons }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::cost::CostMatrix;
+    use crate::distance::Direction;
+    use std::sync::Arc;
+
+    fn words(count: usize) -> Vec<String> {
+        let mut words: Vec<String> = (0..count).map(|i| format!("w{:05}", i)).collect();
+        words.extend(["cold", "cord", "card", "ward", "warm", "lone"].map(String::from));
+        words
+    }
+
+    #[test]
+    fn selects_from_the_dictionary_and_the_limits() {
+        let small = words(10);
+        let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+        let selection = select_algorithm("cold", "warm", &small[..], &options);
+        assert_eq!(selection.algorithm, PathFindingAlgorithm::Dijkstra);
+        assert_eq!(selection.reasons[0], "16 words in the dictionary");
+
+        let large = words(SMALL_DICTIONARY);
+        let selection = select_algorithm("cold", "warm", &large[..], &options);
+        assert_eq!(selection.algorithm, PathFindingAlgorithm::Astar);
+        let selection = select_algorithm("cold", "lone", &large[..], &options);
+        assert_eq!(selection.algorithm, PathFindingAlgorithm::Dijkstra);
+
+        // Insertions weigh more than deletions
+        let asymmetric = CostMatrix::from_csv(",*,2".as_bytes()).unwrap();
+        let backward = SearchOptions::new(PathFindingAlgorithm::Astar)
+            .with_cost_model(Arc::new(asymmetric))
+            .with_direction(Direction::Backward);
+        let selection = select_algorithm("cold", "warm", &large[..], &backward);
+        assert_eq!(selection.algorithm, PathFindingAlgorithm::Dijkstra);
+
+        let tight = SearchOptions::new(PathFindingAlgorithm::Astar).with_max_memory(1_000);
+        let selection = select_algorithm("cold", "warm", &large[..], &tight);
+        assert_eq!(selection.algorithm, PathFindingAlgorithm::BoundedAstar);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::word::levenshtein;
+use std::collections::{BTreeMap, HashMap};
+
+/// Words sharded by first letter then by length, so the neighbours of a word are
+/// found without comparing it to every other word
+pub struct ShardedWords<'w> {
+    words: Vec<&'w str>,
+    positions: HashMap<&'w str, Vec<usize>>,
+    shards: HashMap<Option<char>, BTreeMap<usize, Vec<usize>>>,
+    // Words by everything after their first letter, reaching the neighbours whose
+    // only change is that first letter
+    tails: HashMap<&'w str, Vec<usize>>,
+}
+
+impl<'w> ShardedWords<'w> {
+    pub fn new(words: &[&'w str]) -> ShardedWords<'w> {
+        let mut sharded = ShardedWords {
+            words: words.to_vec(),
+            positions: HashMap::with_capacity(words.len()),
+            shards: HashMap::new(),
+            tails: HashMap::new(),
+        };
+        for (position, &word) in words.iter().enumerate() {
+            sharded.positions.entry(word).or_default().push(position);
+            sharded
+                .shards
+                .entry(word.chars().next())
+                .or_default()
+                .entry(word.chars().count())
+                .or_default()
+                .push(position);
+            sharded.tails.entry(tail(word)).or_default().push(position);
+        }
+        sharded
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // Positions of the words whose length is `length` give or take `k`
+    fn lengths_within<'s>(
+        shard: &'s BTreeMap<usize, Vec<usize>>,
+        length: usize,
+        k: usize,
+    ) -> impl Iterator<Item = usize> + 's {
+        shard
+            .range(length.saturating_sub(k)..=length.saturating_add(k))
+            .flat_map(|(_, positions)| positions.iter().copied())
+    }
+}
+
+// Everything after the first letter
+fn tail(word: &str) -> &str {
+    word.char_indices()
+        .nth(1)
+        .map_or("", |(index, _)| &word[index..])
+}
+
+impl Dictionary for ShardedWords<'_> {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.words.iter().copied())
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.positions.contains_key(word)
+    }
+
+    // Every edit changes the length by at most one, and a single edit either keeps the
+    // first letter or only touches it. Bigger radii look through every first letter.
+    fn extend_neighbors<'d>(&'d self, word: &str, k: usize, neighbors: &mut Vec<&'d str>) {
+        let length = word.chars().count();
+        let mut candidates: Vec<usize> = match k {
+            0 => vec![],
+            1 => {
+                let same_first = self.shards.get(&word.chars().next());
+                let mut candidates: Vec<usize> = same_first
+                    .into_iter()
+                    .flat_map(|shard| Self::lengths_within(shard, length, 1))
+                    .collect();
+                // First letter replaced, or added in front of `word`
+                for key in [tail(word), word] {
+                    candidates.extend(self.tails.get(key).into_iter().flatten());
+                }
+                // First letter removed
+                if length > 0 {
+                    candidates.extend(self.positions.get(tail(word)).into_iter().flatten());
+                }
+                candidates
+            }
+            _ => self
+                .shards
+                .values()
+                .flat_map(|shard| Self::lengths_within(shard, length, k))
+                .collect(),
+        };
+        // Same order as a scan of every word
+        candidates.sort_unstable();
+        candidates.dedup();
+        neighbors.extend(
+            candidates
+                .into_iter()
+                .map(|position| self.words[position])
+                .filter(|&candidate| candidate != word && levenshtein(word, candidate) <= k),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_neighbours_as_a_scan() {
+        let words = [
+            "cold", "bold", "old", "scold", "cord", "colder", "cold", "", "c", "é", "éa", "a",
+            "warm", "wold", "old",
+        ];
+        let sharded = ShardedWords::new(&words);
+        for word in words.iter().chain(&["colt", "x", "ol"]) {
+            for k in 0..4 {
+                assert_eq!(
+                    sharded.neighbors_within(word, k),
+                    words[..].neighbors_within(word, k),
+                    "{:?} within {}",
+                    word,
+                    k
+                );
+            }
+        }
+        assert!(Dictionary::contains(&sharded, "scold"));
+        assert!(!Dictionary::contains(&sharded, "colt"));
+    }
+}
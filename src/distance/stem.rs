@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+// Shortest lemma a word is folded into, so that `as` or `is` are left alone
+const MIN_LEMMA_LENGTH: usize = 3;
+
+/// Language whose inflections are folded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StemLanguage {
+    En,
+}
+
+impl StemLanguage {
+    pub const ALL: [StemLanguage; 1] = [StemLanguage::En];
+
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            StemLanguage::En => "en",
+        }
+    }
+}
+
+impl fmt::Display for StemLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for StemLanguage {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<StemLanguage, ()> {
+        StemLanguage::ALL
+            .iter()
+            .copied()
+            .find(|language| language.name() == s)
+            .ok_or(())
+    }
+}
+
+// `word` without its last `count` characters
+fn cut(word: &str, count: usize) -> Option<&str> {
+    let (index, _) = word.char_indices().rev().nth(count - 1)?;
+    Some(&word[..index])
+}
+
+// Base forms `word` may be an inflection of, the likeliest first: `stopped` may come
+// from `stoppe`, `stopp` or `stop`, only the ones listed in the dictionary count
+fn candidates(word: &str, language: StemLanguage) -> Vec<String> {
+    let mut bases = Vec::new();
+    let mut push = |base: Option<&str>, ending: &str| {
+        if let Some(base) = base {
+            bases.push(format!("{}{}", base, ending));
+        }
+    };
+    // A doubled last consonant of a stem, as in `running` or `stopped`
+    let undoubled = |stem: &str| -> Option<String> {
+        let mut chars = stem.chars().rev();
+        let (last, before) = (chars.next()?, chars.next()?);
+        (last == before && !"aeiouls".contains(last)).then(|| cut(stem, 1).unwrap().to_string())
+    };
+    match language {
+        StemLanguage::En => {
+            if word.ends_with("'s") {
+                push(cut(word, 2), "");
+            }
+            if word.ends_with("ies") || word.ends_with("ied") {
+                push(cut(word, 3), "y");
+            }
+            if word.ends_with("ves") {
+                push(cut(word, 3), "f");
+                push(cut(word, 3), "fe");
+            }
+            if word.ends_with("es") {
+                push(cut(word, 1), "");
+                push(cut(word, 2), "");
+            } else if word.ends_with('s') && !word.ends_with("ss") {
+                push(cut(word, 1), "");
+            }
+            if word.ends_with("ed") {
+                push(cut(word, 1), "");
+                push(cut(word, 2), "");
+                push(cut(word, 2).and_then(undoubled).as_deref(), "");
+            }
+            if word.ends_with("ing") {
+                push(cut(word, 3), "");
+                push(cut(word, 3), "e");
+                push(cut(word, 3).and_then(undoubled).as_deref(), "");
+            }
+        }
+    }
+    bases.retain(|base| base.chars().count() >= MIN_LEMMA_LENGTH);
+    bases
+}
+
+/// Words of `words` that are inflections of another one, such as `cats` of `cat` or
+/// `baked` of `bake`, each with the word it folds into. Words whose base form is not in
+/// the dictionary are left alone, so `news` only folds into `new` when both are listed.
+pub fn fold_stems<'a>(words: &[&'a str], language: StemLanguage) -> HashMap<&'a str, &'a str> {
+    let listed: HashSet<&str> = words.iter().copied().collect();
+    words
+        .iter()
+        .filter_map(|&word| {
+            let lemma = candidates(word, language)
+                .iter()
+                .find_map(|base| listed.get(base.as_str()).copied())
+                .filter(|&lemma| lemma != word)?;
+            Some((word, lemma))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inflections_fold_into_listed_words() {
+        let words = [
+            "cat", "cats", "bake", "baked", "baking", "stop", "stopped", "run", "running", "fly",
+            "flies", "lie", "lies", "wolf", "wolves", "box", "boxes", "base", "bases", "bus",
+            "glass", "news", "dog's", "dog", "is", "as",
+        ];
+        let folded = fold_stems(&words, StemLanguage::En);
+        let mut pairs: Vec<(&str, &str)> = folded.into_iter().collect();
+        pairs.sort_unstable();
+        assert_eq!(
+            pairs,
+            vec![
+                ("baked", "bake"),
+                ("baking", "bake"),
+                ("bases", "base"),
+                ("boxes", "box"),
+                ("cats", "cat"),
+                ("dog's", "dog"),
+                ("flies", "fly"),
+                ("lies", "lie"),
+                ("running", "run"),
+                ("stopped", "stop"),
+                ("wolves", "wolf"),
+            ]
+        );
+        assert_eq!("en".parse(), Ok(StemLanguage::En));
+        assert_eq!("xx".parse::<StemLanguage>(), Err(()));
+    }
+}
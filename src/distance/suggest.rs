@@ -0,0 +1,61 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::word::levenshtein;
+
+/// The `k` words the closest to `word` with their edit distance to it, closest first and
+/// in dictionary order between words as close, `word` itself at 0 when listed
+pub fn suggest<'w, D: Dictionary + ?Sized>(
+    words: &'w D,
+    word: &str,
+    k: usize,
+) -> Vec<(&'w str, usize)> {
+    let itself = words.iter().find(|&candidate| candidate == word);
+    let others = words.iter().count() - usize::from(itself.is_some());
+    let wanted = k.saturating_sub(usize::from(itself.is_some())).min(others);
+    // Every word within the radius is found, so the closest ones are among them as soon
+    // as there are enough; doubling it keeps the number of lookups logarithmic
+    let mut radius = 1;
+    let mut found = words.neighbors_within(word, radius);
+    while found.len() < wanted {
+        radius *= 2;
+        found = words.neighbors_within(word, radius);
+    }
+    let mut closest: Vec<(&str, usize)> = itself
+        .map(|itself| (itself, 0))
+        .into_iter()
+        .chain(
+            found
+                .into_iter()
+                .map(|candidate| (candidate, levenshtein(word, candidate))),
+        )
+        .collect();
+    closest.sort_by_key(|&(_, distance)| distance);
+    closest.truncate(k);
+    closest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance::{ShardedWords, TrieWords};
+
+    #[test]
+    fn closest_words_first() {
+        let words = ["warm", "cold", "cord", "colder", "card", "scold", "c"];
+        assert_eq!(
+            suggest(&words[..], "cold", 4),
+            vec![("cold", 0), ("cord", 1), ("scold", 1), ("colder", 2)]
+        );
+        assert_eq!(
+            suggest(&words[..], "colt", 2),
+            vec![("cold", 1), ("cord", 2)]
+        );
+        assert_eq!(suggest(&words[..], "cold", 20).len(), words.len());
+        assert_eq!(suggest(&words[..], "cold", 0), vec![]);
+        let sharded = ShardedWords::new(&words);
+        let trie = TrieWords::new(&words);
+        for k in 0..8 {
+            assert_eq!(suggest(&sharded, "colt", k), suggest(&words[..], "colt", k));
+            assert_eq!(suggest(&trie, "colt", k), suggest(&words[..], "colt", k));
+        }
+    }
+}
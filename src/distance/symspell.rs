@@ -0,0 +1,157 @@
+use crate::distance::dictionary::Dictionary;
+use crate::distance::word::levenshtein;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Letters deleted at most from every word, enough to find the words 2 edits away
+pub const DELETION_DEPTH: usize = 2;
+
+/// Every string obtained by deleting up to `depth` letters from the words, with the words
+/// it comes from. Two words `k` edits apart share such a string with at most `k` letters
+/// deleted from each, so the words near another one are found by looking its own
+/// deletions up instead of comparing it to every word.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Deletions {
+    depth: usize,
+    variants: BTreeMap<String, Vec<u32>>,
+}
+
+// `word` itself and every string made by deleting up to `depth` of its letters
+fn variants(word: &str, depth: usize) -> BTreeSet<String> {
+    let mut all = BTreeSet::new();
+    all.insert(word.to_string());
+    let mut last: Vec<String> = vec![word.to_string()];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for variant in &last {
+            for (index, letter) in variant.char_indices() {
+                let mut shorter = variant.clone();
+                shorter.replace_range(index..index + letter.len_utf8(), "");
+                if all.insert(shorter.clone()) {
+                    next.push(shorter);
+                }
+            }
+        }
+        last = next;
+    }
+    all
+}
+
+impl Deletions {
+    pub(crate) fn new<'w>(words: impl IntoIterator<Item = &'w str>, depth: usize) -> Deletions {
+        let mut variants_of: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for (index, word) in words.into_iter().enumerate() {
+            for variant in variants(word, depth) {
+                variants_of.entry(variant).or_default().push(index as u32);
+            }
+        }
+        Deletions {
+            depth,
+            variants: variants_of,
+        }
+    }
+
+    pub(crate) fn from_parts(depth: usize, variants: BTreeMap<String, Vec<u32>>) -> Deletions {
+        Deletions { depth, variants }
+    }
+
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub(crate) fn variants(&self) -> &BTreeMap<String, Vec<u32>> {
+        &self.variants
+    }
+
+    /// Indices of the words possibly within `k` edits of `word`, in increasing order, or
+    /// `None` when `k` is beyond the deletions stored
+    pub(crate) fn candidates(&self, word: &str, k: usize) -> Option<Vec<u32>> {
+        if k > self.depth {
+            return None;
+        }
+        let candidates: HashSet<u32> = variants(word, k)
+            .iter()
+            .filter_map(|variant| self.variants.get(variant))
+            .flatten()
+            .copied()
+            .collect();
+        let mut candidates: Vec<u32> = candidates.into_iter().collect();
+        candidates.sort_unstable();
+        Some(candidates)
+    }
+}
+
+/// Words along with their deletions, finding the words up to 2 edits away from another
+/// one without scanning the whole list
+pub struct SymSpellWords<'w> {
+    words: Vec<&'w str>,
+    known: HashSet<&'w str>,
+    deletions: Deletions,
+}
+
+impl<'w> SymSpellWords<'w> {
+    pub fn new(words: &[&'w str]) -> SymSpellWords<'w> {
+        SymSpellWords {
+            words: words.to_vec(),
+            known: words.iter().copied().collect(),
+            deletions: Deletions::new(words.iter().copied(), DELETION_DEPTH),
+        }
+    }
+}
+
+impl Dictionary for SymSpellWords<'_> {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.words.iter().copied())
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.known.contains(word)
+    }
+
+    // Radii beyond the deletions stored scan every word
+    fn extend_neighbors<'d>(&'d self, word: &str, k: usize, neighbors: &mut Vec<&'d str>) {
+        let candidates = match self.deletions.candidates(word, k) {
+            Some(candidates) => candidates,
+            None => (0..self.words.len() as u32).collect(),
+        };
+        neighbors.extend(
+            candidates
+                .into_iter()
+                .map(|index| self.words[index as usize])
+                .filter(|&candidate| candidate != word && levenshtein(word, candidate) <= k),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deletions_of_a_word() {
+        let deleted = variants("éta", 2);
+        let expected = ["éta", "ta", "éa", "ét", "a", "t", "é"];
+        assert_eq!(deleted, expected.iter().map(|v| v.to_string()).collect());
+    }
+
+    #[test]
+    fn same_neighbours_as_a_scan() {
+        let words = [
+            "cold", "bold", "old", "scold", "cord", "colder", "cold", "", "c", "é", "éa", "a",
+            "warm", "wold", "old", "clod",
+        ];
+        let indexed = SymSpellWords::new(&words);
+        for word in words.iter().chain(&["colt", "x", "ol"]) {
+            for k in 0..4 {
+                assert_eq!(
+                    indexed.neighbors_within(word, k),
+                    words[..].neighbors_within(word, k),
+                    "{:?} within {}",
+                    word,
+                    k
+                );
+            }
+        }
+        assert!(Dictionary::contains(&indexed, "clod"));
+        assert!(!Dictionary::contains(&indexed, "colt"));
+    }
+}
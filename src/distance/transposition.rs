@@ -0,0 +1,215 @@
+use num_traits::Zero;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Number of words whose best cost IDA* remembers unless told otherwise
+pub const DEFAULT_TRANSPOSITION_CAPACITY: usize = 1 << 20;
+
+// Best cost at which every node has been reached, and during which iteration
+struct TranspositionTable<N, C> {
+    entries: HashMap<N, (C, usize)>,
+    capacity: usize,
+}
+
+impl<N: Eq + Hash + Clone, C: Ord + Copy> TranspositionTable<N, C> {
+    // A node reached at a higher cost than before is reached at the lower cost during
+    // this iteration too, and a node reached again at the same cost during the same
+    // iteration has already been searched as deep as the bound allows.
+    fn prune(&mut self, node: &N, cost: C, iteration: usize) -> bool {
+        match self.entries.get_mut(node) {
+            Some((best, _)) if cost > *best => true,
+            Some((best, seen)) if cost == *best && *seen == iteration => true,
+            Some(entry) => {
+                *entry = (cost, iteration);
+                false
+            }
+            // Once full, nodes left out are simply never pruned
+            None => {
+                if self.entries.len() < self.capacity {
+                    self.entries.insert(node.clone(), (cost, iteration));
+                }
+                false
+            }
+        }
+    }
+}
+
+enum Step<C> {
+    Found(C),
+    // Smallest estimate beyond the bound, the next bound to try
+    Exceeded(C),
+    Exhausted,
+}
+
+/// IDA* remembering the cheapest cost each node was reached at, so that a node reached
+/// again through a costlier path is not searched over and over across iterations.
+/// At most `capacity` nodes are remembered.
+pub fn idastar_tt<N, C, FN, IN, FH, FS>(
+    start: &N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+    capacity: usize,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let mut table = TranspositionTable {
+        entries: HashMap::new(),
+        capacity,
+    };
+    let mut bound = heuristic(start);
+    let mut path = vec![start.clone()];
+    let mut iteration = 0;
+    loop {
+        table.prune(start, C::zero(), iteration);
+        let step = search(
+            &mut path,
+            C::zero(),
+            bound,
+            iteration,
+            &mut table,
+            &mut successors,
+            &mut heuristic,
+            &mut success,
+        );
+        match step {
+            Step::Found(cost) => return Some((path, cost)),
+            Step::Exceeded(next) => bound = next,
+            Step::Exhausted => return None,
+        }
+        iteration += 1;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search<N, C, FN, IN, FH, FS>(
+    path: &mut Vec<N>,
+    cost: C,
+    bound: C,
+    iteration: usize,
+    table: &mut TranspositionTable<N, C>,
+    successors: &mut FN,
+    heuristic: &mut FH,
+    success: &mut FS,
+) -> Step<C>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    let node = path.last().unwrap().clone();
+    let estimate = cost + heuristic(&node);
+    if estimate > bound {
+        return Step::Exceeded(estimate);
+    }
+    if success(&node) {
+        return Step::Found(cost);
+    }
+    let mut neighbours: Vec<(N, C, C)> = successors(&node)
+        .into_iter()
+        .filter(|(neighbour, _)| !path.contains(neighbour))
+        .map(|(neighbour, hop)| {
+            let estimate = cost + hop + heuristic(&neighbour);
+            (neighbour, hop, estimate)
+        })
+        .collect();
+    neighbours.sort_by_key(|&(_, _, estimate)| estimate);
+    let mut next = None;
+    for (neighbour, hop, _) in neighbours {
+        if table.prune(&neighbour, cost + hop, iteration) {
+            continue;
+        }
+        path.push(neighbour);
+        match search(
+            path,
+            cost + hop,
+            bound,
+            iteration,
+            table,
+            successors,
+            heuristic,
+            success,
+        ) {
+            Step::Found(cost) => return Step::Found(cost),
+            Step::Exceeded(estimate) => {
+                next = Some(next.map_or(estimate, |next: C| next.min(estimate)))
+            }
+            Step::Exhausted => (),
+        }
+        path.pop();
+    }
+    next.map_or(Step::Exhausted, Step::Exceeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pathfinding::directed::idastar::idastar;
+
+    // Every node of a grid is reachable through many paths of the same length
+    fn grid(&(x, y): &(u32, u32)) -> Vec<((u32, u32), u32)> {
+        let mut neighbours = vec![((x + 1, y), 1), ((x, y + 1), 1)];
+        if x > 0 {
+            neighbours.push(((x - 1, y), 1));
+        }
+        if y > 0 {
+            neighbours.push(((x, y - 1), 1));
+        }
+        neighbours
+    }
+
+    #[test]
+    fn same_cost_as_idastar() {
+        let goal = (4, 3);
+        let expected = idastar(&(0, 0), grid, |_| 0, |&node| node == goal).unwrap();
+        for &capacity in &[0, 3, DEFAULT_TRANSPOSITION_CAPACITY] {
+            let (path, cost) =
+                idastar_tt(&(0, 0), grid, |_| 0, |&node| node == goal, capacity).unwrap();
+            assert_eq!(cost, expected.1);
+            assert_eq!(path.len(), expected.0.len());
+            assert_eq!(path.last(), Some(&goal));
+        }
+    }
+
+    #[test]
+    fn fewer_expansions_than_idastar() {
+        let goal = (4, 3);
+        let mut plain = 0;
+        idastar(
+            &(0, 0),
+            |node| {
+                plain += 1;
+                grid(node)
+            },
+            |_| 0,
+            |&node| node == goal,
+        );
+        let mut cached = 0;
+        idastar_tt(
+            &(0, 0),
+            |node| {
+                cached += 1;
+                grid(node)
+            },
+            |_| 0,
+            |&node| node == goal,
+            DEFAULT_TRANSPOSITION_CAPACITY,
+        );
+        assert!(cached < plain, "{} >= {}", cached, plain);
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let successors = |&n: &u32| if n < 5 { vec![(n + 1, 1)] } else { vec![] };
+        assert_eq!(idastar_tt(&0, successors, |_| 0, |&n| n == 9, 16), None);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::distance::dictionary::Dictionary;
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+#[derive(Debug, Default, Clone)]
+struct Node {
+    children: BTreeMap<char, usize>,
+    // Position of the word ending here
+    word: Option<usize>,
+}
+
+/// Words stored in a trie, so the words near another one are enumerated by walking the
+/// prefixes they share with it, and words can be inserted or removed at any time
+#[derive(Debug, Clone)]
+pub struct TrieWords {
+    words: Vec<String>,
+    nodes: Vec<Node>,
+}
+
+impl Default for TrieWords {
+    fn default() -> TrieWords {
+        TrieWords {
+            words: Vec::new(),
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl TrieWords {
+    pub fn new<S: AsRef<str>>(words: &[S]) -> TrieWords {
+        let mut trie = TrieWords::default();
+        for word in words {
+            trie.insert(word.as_ref());
+        }
+        trie
+    }
+
+    /// Trie of `words`, taken without copying them, repeated ones kept once
+    pub fn from_words(words: Vec<String>) -> TrieWords {
+        let mut trie = TrieWords::default();
+        for word in words {
+            trie.insert_owned(word);
+        }
+        trie
+    }
+
+    /// Add `word` after the others, returns false when it was already there
+    pub fn insert(&mut self, word: &str) -> bool {
+        match self.contains(word) {
+            true => false,
+            false => self.insert_owned(word.to_string()),
+        }
+    }
+
+    // Node `word` ends at, or None when no listed word starts with it
+    fn find(&self, word: &str) -> Option<usize> {
+        word.chars().try_fold(0, |node, letter| {
+            self.nodes[node].children.get(&letter).copied()
+        })
+    }
+
+    fn insert_owned(&mut self, word: String) -> bool {
+        let mut node = 0;
+        for letter in word.chars() {
+            node = match self.nodes[node].children.get(&letter) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(letter, child);
+                    child
+                }
+            };
+        }
+        if self.nodes[node].word.is_some() {
+            return false;
+        }
+        self.nodes[node].word = Some(self.words.len());
+        self.words.push(word);
+        true
+    }
+
+    /// Remove `word`, the others keeping their order, returns false when it was not there
+    pub fn remove(&mut self, word: &str) -> bool {
+        let mut path = vec![0];
+        for letter in word.chars() {
+            match self.nodes[*path.last().unwrap()].children.get(&letter) {
+                Some(&child) => path.push(child),
+                None => return false,
+            }
+        }
+        let position = match self.nodes[*path.last().unwrap()].word.take() {
+            Some(position) => position,
+            None => return false,
+        };
+        self.words.remove(position);
+        for node in &mut self.nodes {
+            if let Some(later) = node.word.as_mut().filter(|later| **later > position) {
+                *later -= 1;
+            }
+        }
+        // Branches left without words are cut so walks skip them, their nodes stay
+        // allocated until the trie is built again
+        for (letter, pair) in word.chars().rev().zip(path.windows(2).rev()) {
+            let (parent, child) = (pair[0], pair[1]);
+            if self.nodes[child].word.is_some() || !self.nodes[child].children.is_empty() {
+                break;
+            }
+            self.nodes[parent].children.remove(&letter);
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // Walk down from `node`, whose prefix is `row[0]` letters long away from every
+    // prefix of `word` as `row` tells, collecting the words within `k` edits
+    fn collect(&self, node: usize, word: &[char], row: &[usize], k: usize, found: &mut Vec<usize>) {
+        if let Some(position) = self.nodes[node].word {
+            if row[word.len()] <= k {
+                found.push(position);
+            }
+        }
+        for (&letter, &child) in &self.nodes[node].children {
+            let mut next = Vec::with_capacity(row.len());
+            next.push(row[0] + 1);
+            for (column, &expected) in word.iter().enumerate() {
+                let substitution = row[column] + usize::from(expected != letter);
+                let cost = substitution.min(row[column + 1] + 1).min(next[column] + 1);
+                next.push(cost);
+            }
+            // Longer prefixes only get farther once every cell exceeds `k`
+            if next.iter().min().is_some_and(|&closest| closest <= k) {
+                self.collect(child, word, &next, k, found);
+            }
+        }
+    }
+}
+
+impl Dictionary for TrieWords {
+    fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.words.iter().map(String::as_str))
+    }
+
+    fn contains(&self, word: &str) -> bool {
+        self.find(word)
+            .is_some_and(|node| self.nodes[node].word.is_some())
+    }
+
+    fn extend_neighbors<'d>(&'d self, word: &str, k: usize, neighbors: &mut Vec<&'d str>) {
+        let letters: Vec<char> = word.chars().collect();
+        let row: Vec<usize> = (0..=letters.len()).collect();
+        let mut found = Vec::new();
+        self.collect(0, &letters, &row, k, &mut found);
+        // Same order as a scan of every word
+        found.sort_unstable();
+        neighbors.extend(
+            found
+                .into_iter()
+                .map(|position| self.words[position].as_str())
+                .filter(|&candidate| candidate != word),
+        );
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for TrieWords {
+    fn from_iter<I: IntoIterator<Item = S>>(words: I) -> TrieWords {
+        let mut trie = TrieWords::default();
+        trie.extend(words);
+        trie
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for TrieWords {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, words: I) {
+        for word in words {
+            self.insert(word.as_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_neighbours_as_a_scan() {
+        let mut words = vec![
+            "cold", "bold", "old", "scold", "cord", "colder", "", "c", "é", "éa", "a", "warm",
+            "wold", "clod",
+        ];
+        let mut trie = TrieWords::new(&words);
+        assert!(trie.insert("cole"));
+        assert!(!trie.insert("cold"));
+        words.push("cole");
+        assert_eq!(trie.len(), words.len());
+        for word in words.iter().chain(&["colt", "x", "ol"]) {
+            for k in 0..4 {
+                assert_eq!(
+                    trie.neighbors_within(word, k),
+                    words[..].neighbors_within(word, k),
+                    "{:?} within {}",
+                    word,
+                    k
+                );
+            }
+        }
+        assert!(Dictionary::contains(&trie, "cole"));
+        assert!(Dictionary::contains(&trie, ""));
+        assert!(!Dictionary::contains(&trie, "col"));
+    }
+
+    #[test]
+    fn words_come_and_go() {
+        let owned = vec!["cold".to_string(), "cord".to_string(), "cold".to_string()];
+        let mut trie = TrieWords::from_words(owned);
+        assert_eq!(trie.iter().collect::<Vec<_>>(), vec!["cold", "cord"]);
+        trie.extend(["card", "colder", "ward"]);
+        assert!(trie.remove("cold"));
+        assert!(!trie.remove("cold"));
+        assert!(!trie.remove("col"));
+        assert!(trie.remove("ward"));
+        let words = ["cord", "card", "colder"];
+        assert_eq!(trie.iter().collect::<Vec<_>>(), words);
+        for word in ["cold", "cord", "colde", "ward"] {
+            assert_eq!(
+                trie.neighbors_within(word, 2),
+                words[..].neighbors_within(word, 2)
+            );
+        }
+        assert!(!Dictionary::contains(&trie, "cold"));
+        assert!(Dictionary::contains(&trie, "colder"));
+        assert!(trie.insert("cold"));
+
+        let collected: TrieWords = words.iter().collect();
+        assert_eq!(collected.len(), 3);
+        let path = crate::distance::find_shortest_path(
+            "cord",
+            "card",
+            &collected,
+            &crate::distance::PathFindingAlgorithm::Astar,
+        );
+        assert_eq!(path.unwrap().0, vec!["cord", "card"]);
+    }
+}
@@ -1,22 +1,428 @@
 extern crate edit_distance;
 
 use crate::distance::path::{PathMultiCost, MAX_DIMENSION};
-use num_traits::Bounded;
-use std::cmp::min;
+use num_traits::{Bounded, CheckedAdd, One, Zero};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
 
-pub type EditDistance = u8;
+/// Number of hops counted in each dimension of the costs returned by the search
+pub type EditDistance = u16;
+
+/// Integer able to count hops in a `PathMultiCost`
+pub trait HopCount: Bounded + Zero + One + CheckedAdd + Copy + TryFrom<usize> + Into<u64> {}
+
+impl<T: Bounded + Zero + One + CheckedAdd + Copy + TryFrom<usize> + Into<u64>> HopCount for T {}
+
+/// Longest word whose distances to other words always fit in an `EditDistance`
+pub const MAX_WORD_LENGTH: usize = EditDistance::MAX as usize;
+
+/// A word longer than `MAX_WORD_LENGTH` letters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordTooLong {
+    pub length: usize,
+}
+
+impl fmt::Display for WordTooLong {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "word of {} letters exceeds the {} letters supported",
+            self.length, MAX_WORD_LENGTH
+        )
+    }
+}
+
+impl Error for WordTooLong {}
+
+/// Reject words whose edit distances could not be counted without overflowing
+pub fn check_length(word: &str) -> Result<(), WordTooLong> {
+    match word.chars().count() {
+        length if length > MAX_WORD_LENGTH => Err(WordTooLong { length }),
+        _ => Ok(()),
+    }
+}
 
 //This method returns a Path with ordering and additivity properties
 //This is not a distance since it does not respect the triangular inequality
 pub fn path_cost(w1: &str, w2: &str) -> PathMultiCost<EditDistance> {
-    match edit_distance::edit_distance(w1, w2) {
-        0 => PathMultiCost::<EditDistance>::min_value(),
-        n => PathMultiCost::new(1 as EditDistance, min(n, MAX_DIMENSION) - 1),
-    }
+    hop_cost(levenshtein(w1, w2))
 }
 
 pub fn edit_distance(w1: &str, w2: &str) -> PathMultiCost<EditDistance> {
-    PathMultiCost::new(edit_distance::edit_distance(w1, w2) as EditDistance, 0)
+    hops_lower_bound(levenshtein(w1, w2))
+}
+
+/// Rarity of `word` according to `frequencies`, from 0 for words used billions of times
+/// up to 32 for words missing from them: every halving of the count adds 1
+pub fn rarity(word: &str, frequencies: &HashMap<String, u32>) -> usize {
+    frequencies.get(word).copied().unwrap_or(0).leading_zeros() as usize
+}
+
+/// Same as `path_cost`, entering `w2` also costing its rarity, which only decides between
+/// costs whose hops are the same
+pub fn path_cost_with_frequencies(
+    w1: &str,
+    w2: &str,
+    frequencies: &HashMap<String, u32>,
+) -> PathMultiCost<EditDistance> {
+    let rarity = EditDistance::try_from(rarity(w2, frequencies)).unwrap_or(EditDistance::MAX);
+    path_cost(w1, w2) + PathMultiCost::rarity_only(rarity)
+}
+
+thread_local! {
+    // Letters of both words and a row of distances, kept from one call to the next since
+    // searches compute distances to thousands of candidates for every word they expand
+    static LEVENSHTEIN_SCRATCH: RefCell<(Vec<char>, Vec<char>, Vec<usize>)> =
+        RefCell::new(Default::default());
+}
+
+/// Levenshtein distance in characters, without allocating once the thread computed a
+/// distance between words as long
+pub fn levenshtein(w1: &str, w2: &str) -> usize {
+    LEVENSHTEIN_SCRATCH.with(|scratch| {
+        let (w1_letters, w2_letters, row) = &mut *scratch.borrow_mut();
+        w1_letters.clear();
+        w1_letters.extend(w1.chars());
+        w2_letters.clear();
+        w2_letters.extend(w2.chars());
+        row.clear();
+        row.extend(0..=w2_letters.len());
+        for (i, &a) in w1_letters.iter().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, &b) in w2_letters.iter().enumerate() {
+                let above = row[j + 1];
+                row[j + 1] = (above + 1)
+                    .min(row[j] + 1)
+                    .min(diagonal + usize::from(a != b));
+                diagonal = above;
+            }
+        }
+        row[w2_letters.len()]
+    })
+}
+
+/// Dimension holding every hop changing `MAX_DIMENSION` letters or more.
+/// There a hop counts as many letters as it changes instead of 1, so bigger
+/// mutations still cost more: a 50-letter hop outweighs a 20-letter one and
+/// outweighs any number of hops below `MAX_DIMENSION` letters. Between several
+/// such hops only the total number of letters changed is compared.
+pub const OVERFLOW_DIMENSION: usize = MAX_DIMENSION - 1;
+
+/// Cost of a single hop changing `distance` letters
+pub fn hop_cost<D: HopCount>(distance: usize) -> PathMultiCost<D> {
+    match distance {
+        0 => PathMultiCost::<D>::min_value(),
+        n if n > OVERFLOW_DIMENSION => PathMultiCost::new(
+            D::try_from(n).unwrap_or_else(|_| D::max_value()),
+            OVERFLOW_DIMENSION,
+        ),
+        n => PathMultiCost::new(D::one(), n - 1),
+    }
+}
+
+/// Heuristic value of a path needing at least `hops` 1-letter mutations
+pub fn hops_lower_bound<D: HopCount>(hops: usize) -> PathMultiCost<D> {
+    // Sums of costs saturate at the maximum too, so saturating keeps the bound admissible
+    PathMultiCost::new(D::try_from(hops).unwrap_or_else(|_| D::max_value()), 0)
+}
+
+/// Rewriting of a group of letters into another one at a given weight, e.g. "rn" read as "m"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    pub from: Vec<char>,
+    pub to: Vec<char>,
+    pub weight: usize,
+}
+
+/// Edit distance where insertions, deletions and substitutions weigh `edit_weight`
+/// and where the given substitutions may be applied at their own weight
+pub fn weighted_edit_distance(
+    w1: &str,
+    w2: &str,
+    edit_weight: usize,
+    substitutions: &[Substitution],
+) -> usize {
+    let w1: Vec<char> = w1.chars().collect();
+    let w2: Vec<char> = w2.chars().collect();
+    let mut distances = vec![vec![0; w2.len() + 1]; w1.len() + 1];
+    for i in 0..=w1.len() {
+        for j in 0..=w2.len() {
+            if i == 0 || j == 0 {
+                distances[i][j] = (i + j) * edit_weight;
+                continue;
+            }
+            let replace = if w1[i - 1] == w2[j - 1] {
+                0
+            } else {
+                edit_weight
+            };
+            let mut best = (distances[i - 1][j] + edit_weight)
+                .min(distances[i][j - 1] + edit_weight)
+                .min(distances[i - 1][j - 1] + replace);
+            for substitution in substitutions {
+                let (from, to) = (substitution.from.len(), substitution.to.len());
+                if from <= i
+                    && to <= j
+                    && w1[i - from..i] == substitution.from[..]
+                    && w2[j - to..j] == substitution.to[..]
+                {
+                    best = best.min(distances[i - from][j - to] + substitution.weight);
+                }
+            }
+            distances[i][j] = best;
+        }
+    }
+    distances[w1.len()][w2.len()]
+}
+
+/// Levenshtein distance between two sequences of any kind, e.g. the words of two phrases
+pub fn sequence_edit_distance<T: PartialEq>(s1: &[T], s2: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=s2.len()).collect();
+    for (i, item1) in s1.iter().enumerate() {
+        let mut current = vec![i + 1; s2.len() + 1];
+        for (j, item2) in s2.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(item1 != item2))
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[s2.len()]
+}
+
+/// Number of whole words inserted, deleted or replaced between two phrases
+pub fn token_edit_distance(p1: &str, p2: &str) -> usize {
+    let t1: Vec<&str> = p1.split_whitespace().collect();
+    let t2: Vec<&str> = p2.split_whitespace().collect();
+    sequence_edit_distance(&t1, &t2)
+}
+
+/// Per-letter weights of each edit operation, letters not listed use the defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditWeights {
+    pub substitutions: HashMap<(char, char), usize>,
+    pub insertions: HashMap<char, usize>,
+    pub deletions: HashMap<char, usize>,
+    pub default_substitution: usize,
+    pub default_insertion: usize,
+    pub default_deletion: usize,
+}
+
+impl Default for EditWeights {
+    fn default() -> EditWeights {
+        EditWeights {
+            substitutions: HashMap::new(),
+            insertions: HashMap::new(),
+            deletions: HashMap::new(),
+            default_substitution: 1,
+            default_insertion: 1,
+            default_deletion: 1,
+        }
+    }
+}
+
+impl EditWeights {
+    pub fn substitution(&self, from: char, to: char) -> usize {
+        if from == to {
+            return 0;
+        }
+        *self
+            .substitutions
+            .get(&(from, to))
+            .unwrap_or(&self.default_substitution)
+    }
+
+    pub fn insertion(&self, letter: char) -> usize {
+        *self
+            .insertions
+            .get(&letter)
+            .unwrap_or(&self.default_insertion)
+    }
+
+    pub fn deletion(&self, letter: char) -> usize {
+        *self
+            .deletions
+            .get(&letter)
+            .unwrap_or(&self.default_deletion)
+    }
+
+    /// Whether every operation weighs as much as the one undoing it, so that turning a
+    /// word into another costs the same both ways
+    pub fn symmetric(&self) -> bool {
+        self.default_insertion == self.default_deletion
+            && self
+                .insertions
+                .keys()
+                .chain(self.deletions.keys())
+                .all(|&letter| self.insertion(letter) == self.deletion(letter))
+            && self
+                .substitutions
+                .keys()
+                .all(|&(from, to)| self.substitution(from, to) == self.substitution(to, from))
+    }
+
+    /// Lightest operation of the whole matrix
+    pub fn min_weight(&self) -> usize {
+        self.substitutions
+            .values()
+            .chain(self.insertions.values())
+            .chain(self.deletions.values())
+            .chain(
+                [
+                    self.default_substitution,
+                    self.default_insertion,
+                    self.default_deletion,
+                ]
+                .iter(),
+            )
+            .copied()
+            .min()
+            .unwrap()
+    }
+}
+
+// Distance between every prefix of `w1` and every prefix of `w2`
+fn prefix_distances(w1: &[char], w2: &[char], weights: &EditWeights) -> Vec<Vec<usize>> {
+    let mut distances = vec![vec![0; w2.len() + 1]; w1.len() + 1];
+    for i in 0..=w1.len() {
+        for j in 0..=w2.len() {
+            distances[i][j] = match (i, j) {
+                (0, 0) => 0,
+                (0, j) => distances[0][j - 1] + weights.insertion(w2[j - 1]),
+                (i, 0) => distances[i - 1][0] + weights.deletion(w1[i - 1]),
+                (i, j) => (distances[i - 1][j] + weights.deletion(w1[i - 1]))
+                    .min(distances[i][j - 1] + weights.insertion(w2[j - 1]))
+                    .min(distances[i - 1][j - 1] + weights.substitution(w1[i - 1], w2[j - 1])),
+            };
+        }
+    }
+    distances
+}
+
+/// Edit distance where every operation weighs what `weights` says for the letters involved
+pub fn matrix_edit_distance(w1: &str, w2: &str, weights: &EditWeights) -> usize {
+    let w1: Vec<char> = w1.chars().collect();
+    let w2: Vec<char> = w2.chars().collect();
+    prefix_distances(&w1, &w2, weights)[w1.len()][w2.len()]
+}
+
+/// Step of an alignment turning a word into another, `from` and `to` being the positions
+/// in characters it applies at in the first word and in the second one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Keep {
+        from: usize,
+        to: usize,
+        letter: char,
+    },
+    Substitute {
+        from: usize,
+        to: usize,
+        old: char,
+        new: char,
+    },
+    /// `letter` of the second word inserted before `from` in the first one
+    Insert {
+        from: usize,
+        to: usize,
+        letter: char,
+    },
+    /// `letter` of the first word removed before `to` in the second one
+    Delete {
+        from: usize,
+        to: usize,
+        letter: char,
+    },
+}
+
+impl Edit {
+    pub fn name(self) -> &'static str {
+        match self {
+            Edit::Keep { .. } => "keep",
+            Edit::Substitute { .. } => "substitute",
+            Edit::Insert { .. } => "insert",
+            Edit::Delete { .. } => "delete",
+        }
+    }
+
+    pub fn is_change(self) -> bool {
+        !matches!(self, Edit::Keep { .. })
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Edit::Keep { from, letter, .. } => write!(f, "keep {} at {}", letter, from),
+            Edit::Substitute { from, old, new, .. } => {
+                write!(f, "substitute {} with {} at {}", old, new, from)
+            }
+            Edit::Insert { from, letter, .. } => write!(f, "insert {} at {}", letter, from),
+            Edit::Delete { from, letter, .. } => write!(f, "delete {} at {}", letter, from),
+        }
+    }
+}
+
+/// Cheapest sequence of edits turning `w1` into `w2`, every operation weighing what
+/// `weights` says; its changes weigh `matrix_edit_distance(w1, w2, weights)`
+pub fn matrix_alignment(w1: &str, w2: &str, weights: &EditWeights) -> Vec<Edit> {
+    let w1: Vec<char> = w1.chars().collect();
+    let w2: Vec<char> = w2.chars().collect();
+    let distances = prefix_distances(&w1, &w2, weights);
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (w1.len(), w2.len());
+    // Walking back from the end, keeping and substituting are preferred over deleting and
+    // deleting over inserting when they cost as much, so alignments are deterministic
+    while i > 0 || j > 0 {
+        let (from, to) = (i.saturating_sub(1), j.saturating_sub(1));
+        if i > 0
+            && j > 0
+            && distances[i][j] == distances[i - 1][j - 1] + weights.substitution(w1[from], w2[to])
+        {
+            edits.push(match w1[from] == w2[to] {
+                true => Edit::Keep {
+                    from,
+                    to,
+                    letter: w1[from],
+                },
+                false => Edit::Substitute {
+                    from,
+                    to,
+                    old: w1[from],
+                    new: w2[to],
+                },
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0
+            && (j == 0 || distances[i][j] == distances[i - 1][j] + weights.deletion(w1[from]))
+        {
+            edits.push(Edit::Delete {
+                from,
+                to: j,
+                letter: w1[from],
+            });
+            i -= 1;
+        } else {
+            edits.push(Edit::Insert {
+                from: i,
+                to,
+                letter: w2[to],
+            });
+            j -= 1;
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+/// Alignment of the Levenshtein distance, where every operation weighs 1
+pub fn alignment(w1: &str, w2: &str) -> Vec<Edit> {
+    matrix_alignment(w1, w2, &EditWeights::default())
 }
 
 #[cfg(test)]
@@ -33,6 +439,142 @@ mod tests {
         );
     }
 
+    fn substitution(from: &str, to: &str, weight: usize) -> Substitution {
+        Substitution {
+            from: from.chars().collect(),
+            to: to.chars().collect(),
+            weight,
+        }
+    }
+
+    #[test]
+    fn weighted_edit_distance_applies_substitutions() {
+        let rules = vec![substitution("rn", "m", 1), substitution("l", "1", 1)];
+        assert_eq!(weighted_edit_distance("corn", "com", 2, &rules), 1);
+        assert_eq!(weighted_edit_distance("com", "corn", 2, &rules), 4);
+        assert_eq!(weighted_edit_distance("lake", "1ake", 2, &rules), 1);
+        assert_eq!(weighted_edit_distance("lake", "bake", 2, &rules), 2);
+        assert_eq!(weighted_edit_distance("", "abc", 2, &rules), 6);
+    }
+
+    #[test]
+    fn hop_counts_do_not_wrap() {
+        assert_eq!(hops_lower_bound::<u8>(300), PathMultiCost::new(u8::MAX, 0));
+        assert_eq!(
+            hops_lower_bound::<EditDistance>(300).get_cost(),
+            vec![(300, 1)]
+        );
+        assert_eq!(hop_cost::<u8>(3), PathMultiCost::new(1, 2));
+    }
+
+    #[test]
+    fn long_hops_share_overflow_dimension() {
+        let hop = hop_cost::<EditDistance>;
+        assert!(hop(MAX_DIMENSION - 1) < hop(MAX_DIMENSION));
+        assert!(hop(MAX_DIMENSION) < hop(50));
+        assert!(hop(50) > hop(30) + hop(MAX_DIMENSION - 1) + hop(MAX_DIMENSION - 1));
+        assert!(hop(50) > hop(25) + hop(5));
+        assert_eq!(hop(50).get_cost(), vec![(50, MAX_DIMENSION)]);
+    }
+
+    #[test]
+    fn check_length_rejects_absurd_words() {
+        assert_eq!(check_length("banana"), Ok(()));
+        let word = "a".repeat(MAX_WORD_LENGTH + 1);
+        assert_eq!(
+            check_length(&word),
+            Err(WordTooLong {
+                length: MAX_WORD_LENGTH + 1
+            })
+        );
+    }
+
+    #[test]
+    fn token_edit_distance_counts_words() {
+        assert_eq!(token_edit_distance("the cat sat", "the cat sat"), 0);
+        assert_eq!(token_edit_distance("the cat sat", "the dog sat"), 1);
+        assert_eq!(token_edit_distance("the cat sat", "cat sat down"), 2);
+        assert_eq!(token_edit_distance("the  cat", "the cat"), 0);
+    }
+
+    #[test]
+    fn matrix_edit_distance_uses_letter_weights() {
+        let mut weights = EditWeights::default();
+        weights.substitutions.insert(('a', 'e'), 3);
+        weights.insertions.insert('h', 2);
+        weights.default_deletion = 4;
+        assert_eq!(matrix_edit_distance("cat", "cet", &weights), 3);
+        assert_eq!(matrix_edit_distance("cet", "cat", &weights), 1);
+        assert_eq!(matrix_edit_distance("cat", "chat", &weights), 2);
+        assert_eq!(matrix_edit_distance("chat", "cat", &weights), 4);
+        assert_eq!(weights.min_weight(), 1);
+    }
+
+    #[test]
+    fn alignment_lists_every_edit() {
+        let edits = alignment("cold", "scald");
+        let steps: Vec<String> = edits.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            steps,
+            vec![
+                "insert s at 0",
+                "keep c at 0",
+                "substitute o with a at 1",
+                "keep l at 2",
+                "keep d at 3"
+            ]
+        );
+        assert_eq!(
+            edits[2],
+            Edit::Substitute {
+                from: 1,
+                to: 2,
+                old: 'o',
+                new: 'a'
+            }
+        );
+        assert_eq!(
+            alignment("ab", ""),
+            vec![
+                Edit::Delete {
+                    from: 0,
+                    to: 0,
+                    letter: 'a'
+                },
+                Edit::Delete {
+                    from: 1,
+                    to: 0,
+                    letter: 'b'
+                }
+            ]
+        );
+        let weights = EditWeights {
+            default_substitution: 3,
+            ..EditWeights::default()
+        };
+        let names: Vec<&str> = matrix_alignment("cat", "cut", &weights)
+            .into_iter()
+            .map(Edit::name)
+            .collect();
+        assert_eq!(names, vec!["keep", "insert", "delete", "keep"]);
+    }
+
+    // Letters of the second word rebuilt from the first one and an alignment
+    fn apply(w1: &str, edits: &[Edit]) -> Option<String> {
+        let w1: Vec<char> = w1.chars().collect();
+        let mut rebuilt = String::new();
+        for edit in edits {
+            match *edit {
+                Edit::Keep { from, letter, .. } if w1[from] == letter => rebuilt.push(letter),
+                Edit::Substitute { from, old, new, .. } if w1[from] == old => rebuilt.push(new),
+                Edit::Insert { letter, .. } => rebuilt.push(letter),
+                Edit::Delete { from, letter, .. } if w1[from] == letter => (),
+                _ => return None,
+            }
+        }
+        Some(rebuilt)
+    }
+
     extern crate quickcheck;
     use quickcheck::quickcheck;
 
@@ -44,5 +586,25 @@ mod tests {
         fn heuristic_prop(a: String, b: String) -> bool {
             path_cost(&a, &b) >= edit_distance(&a, &b)
         }
+        fn levenshtein_matches_edit_distance_crate_prop(a: String, b: String) -> bool {
+            levenshtein(&a, &b) == edit_distance::edit_distance(&a, &b)
+        }
+        fn weighted_edit_distance_matches_levenshtein_prop(a: String, b: String) -> bool {
+            weighted_edit_distance(&a, &b, 1, &[]) == edit_distance::edit_distance(&a, &b)
+        }
+        fn sequence_edit_distance_matches_levenshtein_prop(a: String, b: String) -> bool {
+            let a: Vec<char> = a.chars().collect();
+            let b: Vec<char> = b.chars().collect();
+            let (sa, sb): (String, String) = (a.iter().collect(), b.iter().collect());
+            sequence_edit_distance(&a, &b) == edit_distance::edit_distance(&sa, &sb)
+        }
+        fn matrix_edit_distance_matches_levenshtein_prop(a: String, b: String) -> bool {
+            matrix_edit_distance(&a, &b, &EditWeights::default()) == edit_distance::edit_distance(&a, &b)
+        }
+        fn alignment_rebuilds_the_second_word_prop(a: String, b: String) -> bool {
+            let edits = alignment(&a, &b);
+            let changes = edits.iter().filter(|edit| edit.is_change()).count();
+            apply(&a, &edits) == Some(b.clone()) && changes == edit_distance::edit_distance(&a, &b)
+        }
     }
 }
@@ -0,0 +1,156 @@
+use crate::resources::Value;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use typos::distance::path::PathMultiCost;
+use typos::distance::word::EditDistance;
+use typos::distance::SearchObserver;
+
+/// One line of `--format ndjson` output
+pub enum Event<'a> {
+    /// Emitted every few thousand expansions while the search runs
+    Progress { expanded: usize, word: &'a str },
+    /// The outcome of the search, `path` is `None` when the words are not connected. Along
+    /// with the words and the cost come the letters each hop changes.
+    Result {
+        path: Option<(&'a [String], &'a str, &'a [usize])>,
+    },
+    /// The search could not complete
+    Error { message: &'a str },
+    /// What the run cost, with `--report`
+    Resources { fields: &'a [(&'a str, Value)] },
+}
+
+impl<'a> fmt::Display for Event<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Progress { expanded, word } => write!(
+                f,
+                "{{\"event\":\"progress\",\"expanded\":{},\"word\":{}}}",
+                expanded,
+                JsonString(word)
+            ),
+            Event::Result { path: None } => write!(f, "{{\"event\":\"result\",\"path\":null}}"),
+            Event::Result {
+                path: Some((words, cost, hops)),
+            } => {
+                let words: Vec<String> = words.iter().map(|w| JsonString(w).to_string()).collect();
+                let hops: Vec<String> = hops.iter().map(usize::to_string).collect();
+                write!(
+                    f,
+                    "{{\"event\":\"result\",\"path\":[{}],\"cost\":{},\"hops\":[{}]}}",
+                    words.join(","),
+                    JsonString(cost),
+                    hops.join(",")
+                )
+            }
+            Event::Error { message } => write!(
+                f,
+                "{{\"event\":\"error\",\"message\":{}}}",
+                JsonString(message)
+            ),
+            Event::Resources { fields } => {
+                write!(f, "{{\"event\":\"resources\"")?;
+                for (name, value) in fields.iter() {
+                    write!(f, ",{}:{}", JsonString(name), value.json())?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A quoted JSON string, words come from arbitrary files and may need escaping
+pub struct JsonString<'a>(pub &'a str);
+
+impl<'a> fmt::Display for JsonString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Prints a progress event to stdout every `interval` expansions
+pub struct Progress {
+    interval: usize,
+    expanded: AtomicUsize,
+}
+
+impl Progress {
+    pub fn new(interval: usize) -> Progress {
+        Progress {
+            interval,
+            expanded: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl SearchObserver for Progress {
+    fn expanded(
+        &self,
+        word: &str,
+        _heuristic: PathMultiCost<EditDistance>,
+        _successors: &[(&str, PathMultiCost<EditDistance>)],
+    ) {
+        let expanded = self.expanded.fetch_add(1, Ordering::Relaxed) + 1;
+        if expanded.is_multiple_of(self.interval) {
+            println!("{}", Event::Progress { expanded, word });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_single_json_lines() {
+        let words = vec!["cold".to_string(), "co\"ld\n".to_string()];
+        assert_eq!(
+            Event::Result {
+                path: Some((&words, "1 1-letter mutation", &[1]))
+            }
+            .to_string(),
+            r#"{"event":"result","path":["cold","co\"ld\n"],"cost":"1 1-letter mutation","hops":[1]}"#
+        );
+        assert_eq!(
+            Event::Result { path: None }.to_string(),
+            r#"{"event":"result","path":null}"#
+        );
+        assert_eq!(
+            Event::Progress {
+                expanded: 5000,
+                word: "a\u{1}"
+            }
+            .to_string(),
+            r#"{"event":"progress","expanded":5000,"word":"a\u0001"}"#
+        );
+        assert_eq!(
+            Event::Error {
+                message: "search deadline exceeded"
+            }
+            .to_string(),
+            r#"{"event":"error","message":"search deadline exceeded"}"#
+        );
+        assert_eq!(
+            Event::Resources {
+                fields: &[
+                    ("expanded_words", Value::Count(4)),
+                    ("peak_rss_bytes", Value::Unknown)
+                ]
+            }
+            .to_string(),
+            r#"{"event":"resources","expanded_words":4,"peak_rss_bytes":null}"#
+        );
+    }
+}
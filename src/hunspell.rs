@@ -0,0 +1,291 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufRead};
+
+// How the flags of a word are written after its slash
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FlagType {
+    /// One character per flag, the default
+    Char,
+    /// Two characters per flag
+    Long,
+    /// Numbers separated by commas
+    Num,
+}
+
+impl FlagType {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagType::Char => flags.chars().map(String::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagType::Num => flags.split(',').map(str::to_string).collect(),
+        }
+    }
+}
+
+// A letter of a condition
+#[derive(Debug, Clone, PartialEq)]
+enum Letter {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl Letter {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Letter::Any => true,
+            Letter::OneOf(chars) => chars.contains(&c),
+            Letter::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+// Letters a word must start with, for prefixes, or end with, for suffixes, such as `[^aeiou]y`
+fn condition(pattern: &str) -> Vec<Letter> {
+    let mut letters = Vec::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        letters.push(match c {
+            '.' => Letter::Any,
+            '[' => {
+                let class: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                match class.strip_prefix('^') {
+                    Some(negated) => Letter::NoneOf(negated.chars().collect()),
+                    None => Letter::OneOf(class.chars().collect()),
+                }
+            }
+            c => Letter::OneOf(vec![c]),
+        });
+    }
+    letters
+}
+
+// A way of turning a word into another one by its start or its end
+#[derive(Debug, Clone)]
+struct Rule {
+    strip: String,
+    add: String,
+    condition: Vec<Letter>,
+}
+
+#[derive(Debug, Default)]
+struct Affix {
+    prefix: bool,
+    /// Whether words may take a prefix and a suffix of such rules at once
+    cross: bool,
+    rules: Vec<Rule>,
+}
+
+impl Affix {
+    fn apply(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                let length = rule.condition.len();
+                if chars.len() < length.max(1) {
+                    return None;
+                }
+                let (tested, base) = match self.prefix {
+                    true => (&chars[..length], word.strip_prefix(rule.strip.as_str())?),
+                    false => (
+                        &chars[chars.len() - length..],
+                        word.strip_suffix(rule.strip.as_str())?,
+                    ),
+                };
+                if !rule
+                    .condition
+                    .iter()
+                    .zip(tested)
+                    .all(|(l, &c)| l.matches(c))
+                {
+                    return None;
+                }
+                Some(match self.prefix {
+                    true => format!("{}{}", rule.add, base),
+                    false => format!("{}{}", base, rule.add),
+                })
+            })
+            .filter(|derived| !derived.is_empty())
+            .collect()
+    }
+}
+
+/// Prefix and suffix rules of a `.aff` file
+#[derive(Debug)]
+pub struct Affixes {
+    flag_type: FlagType,
+    affixes: HashMap<String, Affix>,
+    /// Flag of the words only listed to carry affixes, never used bare
+    need_affix: Option<String>,
+}
+
+impl Default for Affixes {
+    fn default() -> Affixes {
+        Affixes {
+            flag_type: FlagType::Char,
+            affixes: HashMap::new(),
+            need_affix: None,
+        }
+    }
+}
+
+impl Affixes {
+    /// Read the flag type, the affix rules and NEEDAFFIX of a `.aff` file, other
+    /// directives such as compounding or suggestion settings are ignored
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Affixes> {
+        let mut affixes = Affixes::default();
+        for line in reader.lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                ["FLAG", "long", ..] => affixes.flag_type = FlagType::Long,
+                ["FLAG", "num", ..] => affixes.flag_type = FlagType::Num,
+                ["NEEDAFFIX", flag, ..] => affixes.need_affix = Some(flag.to_string()),
+                // A header `PFX flag cross count`, then `count` rules `PFX flag strip add condition`
+                [kind @ ("PFX" | "SFX"), flag, cross, count]
+                    if count.parse::<usize>().is_ok() && !affixes.affixes.contains_key(flag) =>
+                {
+                    affixes.affixes.insert(
+                        flag.to_string(),
+                        Affix {
+                            prefix: kind == "PFX",
+                            cross: cross == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                ["PFX" | "SFX", flag, strip, add, ref rest @ ..] => {
+                    let affix = affixes.affixes.get_mut(flag).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("affix rule of flag {} without its header", flag),
+                        )
+                    })?;
+                    let field = |value: &str| match value {
+                        "0" => String::new(),
+                        value => value.to_string(),
+                    };
+                    // Affixes carrying flags of their own are applied once, without them
+                    let add = add.split('/').next().unwrap_or_default();
+                    affix.rules.push(Rule {
+                        strip: field(strip),
+                        add: field(add),
+                        condition: condition(rest.first().copied().unwrap_or(".")),
+                    });
+                }
+                _ => (),
+            }
+        }
+        Ok(affixes)
+    }
+
+    /// `word` and every word its flags derive from it, bare `word` left out when it
+    /// needs an affix
+    pub fn expand(&self, word: &str, flags: &str) -> Vec<String> {
+        let flags = self.flag_type.split(flags);
+        let mut words = Vec::new();
+        if !flags
+            .iter()
+            .any(|flag| Some(flag) == self.need_affix.as_ref())
+        {
+            words.push(word.to_string());
+        }
+        let affixes: Vec<&Affix> = flags
+            .iter()
+            .filter_map(|flag| self.affixes.get(flag))
+            .collect();
+        let mut suffixed = Vec::new();
+        for affix in affixes.iter().filter(|affix| !affix.prefix) {
+            for derived in affix.apply(word) {
+                if affix.cross {
+                    suffixed.push(derived.clone());
+                }
+                words.push(derived);
+            }
+        }
+        for affix in affixes.iter().filter(|affix| affix.prefix) {
+            words.extend(affix.apply(word));
+            if affix.cross {
+                for derived in &suffixed {
+                    words.extend(affix.apply(derived));
+                }
+            }
+        }
+        words
+    }
+}
+
+/// Every word of a `.dic` file with the words its affixes derive, in order and without
+/// repeats. The count on the first line and the morphological fields are skipped.
+pub fn words(dic: impl BufRead, affixes: &Affixes) -> io::Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, line) in dic.lines().enumerate() {
+        let line = line?;
+        let entry = line.split('\t').next().unwrap_or_default().trim();
+        if entry.is_empty()
+            || entry.starts_with('#')
+            || (index == 0 && entry.parse::<usize>().is_ok())
+        {
+            continue;
+        }
+        // The slash before the flags is the first one not escaped as `\/`
+        let entry = entry.split_whitespace().next().unwrap_or_default();
+        let mut word = String::new();
+        let mut flags = "";
+        let mut chars = entry.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => word.extend(chars.next().map(|(_, c)| c)),
+                '/' => {
+                    flags = &entry[i + 1..];
+                    break;
+                }
+                c => word.push(c),
+            }
+        }
+        for derived in affixes.expand(&word, flags) {
+            if seen.insert(derived.clone()) {
+                words.push(derived);
+            }
+        }
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "SET UTF-8
+NEEDAFFIX X
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [aeiou]y
+SFX D N 1
+SFX D   0     ed/S       .
+PFX U Y 1
+PFX U   0     un         .
+";
+
+    #[test]
+    fn affixes_are_expanded() {
+        let affixes = Affixes::from_reader(AFF.as_bytes()).unwrap();
+        let dic = "4\nfly/SU\nplay/SD\nlock/XU\nand\\/or\ncat/S po:noun\n";
+        assert_eq!(
+            words(dic.as_bytes(), &affixes).unwrap(),
+            vec![
+                "fly", "flies", "unfly", "unflies", "play", "plays", "played", "unlock", "and/or",
+                "cat"
+            ]
+        );
+        let long = Affixes::from_reader("FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n".as_bytes());
+        let long = long.unwrap();
+        assert_eq!(long.expand("cat", "AaBb"), vec!["cat", "cats"]);
+        assert!(Affixes::from_reader("SFX S 0 s .\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::mem;
+use typos::distance::prebuilt::{self, PrebuiltGraph};
+use typos::words;
+
+/// Facts about a word list or a graph to look at when searches over it are slow or odd,
+/// as names and values in the order they are printed
+pub type Facts = Vec<(String, String)>;
+
+// Sizes as `ls -h` prints them
+fn bytes(count: usize) -> String {
+    let mut size = count as f64;
+    for unit in ["B", "K", "M", "G"] {
+        if size < 1024.0 || unit == "G" {
+            return match unit {
+                "B" => format!("{}B", count),
+                unit => format!("{:.1}{}", size, unit),
+            };
+        }
+        size /= 1024.0;
+    }
+    unreachable!()
+}
+
+fn yes_no(value: bool) -> String {
+    match value {
+        true => "yes".to_string(),
+        false => "no".to_string(),
+    }
+}
+
+// Letters used and the number of words of every length, common to lists and graphs
+fn word_facts(words: &[String], facts: &mut Facts) {
+    let alphabet: BTreeSet<char> = words.iter().flat_map(|word| word.chars()).collect();
+    let letters: String = alphabet.iter().collect();
+    facts.push((
+        "alphabet".to_string(),
+        format!("{} letters {}", alphabet.len(), letters),
+    ));
+    for (length, count) in words::length_counts(words) {
+        facts.push((format!("words of {} letters", length), count.to_string()));
+    }
+}
+
+/// Facts about a word list, read as `words` with `duplicates` words dropped and
+/// `weighted` words given a weight
+pub fn dictionary(words: &[String], duplicates: usize, weighted: usize) -> Facts {
+    let mut facts = vec![
+        ("words".to_string(), words.len().to_string()),
+        ("duplicates removed".to_string(), duplicates.to_string()),
+        ("weighted words".to_string(), weighted.to_string()),
+    ];
+    word_facts(words, &mut facts);
+    // Searches hold every word once along with a reference to it
+    let memory: usize = words
+        .iter()
+        .map(|word| mem::size_of::<String>() + mem::size_of::<&str>() + word.len())
+        .sum();
+    facts.push(("estimated memory".to_string(), bytes(memory)));
+    facts
+}
+
+/// Facts about a graph file written by build-graph, along with the options it was built with
+pub fn graph(graph: &PrebuiltGraph) -> Facts {
+    let mut facts = vec![
+        ("format version".to_string(), prebuilt::VERSION.to_string()),
+        ("words".to_string(), graph.word_count().to_string()),
+        ("hops".to_string(), graph.edge_count().to_string()),
+        ("landmarks".to_string(), graph.landmark_count().to_string()),
+        ("length buckets".to_string(), yes_no(graph.has_buckets())),
+        ("frequencies".to_string(), yes_no(graph.has_frequencies())),
+        (
+            "deletion index".to_string(),
+            graph
+                .deletion_depth()
+                .map_or_else(|| yes_no(false), |depth| format!("depth {}", depth)),
+        ),
+    ];
+    word_facts(graph.words(), &mut facts);
+    facts.push((
+        "estimated memory".to_string(),
+        bytes(graph.memory_estimate()),
+    ));
+    facts
+}
+
+/// One `name: value` line per fact, values aligned
+pub fn write(facts: &Facts, mut output: impl Write) -> io::Result<()> {
+    let width = facts.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, value) in facts {
+        writeln!(
+            output,
+            "{:width$} {}",
+            format!("{}:", name),
+            value,
+            width = width + 1
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(facts: &Facts) -> Vec<String> {
+        let mut output = Vec::new();
+        write(facts, &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn describes_lists_and_graphs() {
+        let words: Vec<String> = ["cold", "cord", "card", "warm", "é"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        let facts = dictionary(&words, 2, 1);
+        assert_eq!(
+            lines(&facts)[..6],
+            [
+                "words:              5",
+                "duplicates removed: 2",
+                "weighted words:     1",
+                "alphabet:           9 letters acdlmorwé",
+                "words of 1 letters: 1",
+                "words of 4 letters: 4",
+            ]
+        );
+        let built = PrebuiltGraph::build(words).with_buckets().with_landmarks(1);
+        let facts = graph(&built);
+        assert_eq!(facts[2], ("hops".to_string(), "2".to_string()));
+        assert_eq!(facts[4], ("length buckets".to_string(), "yes".to_string()));
+        assert_eq!(facts[6], ("deletion index".to_string(), "no".to_string()));
+        assert_eq!(bytes(1000), "1000B");
+        assert_eq!(bytes(1536), "1.5K");
+        assert_eq!(bytes(3 << 30), "3.0G");
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cache;
+pub mod database;
+pub mod distance;
+pub mod remote;
+pub mod words;
@@ -0,0 +1,299 @@
+use crate::pipe::{split_words, Speller};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+// Unknown words are not necessarily mistakes, they are reported as information
+const INFORMATION: u64 = 3;
+const METHOD_NOT_FOUND: i64 = -32601;
+// Documents are always sent whole, which keeps their text simple to follow
+const FULL_SYNC: u64 = 1;
+
+/// Language server reporting the words of open documents missing from the dictionary,
+/// with quick fixes replacing them by the closest listed words
+pub struct Server<'w> {
+    speller: Speller<'w>,
+    // Text of every open document by URI
+    documents: HashMap<String, String>,
+}
+
+// Body of the next message, `None` once the client closed the stream
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "message without Content-Length")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(io::Error::from)
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+// Positions count UTF-16 code units unless the client negotiates otherwise
+fn utf16_column(line: &str, offset: usize) -> usize {
+    line.chars().take(offset).map(char::len_utf16).sum()
+}
+
+// Text of `line` between two UTF-16 columns
+fn utf16_slice(line: &str, start: usize, end: usize) -> String {
+    let mut column = 0;
+    let mut slice = String::new();
+    for c in line.chars() {
+        if column >= start && column < end {
+            slice.push(c);
+        }
+        column += c.len_utf16();
+    }
+    slice
+}
+
+fn range(line: usize, start: usize, end: usize) -> Value {
+    json!({
+        "start": {"line": line, "character": start},
+        "end": {"line": line, "character": end}
+    })
+}
+
+fn response(id: &Value, result: Value) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "result": result})
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+impl<'w> Server<'w> {
+    pub fn new(words: &[&'w str]) -> Server<'w> {
+        Server {
+            speller: Speller::new(words),
+            documents: HashMap::new(),
+        }
+    }
+
+    fn diagnostics(&self, text: &str) -> Vec<Value> {
+        let mut diagnostics = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            for (offset, word) in split_words(line) {
+                if self.speller.knows(&word) {
+                    continue;
+                }
+                let start = utf16_column(line, offset);
+                let end = start + word.encode_utf16().count();
+                diagnostics.push(json!({
+                    "range": range(number, start, end),
+                    "severity": INFORMATION,
+                    "source": "typos",
+                    "message": format!("{} is not in the dictionary", word)
+                }));
+            }
+        }
+        diagnostics
+    }
+
+    fn publish(&self, uri: &str) -> Value {
+        let diagnostics = self
+            .documents
+            .get(uri)
+            .map_or_else(Vec::new, |text| self.diagnostics(text));
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({"uri": uri, "diagnostics": diagnostics}),
+        )
+    }
+
+    // Every suggestion for the words the diagnostics of this server point at
+    fn code_actions(&self, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let text = match self.documents.get(uri) {
+            Some(text) => text,
+            None => return Vec::new(),
+        };
+        let diagnostics = params["context"]["diagnostics"].as_array();
+        let mut actions = Vec::new();
+        for diagnostic in diagnostics.into_iter().flatten() {
+            if diagnostic["source"] != "typos" {
+                continue;
+            }
+            let range = &diagnostic["range"];
+            let column = |end: &str| range[end]["character"].as_u64().unwrap_or(0) as usize;
+            let line = range["start"]["line"].as_u64().unwrap_or(0) as usize;
+            let word = match text.lines().nth(line) {
+                Some(line) => utf16_slice(line, column("start"), column("end")),
+                None => continue,
+            };
+            for suggestion in self.speller.suggestions(&word) {
+                actions.push(json!({
+                    "title": format!("Replace with {}", suggestion),
+                    "kind": "quickfix",
+                    "diagnostics": [diagnostic],
+                    "edit": {"changes": {uri: [{"range": range, "newText": suggestion}]}}
+                }));
+            }
+        }
+        actions
+    }
+
+    /// Messages to send back for `message`, `None` once the client asks to exit
+    pub fn handle(&mut self, message: &Value) -> Option<Vec<Value>> {
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let id = message.get("id");
+        let replies = match (message["method"].as_str().unwrap_or_default(), id) {
+            ("initialize", Some(id)) => vec![response(
+                id,
+                json!({
+                    "capabilities": {
+                        "textDocumentSync": FULL_SYNC,
+                        "codeActionProvider": true
+                    },
+                    "serverInfo": {"name": "typos", "version": env!("CARGO_PKG_VERSION")}
+                }),
+            )],
+            ("textDocument/didOpen", _) => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                vec![self.publish(&uri)]
+            }
+            ("textDocument/didChange", _) => {
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|c| c.last()?["text"].as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                vec![self.publish(&uri)]
+            }
+            // Diagnostics of closed documents are cleared
+            ("textDocument/didClose", _) => {
+                self.documents.remove(&uri);
+                vec![self.publish(&uri)]
+            }
+            ("textDocument/codeAction", Some(id)) => {
+                vec![response(id, Value::from(self.code_actions(params)))]
+            }
+            ("shutdown", Some(id)) => vec![response(id, Value::Null)],
+            ("exit", _) => return None,
+            (method, Some(id)) if !method.is_empty() => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {"code": METHOD_NOT_FOUND, "message": format!("{} is not supported", method)}
+            })],
+            // Other notifications and the answers of the client need no reply
+            _ => Vec::new(),
+        };
+        Some(replies)
+    }
+}
+
+pub fn run(server: &mut Server, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    while let Some(message) = read_message(&mut input)? {
+        match server.handle(&message) {
+            Some(replies) => {
+                for reply in &replies {
+                    write_message(&mut output, reply)?;
+                }
+            }
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(messages: &[Value]) -> Vec<u8> {
+        let mut framed = Vec::new();
+        for message in messages {
+            write_message(&mut framed, message).unwrap();
+        }
+        framed
+    }
+
+    #[test]
+    fn diagnoses_and_fixes_unknown_words() {
+        let uri = "file:///notes.txt";
+        let diagnostic = json!({
+            "range": range(1, 3, 7),
+            "severity": INFORMATION,
+            "source": "typos",
+            "message": "colt is not in the dictionary"
+        });
+        let input = frame(&[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            notification(
+                "textDocument/didOpen",
+                json!({"textDocument": {"uri": uri, "text": "Cold warm\n😀 colt"}}),
+            ),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/codeAction", "params": {
+                "textDocument": {"uri": uri},
+                "range": range(1, 3, 7),
+                "context": {"diagnostics": [diagnostic]}
+            }}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/hover", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 4, "method": "shutdown"}),
+            notification("exit", Value::Null),
+            json!({"jsonrpc": "2.0", "id": 5, "method": "shutdown"}),
+        ]);
+        let words = ["cold", "cord", "warm"];
+        let mut output = Vec::new();
+        run(&mut Server::new(&words), &input[..], &mut output).unwrap();
+        let mut output = &output[..];
+        let mut replies = Vec::new();
+        while let Some(reply) = read_message(&mut output).unwrap() {
+            replies.push(reply);
+        }
+
+        assert_eq!(replies.len(), 5);
+        assert_eq!(
+            replies[0]["result"]["capabilities"]["codeActionProvider"],
+            true
+        );
+        assert_eq!(
+            replies[1]["params"],
+            json!({"uri": uri, "diagnostics": [diagnostic]})
+        );
+        let titles: Vec<&Value> = replies[2]["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|action| &action["title"])
+            .collect();
+        assert_eq!(titles, vec!["Replace with cold", "Replace with cord"]);
+        assert_eq!(
+            replies[2]["result"][0]["edit"]["changes"][uri][0]["newText"],
+            "cold"
+        );
+        assert_eq!(replies[3]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[4], response(&json!(4), Value::Null));
+    }
+
+    #[test]
+    fn columns_count_utf16_units() {
+        assert_eq!(utf16_column("😀 colt", 2), 3);
+        assert_eq!(utf16_slice("😀 colt", 3, 7), "colt");
+    }
+}
@@ -1,48 +1,1909 @@
 extern crate clap;
 
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, Shell, SubCommand};
 use std::str::FromStr;
 
-mod distance;
+mod batch;
+mod bench;
+mod check;
+mod checkpoint;
+mod cluster;
+mod columnar;
+mod convert;
+mod daily;
+mod events;
+mod hunspell;
+mod info;
+#[cfg(feature = "lsp")]
+mod lsp;
+mod matrix;
+mod pipe;
+mod random;
+mod render;
+mod repl;
+mod resources;
+mod server;
+mod session;
+mod tokenize;
+#[cfg(feature = "tui")]
+mod tui;
+mod validate;
+mod verify;
+mod walk;
 
-use crate::distance::PathFindingAlgorithm;
-use crate::distance::PathFindingAlgorithm::{Astar, Dijkstra, Fringe, Idastar};
-use core::borrow::Borrow;
-use std::time::Instant;
+use render::{Found, Renderer};
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::{
-    fs::File,
-    io::{self, BufRead, BufReader},
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
     path::Path,
+    process,
 };
+use typos::distance;
+#[cfg(feature = "scripting")]
+use typos::distance::cost::ScriptCost;
+use typos::distance::cost::{
+    Confusion, CostMatrix, CostModel, CostModelName, Levenshtein, Tokens, Unit,
+};
+use typos::distance::graph::Graph;
+use typos::distance::path::Objective;
+use typos::distance::prebuilt::{self, PrebuiltGraph};
+use typos::distance::word;
+use typos::distance::PathFindingAlgorithm;
+use typos::distance::PathFindingAlgorithm::{Astar, Dijkstra};
+use typos::distance::{
+    Dictionary, Direction, Disjoint, Heuristic, HeuristicChecker, Interruption, Locale, PathResult,
+    PruningExplainer, Sampling, Saturation, SearchOptions, SearchState, SearchStats, ShardedWords,
+    ShortestPath, SymSpellWords, TrieWords,
+};
+use typos::{cache, words};
+
+// Exit statuses, stable so scripts need not parse the printed text
+const EXIT_NO_PATH: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_DICTIONARY: i32 = 3;
+const EXIT_INTERRUPTED: i32 = 4;
+// The check subcommand found words missing from the dictionary, as grep finding nothing
+// is not an error either way
+const EXIT_UNKNOWN_WORDS: i32 = 1;
+// The verify subcommand got a path breaking the rules of the game
+const EXIT_INVALID_PATH: i32 = 1;
+// The bench subcommand measured queries doing worse than in the baseline
+const EXIT_REGRESSION: i32 = 1;
+
+// Value of --algorithm picking one from the dictionary and the ends of the search
+const AUTO_ALGORITHM: &str = "auto";
+
+fn usage_error(description: &str, kind: clap::ErrorKind) -> ! {
+    eprintln!(
+        "{}",
+        clap::Error::with_description(description, kind).message
+    );
+    process::exit(EXIT_USAGE)
+}
+
+// Progress events are emitted every that many expansions with --format ndjson
+const PROGRESS_INTERVAL: usize = 1000;
+
+// Time between two saves of --checkpoint without --checkpoint-interval
+const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How the outcome of a search is printed
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Quiet,
+    Ndjson,
+    Csv,
+}
+
+impl Output {
+    fn from_matches(matches: &clap::ArgMatches) -> Output {
+        match (matches.value_of("FORMAT"), matches.is_present("QUIET")) {
+            (Some("ndjson"), _) => Output::Ndjson,
+            (Some("csv"), _) => Output::Csv,
+            (_, true) => Output::Quiet,
+            _ => Output::Text,
+        }
+    }
+
+    // Reports a failure on stdout as an error event, or as prose on stderr
+    fn error(self, message: &str, code: i32) -> ! {
+        match self {
+            Output::Ndjson => println!("{}", events::Event::Error { message }),
+            _ => eprintln!("{}", message),
+        }
+        process::exit(code)
+    }
+}
+
+// Duplicates are dropped, every one of them would be expanded again during searches
+fn load_words(filename: &str, output: Output, policy: &words::Policy) -> Vec<String> {
+    load_weighted_words(filename, output, policy).words
+}
+
+// Entries of `filename` without duplicates, with a warning telling what was removed unless
+// nothing is left, which callers report themselves
+fn read_list(filename: &str, output: Output, policy: &words::Policy) -> words::List {
+    let mut list = words::from_location(filename, policy).unwrap_or_else(|error| {
+        output.error(
+            &format!("cannot load word list {}: {}", filename, error),
+            EXIT_DICTIONARY,
+        )
+    });
+    list.dedup();
+    if list.summary.removed_any() && !list.words.is_empty() && output != Output::Quiet {
+        eprintln!("warning: {}: {}", filename, list.summary);
+    }
+    list
+}
+
+// Lines given as `word<TAB>weight` penalize entering the word when searching, those given
+// as `word<TAB>lang` with --lang or --cross-language tag it with languages. A list left
+// without a word is an error: every search through it would be pointless.
+fn load_weighted_words(filename: &str, output: Output, policy: &words::Policy) -> words::List {
+    let list = read_list(filename, output, policy);
+    if list.words.is_empty() {
+        let message = match list.summary.read {
+            0 => format!("word list {} holds no words", filename),
+            _ => format!(
+                "dictionary {} empty after filters: {}",
+                filename, list.summary
+            ),
+        };
+        output.error(&message, EXIT_DICTIONARY);
+    }
+    list
+}
+
+fn load_frequencies(filename: &str, output: Output) -> HashMap<String, u32> {
+    File::open(filename)
+        .and_then(|file| prebuilt::frequencies_from_csv(BufReader::new(file)))
+        .unwrap_or_else(|error| {
+            output.error(
+                &format!("cannot load frequencies {}: {}", filename, error),
+                EXIT_DICTIONARY,
+            )
+        })
+}
+
+// A number of bytes such as 4096, 64K, 512M or 2G
+fn parse_size(size: &str) -> Option<usize> {
+    let (digits, unit) = match size.char_indices().last()? {
+        (i, 'k') | (i, 'K') => (&size[..i], 1 << 10),
+        (i, 'm') | (i, 'M') => (&size[..i], 1 << 20),
+        (i, 'g') | (i, 'G') => (&size[..i], 1 << 30),
+        _ => (size, 1),
+    };
+    digits.parse::<usize>().ok()?.checked_mul(unit)
+}
+
+// Where --checkpoint saves a search, and whether to go on with the one saved there
+struct Checkpointing<'m> {
+    path: &'m Path,
+    /// Dictionary, words and options of the search, which a resumed one must share
+    fingerprint: String,
+    interval: Duration,
+    resume: bool,
+}
+
+fn search_with_checkpoint<'a, D: Dictionary + ?Sized>(
+    start: &'a str,
+    stop: &'a str,
+    words: &'a D,
+    options: &SearchOptions,
+    checkpointing: &Checkpointing,
+    output: Output,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    let path = checkpointing.path;
+    let resume = match checkpointing.resume {
+        true => match checkpoint::load(path, &checkpointing.fingerprint) {
+            Ok(state) => {
+                if output == Output::Text {
+                    println!(
+                        "Resuming from {} words reached, {} of them expanded",
+                        state.reached(),
+                        state.expanded()
+                    );
+                }
+                Some(state)
+            }
+            Err(error) => output.error(
+                &format!("cannot resume from {}: {}", path.display(), error),
+                EXIT_USAGE,
+            ),
+        },
+        false => None,
+    };
+    let mut save = |state: &SearchState| {
+        if let Err(error) = checkpoint::save(path, &checkpointing.fingerprint, state) {
+            eprintln!("warning: cannot save {}: {}", path.display(), error);
+        }
+    };
+    let searched = distance::find_shortest_path_checkpointed(
+        start,
+        stop,
+        words,
+        options,
+        resume.as_ref(),
+        checkpointing.interval,
+        &mut save,
+    );
+    // Nothing is left to resume once the search ended
+    if searched.0.is_ok() && path.exists() {
+        if let Err(error) = fs::remove_file(path) {
+            eprintln!("warning: cannot remove {}: {}", path.display(), error);
+        }
+    }
+    searched
+}
+
+fn interrupted(interruption: Interruption, output: Output) -> ! {
+    match output {
+        Output::Text => println!("No path found: {}", interruption),
+        Output::Quiet => (),
+        Output::Csv => eprintln!("No path found: {}", interruption),
+        Output::Ndjson => println!(
+            "{}",
+            events::Event::Error {
+                message: &interruption.to_string()
+            }
+        ),
+    }
+    process::exit(EXIT_INTERRUPTED)
+}
+
+// Warns about what may make the path printed wrong or not the shortest
+fn warn_stats(stats: SearchStats, output: Output) {
+    if output == Output::Quiet {
+        return;
+    }
+    if stats.saturated {
+        eprintln!("warning: the path cost overflowed and is only a lower bound, see --saturation");
+    }
+    if stats.memory_fallback {
+        eprintln!(
+            "warning: the search outgrew --max-memory and went on with bounded-astar, the path may not be the shortest"
+        );
+    }
+}
+
+fn cost_model(name: CostModelName, confusion: Option<&str>) -> io::Result<Arc<dyn CostModel>> {
+    Ok(match (name, confusion) {
+        (CostModelName::Levenshtein, _) => Arc::new(Levenshtein),
+        (CostModelName::Ocr, None) => Arc::new(Confusion::ocr()),
+        (CostModelName::Ocr, Some(filename)) => {
+            Arc::new(Confusion::from_csv(BufReader::new(File::open(filename)?))?)
+        }
+        (CostModelName::Phonetic, _) => Arc::new(Confusion::phonetic()),
+    })
+}
+
+#[cfg(feature = "scripting")]
+fn script_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![Arg::with_name("COST_SCRIPT")
+        .long("cost-script")
+        .help("Rhai script defining fn cost(w1, w2) used instead of --cost")
+        .conflicts_with_all(&["CONFUSION", "COST_MATRIX"])
+        .takes_value(true)]
+}
+
+#[cfg(not(feature = "scripting"))]
+fn script_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![]
+}
+
+#[cfg(feature = "scripting")]
+fn script_cost_model(filename: &str) -> io::Result<Arc<dyn CostModel>> {
+    Ok(Arc::new(ScriptCost::from_file(filename)?))
+}
+
+// --cost-script is only declared with the scripting feature, so it never has a value here
+#[cfg(not(feature = "scripting"))]
+fn script_cost_model(_filename: &str) -> io::Result<Arc<dyn CostModel>> {
+    unreachable!("--cost-script requires the scripting feature")
+}
+
+// Taken by the commands playing series of games: searches, the repl and daily puzzles
+fn session_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("SESSION")
+            .long("session")
+            .help("file recording the words every path found goes through, kept from a run to the next")
+            .takes_value(true),
+        Arg::with_name("NO_REPEAT_SESSION")
+            .long("no-repeat-session")
+            .help("keep paths out of the words of the session, but for their ends")
+            .requires("SESSION"),
+    ]
+}
+
+fn open_session(matches: &clap::ArgMatches, output: Output) -> Option<session::Session> {
+    let path = matches.value_of("SESSION")?;
+    let session = session::Session::open(path).unwrap_or_else(|error| {
+        output.error(
+            &format!("cannot read session {}: {}", path, error),
+            EXIT_USAGE,
+        )
+    });
+    if output == Output::Text {
+        println!("{} words used in session {}", session.used(), path);
+    }
+    Some(session)
+}
+
+// With --no-repeat-session, the words of the session but `ends` are left out of `words`
+fn avoid_session(
+    session: Option<&session::Session>,
+    matches: &clap::ArgMatches,
+    words: &mut Vec<String>,
+    ends: &[&str],
+) {
+    if let Some(session) = session.filter(|_| matches.is_present("NO_REPEAT_SESSION")) {
+        words.retain(|word| ends.contains(&word.as_str()) || !session.contains(word));
+    }
+}
+
+// Losing the words of a path only makes later paths free to repeat them, a warning is enough
+fn record_session(session: Option<&mut session::Session>, path: &[&str]) {
+    if let Some(Err(error)) = session.map(|session| session.record(path)) {
+        eprintln!("warning: cannot save the session: {}", error);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn tui_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![Arg::with_name("TUI")
+        .long("tui")
+        .help("show the progress of the search in the terminal")]
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![]
+}
+
+#[cfg(feature = "tui")]
+fn search_with_tui<'a>(
+    start: &'a str,
+    stop: &str,
+    words: &'a [&str],
+    options: SearchOptions,
+    unit: Unit,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    tui::search(start, stop, words, options, unit).unwrap_or_else(|error| {
+        Output::Text.error(
+            &format!("cannot show the search in the terminal: {}", error),
+            EXIT_USAGE,
+        )
+    })
+}
+
+// --tui is only declared with the tui feature, so it is never present here
+#[cfg(not(feature = "tui"))]
+fn search_with_tui<'a>(
+    _start: &'a str,
+    _stop: &str,
+    _words: &'a [&str],
+    _options: SearchOptions,
+    _unit: Unit,
+) -> (Result<Option<ShortestPath<'a>>, Interruption>, SearchStats) {
+    unreachable!("--tui requires the tui feature")
+}
+
+fn graph(matches: &clap::ArgMatches) {
+    let filename = matches.value_of("EDGES").unwrap();
+    let start = matches.value_of("START").unwrap();
+    let stop = matches.value_of("END").unwrap();
+    let algorithm = matches
+        .value_of("ALGORITHM")
+        .map(PathFindingAlgorithm::from_str)
+        .unwrap()
+        .unwrap();
+    let graph = File::open(filename)
+        .and_then(|file| Graph::from_csv(BufReader::new(file)))
+        .unwrap_or_else(|error| {
+            Output::Text.error(
+                &format!("cannot load graph {}: {}", filename, error),
+                EXIT_DICTIONARY,
+            )
+        });
+    println!(
+        "{} nodes loaded into memory, each edge weighs {} criteria",
+        graph.node_count(),
+        graph.criteria()
+    );
+    let start_time = Instant::now();
+    let res = graph.shortest_path(start, stop, algorithm);
+    let duration = start_time.elapsed();
+    match res {
+        Some((nodes, cost)) => {
+            let weights = graph
+                .weights(&cost)
+                .iter()
+                .map(|weight| weight.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            println!(
+                "Shortest path found in {:?}: {} (achieved with weights {})",
+                duration,
+                nodes.join("->"),
+                weights
+            )
+        }
+        None => {
+            println!("No path found");
+            process::exit(EXIT_NO_PATH)
+        }
+    }
+}
+
+// The word list may be omitted or given with --input, in which case the
+// positional arguments shift left: `typos <START> <END> [ALGORITHM]`
+// The algorithm is `None` for `auto`, picked once the words are loaded.
+// With --end-pattern there is no ending word, which is then empty.
+fn search_arguments<'a>(
+    matches: &'a clap::ArgMatches,
+) -> (&'a str, &'a str, &'a str, Option<PathFindingAlgorithm>) {
+    let ends = match matches.is_present("END_PATTERN") {
+        true => 1,
+        false => 2,
+    };
+    let positionals: Vec<&str> = ["INPUT", "START", "END", "ALGORITHM"]
+        .iter()
+        .filter(|name| matches.occurrences_of(name) > 0)
+        .filter_map(|name| matches.value_of(name))
+        .collect();
+    let is_algorithm = |value: &str| {
+        value.eq_ignore_ascii_case(AUTO_ALGORITHM) || PathFindingAlgorithm::from_str(value).is_ok()
+    };
+    let (filename, rest) = match (matches.value_of("INPUT_FILE"), positionals.as_slice()) {
+        (Some(filename), rest) => (filename, rest),
+        (None, [first, rest @ ..])
+            if rest.len() == ends + 1
+                || (rest.len() == ends
+                    && (Path::new(first).exists() || !is_algorithm(rest[ends - 1]))) =>
+        {
+            (*first, rest)
+        }
+        (None, rest) if rest.len() < ends => search_usage_error(),
+        (None, rest) => (system_dictionary(Output::from_matches(matches)), rest),
+    };
+    if rest.len() < ends || rest.len() > ends + 1 {
+        search_usage_error()
+    }
+    let (start, stop) = match ends {
+        1 => (rest[0], ""),
+        _ => (rest[0], rest[1]),
+    };
+    match rest.get(ends) {
+        None => (filename, start, stop, Some(Astar)),
+        Some(algorithm) if algorithm.eq_ignore_ascii_case(AUTO_ALGORITHM) => {
+            (filename, start, stop, None)
+        }
+        Some(algorithm) => match PathFindingAlgorithm::from_str(algorithm) {
+            Ok(algorithm) => (filename, start, stop, Some(algorithm)),
+            Err(error) => usage_error(&error.to_string(), clap::ErrorKind::InvalidValue),
+        },
+    }
+}
+
+fn system_dictionary(output: Output) -> &'static str {
+    match words::system_dictionary() {
+        Some(filename) => {
+            if output == Output::Text {
+                println!("No input file given, using system word list {}", filename);
+            }
+            filename
+        }
+        None => output.error(
+            &format!(
+                "no input file given and no system word list found in {}",
+                words::SYSTEM_DICTIONARIES.join(", ")
+            ),
+            EXIT_DICTIONARY,
+        ),
+    }
+}
+
+// Searching from or to a word outside of the list is rarely intended, offer the closest ones
+fn missing_endpoint(word: &str, filename: &str, words: &[String], output: Output) -> ! {
+    let suggestions = words::suggestions(word, words, 3);
+    let mut message = format!("{} is not in the word list {}", word, filename);
+    if !suggestions.is_empty() {
+        message.push_str(&format!(", did you mean {}?", suggestions.join(", ")));
+    }
+    message.push_str(" Use --allow-missing-endpoints to search anyway.");
+    output.error(&message, EXIT_USAGE)
+}
+
+fn search_usage_error() -> ! {
+    usage_error(
+        "expected [INPUT] <START> <END> [ALGORITHM], or [INPUT] <START> [ALGORITHM] with --end-pattern",
+        clap::ErrorKind::WrongNumberOfValues,
+    )
+}
+
+// How `report` prints a path
+#[derive(Clone, Copy)]
+struct Reporting<'r> {
+    max_hops: Option<usize>,
+    // Unit to explain every hop in
+    explain: Option<Unit>,
+    locale: Locale,
+    output: Output,
+    // Printed after the paths, for --report
+    resources: Option<&'r resources::ResourceReport>,
+}
+
+// Prints what the run cost, a block on stderr or an event with --format ndjson
+fn report_resources(resources: &resources::ResourceReport, output: Output) {
+    let fields = resources.fields();
+    match output {
+        Output::Ndjson => println!("{}", events::Event::Resources { fields: &fields }),
+        Output::Text | Output::Quiet | Output::Csv => {
+            eprintln!("resources:");
+            for (name, value) in &fields {
+                eprintln!("  {}: {}", name, value);
+            }
+        }
+    }
+}
+
+impl Reporting<'_> {
+    // Hops are highlighted when explained on a terminal, unless NO_COLOR is set
+    fn renderer(&self) -> Box<dyn Renderer> {
+        let text = render::Text {
+            locale: self.locale,
+            explain: self.explain,
+        };
+        match self.output {
+            Output::Text
+                if self.explain.is_some()
+                    && io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none() =>
+            {
+                Box::new(render::Colored(text))
+            }
+            Output::Text => Box::new(text),
+            Output::Quiet => Box::new(render::Quiet),
+            Output::Ndjson => Box::new(render::Json),
+            Output::Csv => Box::new(render::Csv),
+        }
+    }
+}
+
+// Prints the outcome of a search, exiting with EXIT_NO_PATH when there is none. Paths
+// found without searching, in the cache, took no time.
+fn report(path: Option<Found>, took: Option<Duration>, reporting: Reporting) {
+    let mut out = io::stdout().lock();
+    match &path {
+        Some(path) => reporting.renderer().path(&mut out, path, took),
+        None => reporting.renderer().no_path(&mut out, reporting.max_hops),
+    }
+    .unwrap();
+    if let Some(resources) = reporting.resources {
+        report_resources(resources, reporting.output);
+    }
+    if path.is_none() {
+        process::exit(EXIT_NO_PATH)
+    }
+}
+
+// Same as `report` for several paths, `kind` telling how they relate in the text output
+fn report_alternatives(paths: Vec<Found>, kind: &str, duration: Duration, reporting: Reporting) {
+    if paths.is_empty() {
+        return report(None, None, reporting);
+    }
+    reporting
+        .renderer()
+        .alternatives(&mut io::stdout().lock(), &paths, kind, duration)
+        .unwrap();
+    if let Some(resources) = reporting.resources {
+        report_resources(resources, reporting.output);
+    }
+}
+
+fn validate(matches: &clap::ArgMatches) {
+    let filename = matches.value_of("INPUT").unwrap();
+    let report = File::open(filename)
+        .and_then(|file| validate::validate(BufReader::new(file)))
+        .unwrap_or_else(|error| {
+            Output::Text.error(
+                &format!("cannot read word list {}: {}", filename, error),
+                EXIT_DICTIONARY,
+            )
+        });
+    for (line, issue) in &report.issues {
+        println!("line {}: {}", line, issue);
+    }
+    println!(
+        "{} lines checked, {} errors, {} warnings",
+        report.lines,
+        report.errors(),
+        report.issues.len() - report.errors()
+    );
+    match matches.value_of("FIX") {
+        Some(output) => {
+            if let Err(error) = File::create(output).and_then(|file| report.write_clean(file)) {
+                Output::Text.error(&format!("cannot write {}: {}", output, error), EXIT_USAGE);
+            }
+            println!("{} words written to {}", report.clean.len(), output);
+        }
+        None if report.errors() > 0 => process::exit(EXIT_DICTIONARY),
+        None => (),
+    }
+}
+
+fn verify(matches: &clap::ArgMatches) {
+    let path: Vec<String> = matches
+        .value_of("PATH")
+        .unwrap()
+        .split(',')
+        .map(words::normalize)
+        .collect();
+    let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+    let max_hop_distance = matches.value_of("MAX_HOP_DISTANCE").map(|distance| {
+        distance.parse::<usize>().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    let verdict = verify::verify(&path, &indexed, &verify::options(max_hop_distance));
+    for issue in &verdict.issues {
+        println!("invalid: {}", issue);
+    }
+    println!("cost: {}", verdict.cost);
+    match verdict.optimal {
+        Some(optimal) => println!("optimal cost: {}", optimal),
+        None => println!("optimal cost: no path"),
+    }
+    match (verdict.is_valid(), verdict.is_optimal()) {
+        (false, _) => process::exit(EXIT_INVALID_PATH),
+        (true, true) => println!("the path is optimal"),
+        (true, false) => println!("a cheaper path exists"),
+    }
+}
+
+fn serve(matches: &clap::ArgMatches) {
+    let address = matches.value_of("ADDRESS").unwrap();
+    let dictionaries = matches
+        .values_of("DICT")
+        .unwrap()
+        .map(|dict| match dict.find('=') {
+            Some(i) => (dict[..i].to_string(), &dict[i + 1..]),
+            None => (dict.to_string(), dict),
+        })
+        .map(|(name, filename)| {
+            let words = load_words(filename, Output::Text, &word_policy(matches));
+            println!("{} words loaded into memory for {}", words.len(), name);
+            (name, words)
+        })
+        .collect();
+
+    let cache_size = matches
+        .value_of("CACHE_SIZE")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or_else(|_| {
+            usage_error(
+                "--cache-size expects a number of paths",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    let timeout = matches
+        .value_of("TIMEOUT")
+        .map(|timeout| {
+            timeout.parse::<u64>().unwrap_or_else(|_| {
+                usage_error(
+                    "--timeout expects a number of milliseconds",
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+        })
+        .map(Duration::from_millis);
+
+    let listener = TcpListener::bind(address).unwrap_or_else(|error| {
+        Output::Text.error(
+            &format!("cannot listen on {}: {}", address, error),
+            EXIT_USAGE,
+        )
+    });
+    println!("Listening on {}", address);
+    let served = server::serve(
+        listener,
+        server::Dictionaries::new(dictionaries),
+        server::Options {
+            cache_size,
+            timeout,
+        },
+    );
+    if let Err(error) = served {
+        Output::Text.error(
+            &format!("cannot accept connections on {}: {}", address, error),
+            EXIT_USAGE,
+        );
+    }
+}
+
+#[cfg(feature = "lsp")]
+fn lsp_subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
+    vec![SubCommand::with_name("lsp")
+        .about("Serve diagnostics and fixes for unknown words to editors over stdio")
+        .arg(
+            Arg::with_name("INPUT")
+                .short("i")
+                .long("input")
+                .help("word list to use, a system word list when omitted")
+                .takes_value(true),
+        )]
+}
+
+#[cfg(not(feature = "lsp"))]
+fn lsp_subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
+    vec![]
+}
+
+#[cfg(feature = "lsp")]
+fn lsp(matches: &clap::ArgMatches) {
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    // Standard output only carries the protocol
+    let words = load_words(filename, Output::Quiet, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let stdin = io::stdin();
+    lsp::run(&mut lsp::Server::new(&words), stdin.lock(), io::stdout()).unwrap();
+}
+
+// The lsp subcommand is only declared with the lsp feature, so it is never matched here
+#[cfg(not(feature = "lsp"))]
+fn lsp(_matches: &clap::ArgMatches) {
+    unreachable!("lsp requires the lsp feature")
+}
+
+fn check(matches: &clap::ArgMatches) {
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    // Warnings about the word list would be mixed with the findings otherwise
+    let mut words = load_words(filename, Output::Quiet, &word_policy(matches));
+    // Identifiers and jargon of the project are offered as suggestions too
+    let ignore_file = std::env::current_dir()
+        .ok()
+        .and_then(|directory| check::find_ignore_file(&directory));
+    if let Some(ignore_file) = ignore_file {
+        let ignore_file = ignore_file.to_string_lossy();
+        // An empty ignore file is no mistake
+        words.extend(read_list(&ignore_file, Output::Quiet, &word_policy(matches)).words);
+        words::dedup(&mut words);
+    }
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let speller = pipe::Speller::new(&words);
+    let modes = tokenize::Modes::parse(matches.values_of("TOKENS").into_iter().flatten())
+        .unwrap_or_else(|error| usage_error(&error, clap::ErrorKind::InvalidValue));
+    let mut findings = Vec::new();
+    let mut failed = false;
+    for argument in matches.values_of("FILES").unwrap() {
+        let errors = match argument {
+            "-" => match io::read_to_string(io::stdin()) {
+                Ok(text) => {
+                    findings.extend(check::check(&speller, &modes, argument, &text));
+                    Vec::new()
+                }
+                Err(error) => vec![format!("cannot read standard input: {}", error)],
+            },
+            argument => {
+                let (found, errors) = check::check_path(&speller, &modes, argument);
+                findings.extend(found);
+                errors
+            }
+        };
+        for error in &errors {
+            eprintln!("{}", error);
+        }
+        failed |= !errors.is_empty();
+    }
+    let stdout = io::stdout();
+    match matches.value_of("FORMAT") {
+        Some("json") => check::write_json(&findings, stdout.lock()),
+        _ => check::write_text(&findings, stdout.lock()),
+    }
+    .unwrap();
+    // Files left unchecked matter more than the words found in the others
+    if failed {
+        process::exit(EXIT_USAGE)
+    }
+    if !findings.is_empty() {
+        process::exit(EXIT_UNKNOWN_WORDS)
+    }
+}
+
+fn pipe(matches: &clap::ArgMatches) {
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    // Standard output only carries the protocol
+    let words = load_words(filename, Output::Quiet, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let stdin = io::stdin();
+    pipe::run(&mut pipe::Session::new(&words), stdin.lock(), io::stdout()).unwrap();
+}
+
+fn align(matches: &clap::ArgMatches) {
+    let (w1, w2) = (
+        matches.value_of("W1").unwrap(),
+        matches.value_of("W2").unwrap(),
+    );
+    let edits = match matches.value_of("COST_MATRIX") {
+        Some(filename) => File::open(filename)
+            .and_then(|file| CostMatrix::from_csv(BufReader::new(file)))
+            .unwrap_or_else(|error| {
+                usage_error(
+                    &format!("cannot load cost matrix {}: {}", filename, error),
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+            .alignment(w1, w2),
+        None => word::alignment(w1, w2),
+    };
+    for edit in &edits {
+        println!("{}", edit);
+    }
+    let changes = edits.iter().filter(|edit| edit.is_change()).count();
+    println!("{} edits", changes);
+}
+
+fn suggest(matches: &clap::ArgMatches) {
+    let word = words::normalize(matches.value_of("WORD").unwrap());
+    if let Err(error) = word::check_length(&word) {
+        usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+    }
+    let count = matches
+        .value_of("COUNT")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or_else(|_| {
+            usage_error(
+                "-k expects a number of words",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    for (suggestion, distance) in distance::suggest(&indexed, &word, count) {
+        println!("{}\t{}", suggestion, distance);
+    }
+}
+
+fn farthest(matches: &clap::ArgMatches) {
+    let start = words::normalize(matches.value_of("WORD").unwrap());
+    if let Err(error) = word::check_length(&start) {
+        usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+    }
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Text),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    println!(
+        "{} loaded into memory",
+        Locale::En.count(words.len(), "word", "words")
+    );
+    let start_time = Instant::now();
+    let options = SearchOptions::new(Dijkstra);
+    let (farthest, _) = distance::find_farthest_words(&start, &words, &options);
+    let farthest = farthest.unwrap_or_else(|interruption| interrupted(interruption, Output::Text));
+    println!(
+        "{} at {} from {}, found in {:?}:",
+        Locale::En.count(farthest.paths.len(), "word", "words"),
+        farthest.cost,
+        start,
+        start_time.elapsed()
+    );
+    for path in &farthest.paths {
+        println!("{}", path.join("->"));
+    }
+    println!(
+        "{} unreachable",
+        Locale::En.count(farthest.unreachable, "word", "words")
+    );
+}
+
+fn reachable(matches: &clap::ArgMatches) {
+    let start = words::normalize(matches.value_of("WORD").unwrap());
+    if let Err(error) = word::check_length(&start) {
+        usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+    }
+    let budget = matches
+        .value_of("BUDGET")
+        .unwrap()
+        .parse()
+        .unwrap_or_else(|()| {
+            usage_error(
+                "--budget expects a cost as searches print them, such as '2 1-letter mutations'",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    let options = SearchOptions::new(Dijkstra);
+    let options = match matches.value_of("MAX_HOP_DISTANCE") {
+        Some(distance) => options.with_max_hop_distance(distance.parse().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })),
+        None => options,
+    };
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    // Small hops are looked up in an index instead of scanning every word
+    let indexed = options
+        .max_hop_distance()
+        .map(|_| ShardedWords::new(&words));
+    let (reachable, _) = match &indexed {
+        Some(indexed) => distance::find_reachable(&start, indexed, budget, &options),
+        None => distance::find_reachable(&start, words.as_slice(), budget, &options),
+    };
+    let reachable =
+        reachable.unwrap_or_else(|interruption| interrupted(interruption, Output::Text));
+    for (word, cost) in reachable {
+        println!("{}\t{}", word, cost);
+    }
+}
+
+fn random_walk(matches: &clap::ArgMatches) {
+    let start = words::normalize(matches.value_of("WORD").unwrap());
+    if let Err(error) = word::check_length(&start) {
+        usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+    }
+    let count = |name: &str, flag: &str| {
+        matches
+            .value_of(name)
+            .unwrap()
+            .parse::<usize>()
+            .unwrap_or_else(|_| {
+                usage_error(
+                    &format!("{} expects a number", flag),
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+    };
+    let hops = count("HOPS", "--hops");
+    let walks = count("WALKS", "--walks");
+    let k = count("MAX_HOP_DISTANCE", "--max-hop-distance");
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    let mut random = seeded(matches);
+    let mut visited = HashSet::new();
+    let mut stuck = 0;
+    for _ in 0..walks {
+        let path = walk::random_walk(&start, &indexed, hops, k, &mut random);
+        println!("{}", path.join("->"));
+        stuck += usize::from(path.len() <= hops);
+        visited.extend(path);
+    }
+    // How far walks spread gives an idea of how connected the word list is around the word
+    eprintln!(
+        "{} visited in {} of {}, {} stopped early without neighbours",
+        Locale::En.count(visited.len(), "distinct word", "distinct words"),
+        Locale::En.count(walks, "walk", "walks"),
+        Locale::En.count(hops, "hop", "hops"),
+        stuck
+    );
+}
+
+fn cluster(matches: &clap::ArgMatches) {
+    let k = matches
+        .value_of("MAX_HOP_DISTANCE")
+        .unwrap()
+        .parse::<usize>()
+        .unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    let adjacency = cluster::adjacency(&words, &indexed, k);
+    let labels = match matches.value_of("ALGORITHM").unwrap() {
+        "louvain" => cluster::louvain(&adjacency),
+        _ => cluster::label_propagation(&adjacency, &mut seeded(matches)),
+    };
+    let clusters = cluster::connected_clusters(&adjacency, &labels);
+    let written = match matches.value_of("OUTPUT") {
+        Some(path) => File::create(path).and_then(|file| {
+            cluster::write_assignments(&words, &clusters, io::BufWriter::new(file))
+        }),
+        None => cluster::write_assignments(&words, &clusters, io::stdout().lock()),
+    };
+    written.unwrap_or_else(|error| {
+        Output::Text.error(&format!("cannot write clusters: {}", error), EXIT_USAGE)
+    });
+    let mut sizes = vec![0; clusters.iter().max().map_or(0, |&last| last + 1)];
+    for &cluster in &clusters {
+        sizes[cluster] += 1;
+    }
+    let biggest = sizes.iter().max().copied().unwrap_or(0);
+    let alone = sizes.iter().filter(|&&size| size == 1).count();
+    eprintln!(
+        "{}, the biggest of {}, {} alone",
+        Locale::En.count(sizes.len(), "cluster", "clusters"),
+        Locale::En.count(biggest, "word", "words"),
+        Locale::En.count(alone, "word", "words")
+    );
+}
+
+fn partition(matches: &clap::ArgMatches) {
+    let seeds: Vec<String> = matches
+        .value_of("SEEDS")
+        .unwrap()
+        .split(',')
+        .map(words::normalize)
+        .filter(|seed| !seed.is_empty())
+        .collect();
+    if seeds.is_empty() {
+        usage_error(
+            "--seeds expects words separated by commas",
+            clap::ErrorKind::InvalidValue,
+        );
+    }
+    let options = SearchOptions::new(Dijkstra);
+    let options = match matches.value_of("MAX_HOP_DISTANCE") {
+        Some(distance) => options.with_max_hop_distance(distance.parse().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })),
+        None => options,
+    };
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let listed: HashSet<&str> = words.iter().copied().collect();
+    if let Some(seed) = seeds.iter().find(|seed| !listed.contains(seed.as_str())) {
+        Output::Text.error(
+            &format!("Seed {} is not in the word list", seed),
+            EXIT_DICTIONARY,
+        );
+    }
+    let seeds: Vec<&str> = seeds.iter().map(AsRef::as_ref).collect();
+    // Small hops are looked up in an index instead of scanning every word
+    let indexed = options
+        .max_hop_distance()
+        .map(|_| ShardedWords::new(&words));
+    let (partition, _) = match &indexed {
+        Some(indexed) => distance::find_partition(&seeds, indexed, &options),
+        None => distance::find_partition(&seeds, words.as_slice(), &options),
+    };
+    let partition =
+        partition.unwrap_or_else(|interruption| interrupted(interruption, Output::Text));
+    let mut sizes: HashMap<&str, usize> = HashMap::new();
+    for assignment in &partition.assignments {
+        *sizes.entry(assignment.seed).or_insert(0) += 1;
+        println!(
+            "{}\t{}\t{}",
+            assignment.word, assignment.seed, assignment.cost
+        );
+    }
+    let mut seen = HashSet::new();
+    for seed in seeds.iter().filter(|seed| seen.insert(**seed)) {
+        let size = sizes.get(seed).copied().unwrap_or(0);
+        eprintln!("{}: {}", seed, Locale::En.count(size, "word", "words"));
+    }
+    eprintln!(
+        "{} unreachable",
+        Locale::En.count(partition.unreachable.len(), "word", "words")
+    );
+}
+
+fn difficulty(matches: &clap::ArgMatches) {
+    let start = words::normalize(matches.value_of("START").unwrap());
+    let stop = words::normalize(matches.value_of("END").unwrap());
+    for word in [&start, &stop].iter() {
+        if let Err(error) = word::check_length(word) {
+            usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+        }
+    }
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    match distance::rate_difficulty(&start, &stop, &indexed) {
+        Some(difficulty) => {
+            println!("difficulty: {}/10", difficulty.rating);
+            println!("hops: {}", difficulty.hops);
+            println!("choices per step: {:.1}", difficulty.branching);
+            println!("shortest ladders: {}", difficulty.optimal_paths);
+        }
+        None => {
+            println!("No ladder of 1-letter hops links {} and {}", start, stop);
+            process::exit(EXIT_NO_PATH)
+        }
+    }
+}
+
+fn daily(matches: &clap::ArgMatches) {
+    let date = match matches.value_of("DATE") {
+        Some(date) => date.parse().unwrap_or_else(|()| {
+            usage_error(
+                "--date expects a date written YYYY-MM-DD",
+                clap::ErrorKind::InvalidValue,
+            )
+        }),
+        None => daily::Date::today(),
+    };
+    let target = matches
+        .value_of("DIFFICULTY")
+        .map_or(Ok(daily::DEFAULT_DIFFICULTY), str::parse::<u8>)
+        .ok()
+        .filter(|target| (1..=10).contains(target))
+        .unwrap_or_else(|| {
+            usage_error(
+                "--difficulty expects a number from 1 to 10",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let mut session = open_session(matches, Output::Text);
+    let mut words = load_words(filename, Output::Text, &word_policy(matches));
+    avoid_session(session.as_ref(), matches, &mut words, &[]);
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let indexed = ShardedWords::new(&words);
+    match daily::puzzle(date, &words, &indexed, target) {
+        Some((start, stop, difficulty)) => {
+            println!("puzzle of {}: {} -> {}", date, start, stop);
+            println!("difficulty: {}", difficulty);
+            if session.is_some() {
+                // The words of a shortest ladder, the one a player is likely to find
+                let options = SearchOptions::new(Astar).with_max_hop_distance(1);
+                let (found, _) =
+                    distance::find_shortest_path_with_options(start, stop, &indexed, &options);
+                if let Ok(Some((path, _))) = found {
+                    record_session(session.as_mut(), &path);
+                }
+            }
+        }
+        None => {
+            println!("No two words of {} are linked by a ladder", filename);
+            process::exit(EXIT_NO_PATH)
+        }
+    }
+}
+
+fn hint(matches: &clap::ArgMatches) {
+    let played: Vec<String> = matches
+        .value_of("PATH")
+        .unwrap()
+        .split(',')
+        .map(words::normalize)
+        .collect();
+    let stop = words::normalize(matches.value_of("END").unwrap());
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    // Hints only make sense for ladders played by the rules
+    if let Some(word) = played.iter().find(|word| !words.contains(&word.as_str())) {
+        usage_error(
+            &format!("{} is not in {}", word, filename),
+            clap::ErrorKind::InvalidValue,
+        );
+    }
+    if let Some(pair) = played
+        .windows(2)
+        .find(|pair| word::levenshtein(&pair[0], &pair[1]) != 1)
+    {
+        usage_error(
+            &format!("{} and {} are not 1 letter apart", pair[0], pair[1]),
+            clap::ErrorKind::InvalidValue,
+        );
+    }
+    let current = played.last().unwrap();
+    if *current == stop {
+        println!("{} is already reached", stop);
+        return;
+    }
+    let indexed = ShardedWords::new(&words);
+    match distance::find_hint(current, &stop, &indexed) {
+        Some(hint) => {
+            let next = match matches.is_present("MASKED") {
+                true => hint.masked.as_str(),
+                false => hint.next,
+            };
+            println!("{}\t{}", next, hint.hops_left);
+        }
+        None => {
+            println!("No ladder of 1-letter hops links {} and {}", current, stop);
+            process::exit(EXIT_NO_PATH)
+        }
+    }
+}
+
+// Entries of the word lists kept, from --alphabet, --punctuation and --no-digits given
+// before or after any subcommand
+fn word_policy(matches: &clap::ArgMatches) -> words::Policy {
+    let alphabet = matches.value_of("ALPHABET").map(|alphabet| {
+        alphabet.parse().unwrap_or_else(|()| {
+            usage_error(
+                "--alphabet expects letters and ranges of letters such as a-z",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    words::Policy {
+        alphabet,
+        punctuation: matches.value_of("PUNCTUATION").unwrap().parse().unwrap(),
+        digits: !matches.is_present("NO_DIGITS"),
+        query: matches.value_of("QUERY").map(str::to_string),
+        tagged: matches.is_present("LANG") || matches.is_present("CROSS_LANGUAGE"),
+        languages: matches.value_of("LANG").map(|list| {
+            list.split(',')
+                .map(|language| language.trim().to_lowercase())
+                .filter(|language| !language.is_empty())
+                .collect()
+        }),
+    }
+}
+
+fn seed(matches: &clap::ArgMatches) -> Option<u64> {
+    matches.value_of("SEED").map(|seed| {
+        seed.parse::<u64>().unwrap_or_else(|_| {
+            usage_error(
+                "--seed expects a non-negative integer",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    })
+}
+
+// Generator of the randomized parts of a command, seeded with --seed to repeat a run.
+// Without it the seed comes from the clock and is printed to stderr.
+fn seeded(matches: &clap::ArgMatches) -> random::Random {
+    match seed(matches) {
+        Some(seed) => random::Random::new(seed),
+        None => {
+            let seed = random::seed_from_time();
+            eprintln!("seed: {} (pass --seed {} to repeat this run)", seed, seed);
+            random::Random::new(seed)
+        }
+    }
+}
+
+fn matrix(matches: &clap::ArgMatches) {
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Text),
+    };
+    let sample = matches.value_of("SAMPLE").map(|sample| {
+        sample.parse::<usize>().unwrap_or_else(|_| {
+            usage_error(
+                "--sample expects a number of words",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let dictionary = load_words(filename, Output::Text, &word_policy(matches));
+    let dictionary: Vec<&str> = dictionary.iter().map(AsRef::as_ref).collect();
+    let words: Vec<&str> = match sample {
+        Some(count) => seeded(matches)
+            .sample(&dictionary, count)
+            .into_iter()
+            .copied()
+            .collect(),
+        None => dictionary.clone(),
+    };
+    let options = SearchOptions::new(Dijkstra);
+    let rows = matrix::compute(&words, dictionary.as_slice(), &options, |done| {
+        eprint!("\r{}/{} rows computed", done, words.len());
+    });
+    eprintln!();
+    let written = match matches.value_of("OUTPUT") {
+        Some(output) if columnar::is_parquet(output) => {
+            columnar::write_matrix(output, &words, &rows)
+        }
+        Some(output) => {
+            File::create(output).and_then(|file| matrix::write_csv(&words, &rows, file))
+        }
+        None => matrix::write_csv(&words, &rows, io::stdout().lock()),
+    };
+    if let Err(error) = written {
+        Output::Text.error(&format!("cannot write the matrix: {}", error), EXIT_USAGE);
+    }
+}
+
+fn reach(matches: &clap::ArgMatches) {
+    let mut seen = HashSet::new();
+    let listed: Vec<String> = matches
+        .value_of("WORDS")
+        .unwrap()
+        .split(',')
+        .map(words::normalize)
+        .filter(|word| !word.is_empty() && seen.insert(word.clone()))
+        .collect();
+    if listed.is_empty() {
+        usage_error(
+            "--words expects words separated by commas",
+            clap::ErrorKind::InvalidValue,
+        );
+    }
+    let options = SearchOptions::new(Dijkstra);
+    let options = match matches.value_of("MAX_HOP_DISTANCE") {
+        Some(distance) => options.with_max_hop_distance(distance.parse().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })),
+        None => options,
+    };
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let dictionary = load_words(filename, Output::Text, &word_policy(matches));
+    let dictionary: Vec<&str> = dictionary.iter().map(AsRef::as_ref).collect();
+    let known: HashSet<&str> = dictionary.iter().copied().collect();
+    if let Some(word) = listed.iter().find(|word| !known.contains(word.as_str())) {
+        Output::Text.error(
+            &format!("Word {} is not in the word list", word),
+            EXIT_DICTIONARY,
+        );
+    }
+    let words: Vec<&str> = listed.iter().map(AsRef::as_ref).collect();
+    // One search per word gives its whole row, small hops looked up in an index
+    let rows = match options.max_hop_distance() {
+        Some(_) => matrix::compute_hops(&words, &ShardedWords::new(&dictionary), &options),
+        None => matrix::compute_hops(&words, dictionary.as_slice(), &options),
+    };
+    matrix::write_table(&words, &rows, io::stdout().lock()).unwrap();
+    let pairs = words.len() * (words.len() - 1);
+    let connected = rows
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().enumerate().filter(move |&(j, _)| j != i))
+        .filter(|(_, hops)| hops.is_some())
+        .count();
+    eprintln!(
+        "{} of {} ordered pairs connected, cells count the hops from the word of the row to that of the column",
+        connected, pairs
+    );
+}
+
+fn bench(matches: &clap::ArgMatches) {
+    let name = matches.value_of("SUITE").unwrap();
+    let number = |name: &str, flag: &str| {
+        matches
+            .value_of(name)
+            .unwrap()
+            .parse::<f64>()
+            .unwrap_or_else(|_| {
+                usage_error(
+                    &format!("{} expects a number", flag),
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+    };
+    let tolerance = number("TOLERANCE", "--tolerance");
+    let repeats = match matches.value_of("REPEATS").unwrap().parse::<usize>() {
+        Ok(repeats) if repeats > 0 => repeats,
+        _ => usage_error(
+            "--repeats expects a number of runs, at least 1",
+            clap::ErrorKind::InvalidValue,
+        ),
+    };
+    let words = bench::dictionary();
+    let queries = bench::suite(name, &words).unwrap();
+    let measures = bench::run(&queries, &words, repeats, |measure| {
+        let hops = measure
+            .hops
+            .map_or_else(|| "no path".to_string(), |hops| format!("{} hops", hops));
+        println!(
+            "{}\t{}µs\t{} expanded\t{}",
+            measure.name, measure.micros, measure.expanded, hops
+        );
+    });
+    let write = |path: &str| {
+        File::create(path)
+            .and_then(|file| bench::write_json(name, &measures, file))
+            .unwrap_or_else(|error| {
+                Output::Text.error(&format!("cannot write {}: {}", path, error), EXIT_USAGE)
+            })
+    };
+    if let Some(output) = matches.value_of("OUTPUT") {
+        write(output);
+    }
+    let baseline = match matches.value_of("BASELINE") {
+        Some(baseline) => baseline,
+        None => return,
+    };
+    // A baseline is recorded by the first run given it
+    if !Path::new(baseline).exists() {
+        write(baseline);
+        eprintln!("baseline recorded to {}", baseline);
+        return;
+    }
+    let before = File::open(baseline)
+        .and_then(|file| bench::read_json(BufReader::new(file)))
+        .unwrap_or_else(|error| {
+            Output::Text.error(
+                &format!("cannot read baseline {}: {}", baseline, error),
+                EXIT_USAGE,
+            )
+        });
+    let regressions = bench::regressions(&before, &measures, tolerance);
+    for regression in &regressions {
+        eprintln!("regression: {}", regression);
+    }
+    if !regressions.is_empty() {
+        eprintln!(
+            "{} regressions out of {} queries against {}",
+            regressions.len(),
+            measures.len(),
+            baseline
+        );
+        process::exit(EXIT_REGRESSION);
+    }
+    eprintln!(
+        "no regression out of {} queries against {}",
+        measures.len(),
+        baseline
+    );
+}
+
+fn batch(matches: &clap::ArgMatches) {
+    let pairs = matches.value_of("PAIRS").unwrap();
+    let pairs = match columnar::is_parquet(pairs) {
+        true => columnar::read_pairs(pairs),
+        false => File::open(pairs).and_then(|file| batch::read_pairs(BufReader::new(file))),
+    }
+    .unwrap_or_else(|error| {
+        Output::Text.error(
+            &format!("cannot read pairs from {}: {}", pairs, error),
+            EXIT_USAGE,
+        )
+    });
+    let pairs: Vec<batch::Pair> = pairs
+        .iter()
+        .map(|(start, end)| (words::normalize(start), words::normalize(end)))
+        .collect();
+    let options = SearchOptions::new(Astar);
+    let options = match matches.value_of("MAX_HOP_DISTANCE") {
+        Some(distance) => options.with_max_hop_distance(distance.parse().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })),
+        None => options,
+    };
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Quiet),
+    };
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    // Small hops are looked up in an index instead of scanning every word
+    let indexed = options
+        .max_hop_distance()
+        .map(|_| ShardedWords::new(&words));
+    let answers = match &indexed {
+        Some(indexed) => batch::solve(&pairs, indexed, &options),
+        None => batch::solve(&pairs, words.as_slice(), &options),
+    }
+    .unwrap_or_else(|interruption| interrupted(interruption, Output::Text));
+    let written = match matches.value_of("OUTPUT") {
+        Some(output) if columnar::is_parquet(output) => columnar::write_answers(output, &answers),
+        Some(output) => File::create(output).and_then(|file| batch::write_csv(&answers, file)),
+        None => batch::write_csv(&answers, io::stdout().lock()),
+    };
+    if let Err(error) = written {
+        Output::Text.error(&format!("cannot write the ladders: {}", error), EXIT_USAGE);
+    }
+}
+
+fn convert(matches: &clap::ArgMatches) {
+    // Both are among the possible values, which hunspell is not for the output
+    let format = |name| convert::Format::from_str(matches.value_of(name).unwrap()).unwrap();
+    let (from, to) = (format("FROM"), format("TO"));
+    let length = |name, flag| {
+        matches.value_of(name).map(|length| {
+            length.parse::<usize>().unwrap_or_else(|_| {
+                usage_error(
+                    &format!("{} expects a number of letters", flag),
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+        })
+    };
+    let filter = convert::Filter {
+        min_length: length("MIN_LENGTH", "--min-length"),
+        max_length: length("MAX_LENGTH", "--max-length"),
+        alphabetic: matches.is_present("ALPHABETIC"),
+        policy: word_policy(matches),
+    };
+    let input = matches.value_of("INPUT").unwrap();
+    let output = matches.value_of("OUTPUT").unwrap();
+    let converted = convert::read(
+        from,
+        Path::new(input),
+        matches.value_of("AFF").map(Path::new),
+    )
+    .unwrap_or_else(|error| {
+        Output::Text.error(
+            &format!("cannot read {} as {}: {}", input, from, error),
+            EXIT_DICTIONARY,
+        )
+    });
+    let converted = convert::normalize(converted, &filter);
+    File::create(output)
+        .and_then(|file| convert::write(to, &converted, BufWriter::new(file)))
+        .unwrap_or_else(|error| {
+            // Half written files are no dictionaries at all
+            let _ = fs::remove_file(output);
+            Output::Text.error(
+                &format!("cannot write {} as {}: {}", output, to, error),
+                EXIT_DICTIONARY,
+            )
+        });
+    println!(
+        "{} words written to {} as {}, {} entries dropped",
+        converted.words.len(),
+        output,
+        to,
+        converted.dropped
+    );
+}
+
+fn build_graph(matches: &clap::ArgMatches) {
+    let filename = match matches.value_of("INPUT") {
+        Some(filename) => filename,
+        None => system_dictionary(Output::Text),
+    };
+    let landmarks = matches.value_of("LANDMARKS").map(|landmarks| {
+        landmarks.parse::<usize>().unwrap_or_else(|_| {
+            usage_error(
+                "--landmarks expects a number of words",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let frequencies = matches
+        .value_of("FREQUENCIES")
+        .map(|filename| load_frequencies(filename, Output::Text));
+    let words = load_words(filename, Output::Text, &word_policy(matches));
+    let start_time = Instant::now();
+    let mut graph = PrebuiltGraph::build(words);
+    if let Some(landmarks) = landmarks {
+        graph = graph.with_landmarks(landmarks);
+    }
+    if matches.is_present("BUCKETS") {
+        graph = graph.with_buckets();
+    }
+    if let Some(frequencies) = &frequencies {
+        graph = graph.with_frequencies(frequencies);
+    }
+    if matches.value_of("INDEX") == Some("symspell") {
+        graph = graph.with_deletions();
+    }
+    let output = matches.value_of("OUTPUT").unwrap();
+    let written = File::create(output).and_then(|file| graph.write(BufWriter::new(file)));
+    if let Err(error) = written {
+        Output::Text.error(&format!("cannot write {}: {}", output, error), EXIT_USAGE);
+    }
+    println!(
+        "{} words and {} hops written to {} in {:?}, with {} landmarks",
+        graph.word_count(),
+        graph.edge_count(),
+        output,
+        start_time.elapsed(),
+        graph.landmark_count()
+    );
+}
+
+fn load_graph(filename: &str) -> PrebuiltGraph {
+    File::open(filename)
+        .and_then(PrebuiltGraph::read)
+        .unwrap_or_else(|error| {
+            Output::Text.error(
+                &format!("cannot load graph {}: {}", filename, error),
+                EXIT_DICTIONARY,
+            )
+        })
+}
+
+fn info(matches: &clap::ArgMatches) {
+    let facts = match matches.value_of("GRAPH") {
+        Some(filename) => info::graph(&load_graph(filename)),
+        None => {
+            let filename = match matches.value_of("INPUT") {
+                Some(filename) => filename,
+                None => system_dictionary(Output::Quiet),
+            };
+            // Read as is, duplicates are counted rather than removed with a warning
+            let mut list =
+                words::from_location(filename, &word_policy(matches)).unwrap_or_else(|error| {
+                    Output::Text.error(
+                        &format!("cannot load word list {}: {}", filename, error),
+                        EXIT_DICTIONARY,
+                    )
+                });
+            list.dedup();
+            info::dictionary(&list.words, list.summary.duplicates, list.weights.len())
+        }
+    };
+    info::write(&facts, io::stdout().lock()).unwrap();
+}
+
+fn update_graph(matches: &clap::ArgMatches) {
+    let filename = matches.value_of("GRAPH").unwrap();
+    let added = matches.value_of("ADD").map_or_else(Vec::new, |list| {
+        load_words(list, Output::Text, &word_policy(matches))
+    });
+    let removed = matches.value_of("REMOVE").map_or_else(Vec::new, |list| {
+        load_words(list, Output::Text, &word_policy(matches))
+    });
+    let mut graph = load_graph(filename);
+    let start_time = Instant::now();
+    let (added, removed) = graph.update(added, &removed);
+    // Written next to the graph first, so an interrupted write leaves the graph intact
+    let updated = format!("{}.tmp", filename);
+    let written = File::create(&updated)
+        .and_then(|file| graph.write(BufWriter::new(file)))
+        .and_then(|_| fs::rename(&updated, filename));
+    if let Err(error) = written {
+        Output::Text.error(&format!("cannot write {}: {}", filename, error), EXIT_USAGE);
+    }
+    println!(
+        "{} words added and {} removed in {:?}, {} words and {} hops now",
+        added,
+        removed,
+        start_time.elapsed(),
+        graph.word_count(),
+        graph.edge_count()
+    );
+}
+
+fn migrate_graph(matches: &clap::ArgMatches) {
+    let filename = matches.value_of("GRAPH").unwrap();
+    let fail = |error: io::Error| -> ! {
+        Output::Text.error(
+            &format!("cannot migrate graph {}: {}", filename, error),
+            EXIT_DICTIONARY,
+        )
+    };
+    let bytes = fs::read(filename).unwrap_or_else(|error| fail(error));
+    let version = PrebuiltGraph::version(&bytes).unwrap_or_else(|error| fail(error));
+    let output = matches.value_of("OUTPUT").unwrap_or(filename);
+    let migrated = PrebuiltGraph::migrate(&bytes).unwrap_or_else(|error| fail(error));
+    if migrated.is_none() {
+        println!("{} is already at version {}", filename, prebuilt::VERSION);
+        if output == filename {
+            return;
+        }
+    }
+    // Written next to the output first, so an interrupted write leaves any file there intact
+    let written = format!("{}.tmp", output);
+    fs::write(&written, migrated.as_ref().unwrap_or(&bytes))
+        .and_then(|()| fs::rename(&written, output))
+        .unwrap_or_else(|error| fail(error));
+    if migrated.is_some() {
+        println!(
+            "{} migrated from version {} to version {} into {}",
+            filename,
+            version,
+            prebuilt::VERSION,
+            output
+        );
+    }
+}
+
+fn query(matches: &clap::ArgMatches) {
+    let filename = matches.value_of("GRAPH").unwrap();
+    let start = words::normalize(matches.value_of("START").unwrap());
+    let stop = words::normalize(matches.value_of("END").unwrap());
+    let graph = load_graph(filename);
+    println!(
+        "{} words loaded from {}, with {} landmarks",
+        graph.word_count(),
+        filename,
+        graph.landmark_count()
+    );
+    let start_time = Instant::now();
+    let path = graph.shortest_path(&start, &stop);
+    let duration = start_time.elapsed();
+    match path {
+        Some((words, cost)) => {
+            println!(
+                "Shortest path found in {:?}: {} (achieved with {})",
+                duration,
+                words.join("->"),
+                distance::describe_cost(&cost, Unit::Letter)
+            );
+            let rarest = words
+                .iter()
+                .filter_map(|word| Some((graph.frequency(word)?, word)))
+                .min();
+            if let Some((count, word)) = rarest {
+                println!("Rarest word on the way: {} ({} uses)", word, count);
+            }
+        }
+        None => {
+            println!("No path found");
+            process::exit(EXIT_NO_PATH)
+        }
+    }
+}
 
-fn lines_from_file(filename: impl AsRef<Path>) -> io::Result<Vec<String>> {
-    BufReader::new(File::open(filename)?).lines().collect()
+// Built-in algorithms and the ones registered before the arguments are parsed
+fn algorithm_names() -> Vec<&'static str> {
+    PathFindingAlgorithm::all()
+        .into_iter()
+        .map(PathFindingAlgorithm::name)
+        .collect()
 }
 
-fn main() {
-    let default_algorithm = format!("{}", Astar);
-    let matches = App::new("typos")
+// Other names of the algorithms, such as a*
+fn algorithm_aliases() -> Vec<&'static str> {
+    PathFindingAlgorithm::ALIASES
+        .iter()
+        .map(|&(alias, _)| alias)
+        .collect()
+}
+
+fn list_algorithms(format: &str) {
+    let algorithms = algorithm_names();
+    let cost_models: Vec<&str> = CostModelName::ALL.iter().map(|c| c.name()).collect();
+    let units: Vec<&str> = Unit::ALL.iter().map(|u| u.name()).collect();
+    match format {
+        // Names are plain lowercase identifiers, nothing needs escaping
+        "json" => {
+            let array = |names: &[&str]| {
+                let quoted: Vec<String> = names.iter().map(|n| format!("\"{}\"", n)).collect();
+                format!("[{}]", quoted.join(","))
+            };
+            println!(
+                "{{\"algorithms\":{},\"cost_models\":{},\"units\":{}}}",
+                array(&algorithms),
+                array(&cost_models),
+                array(&units)
+            );
+        }
+        _ => {
+            println!("algorithms: {}", algorithms.join(" "));
+            println!("cost models: {}", cost_models.join(" "));
+            println!("units: {}", units.join(" "));
+        }
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    App::new("typos")
         .version("1.0")
         .author("Adrien adrien@apapa.fr")
         .about("Find a shortest edit-path between two input words")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .setting(AppSettings::ArgsNegateSubcommands)
+        .arg(
+            Arg::with_name("SEED")
+                .long("seed")
+                .help("seed of every random choice, to repeat a run exactly")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("ALPHABET")
+                .long("alphabet")
+                .help("letters words of the word list may use, such as a-z, the others are left out")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("PUNCTUATION")
+                .long("punctuation")
+                .help("what becomes of hyphens and apostrophes in the word list: kept, stripped, or words holding them left out")
+                .possible_values(&["keep", "strip", "drop"])
+                .default_value("keep")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("NO_DIGITS")
+                .long("no-digits")
+                .help("leave out the words of the word list holding digits")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("QUERY")
+                .long("query")
+                .help("SQL query selecting the words of a word list that is a SQLite database, their weights in a second column")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("LANG")
+                .long("lang")
+                .help("languages, comma separated, words of a word list of word<TAB>lang lines must be tagged with one of to be kept; hops stay within one of them")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("CROSS_LANGUAGE")
+                .long("cross-language")
+                .help("read the languages of a word list of word<TAB>lang lines, but let hops go from a word of one language to a word of another")
+                .global(true),
+        )
         .arg(
             Arg::with_name("INPUT")
+                .help("Sets the input file to use, a system word list when omitted")
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("INPUT_FILE")
                 .short("i")
                 .long("input")
-                .help("Sets the input file to use")
-                .required(true)
-                .index(1),
+                .help("Sets the input file to use, START and END then come first")
+                .takes_value(true),
         )
         .arg(
             Arg::with_name("ALGORITHM")
                 .short("a")
                 .long("algorithm")
                 .help("algorithm to use to compute shortest path")
-                .possible_value(format!("{}", Astar).as_str())
-                .possible_value(format!("{}", Idastar).as_str())
-                .possible_value(format!("{}", Dijkstra).as_str())
-                .possible_value(format!("{}", Fringe).as_str())
-                .default_value(default_algorithm.as_str())
+                .possible_values(&algorithm_names())
+                .possible_values(&algorithm_aliases())
+                .possible_value(AUTO_ALGORITHM)
+                .case_insensitive(true)
+                .default_value(Astar.name())
                 .index(4),
         )
         .arg(
@@ -51,7 +1912,6 @@ fn main() {
                 .long("start")
                 .help("starting word")
                 .case_insensitive(true)
-                .required(true)
                 .index(2),
         )
         .arg(
@@ -60,51 +1920,1918 @@ fn main() {
                 .long("end")
                 .help("ending word")
                 .case_insensitive(true)
-                .required(true)
                 .index(3),
         )
-        .get_matches();
+        .arg(
+            Arg::with_name("COST")
+                .long("cost")
+                .help("cost model weighing each mutation")
+                .possible_value(CostModelName::Levenshtein.name())
+                .possible_value(CostModelName::Ocr.name())
+                .possible_value(CostModelName::Phonetic.name())
+                .case_insensitive(true)
+                .default_value(CostModelName::Levenshtein.name()),
+        )
+        .arg(
+            Arg::with_name("CONFUSION")
+                .long("confusion")
+                .help("CSV of from,to[,weight] confusions replacing the built-in OCR table")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("UNIT")
+                .long("unit")
+                .help("what a single edit changes: a letter, or a whole word of a phrase")
+                .possible_value(Unit::Letter.name())
+                .possible_value(Unit::Token.name())
+                .default_value(Unit::Letter.name()),
+        )
+        .arg(
+            Arg::with_name("HEURISTIC")
+                .long("heuristic")
+                .help("lower bound guiding the search: edit-distance, length-diff, alt or max(h1,h2,..)")
+                .default_value("edit-distance"),
+        )
+        .arg(
+            Arg::with_name("TT_CAPACITY")
+                .long("tt-capacity")
+                .help("number of words idastar-tt remembers the best cost of, bounding its memory")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("MAX_HOPS")
+                .long("max-hops")
+                .help("longest path allowed, in hops between words")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("MAX_HOP_DISTANCE")
+                .long("max-hop-distance")
+                .help("biggest mutation allowed in a single hop, 1 for classic word ladders")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("MONOTONE")
+                .long("monotone")
+                .help("only take hops getting strictly closer to the end word"),
+        )
+        .arg(
+            Arg::with_name("APPROX")
+                .long("approx")
+                .help("only go through a sample of the successors of every word, the same for a given --seed: much faster on huge word lists, but the path found may not be the shortest"),
+        )
+        .arg(
+            Arg::with_name("SAMPLE_RATE")
+                .long("sample-rate")
+                .help("share of the successors --approx goes through, 0.1 by default")
+                .takes_value(true)
+                .requires("APPROX"),
+        )
+        .arg(
+            Arg::with_name("FOLD_STEMS")
+                .long("fold-stems")
+                .help("leave inflected forms such as cats or baked out of the path when their stem is a word too, keeping the start and end words")
+                .possible_values(&["en"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("FREQUENCIES")
+                .long("frequencies")
+                .help("CSV of word,count lines: among equally good paths, prefer the one entering the most common words")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("INDEX")
+                .long("index")
+                .help("how --max-hop-distance finds the neighbours of a word: scan every word, shard by first letter and length, look deletions up or walk a trie")
+                .possible_values(&["scan", "shard", "symspell", "trie"])
+                .default_value("shard"),
+        )
+        .arg(
+            Arg::with_name("LOCALE")
+                .long("locale")
+                .help("language of the results, not of the words as with --lang, taken from LC_ALL, LC_MESSAGES or LANG by default")
+                .possible_values(&["en", "fr"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("EXPLAIN")
+                .long("explain")
+                .help("print the letters every hop of the path changes"),
+        )
+        .arg(
+            Arg::with_name("REVERSE")
+                .long("reverse")
+                .help("search from the end word back to the start word"),
+        )
+        .arg(
+            Arg::with_name("AUTO_DIRECTION")
+                .long("auto-direction")
+                .help("search from whichever of the start and end words has the fewest neighbours")
+                .conflicts_with("REVERSE"),
+        )
+        .arg(
+            Arg::with_name("OBJECTIVE")
+                .long("objective")
+                .help("what the path minimizes first: the biggest hop, the number of hops or the letters changed")
+                .possible_values(&["smallest-max-mutation", "fewest-hops", "fewest-letters"])
+                .default_value("smallest-max-mutation"),
+        )
+        .arg(
+            Arg::with_name("SATURATION")
+                .long("saturation")
+                .help("what to do once a path costs more than 65535 hops of a size: fail, keep the highest cost with a warning, or count with 64 bits")
+                .possible_values(&["error", "saturate", "widen"])
+                .default_value("saturate"),
+        )
+        .arg(
+            Arg::with_name("MAX_MEMORY")
+                .long("max-memory")
+                .help("bytes the search may keep its nodes in, with an optional K, M or G suffix; astar, fringe and dijkstra go on with bounded-astar beyond")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("CHECKPOINT")
+                .long("checkpoint")
+                .help("file the search state is saved to as it goes, searching with astar, removed once the search ends")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("CHECKPOINT_INTERVAL")
+                .long("checkpoint-interval")
+                .help("time between two saves of --checkpoint, such as 60s, 5m or 2h, 60s when omitted")
+                .takes_value(true)
+                .requires("CHECKPOINT"),
+        )
+        .arg(
+            Arg::with_name("RESUME")
+                .long("resume")
+                .help("go on with the search saved to --checkpoint, after a crash or a reboot")
+                .requires("CHECKPOINT"),
+        )
+        .arg(
+            Arg::with_name("EXPLAIN_PRUNING")
+                .long("explain-pruning")
+                .help("words to tell why the search did not go through, separated by commas")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("CHECK_HEURISTIC")
+                .long("check-heuristic")
+                .help("check on a sample of expansions that the heuristic never overestimates the cost left, for debugging cost models"),
+        )
+        .arg(
+            Arg::with_name("END_PATTERN")
+                .long("end-pattern")
+                .help("end on the first word of the list matching this regular expression, such as '^war.', instead of an ending word")
+                .takes_value(true)
+                .conflicts_with_all(&[
+                    "PARETO",
+                    "CHECKPOINT",
+                    "REVERSE",
+                    "AUTO_DIRECTION",
+                    "MONOTONE",
+                    "APPROX",
+                    "EXPLAIN_PRUNING",
+                    "CHECK_HEURISTIC",
+                ]),
+        )
+        .arg(
+            Arg::with_name("ALLOW_MISSING_ENDPOINTS")
+                .long("allow-missing-endpoints")
+                .help("search even when the start or end word is not in the word list"),
+        )
+        .arg(
+            Arg::with_name("STATS")
+                .long("stats")
+                .help("print how many words of every length the word list holds"),
+        )
+        .arg(
+            Arg::with_name("REPORT")
+                .long("report")
+                .help("once the search is over, print the peak memory, the time spent loading, indexing and searching, cache hit rates and allocations"),
+        )
+        .arg(
+            Arg::with_name("ALTERNATIVES")
+                .long("alternatives")
+                .help("number of paths to find, each avoiding what the previous ones went through")
+                .default_value("1"),
+        )
+        .arg(
+            Arg::with_name("PARETO")
+                .long("pareto")
+                .help("find every path no other one beats in hops, biggest hop and letters changed at once"),
+        )
+        .arg(
+            Arg::with_name("DISJOINT")
+                .long("disjoint")
+                .help("what alternative paths may not share: intermediate words or hops")
+                .possible_values(&["node", "edge"])
+                .default_value("node"),
+        )
+        .arg(
+            Arg::with_name("COST_MATRIX")
+                .long("cost-matrix")
+                .help("CSV of from,to,weight edit costs used instead of --cost")
+                .conflicts_with("CONFUSION")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("CACHE_DIR")
+                .long("cache-dir")
+                .help("directory where results are memoized between runs")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("QUIET")
+                .short("q")
+                .long("quiet")
+                .help("only print the path, if any; the exit status tells the outcome"),
+        )
+        .arg(
+            Arg::with_name("FORMAT")
+                .long("format")
+                .help("ndjson prints one JSON event per line: progress, then result or error; csv prints a path,cost,letters line per path")
+                .possible_values(&["text", "ndjson", "csv"])
+                .default_value("text"),
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Answer shortest-path queries over HTTP")
+                .arg(
+                    Arg::with_name("DICT")
+                        .short("d")
+                        .long("dict")
+                        .help("named dictionary to load as name=file, the first one is the default")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("ADDRESS")
+                        .long("address")
+                        .help("address to listen on")
+                        .default_value("127.0.0.1:8080"),
+                )
+                .arg(
+                    Arg::with_name("CACHE_SIZE")
+                        .long("cache-size")
+                        .help("number of results kept in memory, 0 disables the cache")
+                        .default_value("1024"),
+                )
+                .arg(
+                    Arg::with_name("TIMEOUT")
+                        .long("timeout")
+                        .help("milliseconds after which a search is aborted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("graph")
+                .about("Find a shortest path over a list of weighted edges")
+                .arg(
+                    Arg::with_name("EDGES")
+                        .long("edges")
+                        .help("CSV of from,to,weight[,weight…] directed edges")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("START")
+                        .help("starting node")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("END")
+                        .help("ending node")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    Arg::with_name("ALGORITHM")
+                        .help("algorithm to use to compute shortest path")
+                        .possible_values(&algorithm_names())
+                        .possible_values(&algorithm_aliases())
+                        .case_insensitive(true)
+                        .default_value(Astar.name())
+                        .index(3),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Report problems in a word list")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to check")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("FIX")
+                        .long("fix")
+                        .help("file where a cleaned copy of the word list is written")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Check a ladder submitted by a player and compare its cost with the optimal one")
+                .arg(
+                    Arg::with_name("PATH")
+                        .help("words of the ladder separated by commas, such as cold,cord,card")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change, any number when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("repl")
+                .about("Answer queries typed one per line, keeping the dictionary loaded")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .args(&session_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("farthest")
+                .about("Find the words the most expensive to reach from a word")
+                .arg(Arg::with_name("WORD").required(true))
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reachable")
+                .about("List the words a path within a cost budget leads to from a word, with the cost of the shortest one")
+                .arg(Arg::with_name("WORD").required(true))
+                .arg(
+                    Arg::with_name("BUDGET")
+                        .long("budget")
+                        .help("highest cost, written as searches print them such as '2 1-letter mutations'")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change, any number when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("random-walk")
+                .about("Print a random walk from a word, every hop going to a neighbour picked at random")
+                .arg(Arg::with_name("WORD").required(true))
+                .arg(
+                    Arg::with_name("HOPS")
+                        .long("hops")
+                        .help("hops of every walk, fewer when it reaches a word without neighbours")
+                        .takes_value(true)
+                        .default_value("10"),
+                )
+                .arg(
+                    Arg::with_name("WALKS")
+                        .long("walks")
+                        .help("walks to take from the word, one per line")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change")
+                        .takes_value(true)
+                        .default_value("1"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cluster")
+                .about("Group words into clusters of ladders, a ladder within a cluster linking any two of its words")
+                .arg(
+                    Arg::with_name("ALGORITHM")
+                        .short("a")
+                        .long("algorithm")
+                        .help("how words are grouped, label-propagation following the --seed")
+                        .possible_values(&cluster::ALGORITHMS)
+                        .default_value(cluster::ALGORITHMS[0]),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("file the word<TAB>cluster lines are written to, standard output when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("partition")
+                .about("Assign every word to the seed the cheapest ladder leads from, with its cost")
+                .arg(
+                    Arg::with_name("SEEDS")
+                        .long("seeds")
+                        .help("words to split the word list around, separated by commas")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change, any number when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("difficulty")
+                .about("Rate from 1 to 10 how hard the ladder of 1-letter hops between two words is")
+                .arg(Arg::with_name("START").required(true))
+                .arg(Arg::with_name("END").required(true))
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("daily")
+                .about("Print the puzzle of the day, the same for everyone using the same word list")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("DATE")
+                        .long("date")
+                        .help("day of the puzzle, written YYYY-MM-DD, today in UTC when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("DIFFICULTY")
+                        .long("difficulty")
+                        .help("difficulty from 1 to 10 the puzzle aims for, 5 when omitted")
+                        .takes_value(true),
+                )
+                .args(&session_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("hint")
+                .about("Print the best word to play next in a ladder in progress, and the hops left after it")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("PATH")
+                        .long("path")
+                        .help("words played so far, separated by commas, starting with the start word")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("END")
+                        .long("end")
+                        .help("word to reach")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("MASKED")
+                        .long("masked")
+                        .help("blank the letters to change with _ rather than giving the word away"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("align")
+                .about("List the edits turning a word into another, letter by letter")
+                .arg(Arg::with_name("W1").required(true))
+                .arg(Arg::with_name("W2").required(true))
+                .arg(
+                    Arg::with_name("COST_MATRIX")
+                        .long("cost-matrix")
+                        .help("CSV of from,to,weight edit costs the alignment minimizes")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("suggest")
+                .about("List the words the closest to a word, with their edit distance to it")
+                .arg(Arg::with_name("WORD").required(true))
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("COUNT")
+                        .short("k")
+                        .help("number of words to list")
+                        .takes_value(true)
+                        .default_value("5"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check")
+                .about("Report the words of files missing from the dictionary, with fixes")
+                .arg(
+                    Arg::with_name("FILES")
+                        .help("files, directories or glob patterns to check, - for standard input")
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("FORMAT")
+                        .long("format")
+                        .help("json prints a single document with a stable schema, for tools")
+                        .possible_values(&["text", "json"])
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::with_name("TOKENS")
+                        .long("tokens")
+                        .help(
+                            "words to check, as [EXTENSION=]MODE: prose, identifiers, comments or \
+                             markdown; by default comments of source code, markdown for .md \
+                             files and prose otherwise",
+                        )
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pipe")
+                .about("Spell check lines of standard input as ispell -a does, for editors")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("ISPELL")
+                        .short("a")
+                        .help("ignored, accepted as editors pass it to ispell"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("matrix")
+                .about("Write the cost of the shortest path between every pair of words as CSV")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("CSV file to write, or Parquet if it ends in .parquet, standard output when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("SAMPLE")
+                        .long("sample")
+                        .help("only pair that many words picked at random, ladders still use them all")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("reach")
+                .about("Tell for every ordered pair of a few words whether a ladder links them and in how many hops")
+                .arg(
+                    Arg::with_name("WORDS")
+                        .long("words")
+                        .help("words to link, separated by commas")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change, any number when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Time a fixed suite of searches over a bundled word list, and compare them with a baseline")
+                .arg(
+                    Arg::with_name("SUITE")
+                        .long("suite")
+                        .help("searches to run")
+                        .possible_values(&bench::SUITES)
+                        .default_value(bench::SUITES[0]),
+                )
+                .arg(
+                    Arg::with_name("BASELINE")
+                        .long("baseline")
+                        .help("JSON file of earlier measures to compare with, recorded by this run when missing")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("JSON file the measures are written to")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("TOLERANCE")
+                        .long("tolerance")
+                        .help("share of the time of the baseline a search may take on top of it")
+                        .takes_value(true)
+                        .default_value("0.25"),
+                )
+                .arg(
+                    Arg::with_name("REPEATS")
+                        .long("repeats")
+                        .help("runs of every search, the fastest one counting")
+                        .takes_value(true)
+                        .default_value("3"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("batch")
+                .about("Find the shortest path between the words of every pair of a list, written as CSV")
+                .arg(
+                    Arg::with_name("PAIRS")
+                        .long("pairs")
+                        .help("start,end lines, or a Parquet file with start and end columns if it ends in .parquet")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("CSV file to write, or Parquet if it ends in .parquet, standard output when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_HOP_DISTANCE")
+                        .long("max-hop-distance")
+                        .help("most letters a hop may change, any number when omitted")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("Convert a dictionary from a format to another, normalizing and filtering its words")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("dictionary to convert")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("file the converted dictionary is written to")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("FROM")
+                        .long("from")
+                        .help("format of the input")
+                        .possible_values(&["plain", "hunspell", "fst", "graph"])
+                        .default_value("plain"),
+                )
+                .arg(
+                    Arg::with_name("TO")
+                        .long("to")
+                        .help("format of the output")
+                        .possible_values(&["plain", "fst", "graph"])
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("AFF")
+                        .long("aff")
+                        .help("affix file of a hunspell dictionary, the .aff file next to the input when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MIN_LENGTH")
+                        .long("min-length")
+                        .help("drop words of fewer letters")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("MAX_LENGTH")
+                        .long("max-length")
+                        .help("drop words of more letters")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("ALPHABETIC")
+                        .long("alphabetic")
+                        .help("drop entries with anything but letters, such as phrases and numbers"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("build-graph")
+                .about("Link the words of a word list once and save the graph to a file")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to use, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("graph file to write")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("LANDMARKS")
+                        .long("landmarks")
+                        .help("number of landmark words whose hops to every word are stored")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("BUCKETS")
+                        .long("buckets")
+                        .help("store the words by length, to query words missing from the list"),
+                )
+                .arg(
+                    Arg::with_name("FREQUENCIES")
+                        .long("frequencies")
+                        .help("CSV of word,count lines to store along the words")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("INDEX")
+                        .long("index")
+                        .help("store the deletions of every word, to find words 2 letters away quickly")
+                        .possible_values(&["symspell"])
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("update-graph")
+                .about("Add and remove words of a prebuilt graph without building it again")
+                .arg(
+                    Arg::with_name("GRAPH")
+                        .long("graph")
+                        .help("graph file written by build-graph, replaced by the updated one")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("ADD")
+                        .long("add")
+                        .help("word list whose words are added")
+                        .takes_value(true)
+                        .required_unless("REMOVE"),
+                )
+                .arg(
+                    Arg::with_name("REMOVE")
+                        .long("remove")
+                        .help("word list whose words are removed")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("migrate-graph")
+                .about("Upgrade a graph file written by an older release to the current format")
+                .arg(
+                    Arg::with_name("GRAPH")
+                        .long("graph")
+                        .help("graph file to upgrade, replaced by the upgraded one unless --output is given")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .short("o")
+                        .long("output")
+                        .help("file to write the upgraded graph to")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("query")
+                .about("Find a shortest path of 1-letter hops through a prebuilt graph")
+                .arg(
+                    Arg::with_name("GRAPH")
+                        .long("graph")
+                        .help("graph file written by build-graph")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("START")
+                        .help("starting word")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("END")
+                        .help("ending word")
+                        .required(true)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("info")
+                .about("Describe a word list or a graph file: words, lengths, letters, build options and memory use")
+                .arg(
+                    Arg::with_name("INPUT")
+                        .short("i")
+                        .long("input")
+                        .help("word list to describe, a system word list when omitted")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("GRAPH")
+                        .long("graph")
+                        .help("graph file written by build-graph to describe instead")
+                        .takes_value(true)
+                        .conflicts_with("INPUT"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("completions")
+                .about("Print a shell completion script")
+                .arg(
+                    Arg::with_name("SHELL")
+                        .possible_values(&Shell::variants())
+                        .case_insensitive(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("list-algorithms")
+                .about("List the supported algorithms, cost models and units")
+                .arg(
+                    Arg::with_name("FORMAT")
+                        .long("format")
+                        .possible_values(&["text", "json"])
+                        .default_value("text"),
+                ),
+        )
+        .args(&script_args())
+        .args(&tui_args())
+        .args(&session_args())
+        .subcommands(lsp_subcommands())
+}
 
-    //Safe unwrapping thanks to clap validation
-    let filename = matches.value_of("INPUT").unwrap();
-    let start = matches.value_of("START").unwrap().to_lowercase();
+fn main() {
+    let matches = match app().get_matches_safe() {
+        Ok(matches) => matches,
+        Err(error) if error.use_stderr() => {
+            eprintln!("{}", error.message);
+            process::exit(EXIT_USAGE)
+        }
+        Err(error) => error.exit(),
+    };
+    // Checked up front, most runs never draw a random number
+    seed(matches.subcommand().1.unwrap_or(&matches));
+
+    if let Some(matches) = matches.subcommand_matches("serve") {
+        serve(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("graph") {
+        graph(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        verify(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("validate") {
+        validate(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("completions") {
+        let shell = matches
+            .value_of("SHELL")
+            .map(Shell::from_str)
+            .unwrap()
+            .unwrap();
+        app().gen_completions_to("typos", shell, &mut io::stdout());
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("list-algorithms") {
+        list_algorithms(matches.value_of("FORMAT").unwrap());
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("lsp") {
+        lsp(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("check") {
+        check(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("pipe") {
+        pipe(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("align") {
+        align(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("suggest") {
+        suggest(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("farthest") {
+        farthest(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("reachable") {
+        reachable(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("random-walk") {
+        random_walk(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("cluster") {
+        cluster(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("partition") {
+        partition(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("difficulty") {
+        difficulty(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("daily") {
+        daily(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("hint") {
+        hint(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("reach") {
+        reach(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("bench") {
+        bench(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("batch") {
+        batch(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("matrix") {
+        matrix(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("convert") {
+        convert(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("build-graph") {
+        build_graph(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("update-graph") {
+        update_graph(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("migrate-graph") {
+        migrate_graph(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("query") {
+        query(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("info") {
+        info(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("repl") {
+        let filename = match matches.value_of("INPUT") {
+            Some(filename) => filename,
+            None => system_dictionary(Output::Text),
+        };
+        let words = load_words(filename, Output::Text, &word_policy(matches));
+        println!(
+            "{} words loaded into memory, type help for commands",
+            words.len()
+        );
+        let repl = repl::Repl::new(words);
+        let mut repl = match open_session(matches, Output::Text) {
+            Some(session) => repl.with_session(session, matches.is_present("NO_REPEAT_SESSION")),
+            None => repl,
+        };
+        let stdin = io::stdin();
+        repl::run(&mut repl, stdin.lock(), io::stdout()).unwrap();
+        return;
+    }
+
+    let (filename, start, stop, algorithm) = search_arguments(&matches);
+    let start = words::normalize(start);
     let start = start.as_str();
-    let stop = matches.value_of("END").unwrap().to_lowercase();
+    let stop = words::normalize(stop);
     let stop = stop.as_str();
-    let algorithm = matches
-        .value_of("ALGORITHM")
-        .map(PathFindingAlgorithm::from_str)
+    if let Err(error) = word::check_length(start).and(word::check_length(stop)) {
+        usage_error(&error.to_string(), clap::ErrorKind::InvalidValue);
+    }
+    let pattern = matches.value_of("END_PATTERN").map(|pattern| {
+        regex::Regex::new(pattern).unwrap_or_else(|error| {
+            usage_error(
+                &format!("--end-pattern expects a regular expression: {}", error),
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let cost_name = matches
+        .value_of("COST")
+        .map(CostModelName::from_str)
         .unwrap()
         .unwrap();
-
-    println!(
-        "Using input file: {} with {} algorithm to compute shortest path between {} and {}",
-        filename, algorithm, start, stop
+    let confusion = matches.value_of("CONFUSION");
+    if confusion.is_some() && cost_name != CostModelName::Ocr {
+        usage_error(
+            "--confusion only applies to --cost ocr",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let cost_matrix = matches.value_of("COST_MATRIX");
+    let cost_script = matches.value_of("COST_SCRIPT");
+    if (cost_matrix.is_some() || cost_script.is_some()) && matches.occurrences_of("COST") > 0 {
+        usage_error(
+            "--cost-matrix and --cost-script cannot be combined with --cost",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let unit = matches
+        .value_of("UNIT")
+        .map(Unit::from_str)
+        .unwrap()
+        .unwrap();
+    if unit == Unit::Token
+        && (matches.occurrences_of("COST") > 0
+            || confusion.is_some()
+            || cost_matrix.is_some()
+            || cost_script.is_some())
+    {
+        usage_error(
+            "--unit token counts whole words and cannot be combined with another cost model",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let loaded: io::Result<Arc<dyn CostModel>> = match (unit, cost_matrix, cost_script) {
+        (Unit::Token, _, _) => Ok(Arc::new(Tokens)),
+        (Unit::Letter, Some(filename), _) => File::open(filename)
+            .and_then(|file| CostMatrix::from_csv(BufReader::new(file)))
+            .map(|matrix| Arc::new(matrix) as Arc<dyn CostModel>),
+        (Unit::Letter, None, Some(filename)) => script_cost_model(filename),
+        (Unit::Letter, None, None) => cost_model(cost_name, confusion),
+    };
+    let model = loaded.unwrap_or_else(|error| {
+        let filename = cost_matrix.or(cost_script).or(confusion).unwrap_or("");
+        usage_error(
+            &format!("cannot load cost model {}: {}", filename, error),
+            clap::ErrorKind::InvalidValue,
+        )
+    });
+    let heuristic = matches.value_of("HEURISTIC").unwrap();
+    let heuristic = Heuristic::from_str(heuristic)
+        .unwrap_or_else(|error| usage_error(&error.to_string(), clap::ErrorKind::InvalidValue));
+    let output = Output::from_matches(&matches);
+    let locale = matches
+        .value_of("LOCALE")
+        .map_or_else(Locale::from_env, |locale| locale.parse().unwrap());
+    let transposition_capacity = matches
+        .value_of("TT_CAPACITY")
+        .map(|capacity| {
+            capacity.parse::<usize>().unwrap_or_else(|_| {
+                usage_error(
+                    "--tt-capacity expects a number of words",
+                    clap::ErrorKind::InvalidValue,
+                )
+            })
+        })
+        .unwrap_or(distance::DEFAULT_TRANSPOSITION_CAPACITY);
+    let options = SearchOptions::new(algorithm.unwrap_or(Astar))
+        .with_cost_model(model.clone())
+        .with_heuristic(heuristic)
+        .with_transposition_capacity(transposition_capacity);
+    let max_hops = matches.value_of("MAX_HOPS").map(|hops| {
+        hops.parse::<usize>().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hops expects a number of hops",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let options = match max_hops {
+        Some(hops) => options.with_max_hops(hops),
+        None => options,
+    };
+    let max_hop_distance = matches.value_of("MAX_HOP_DISTANCE").map(|distance| {
+        distance.parse::<usize>().unwrap_or_else(|_| {
+            usage_error(
+                "--max-hop-distance expects a number of letters",
+                clap::ErrorKind::InvalidValue,
+            )
+        })
+    });
+    let options = match max_hop_distance {
+        Some(distance) => options.with_max_hop_distance(distance),
+        None => options,
+    };
+    let monotone = matches.is_present("MONOTONE");
+    let options = options.with_monotone(monotone);
+    let options = match matches.is_present("APPROX") {
+        true => {
+            let rate = matches
+                .value_of("SAMPLE_RATE")
+                .map_or(Some(distance::DEFAULT_SAMPLE_RATE), |rate| {
+                    rate.parse().ok().filter(|rate| (0.0..=1.0).contains(rate))
+                });
+            let rate = rate.unwrap_or_else(|| {
+                usage_error(
+                    "--sample-rate expects a share between 0 and 1, such as 0.1",
+                    clap::ErrorKind::InvalidValue,
+                )
+            });
+            options.with_sampling(Sampling::new(rate, seeded(&matches).next_u64()))
+        }
+        false => options,
+    };
+    let direction = match (
+        matches.is_present("REVERSE"),
+        matches.is_present("AUTO_DIRECTION"),
+    ) {
+        (true, _) => Direction::Backward,
+        (_, true) => Direction::Auto,
+        _ => Direction::Forward,
+    };
+    let options = options.with_direction(direction);
+    let objective = matches.value_of("OBJECTIVE").unwrap();
+    let options = options.with_objective(Objective::from_str(objective).unwrap());
+    let options = options.with_saturation(
+        matches
+            .value_of("SATURATION")
+            .map(Saturation::from_str)
+            .unwrap()
+            .unwrap(),
     );
+    let options = match matches.value_of("MAX_MEMORY") {
+        Some(size) => match parse_size(size) {
+            Some(bytes) => options.with_max_memory(bytes),
+            None => usage_error(
+                "--max-memory expects a number of bytes such as 512M",
+                clap::ErrorKind::InvalidValue,
+            ),
+        },
+        None => options,
+    };
+    let options = match output {
+        Output::Ndjson => options.with_observer(Arc::new(events::Progress::new(PROGRESS_INTERVAL))),
+        _ => options,
+    };
+    let pareto = matches.is_present("PARETO");
+    let alternatives = match matches.value_of("ALTERNATIVES").unwrap().parse::<usize>() {
+        Ok(count) if count > 0 => count,
+        _ => usage_error(
+            "--alternatives expects a positive number of paths",
+            clap::ErrorKind::InvalidValue,
+        ),
+    };
+    if pareto && alternatives > 1 {
+        usage_error(
+            "--pareto cannot be combined with --alternatives",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    if pattern.is_some() && (alternatives > 1 || matches.is_present("TUI")) {
+        usage_error(
+            "--end-pattern only ends single searches, without --alternatives or --tui",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
 
-    let mut words: Vec<String> = lines_from_file(filename)
-        .unwrap()
+    if output == Output::Text {
+        let end = match &pattern {
+            Some(pattern) => format!("the first word matching {}", pattern),
+            None => stop.to_string(),
+        };
+        println!(
+            "Using input file: {} with {} algorithm to compute shortest path between {} and {}",
+            filename,
+            algorithm.map_or(AUTO_ALGORITHM, PathFindingAlgorithm::name),
+            start,
+            end
+        );
+    }
+
+    let mut session = open_session(&matches, output);
+    let load_time = Instant::now();
+    let words::List {
+        mut words,
+        weights,
+        languages,
+        ..
+    } = load_weighted_words(filename, output, &word_policy(&matches));
+    let load = load_time.elapsed();
+    let mut weighted: Vec<String> = weights
+        .iter()
+        .map(|(word, weight)| format!("{}\t{}", word, weight))
+        .collect();
+    weighted.sort_unstable();
+    let options = match weights.is_empty() {
+        true => options,
+        false => options.with_word_weights(Arc::new(weights)),
+    };
+    // Words tagged with no language shared by both ends of a hop may only be bridged
+    // with --cross-language
+    let mut tagged: Vec<String> = languages
+        .iter()
+        .filter(|_| !matches.is_present("CROSS_LANGUAGE"))
+        .map(|(word, languages)| format!("{}\t{}", word, languages.join(",")))
+        .collect();
+    tagged.sort_unstable();
+    let options = match tagged.is_empty() {
+        true => options,
+        false => options.with_word_languages(Arc::new(languages)),
+    };
+    let frequencies = matches
+        .value_of("FREQUENCIES")
+        .map(|filename| load_frequencies(filename, output));
+    let mut counted: Vec<String> = frequencies
+        .iter()
+        .flatten()
+        .map(|(word, count)| format!("{},{}", word, count))
+        .collect();
+    counted.sort_unstable();
+    let options = match frequencies {
+        Some(frequencies) => options.with_frequencies(Arc::new(frequencies)),
+        None => options,
+    };
+    let endpoints: &[&str] = match pattern {
+        Some(_) => &[start],
+        None => &[start, stop],
+    };
+    if !matches.is_present("ALLOW_MISSING_ENDPOINTS") {
+        for endpoint in endpoints {
+            if !words.iter().any(|word| word == endpoint) {
+                missing_endpoint(endpoint, filename, &words, output);
+            }
+        }
+    }
+    if let Some(language) = matches.value_of("FOLD_STEMS") {
+        // Searching stems only keeps paths from wandering through plurals and tenses
+        let folded: HashSet<String> = {
+            let listed: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+            distance::fold_stems(&listed, language.parse().unwrap())
+                .into_keys()
+                .filter(|word| *word != start && *word != stop)
+                .map(str::to_string)
+                .collect()
+        };
+        words.retain(|word| !folded.contains(word));
+        if output == Output::Text {
+            println!("{} inflected forms folded into their stems", folded.len());
+        }
+    }
+    avoid_session(session.as_ref(), &matches, &mut words, &[start, stop]);
+    let checkpoint = matches.value_of("CHECKPOINT").map(Path::new);
+    if checkpoint.is_some()
+        && (alternatives > 1
+            || pareto
+            || matches.is_present("TUI")
+            || max_hops.is_some()
+            || direction != Direction::Forward
+            || !weighted.is_empty()
+            || !tagged.is_empty()
+            || !counted.is_empty()
+            || options.sampling().is_some())
+    {
+        usage_error(
+            "--checkpoint only saves single forward searches, without --max-hops, weights, languages, --frequencies or --approx",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let explainer = matches.value_of("EXPLAIN_PRUNING").map(|list| {
+        let watched = list
+            .split(',')
+            .map(words::normalize)
+            .filter(|word| !word.is_empty());
+        Arc::new(PruningExplainer::new(watched, &options))
+    });
+    if explainer.is_some()
+        && (alternatives > 1
+            || pareto
+            || matches.is_present("TUI")
+            || checkpoint.is_some()
+            || output == Output::Ndjson)
+    {
+        usage_error(
+            "--explain-pruning only explains single searches, without --tui, --checkpoint or --format ndjson",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let checker = matches
+        .is_present("CHECK_HEURISTIC")
+        .then(|| Arc::new(HeuristicChecker::new()));
+    if checker.is_some()
+        && (explainer.is_some()
+            || alternatives > 1
+            || pareto
+            || matches.is_present("TUI")
+            || checkpoint.is_some()
+            || output == Output::Ndjson)
+    {
+        usage_error(
+            "--check-heuristic only checks single searches, without --explain-pruning, --tui, --checkpoint or --format ndjson",
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    let options = match (&explainer, &checker) {
+        (Some(explainer), _) => options.with_observer(explainer.clone()),
+        (_, Some(checker)) => options.with_observer(checker.clone()),
+        (None, None) => options,
+    };
+    let interval = matches
+        .value_of("CHECKPOINT_INTERVAL")
+        .map_or(
+            Some(DEFAULT_CHECKPOINT_INTERVAL),
+            checkpoint::parse_interval,
+        )
+        .unwrap_or_else(|| {
+            usage_error(
+                "--checkpoint-interval expects a time such as 60s, 5m or 2h",
+                clap::ErrorKind::InvalidValue,
+            )
+        });
+    // Only single exact paths are cached
+    let cache = matches
+        .value_of("CACHE_DIR")
+        .filter(|_| alternatives == 1 && !pareto && explainer.is_none() && checker.is_none())
+        .filter(|_| options.sampling().is_none() && pattern.is_none())
+        .map(|directory| {
+            cache::DiskCache::new(directory).unwrap_or_else(|error| {
+                output.error(
+                    &format!("cannot use --cache-dir {}: {}", directory, error),
+                    EXIT_USAGE,
+                )
+            })
+        });
+    // Files may change between runs under the same names
+    let model_files: Vec<Option<u64>> = [confusion, cost_matrix, cost_script]
         .iter()
-        .map(|w| w.to_lowercase())
+        .flatten()
+        .map(|filename| cache::file_hash(filename).ok())
         .collect();
-    words.insert(0, stop.to_string());
+    let key = cache::CacheKey {
+        dictionary: cache::dictionary_hash(&words),
+        start: start.to_string(),
+        end: stop.to_string(),
+        options: format!(
+            "{} {} {:?} {:?} {} {} {} {} {} {:?} {:?} {:?} {} {} {} {} {:x} {:x} {:x}",
+            algorithm.map_or(AUTO_ALGORITHM, PathFindingAlgorithm::name),
+            matches.value_of("HEURISTIC").unwrap(),
+            direction,
+            matches.value_of("MAX_MEMORY"),
+            unit,
+            cost_name,
+            confusion.unwrap_or(""),
+            cost_matrix.unwrap_or(""),
+            cost_script.unwrap_or(""),
+            model_files,
+            max_hops,
+            max_hop_distance,
+            monotone,
+            objective,
+            matches.value_of("SATURATION").unwrap(),
+            locale,
+            cache::dictionary_hash(&weighted),
+            cache::dictionary_hash(&tagged),
+            cache::dictionary_hash(&counted)
+        ),
+    };
+    let reporting = Reporting {
+        max_hops,
+        explain: Some(unit).filter(|_| matches.is_present("EXPLAIN")),
+        locale,
+        output,
+        resources: None,
+    };
+    let usage = matches
+        .is_present("REPORT")
+        .then(|| resources::ResourceReport {
+            load,
+            result_cache: cache.as_ref().map(|_| false),
+            ..resources::ResourceReport::default()
+        });
+    if let Some(cached) = cache.as_ref().and_then(|cache| cache.get(&key)) {
+        if let Some(path) = &cached {
+            let path: Vec<&str> = path.words.iter().map(AsRef::as_ref).collect();
+            record_session(session.as_mut(), &path);
+        }
+        let path = cached.map(|path| Found::cached(path, model.as_ref()));
+        let usage = usage.map(|usage| resources::ResourceReport {
+            result_cache: Some(true),
+            ..usage
+        });
+        let reporting = Reporting {
+            resources: usage.as_ref(),
+            ..reporting
+        };
+        report(path, None, reporting);
+        return;
+    }
+    if matches.is_present("STATS") && output == Output::Text {
+        for (length, count) in words::length_counts(&words) {
+            println!("{} words of {} letters", count, length);
+        }
+    }
+    // Words of the list only, not the end word a search may need added
+    let word_count = words.len();
+    // Only reached with --allow-missing-endpoints, the search must be able to end there
+    if pattern.is_none() && !words.iter().any(|word| word == stop) {
+        words.insert(0, stop.to_string());
+    }
 
     let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    // Small hops are looked up in an index instead of scanning every word
+    let index_time = Instant::now();
+    let indexed: Option<Box<dyn Dictionary>> = match (max_hop_distance, matches.value_of("INDEX")) {
+        (None, _) | (_, Some("scan")) => None,
+        (_, Some("symspell")) => Some(Box::new(SymSpellWords::new(&words))),
+        (_, Some("trie")) => Some(Box::new(TrieWords::new(&words))),
+        _ => Some(Box::new(ShardedWords::new(&words))),
+    };
+    let index_build = index_time.elapsed();
+    // What the run cost once the search is over
+    let searched = |search, stats: SearchStats| {
+        usage.clone().map(|usage| resources::ResourceReport {
+            index_build,
+            search,
+            expanded: stats.expanded,
+            recycled: stats.recycled,
+            memory_fallback: stats.memory_fallback,
+            ..usage
+        })
+    };
 
-    let word_count = words.len();
+    let options = match algorithm {
+        Some(_) => options,
+        // Nothing to select, searches ending on a pattern always go without a heuristic
+        None if pattern.is_some() => options,
+        None => {
+            let selection = match &indexed {
+                Some(indexed) => {
+                    distance::select_algorithm(start, stop, indexed.as_ref(), &options)
+                }
+                None => distance::select_algorithm(start, stop, words.as_slice(), &options),
+            };
+            if output != Output::Quiet {
+                for reason in &selection.reasons {
+                    eprintln!("info: {}", reason);
+                }
+                eprintln!("info: using {}", selection.algorithm);
+            }
+            options.with_algorithm(selection.algorithm)
+        }
+    };
+    let symmetry = match &indexed {
+        Some(indexed) => options.check_symmetry(indexed.as_ref()),
+        None => options.check_symmetry(words.as_slice()),
+    };
+    if let Err(error) = symmetry {
+        usage_error(
+            &format!("cannot search with {}: {}", options.algorithm(), error),
+            clap::ErrorKind::ArgumentConflict,
+        );
+    }
+    if pattern.is_some() && algorithm != Some(Dijkstra) && output != Output::Quiet {
+        eprintln!(
+            "info: using dijkstra, nothing tells how far the words matching --end-pattern are"
+        );
+    }
+    if output != Output::Quiet && pattern.is_none() {
+        let estimate = match &indexed {
+            Some(indexed) => distance::estimate_search(start, stop, indexed.as_ref(), &options),
+            None => distance::estimate_search(start, stop, words.as_slice(), &options),
+        };
+        eprintln!("info: expected: {}", estimate);
+        if estimate.intractable() {
+            eprintln!(
+                "warning: this search looks intractable with {}, these options may help:",
+                options.algorithm()
+            );
+            for suggestion in distance::suggest_options(&estimate, &options) {
+                eprintln!("  {}", suggestion);
+            }
+            if checkpoint.is_none() {
+                eprintln!("  --checkpoint saves the search to resume it if it is stopped");
+            }
+            if options.sampling().is_none() {
+                eprintln!(
+                    "  --approx goes through a sample of the hops, giving up on the shortest path"
+                );
+            }
+        }
+    }
 
-    println!("{} words loaded into memory", word_count);
+    if output == Output::Text {
+        let plural = if word_count == 1 { "" } else { "s" };
+        println!("{} word{} loaded into memory", word_count, plural);
+    }
     let start_time = Instant::now();
-    let res = distance::find_shortest_path(start, stop, words.as_slice(), algorithm.borrow());
+    if alternatives > 1 {
+        let disjoint = matches
+            .value_of("DISJOINT")
+            .map(Disjoint::from_str)
+            .unwrap()
+            .unwrap();
+        let (paths, stats) = distance::find_disjoint_paths(
+            start,
+            stop,
+            words.as_slice(),
+            &options,
+            alternatives,
+            disjoint,
+        );
+        let paths = paths.unwrap_or_else(|interruption| interrupted(interruption, output));
+        warn_stats(stats, output);
+        let duration = start_time.elapsed();
+        for path in &paths {
+            record_session(session.as_mut(), &path.0);
+        }
+        let paths = paths
+            .into_iter()
+            .map(|path| Found::new(PathResult::new(path, model.as_ref()), unit, locale))
+            .collect();
+        let usage = searched(duration, stats);
+        let reporting = Reporting {
+            resources: usage.as_ref(),
+            ..reporting
+        };
+        report_alternatives(paths, "disjoint paths", duration, reporting);
+        return;
+    }
+    if pareto {
+        let (paths, stats) = match &indexed {
+            Some(indexed) => distance::find_pareto_paths(start, stop, indexed.as_ref(), &options),
+            None => distance::find_pareto_paths(start, stop, words.as_slice(), &options),
+        };
+        let paths = paths.unwrap_or_else(|interruption| interrupted(interruption, output));
+        let duration = start_time.elapsed();
+        for path in &paths {
+            record_session(session.as_mut(), &path.words);
+        }
+        let paths = paths
+            .into_iter()
+            .map(|path| Found {
+                hops: path
+                    .words
+                    .windows(2)
+                    .map(|hop| model.distance(hop[0], hop[1]))
+                    .collect(),
+                words: path.words.iter().map(|word| word.to_string()).collect(),
+                cost: path.tradeoff.to_string(),
+            })
+            .collect();
+        let usage = searched(duration, stats);
+        let reporting = Reporting {
+            resources: usage.as_ref(),
+            ..reporting
+        };
+        report_alternatives(paths, "paths on the Pareto frontier", duration, reporting);
+        return;
+    }
+    let (res, stats) = if let Some(path) = checkpoint {
+        let checkpointing = Checkpointing {
+            path,
+            fingerprint: format!(
+                "{:x} {} {} {}",
+                key.dictionary, key.start, key.end, key.options
+            ),
+            interval,
+            resume: matches.is_present("RESUME"),
+        };
+        match &indexed {
+            Some(indexed) => search_with_checkpoint(
+                start,
+                stop,
+                indexed.as_ref(),
+                &options,
+                &checkpointing,
+                output,
+            ),
+            None => search_with_checkpoint(
+                start,
+                stop,
+                words.as_slice(),
+                &options,
+                &checkpointing,
+                output,
+            ),
+        }
+    } else if matches.is_present("TUI") {
+        search_with_tui(start, stop, words.as_slice(), options.clone(), unit)
+    } else if let Some(pattern) = &pattern {
+        let goal = |word: &str| pattern.is_match(word);
+        match &indexed {
+            Some(indexed) => {
+                distance::find_shortest_path_to_match(start, &goal, indexed.as_ref(), &options)
+            }
+            None => distance::find_shortest_path_to_match(start, &goal, words.as_slice(), &options),
+        }
+    } else if let Some(indexed) = &indexed {
+        distance::find_shortest_path_with_options(start, stop, indexed.as_ref(), &options)
+    } else {
+        distance::find_shortest_path_with_options(start, stop, words.as_slice(), &options)
+    };
+    if let Some(explainer) = &explainer {
+        let reasons = match &indexed {
+            Some(indexed) => {
+                explainer.explain(start, stop, indexed.as_ref(), &options, &res, &stats)
+            }
+            None => explainer.explain(start, stop, words.as_slice(), &options, &res, &stats),
+        };
+        for (word, reason) in reasons {
+            eprintln!("{}: {}", word, reason);
+        }
+    }
+    let res = res.unwrap_or_else(|interruption| interrupted(interruption, output));
+    warn_stats(stats, output);
+    if direction == Direction::Auto && stats.backward && output == Output::Text {
+        println!("{} has fewer neighbours, searched back from it", stop);
+    }
     let duration = start_time.elapsed();
-    match res {
-        Some((words, cost)) => {
-            let words = words.join("->");
-            println!(
-                "Shortest path found in {:?}: {} (achieved in {})",
-                duration, words, cost
-            )
+    // Checked once the search is timed, each word sampled costing a search of its own
+    if let Some(checker) = &checker {
+        let report = match &indexed {
+            Some(indexed) => checker.check(start, stop, indexed.as_ref(), &options, &stats),
+            None => checker.check(start, stop, words.as_slice(), &options, &stats),
+        };
+        for violation in &report.violations {
+            eprintln!("warning: heuristic {}", violation);
+        }
+        eprintln!(
+            "heuristic checked against the hops of {} expansions and the cost left from {} words: {} violations",
+            report.expansions,
+            report.searched,
+            report.violations.len()
+        );
+    }
+    match (options.sampling(), &res) {
+        (Some(sampling), Some((path, cost))) if output != Output::Quiet => {
+            let confidence = match &indexed {
+                Some(indexed) => distance::sampling_confidence(
+                    start,
+                    stop,
+                    indexed.as_ref(),
+                    &options,
+                    cost,
+                    path.len() - 1,
+                ),
+                None => distance::sampling_confidence(
+                    start,
+                    stop,
+                    words.as_slice(),
+                    &options,
+                    cost,
+                    path.len() - 1,
+                ),
+            };
+            eprintln!(
+            "info: approximate: went through {:.0}% of the hops, {:.0}% sure this path is a shortest one",
+            sampling.rate * 100.0,
+            confidence * 100.0
+        );
+        }
+        (Some(_), None) if output != Output::Quiet => {
+            eprintln!("info: approximate: the hops left out may hold a path, an exact search could find one");
+        }
+        _ => (),
+    }
+    // A search gone on with bounded-astar may have missed the shortest path
+    if let Some(cache) = cache.as_ref().filter(|_| !stats.memory_fallback) {
+        let cached = res.as_ref().map(|(words, cost)| cache::CachedPath {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            cost: distance::describe_cost(cost, unit),
+        });
+        if let Err(error) = cache.insert(&key, &cached) {
+            eprintln!("warning: cannot cache the path: {}", error);
         }
-        None => println!("No path found, something went wrong ?"),
+    }
+    if let Some((path, _)) = &res {
+        record_session(session.as_mut(), path);
+    }
+    let path = res.map(|path| Found::new(PathResult::new(path, model.as_ref()), unit, locale));
+    let usage = searched(duration, stats);
+    let reporting = Reporting {
+        resources: usage.as_ref(),
+        ..reporting
+    };
+    report(path, Some(duration), reporting);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_options_are_optional() {
+        assert!(app()
+            .get_matches_from_safe(["typos", "cold", "warm"])
+            .is_ok());
+        assert!(app()
+            .get_matches_from_safe(["typos", "cold", "warm", "--checkpoint-interval", "5m"])
+            .is_err());
+        let matches = app()
+            .get_matches_from_safe(["typos", "cold", "warm", "--checkpoint", "search.txt"])
+            .unwrap();
+        assert_eq!(matches.value_of("CHECKPOINT_INTERVAL"), None);
+    }
+
+    #[test]
+    fn sample_rate_needs_approx() {
+        assert!(app()
+            .get_matches_from_safe(["typos", "cold", "warm", "--sample-rate", "0.5"])
+            .is_err());
+        let matches = app()
+            .get_matches_from_safe(["typos", "cold", "warm", "--approx"])
+            .unwrap();
+        assert_eq!(matches.value_of("SAMPLE_RATE"), None);
+    }
+
+    #[test]
+    fn end_patterns_take_the_place_of_the_end_word() {
+        let matches = app()
+            .get_matches_from_safe(["typos", "-i", "words.txt", "cold", "--end-pattern", "^war"])
+            .unwrap();
+        assert_eq!(
+            search_arguments(&matches),
+            ("words.txt", "cold", "", Some(Astar))
+        );
+        let matches = app()
+            .get_matches_from_safe([
+                "typos",
+                "Cargo.toml",
+                "cold",
+                "dijkstra",
+                "--end-pattern",
+                "^war",
+            ])
+            .unwrap();
+        assert_eq!(
+            search_arguments(&matches),
+            ("Cargo.toml", "cold", "", Some(Dijkstra))
+        );
+        assert!(app()
+            .get_matches_from_safe(["typos", "cold", "--end-pattern", "^war", "--monotone"])
+            .is_err());
+    }
+
+    #[test]
+    fn sessions_keep_searches_off_used_words() {
+        let path = std::env::temp_dir().join(format!("typos-main-session-{}", process::id()));
+        let path = path.to_str().unwrap();
+        let args = [
+            "typos",
+            "cold",
+            "warm",
+            "--session",
+            path,
+            "--no-repeat-session",
+        ];
+        let matches = app().get_matches_from_safe(args).unwrap();
+        let mut session = open_session(&matches, Output::Quiet);
+        record_session(session.as_mut(), &["cold", "cord", "card", "warm"]);
+        let mut words: Vec<String> = ["cold", "cord", "card", "ward", "warm"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        avoid_session(session.as_ref(), &matches, &mut words, &["card", "warm"]);
+        assert_eq!(words, vec!["cold", "card", "ward", "warm"]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn completions_for_every_shell() {
+        for shell in Shell::variants().iter() {
+            let mut script = Vec::new();
+            app().gen_completions_to("typos", Shell::from_str(shell).unwrap(), &mut script);
+            assert!(String::from_utf8(script)
+                .unwrap()
+                .contains("list-algorithms"));
+        }
+    }
+
+    #[test]
+    fn sizes_with_suffixes() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("64k"), Some(64 << 10));
+        assert_eq!(parse_size("2G"), Some(2 << 30));
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("12T"), None);
+        assert_eq!(parse_size(""), None);
     }
 }
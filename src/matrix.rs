@@ -0,0 +1,201 @@
+use num_traits::Zero;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use typos::distance;
+use typos::distance::path::PathMultiCost;
+use typos::distance::word::EditDistance;
+use typos::distance::{Dictionary, SearchOptions};
+
+/// Cost of the shortest path from every word to every other, `None` when unreachable
+pub type Row = Vec<Option<PathMultiCost<EditDistance>>>;
+
+/// Hops of the shortest path from every word to every other, `None` when unreachable
+pub type HopRow = Vec<Option<usize>>;
+
+/// One row per word of `words`, ladders going through every word of `dictionary`.
+/// Rows are computed on every core, `progress` is told how many are done after each one.
+pub fn compute<D: Dictionary + Sync + ?Sized>(
+    words: &[&str],
+    dictionary: &D,
+    options: &SearchOptions,
+    progress: impl Fn(usize) + Sync,
+) -> Vec<Row> {
+    rows(words, progress, |word| {
+        let (costs, _) = distance::find_costs_from(word, dictionary, options);
+        row(
+            words,
+            word,
+            &costs.unwrap_or_default(),
+            PathMultiCost::zero(),
+        )
+    })
+}
+
+/// Same as `compute` with the hops of the paths rather than their costs
+pub fn compute_hops<D: Dictionary + Sync + ?Sized>(
+    words: &[&str],
+    dictionary: &D,
+    options: &SearchOptions,
+) -> Vec<HopRow> {
+    rows(
+        words,
+        |_| (),
+        |word| {
+            let (hops, _) = distance::find_hops_from(word, dictionary, options);
+            row(words, word, &hops.unwrap_or_default(), 0)
+        },
+    )
+}
+
+// What `found` tells of every word of `words`, `from` reaching itself at `zero`
+fn row<T: Copy>(words: &[&str], from: &str, found: &HashMap<&str, T>, zero: T) -> Vec<Option<T>> {
+    words
+        .iter()
+        .map(|&word| match word == from {
+            true => Some(zero),
+            false => found.get(word).copied(),
+        })
+        .collect()
+}
+
+// The row of every word of `words`, each computed by `row_of` on one of the cores
+fn rows<T: Clone + Send>(
+    words: &[&str],
+    progress: impl Fn(usize) + Sync,
+    row_of: impl Fn(&str) -> Vec<Option<T>> + Sync,
+) -> Vec<Vec<Option<T>>> {
+    let rows = Mutex::new(vec![Vec::new(); words.len()]);
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    thread::scope(|scope| {
+        for _ in 0..threads.min(words.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= words.len() {
+                    break;
+                }
+                let row = row_of(words[i]);
+                rows.lock().unwrap()[i] = row;
+                progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+            });
+        }
+    });
+    rows.into_inner().unwrap()
+}
+
+/// Number of hops of every size, from 1-letter hops up to the biggest one taken
+pub fn sizes(cost: &PathMultiCost<EditDistance>) -> Vec<EditDistance> {
+    let counts = cost.as_slice();
+    let highest = counts
+        .iter()
+        .position(|&count| count != 0)
+        .unwrap_or(counts.len());
+    counts[highest..].iter().rev().copied().collect()
+}
+
+// Number of hops of every size, from 1-letter hops up, e.g. `2;0;1`
+fn cell(cost: &Option<PathMultiCost<EditDistance>>) -> String {
+    let sizes = match cost {
+        Some(cost) => sizes(cost),
+        None => return String::new(),
+    };
+    match sizes.as_slice() {
+        [] => "0".to_string(),
+        sizes => sizes
+            .iter()
+            .map(|count| count.to_string())
+            .collect::<Vec<String>>()
+            .join(";"),
+    }
+}
+
+/// Words are quoted when they contain a separator or a quote
+pub fn quote(word: &str) -> String {
+    if word.contains(&[',', '"'][..]) {
+        format!("\"{}\"", word.replace('"', "\"\""))
+    } else {
+        word.to_string()
+    }
+}
+
+/// Header line with the words, then one line per word and its costs
+pub fn write_csv(words: &[&str], rows: &[Row], mut writer: impl Write) -> io::Result<()> {
+    let header: Vec<String> = words.iter().map(|word| quote(word)).collect();
+    writeln!(writer, ",{}", header.join(","))?;
+    for (word, row) in words.iter().zip(rows) {
+        let cells: Vec<String> = row.iter().map(cell).collect();
+        writeln!(writer, "{},{}", quote(word), cells.join(","))?;
+    }
+    Ok(())
+}
+
+/// The hops as a table for people to read, a row per word leaving and a column per word
+/// reached, every cell holding the hops of the cheapest ladder or `-` without one
+pub fn write_table(words: &[&str], rows: &[HopRow], mut writer: impl Write) -> io::Result<()> {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|hops| hops.map_or_else(|| "-".to_string(), |hops| hops.to_string()))
+                .collect()
+        })
+        .collect();
+    let width = |word: &str| word.chars().count();
+    let first = words.iter().map(|word| width(word)).max().unwrap_or(0);
+    let header: Vec<String> = words.iter().map(|word| format!("  {}", word)).collect();
+    writeln!(writer, "{:first$}{}", "", header.join(""), first = first)?;
+    for (word, row) in words.iter().zip(&cells) {
+        write!(writer, "{:first$}", word, first = first)?;
+        for (cell, column) in row.iter().zip(words) {
+            write!(writer, "  {:>width$}", cell, width = width(column))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typos::distance::PathFindingAlgorithm;
+
+    #[test]
+    fn matrix_of_ladder_costs() {
+        let dictionary = ["cold", "cord", "card", "ward", "warm"];
+        let words = ["cold", "card", "warm"];
+        let options = SearchOptions::new(PathFindingAlgorithm::Dijkstra);
+        let rows = compute(&words, &dictionary[..], &options, |_| ());
+        let mut csv = Vec::new();
+        write_csv(&words, &rows, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            ",cold,card,warm\ncold,0,2,4\ncard,2,0,2\nwarm,4,2,0\n"
+        );
+
+        let words = ["cold", "warm", "zzz"];
+        let rows = compute_hops(
+            &words,
+            [&dictionary[..], &["zzz"]].concat().as_slice(),
+            &options.clone().with_max_hop_distance(1),
+        );
+        let mut table = Vec::new();
+        write_table(&words, &rows, &mut table).unwrap();
+        assert_eq!(
+            String::from_utf8(table).unwrap(),
+            "      cold  warm  zzz\n\
+             cold     0     4    -\n\
+             warm     4     0    -\n\
+             zzz      -     -    0\n"
+        );
+
+        // A hop of more letters than costs count hops of is still a single hop
+        let long = "abcdefghijklmnopqrstuvwxyz";
+        let words = ["cold", long];
+        let rows = compute_hops(&words, &words[..], &options);
+        assert_eq!(rows, vec![vec![Some(0), Some(1)], vec![Some(1), Some(0)]]);
+    }
+}
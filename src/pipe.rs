@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use typos::distance::{self, Dictionary, ShardedWords};
+
+// Editors only check that the first line starts with `@(#)`
+const BANNER: &str = concat!(
+    "@(#) International Ispell Version 3.1.20 (but really typos ",
+    env!("CARGO_PKG_VERSION"),
+    ")"
+);
+
+/// Suggestions offered for a misspelled word at most
+pub const SUGGESTIONS: usize = 10;
+
+/// Edits a suggestion differs by at most, farther words are no likely fix
+pub const MAX_DISTANCE: usize = 2;
+
+/// Words known to a spell checker and the fixes it offers for the others
+pub struct Speller<'w> {
+    words: ShardedWords<'w>,
+    accepted: HashSet<String>,
+}
+
+impl<'w> Speller<'w> {
+    pub fn new(words: &[&'w str]) -> Speller<'w> {
+        Speller {
+            words: ShardedWords::new(words),
+            accepted: HashSet::new(),
+        }
+    }
+
+    /// Consider `word` correct from now on, in any case
+    pub fn accept(&mut self, word: &str) {
+        self.accepted.insert(word.to_lowercase());
+    }
+
+    /// Whether `word` is listed or accepted, in any case
+    pub fn knows(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.words.contains(&word) || self.accepted.contains(&word)
+    }
+
+    /// Listed words that may be meant instead of `word`, the likeliest first
+    pub fn suggestions(&self, word: &str) -> Vec<&str> {
+        distance::suggest(&self.words, &word.to_lowercase(), SUGGESTIONS)
+            .into_iter()
+            .take_while(|&(_, distance)| distance <= MAX_DISTANCE)
+            .map(|(suggestion, _)| suggestion)
+            .collect()
+    }
+}
+
+/// Spell checking session speaking the line protocol of `ispell -a`
+pub struct Session<'w> {
+    speller: Speller<'w>,
+    // `!` stops acknowledging correct words, `%` resumes it
+    terse: bool,
+}
+
+/// Letters, with apostrophes inside words such as "don't", and the character offset of each
+pub fn split_words(line: &str) -> Vec<(usize, String)> {
+    let letters: Vec<char> = line.chars().collect();
+    let is_letter = |i: usize| letters.get(i).is_some_and(|c| c.is_alphabetic());
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < letters.len() {
+        if !is_letter(i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while is_letter(i) || (letters.get(i) == Some(&'\'') && is_letter(i + 1)) {
+            i += 1;
+        }
+        words.push((start, letters[start..i].iter().collect()));
+    }
+    words
+}
+
+impl<'w> Session<'w> {
+    pub fn new(words: &[&'w str]) -> Session<'w> {
+        Session {
+            speller: Speller::new(words),
+            terse: false,
+        }
+    }
+
+    /// Answer to a line of input, `None` for the commands ispell answers nothing to
+    pub fn answer(&mut self, line: &str) -> Option<Vec<String>> {
+        let mut chars = line.chars();
+        match chars.next() {
+            Some('*') | Some('@') => {
+                self.speller.accept(chars.as_str().trim());
+                None
+            }
+            Some('!') => {
+                self.terse = true;
+                None
+            }
+            Some('%') => {
+                self.terse = false;
+                None
+            }
+            // Saving the personal dictionary and switching the formatter do not apply
+            Some('#') | Some('~') | Some('+') | Some('-') => None,
+            // Offsets still count the `^` protecting the line
+            Some('^') => Some(self.check(chars.as_str(), 1)),
+            _ => Some(self.check(line, 0)),
+        }
+    }
+
+    fn check(&self, line: &str, shift: usize) -> Vec<String> {
+        let mut answers = Vec::new();
+        for (offset, original) in split_words(line) {
+            if self.speller.knows(&original) {
+                if !self.terse {
+                    answers.push("*".to_string());
+                }
+                continue;
+            }
+            let suggestions = self.speller.suggestions(&original);
+            answers.push(match suggestions.len() {
+                0 => format!("# {} {}", original, offset + shift),
+                count => format!(
+                    "& {} {} {}: {}",
+                    original,
+                    count,
+                    offset + shift,
+                    suggestions.join(", ")
+                ),
+            });
+        }
+        answers
+    }
+}
+
+pub fn run(session: &mut Session, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    writeln!(output, "{}", BANNER)?;
+    output.flush()?;
+    for line in input.lines() {
+        if let Some(answers) = session.answer(&line?) {
+            for answer in answers {
+                writeln!(output, "{}", answer)?;
+            }
+            // An empty line ends the answer to every line checked
+            writeln!(output)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 6] = ["cold", "cord", "card", "don't", "warm", "wore"];
+
+    #[test]
+    fn words_and_offsets() {
+        assert_eq!(
+            split_words("Don't  wörk-42 'x'"),
+            vec![
+                (0, "Don't".to_string()),
+                (7, "wörk".to_string()),
+                (16, "x".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn speaks_the_ispell_protocol() {
+        let mut output = Vec::new();
+        let input = "Cold colt\n^warm zzzzzzzzzzzzzzzz\n@colt\n!\ncolt cord\n%\ncord\n";
+        run(&mut Session::new(&WORDS), input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut lines = output.lines();
+        assert!(lines.next().unwrap().starts_with("@(#) "));
+        let rest: Vec<&str> = lines.collect();
+        assert_eq!(
+            rest,
+            vec![
+                "*",
+                "& colt 2 5: cold, cord",
+                "",
+                "*",
+                "# zzzzzzzzzzzzzzzz 6",
+                "",
+                "",
+                "*",
+                ""
+            ]
+        );
+    }
+}
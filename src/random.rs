@@ -0,0 +1,61 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seed different on every run, to print so that the run can be repeated
+pub fn seed_from_time() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    now.as_secs() ^ u64::from(now.subsec_nanos()) << 32
+}
+
+/// Small splitmix64 generator, good enough to pick words and not meant for anything secret
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    /// Every seed, 0 included, starts a sequence of its own
+    pub fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..bound`, `bound` must not be 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// `count` distinct items picked uniformly, in their original order
+    pub fn sample<'a, T>(&mut self, items: &'a [T], count: usize) -> Vec<&'a T> {
+        let mut indices: Vec<usize> = (0..items.len()).collect();
+        let count = count.min(items.len());
+        for i in 0..count {
+            let j = i + self.below(items.len() - i);
+            indices.swap(i, j);
+        }
+        let mut picked = indices[..count].to_vec();
+        picked.sort_unstable();
+        picked.into_iter().map(|i| &items[i]).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_is_distinct_and_ordered() {
+        let items: Vec<usize> = (0..100).collect();
+        let picked = Random::new(42).sample(&items, 10);
+        assert_eq!(picked.len(), 10);
+        assert!(picked.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Random::new(42).sample(&items, 10), picked);
+        assert_eq!(Random::new(7).sample(&items, 1000).len(), 100);
+        assert_ne!(Random::new(0).next_u64(), Random::new(1).next_u64());
+    }
+}
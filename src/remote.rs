@@ -0,0 +1,162 @@
+use crate::cache;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Answer of a server asked for a word list, given the ETag of the copy already cached
+#[derive(Debug)]
+// Only tests answer without the net feature
+#[cfg_attr(not(feature = "net"), allow(dead_code))]
+pub enum Response {
+    /// The cached copy is still current
+    NotModified,
+    Fresh {
+        body: Vec<u8>,
+        etag: Option<String>,
+    },
+}
+
+/// Whether a word list given on the command line is to be downloaded
+pub fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+/// Directory of the downloaded word lists, under `$XDG_CACHE_HOME` or `~/.cache`
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|base| !base.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("typos").join("dictionaries"))
+}
+
+/// Local copy of the word list at `url` kept in `directory`, refreshed with `fetch`
+/// unless the server tells it did not change. The copy left by an earlier run is used
+/// when the server cannot be reached, with a warning.
+pub fn refresh(
+    url: &str,
+    directory: &Path,
+    fetch: impl FnOnce(Option<&str>) -> io::Result<Response>,
+) -> io::Result<PathBuf> {
+    // Hashes of URLs make names safe for any file system
+    let name = format!("{:016x}", cache::dictionary_hash(&[url]));
+    let copy = directory.join(format!("{}.txt", name));
+    let etag_file = directory.join(format!("{}.etag", name));
+    let etag = match copy.is_file() {
+        true => fs::read_to_string(&etag_file).ok(),
+        false => None,
+    };
+    match fetch(etag.as_deref()) {
+        Ok(Response::NotModified) if copy.is_file() => Ok(copy),
+        Ok(Response::NotModified) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} answered not modified to a request for a new copy", url),
+        )),
+        Ok(Response::Fresh { body, etag }) => {
+            fs::create_dir_all(directory)?;
+            // Written next to the copy first, so an interrupted write leaves the old one
+            let written = directory.join(format!("{}.tmp", name));
+            fs::write(&written, body)?;
+            fs::rename(&written, &copy)?;
+            match etag {
+                Some(etag) => fs::write(&etag_file, etag)?,
+                None if etag_file.exists() => fs::remove_file(&etag_file)?,
+                None => (),
+            }
+            Ok(copy)
+        }
+        Err(error) if copy.is_file() => {
+            eprintln!(
+                "warning: cannot refresh {}, using the copy downloaded before: {}",
+                url, error
+            );
+            Ok(copy)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(feature = "net")]
+fn fetch(url: &str, etag: Option<&str>) -> io::Result<Response> {
+    use std::io::Read;
+    let request = ureq::get(url);
+    let request = match etag {
+        Some(etag) => request.set("If-None-Match", etag),
+        None => request,
+    };
+    match request.call() {
+        Ok(response) if response.status() == 304 => Ok(Response::NotModified),
+        Ok(response) => {
+            let etag = response.header("ETag").map(str::to_string);
+            let mut body = Vec::new();
+            response.into_reader().read_to_end(&mut body)?;
+            Ok(Response::Fresh { body, etag })
+        }
+        Err(ureq::Error::Status(304, _)) => Ok(Response::NotModified),
+        Err(error) => Err(io::Error::other(error.to_string())),
+    }
+}
+
+#[cfg(not(feature = "net"))]
+fn fetch(_: &str, _: Option<&str>) -> io::Result<Response> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "this build cannot download word lists, build it with --features net",
+    ))
+}
+
+/// Local copy of the word list at `url`, downloaded again only when it changed
+pub fn download(url: &str) -> io::Result<PathBuf> {
+    let directory = cache_dir().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "no cache directory, set XDG_CACHE_HOME or HOME",
+        )
+    })?;
+    refresh(url, &directory, |etag| fetch(url, etag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_are_refreshed_when_they_change() {
+        let directory =
+            std::env::temp_dir().join(format!("typos-remote-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let url = "https://example.com/words.txt";
+        let fresh = |body: &str, etag: Option<&str>| Response::Fresh {
+            body: body.as_bytes().to_vec(),
+            etag: etag.map(str::to_string),
+        };
+        let offline = || io::Error::other("offline");
+
+        assert!(refresh(url, &directory, |_| Err(offline())).is_err());
+        let copy = refresh(url, &directory, |etag| {
+            assert_eq!(etag, None);
+            Ok(fresh("cold\n", Some("\"v1\"")))
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "cold\n");
+        let same = refresh(url, &directory, |etag| {
+            assert_eq!(etag, Some("\"v1\""));
+            Ok(Response::NotModified)
+        })
+        .unwrap();
+        assert_eq!(same, copy);
+        refresh(url, &directory, |_| Ok(fresh("warm\n", None))).unwrap();
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "warm\n");
+        // Without an ETag the next request asks for the whole list again
+        refresh(url, &directory, |etag| {
+            assert_eq!(etag, None);
+            Err(offline())
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&copy).unwrap(), "warm\n");
+
+        assert!(is_url(url) && is_url("http://x") && !is_url("words.txt"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
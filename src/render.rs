@@ -0,0 +1,365 @@
+use crate::events;
+use crate::matrix::quote;
+use std::io::{self, Write};
+use std::time::Duration;
+use typos::cache;
+use typos::distance::cost::{CostModel, Unit};
+use typos::distance::word::{self, Edit};
+use typos::distance::Locale;
+use typos::distance::{self, PathResult};
+
+/// A path ready to print: its words, its cost in the unit searched and the letters each
+/// hop changes
+pub struct Found {
+    pub words: Vec<String>,
+    pub cost: String,
+    pub hops: Vec<usize>,
+}
+
+impl Found {
+    pub fn new(path: PathResult, unit: Unit, locale: Locale) -> Found {
+        Found {
+            cost: distance::describe_cost_in(&path.cost, unit, locale),
+            hops: path.hops.iter().map(|hop| hop.distance).collect(),
+            words: path.words,
+        }
+    }
+
+    /// Cached paths only keep their words and cost, hops are measured again
+    pub fn cached(path: cache::CachedPath, model: &dyn CostModel) -> Found {
+        Found {
+            hops: path
+                .words
+                .windows(2)
+                .map(|hop| model.distance(&hop[0], &hop[1]))
+                .collect(),
+            words: path.words,
+            cost: path.cost,
+        }
+    }
+
+    fn event(&self) -> events::Event<'_> {
+        events::Event::Result {
+            path: Some((&self.words, &self.cost, &self.hops)),
+        }
+    }
+}
+
+// Both words of a hop as they are printed
+type Hop = fn(&str, &str) -> (String, String);
+
+fn plain(from: &str, to: &str) -> (String, String) {
+    (from.to_string(), to.to_string())
+}
+
+/// How the outcome of a search is printed
+pub trait Renderer {
+    /// The path found, in `took` unless it was cached
+    fn path(&self, out: &mut dyn Write, path: &Found, took: Option<Duration>) -> io::Result<()>;
+
+    /// The words are not connected, in at most `max_hops` hops if limited
+    fn no_path(&self, out: &mut dyn Write, max_hops: Option<usize>) -> io::Result<()>;
+
+    /// Several paths found in `took`, `kind` telling how they relate
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        kind: &str,
+        took: Duration,
+    ) -> io::Result<()>;
+}
+
+/// Sentences in the language of `locale`, every hop explained below the path with
+/// `--explain`
+pub struct Text {
+    pub locale: Locale,
+    pub explain: Option<Unit>,
+}
+
+impl Text {
+    // Every hop of `path` with the letters it changes, for --explain
+    fn explain(&self, out: &mut dyn Write, path: &Found, hop: Hop) -> io::Result<()> {
+        let unit = match self.explain {
+            Some(unit) => unit,
+            None => return Ok(()),
+        };
+        for (words, &distance) in path.words.windows(2).zip(&path.hops) {
+            let (from, to) = hop(&words[0], &words[1]);
+            let changed = self.locale.units_changed(distance, unit);
+            writeln!(out, "  {} -> {}: {}", from, to, changed)?;
+        }
+        Ok(())
+    }
+
+    fn path_with(
+        &self,
+        out: &mut dyn Write,
+        path: &Found,
+        took: Option<Duration>,
+        hop: Hop,
+    ) -> io::Result<()> {
+        let words = path.words.join("->");
+        writeln!(out, "{}", self.locale.path_found(&words, took, &path.cost))?;
+        self.explain(out, path, hop)
+    }
+
+    fn alternatives_with(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        kind: &str,
+        took: Duration,
+        hop: Hop,
+    ) -> io::Result<()> {
+        writeln!(out, "{} {} found in {:?}:", paths.len(), kind, took)?;
+        for path in paths {
+            writeln!(out, "{} (achieved in {})", path.words.join("->"), path.cost)?;
+            self.explain(out, path, hop)?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for Text {
+    fn path(&self, out: &mut dyn Write, path: &Found, took: Option<Duration>) -> io::Result<()> {
+        self.path_with(out, path, took, plain)
+    }
+
+    fn no_path(&self, out: &mut dyn Write, max_hops: Option<usize>) -> io::Result<()> {
+        writeln!(out, "{}", self.locale.no_path(max_hops))
+    }
+
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        kind: &str,
+        took: Duration,
+    ) -> io::Result<()> {
+        self.alternatives_with(out, paths, kind, took, plain)
+    }
+}
+
+/// The words of every path and nothing else, for `--quiet`
+pub struct Quiet;
+
+impl Renderer for Quiet {
+    fn path(&self, out: &mut dyn Write, path: &Found, _: Option<Duration>) -> io::Result<()> {
+        writeln!(out, "{}", path.words.join("->"))
+    }
+
+    fn no_path(&self, _: &mut dyn Write, _: Option<usize>) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        _: &str,
+        _: Duration,
+    ) -> io::Result<()> {
+        for path in paths {
+            self.path(out, path, None)?;
+        }
+        Ok(())
+    }
+}
+
+/// A result event per path, for `--format ndjson`
+pub struct Json;
+
+impl Renderer for Json {
+    fn path(&self, out: &mut dyn Write, path: &Found, _: Option<Duration>) -> io::Result<()> {
+        writeln!(out, "{}", path.event())
+    }
+
+    fn no_path(&self, out: &mut dyn Write, _: Option<usize>) -> io::Result<()> {
+        writeln!(out, "{}", events::Event::Result { path: None })
+    }
+
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        _: &str,
+        _: Duration,
+    ) -> io::Result<()> {
+        for path in paths {
+            self.path(out, path, None)?;
+        }
+        Ok(())
+    }
+}
+
+/// A header line, then a `path,cost,letters` line per path, for `--format csv`. Words
+/// are joined with `->` and the letters each hop changes with `;`, as `typos batch` does.
+pub struct Csv;
+
+impl Renderer for Csv {
+    fn path(&self, out: &mut dyn Write, path: &Found, took: Option<Duration>) -> io::Result<()> {
+        self.alternatives(
+            out,
+            std::slice::from_ref(path),
+            "",
+            took.unwrap_or_default(),
+        )
+    }
+
+    fn no_path(&self, out: &mut dyn Write, _: Option<usize>) -> io::Result<()> {
+        self.alternatives(out, &[], "", Duration::ZERO)
+    }
+
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        _: &str,
+        _: Duration,
+    ) -> io::Result<()> {
+        writeln!(out, "path,cost,letters")?;
+        for path in paths {
+            let letters: Vec<String> = path.hops.iter().map(usize::to_string).collect();
+            writeln!(
+                out,
+                "{},{},{}",
+                quote(&path.words.join("->")),
+                quote(&path.cost),
+                letters.join(";")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Both words of a hop, the letters removed or replaced from the first in red and those
+/// inserted or replacing them in the second in green
+pub fn highlight(from: &str, to: &str) -> (String, String) {
+    let colored = |out: &mut String, color, letter| {
+        out.push_str(color);
+        out.push(letter);
+        out.push_str(RESET);
+    };
+    let (mut old, mut new) = (String::new(), String::new());
+    for edit in word::alignment(from, to) {
+        match edit {
+            Edit::Keep { letter, .. } => {
+                old.push(letter);
+                new.push(letter);
+            }
+            Edit::Substitute {
+                old: removed,
+                new: added,
+                ..
+            } => {
+                colored(&mut old, RED, removed);
+                colored(&mut new, GREEN, added);
+            }
+            Edit::Insert { letter, .. } => colored(&mut new, GREEN, letter),
+            Edit::Delete { letter, .. } => colored(&mut old, RED, letter),
+        }
+    }
+    (old, new)
+}
+
+/// The text output with the letters every hop changes highlighted, for `--explain` on
+/// a terminal
+pub struct Colored(pub Text);
+
+impl Renderer for Colored {
+    fn path(&self, out: &mut dyn Write, path: &Found, took: Option<Duration>) -> io::Result<()> {
+        self.0.path_with(out, path, took, highlight)
+    }
+
+    fn no_path(&self, out: &mut dyn Write, max_hops: Option<usize>) -> io::Result<()> {
+        self.0.no_path(out, max_hops)
+    }
+
+    fn alternatives(
+        &self,
+        out: &mut dyn Write,
+        paths: &[Found],
+        kind: &str,
+        took: Duration,
+    ) -> io::Result<()> {
+        self.0.alternatives_with(out, paths, kind, took, highlight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rendered(render: impl Fn(&mut dyn Write) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        render(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn paths_render_in_every_format() {
+        let path = Found {
+            words: vec!["cold".to_string(), "cord".to_string(), "card".to_string()],
+            cost: "2 1-letter mutations".to_string(),
+            hops: vec![1, 1],
+        };
+        let text = Text {
+            locale: Locale::En,
+            explain: Some(Unit::Letter),
+        };
+        assert_eq!(
+            rendered(|out| text.alternatives(
+                out,
+                std::slice::from_ref(&path),
+                "disjoint paths",
+                Duration::ZERO
+            )),
+            "1 disjoint paths found in 0ns:\n\
+             cold->cord->card (achieved in 2 1-letter mutations)\n  \
+             cold -> cord: 1 letter changed\n  \
+             cord -> card: 1 letter changed\n"
+        );
+        assert_eq!(
+            rendered(|out| Quiet.path(out, &path, None)),
+            "cold->cord->card\n"
+        );
+        assert_eq!(rendered(|out| Quiet.no_path(out, None)), "");
+        assert_eq!(
+            rendered(|out| Json.path(out, &path, None)),
+            "{\"event\":\"result\",\"path\":[\"cold\",\"cord\",\"card\"],\"cost\":\"2 1-letter mutations\",\"hops\":[1,1]}\n"
+        );
+        assert_eq!(
+            rendered(|out| Csv.path(out, &path, None)),
+            "path,cost,letters\ncold->cord->card,2 1-letter mutations,1;1\n"
+        );
+        assert_eq!(
+            rendered(|out| Csv.no_path(out, Some(3))),
+            "path,cost,letters\n"
+        );
+        let colored = Colored(text);
+        assert!(rendered(|out| colored.path(out, &path, None)).ends_with(
+            "  co\x1b[31ml\x1b[0md -> co\x1b[32mr\x1b[0md: 1 letter changed\n  \
+                        c\x1b[31mo\x1b[0mrd -> c\x1b[32ma\x1b[0mrd: 1 letter changed\n"
+        ));
+    }
+
+    #[test]
+    fn hops_highlight_the_letters_they_change() {
+        assert_eq!(
+            highlight("cold", "scald"),
+            (
+                "c\x1b[31mo\x1b[0mld".to_string(),
+                "\x1b[32ms\x1b[0mc\x1b[32ma\x1b[0mld".to_string()
+            )
+        );
+        assert_eq!(
+            highlight("ward", "ward"),
+            ("ward".to_string(), "ward".to_string())
+        );
+    }
+}
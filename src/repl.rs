@@ -0,0 +1,338 @@
+use crate::session::Session;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use typos::distance::cost::{CostModel, CostModelName, Tokens, Unit};
+use typos::distance::{self, PathFindingAlgorithm, SearchControl, SearchOptions};
+
+const HELP: &str = "\
+path <start> <end>      shortest path between two words
+neighbors <word>        dictionary words one mutation away
+distance <a> <b>        number of units changed between two words
+set algorithm <name>    astar, fringe, idastar, idastar-tt, bounded-astar or dijkstra
+set cost <name>         levenshtein, ocr or phonetic
+set unit <name>         letter or token
+set timeout <ms|none>   abort searches running for too long
+session [clear]         words the paths of the session went through, or forget them
+show                    current settings
+quit                    leave
+Quote phrases containing spaces: path \"the cat\" \"a dog\"";
+
+/// Interactive session keeping a dictionary in memory between queries
+pub struct Repl {
+    words: Vec<String>,
+    algorithm: PathFindingAlgorithm,
+    cost: CostModelName,
+    unit: Unit,
+    model: Arc<dyn CostModel>,
+    timeout: Option<Duration>,
+    session: Option<Session>,
+    /// Paths keep out of the words of the session, but for their ends
+    no_repeat: bool,
+}
+
+// Arguments are separated by whitespace, double quotes keep phrases together
+fn split_arguments(line: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => arguments.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    arguments.extend(current);
+    arguments
+}
+
+impl Repl {
+    pub fn new(words: Vec<String>) -> Repl {
+        Repl {
+            words,
+            algorithm: PathFindingAlgorithm::Astar,
+            cost: CostModelName::Levenshtein,
+            unit: Unit::Letter,
+            model: crate::cost_model(CostModelName::Levenshtein, None).unwrap(),
+            timeout: None,
+            session: None,
+            no_repeat: false,
+        }
+    }
+
+    /// Record the words of every path found in `session`, and keep later paths out of
+    /// them with `no_repeat`
+    pub fn with_session(mut self, session: Session, no_repeat: bool) -> Repl {
+        self.session = Some(session);
+        self.no_repeat = no_repeat;
+        self
+    }
+
+    /// Answer one command line, `None` once the session is over
+    pub fn execute(&mut self, line: &str) -> Option<String> {
+        let arguments = split_arguments(line);
+        let arguments: Vec<&str> = arguments.iter().map(AsRef::as_ref).collect();
+        let answer = match arguments.as_slice() {
+            [] => String::new(),
+            ["quit"] | ["exit"] => return None,
+            ["help"] => HELP.to_string(),
+            ["show"] => format!(
+                "algorithm {}, cost {}, unit {}, timeout {}, {} words",
+                self.algorithm,
+                self.cost,
+                self.unit,
+                self.timeout
+                    .map_or("none".to_string(), |t| format!("{}ms", t.as_millis())),
+                self.words.len()
+            ),
+            ["path", start, stop] => self.path(
+                &typos::words::normalize(start),
+                &typos::words::normalize(stop),
+            ),
+            ["neighbors", word] => self.neighbors(&typos::words::normalize(word)),
+            ["distance", a, b] => {
+                let (a, b) = (typos::words::normalize(a), typos::words::normalize(b));
+                format!("{} {}s", self.model.distance(&a, &b), self.unit.noun())
+            }
+            ["set", option, value] => self.set(option, value),
+            ["session"] => match &self.session {
+                Some(session) if self.no_repeat => {
+                    format!("{} words used, left out of paths", session.used())
+                }
+                Some(session) => format!("{} words used", session.used()),
+                None => "No session, start with --session <file>".to_string(),
+            },
+            ["session", "clear"] => match &mut self.session {
+                Some(session) => match session.clear() {
+                    Ok(()) => "Session cleared".to_string(),
+                    Err(error) => format!("Cannot save the session: {}", error),
+                },
+                None => "No session, start with --session <file>".to_string(),
+            },
+            _ => format!("Unknown command {:?}, try help", line.trim()),
+        };
+        Some(answer)
+    }
+
+    fn path(&mut self, start: &str, stop: &str) -> String {
+        let mut words: Vec<&str> = Vec::with_capacity(self.words.len() + 1);
+        if !self.words.iter().any(|word| word == stop) {
+            words.push(stop);
+        }
+        let used = |word: &str| {
+            self.no_repeat
+                && word != start
+                && word != stop
+                && self.session.as_ref().is_some_and(|s| s.contains(word))
+        };
+        words.extend(
+            self.words
+                .iter()
+                .map(AsRef::<str>::as_ref)
+                .filter(|&word| !used(word)),
+        );
+        let control = match self.timeout {
+            Some(timeout) => SearchControl::new().with_timeout(timeout),
+            None => SearchControl::new(),
+        };
+        let options = SearchOptions::new(self.algorithm)
+            .with_cost_model(self.model.clone())
+            .with_control(control);
+        match distance::find_shortest_path_with_options(start, stop, words.as_slice(), &options).0 {
+            Ok(Some((path, cost))) => {
+                let found = format!(
+                    "{} (achieved in {})",
+                    path.join("->"),
+                    distance::describe_cost(&cost, self.unit)
+                );
+                match self.session.as_mut().map(|session| session.record(&path)) {
+                    Some(Err(error)) => format!("{}\nCannot save the session: {}", found, error),
+                    _ => found,
+                }
+            }
+            Ok(None) => "No path found".to_string(),
+            Err(interruption) => format!("No path found: {}", interruption),
+        }
+    }
+
+    fn neighbors(&self, word: &str) -> String {
+        let neighbors: Vec<&str> = self
+            .words
+            .iter()
+            .filter(|other| self.model.distance(word, other) == 1)
+            .map(AsRef::as_ref)
+            .collect();
+        match neighbors.as_slice() {
+            [] => format!("No word one {} away from {}", self.unit.noun(), word),
+            neighbors => neighbors.join(" "),
+        }
+    }
+
+    fn set(&mut self, option: &str, value: &str) -> String {
+        match option {
+            "algorithm" => match PathFindingAlgorithm::from_str(value) {
+                Ok(algorithm) => self.algorithm = algorithm,
+                Err(error) => {
+                    return format!(
+                        "Unknown algorithm {}, try {}",
+                        value,
+                        error.expected.join(" ")
+                    )
+                }
+            },
+            "cost" => match CostModelName::from_str(value) {
+                Ok(cost) => self.cost = cost,
+                Err(error) => {
+                    return format!(
+                        "Unknown cost model {}, try {}",
+                        value,
+                        error.expected.join(" ")
+                    )
+                }
+            },
+            "unit" => match Unit::from_str(value) {
+                Ok(unit) => self.unit = unit,
+                Err(_) => return format!("Unknown unit {}", value),
+            },
+            "timeout" => match (value, u64::from_str(value)) {
+                ("none", _) => self.timeout = None,
+                (_, Ok(milliseconds)) => self.timeout = Some(Duration::from_millis(milliseconds)),
+                _ => return format!("Invalid timeout {}", value),
+            },
+            _ => return format!("Unknown option {}", option),
+        }
+        // Whole words are compared in token mode whatever the cost model
+        self.model = match self.unit {
+            Unit::Token => Arc::new(Tokens),
+            Unit::Letter => crate::cost_model(self.cost, None).unwrap(),
+        };
+        format!("{} set to {}", option, value)
+    }
+}
+
+/// Read commands from `input` until it ends or the user quits
+pub fn run(repl: &mut Repl, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        match repl.execute(&line?) {
+            Some(answer) if answer.is_empty() => (),
+            Some(answer) => writeln!(output, "{}", answer)?,
+            None => return Ok(()),
+        }
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repl() -> Repl {
+        Repl::new(
+            ["cold", "cord", "card", "ward", "warm", "wore"]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn answers_queries() {
+        let mut repl = repl();
+        assert_eq!(
+            repl.execute("path cold warm").unwrap(),
+            "cold->cord->card->ward->warm (achieved in 4 1-letter mutations)"
+        );
+        assert_eq!(repl.execute("neighbors cord").unwrap(), "cold card");
+        assert_eq!(repl.execute("distance cold warm").unwrap(), "4 letters");
+        assert_eq!(repl.execute("quit"), None);
+    }
+
+    #[test]
+    fn settings_change_later_queries() {
+        let mut repl = repl();
+        assert_eq!(
+            repl.execute("set algorithm dijkstra").unwrap(),
+            "algorithm set to dijkstra"
+        );
+        assert_eq!(repl.execute("set unit token").unwrap(), "unit set to token");
+        assert_eq!(
+            repl.execute(r#"distance "the cat sat" "the dog sat""#)
+                .unwrap(),
+            "1 words"
+        );
+        assert!(repl
+            .execute("set algorithm bogus")
+            .unwrap()
+            .starts_with("Unknown"));
+        assert!(repl
+            .execute("show")
+            .unwrap()
+            .starts_with("algorithm dijkstra, cost levenshtein, unit token"));
+    }
+
+    #[test]
+    fn session_ends_on_quit() {
+        let mut output = Vec::new();
+        run(
+            &mut repl(),
+            &b"distance cold cord\nbogus\nquit\nshow\n"[..],
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "> 1 letters\n> Unknown command \"bogus\", try help\n> "
+        );
+    }
+
+    #[test]
+    fn sessions_keep_paths_out_of_used_words() {
+        let path = std::env::temp_dir().join(format!("typos-repl-{}", std::process::id()));
+        let words = ["cold", "cord", "cald", "card"];
+        let new = |no_repeat| {
+            Repl::new(words.iter().map(|w| w.to_string()).collect())
+                .with_session(Session::open(&path).unwrap(), no_repeat)
+        };
+        let mut repl = new(true);
+        let first = repl.execute("path cold card").unwrap();
+        let second = repl.execute("path cold card").unwrap();
+        assert!(first.starts_with("cold->c") && second.starts_with("cold->c"));
+        assert_ne!(first, second);
+        // Both words between them used, only the hop of 2 letters is left
+        assert!(repl
+            .execute("path cold card")
+            .unwrap()
+            .starts_with("cold->card "));
+        assert_eq!(
+            repl.execute("session").unwrap(),
+            "2 words used, left out of paths"
+        );
+        // Sessions go on in the next run, repeating words unless told otherwise
+        let mut repl = new(false);
+        assert_eq!(repl.execute("path cold card").unwrap(), first);
+        assert_eq!(repl.execute("session clear").unwrap(), "Session cleared");
+        assert_eq!(
+            new(true).execute("session").unwrap(),
+            "0 words used, left out of paths"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn quotes_group_arguments() {
+        assert_eq!(
+            split_arguments(r#"path "the cat"  dog"#),
+            vec!["path", "the cat", "dog"]
+        );
+        assert_eq!(split_arguments(r#"path """#), vec!["path", ""]);
+    }
+}
@@ -0,0 +1,174 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// Counts allocations for `--report`, one relaxed increment each is cheap enough to keep
+// it on in every run
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Allocations made since the process started, reallocations included, and the bytes
+/// they asked for
+pub fn allocations() -> (usize, usize) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+// Peak resident set size of a `/proc/<pid>/status` file, given in kB there
+fn peak_rss_of(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Most bytes of memory the process held at once, only known on Linux
+pub fn peak_rss() -> Option<u64> {
+    peak_rss_of(&fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// Value of a field of a report
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Count(u64),
+    Seconds(Duration),
+    Ratio(f64),
+    Name(&'static str),
+    /// Not measured on this platform or in this run
+    Unknown,
+}
+
+impl Value {
+    /// The value as JSON, names quoted and unknown values `null`
+    pub fn json(&self) -> String {
+        match self {
+            Value::Name(name) => format!("\"{}\"", name),
+            Value::Unknown => "null".to_string(),
+            value => value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Count(count) => write!(f, "{}", count),
+            Value::Seconds(duration) => write!(f, "{:.6}", duration.as_secs_f64()),
+            Value::Ratio(ratio) => write!(f, "{:.4}", ratio),
+            Value::Name(name) => write!(f, "{}", name),
+            Value::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// What a run of a search cost, as `--report` prints it
+#[derive(Debug, Clone, Default)]
+pub struct ResourceReport {
+    pub load: Duration,
+    pub index_build: Duration,
+    pub search: Duration,
+    pub expanded: usize,
+    /// Expansions whose successor buffer was recycled
+    pub recycled: usize,
+    /// Whether the result cache had the path, `None` without `--cache-dir`
+    pub result_cache: Option<bool>,
+    /// Whether the search outgrew `--max-memory` and went on with bounded-astar
+    pub memory_fallback: bool,
+}
+
+impl ResourceReport {
+    /// Every field with its name, the process-wide ones measured now
+    pub fn fields(&self) -> Vec<(&'static str, Value)> {
+        let (allocations, allocated_bytes) = allocations();
+        vec![
+            (
+                "peak_rss_bytes",
+                peak_rss().map_or(Value::Unknown, Value::Count),
+            ),
+            ("load_seconds", Value::Seconds(self.load)),
+            ("index_build_seconds", Value::Seconds(self.index_build)),
+            ("search_seconds", Value::Seconds(self.search)),
+            ("expanded_words", Value::Count(self.expanded as u64)),
+            (
+                "memory_fallback",
+                Value::Name(match self.memory_fallback {
+                    true => "bounded-astar",
+                    false => "none",
+                }),
+            ),
+            (
+                "result_cache",
+                match self.result_cache {
+                    Some(true) => Value::Name("hit"),
+                    Some(false) => Value::Name("miss"),
+                    None => Value::Name("off"),
+                },
+            ),
+            (
+                "successor_buffer_hit_rate",
+                match self.expanded {
+                    0 => Value::Unknown,
+                    expanded => Value::Ratio(self.recycled as f64 / expanded as f64),
+                },
+            ),
+            ("allocations", Value::Count(allocations as u64)),
+            ("allocated_bytes", Value::Count(allocated_bytes as u64)),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_measure_the_process() {
+        let status = "Name:\ttypos\nVmPeak:\t  10000 kB\nVmHWM:\t    2048 kB\nVmRSS:\t 1024 kB\n";
+        assert_eq!(peak_rss_of(status), Some(2048 * 1024));
+        assert_eq!(peak_rss_of("Name:\ttypos\n"), None);
+
+        let (before, _) = allocations();
+        let buffer: Vec<u8> = Vec::with_capacity(64);
+        assert!(allocations().0 > before);
+        drop(buffer);
+
+        let report = ResourceReport {
+            expanded: 4,
+            recycled: 3,
+            result_cache: Some(false),
+            ..ResourceReport::default()
+        };
+        let fields = report.fields();
+        let field = |name| &fields.iter().find(|(key, _)| *key == name).unwrap().1;
+        assert_eq!(field("successor_buffer_hit_rate").to_string(), "0.7500");
+        assert_eq!(field("result_cache").json(), "\"miss\"");
+        assert_eq!(field("search_seconds").to_string(), "0.000000");
+        assert_eq!(Value::Unknown.json(), "null");
+    }
+}
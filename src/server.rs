@@ -0,0 +1,450 @@
+use crate::server::metrics::Metrics;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use typos::cache::{dictionary_hash, CacheKey, CachedPath, LruCache};
+use typos::distance;
+use typos::distance::word;
+use typos::distance::{Interruption, PathFindingAlgorithm, SearchControl};
+use typos::words;
+
+mod metrics;
+
+struct Dictionary {
+    words: Vec<String>,
+    hash: u64,
+}
+
+/// Named word lists served by a single process
+pub struct Dictionaries {
+    default: String,
+    dictionaries: HashMap<String, Dictionary>,
+}
+
+impl Dictionaries {
+    /// The first dictionary is used when a request does not specify a `lang`
+    pub fn new(dictionaries: Vec<(String, Vec<String>)>) -> Dictionaries {
+        let default = dictionaries
+            .first()
+            .map(|(name, _)| name.clone())
+            .unwrap_or_default();
+        Dictionaries {
+            default,
+            dictionaries: dictionaries
+                .into_iter()
+                .map(|(name, words)| {
+                    let hash = dictionary_hash(&words);
+                    (name, Dictionary { words, hash })
+                })
+                .collect(),
+        }
+    }
+
+    fn get(&self, lang: Option<&str>) -> Option<&Dictionary> {
+        self.dictionaries.get(lang.unwrap_or(&self.default))
+    }
+}
+
+/// Tuning knobs of the server
+pub struct Options {
+    /// Number of results kept in memory, 0 disables the cache
+    pub cache_size: usize,
+    /// Searches running longer than this are aborted
+    pub timeout: Option<Duration>,
+}
+
+/// State shared by every connection
+struct Server {
+    dictionaries: Dictionaries,
+    metrics: Mutex<Metrics>,
+    // A single cache serves every dictionary, keys embed the dictionary hash
+    cache: Mutex<LruCache<CacheKey, Option<CachedPath>>>,
+    timeout: Option<Duration>,
+}
+
+impl Server {
+    fn new(dictionaries: Dictionaries, options: Options) -> Server {
+        let sizes = dictionaries
+            .dictionaries
+            .iter()
+            .map(|(lang, dictionary)| (lang.clone(), dictionary.words.len()))
+            .collect();
+        Server {
+            dictionaries,
+            metrics: Mutex::new(Metrics::new(sizes)),
+            cache: Mutex::new(LruCache::new(options.cache_size)),
+            timeout: options.timeout,
+        }
+    }
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Response {
+        Response {
+            status: "200 OK",
+            body,
+        }
+    }
+
+    fn bad_request(body: String) -> Response {
+        Response {
+            status: "400 Bad Request",
+            body,
+        }
+    }
+
+    fn not_found(body: String) -> Response {
+        Response {
+            status: "404 Not Found",
+            body,
+        }
+    }
+
+    fn unavailable(body: String) -> Response {
+        Response {
+            status: "503 Service Unavailable",
+            body,
+        }
+    }
+}
+
+/// Answer `GET /path?start=..&end=..[&lang=..][&algorithm=..]` and `GET /metrics` requests
+/// coming to `listener` until the process stops
+pub fn serve(
+    listener: TcpListener,
+    dictionaries: Dictionaries,
+    options: Options,
+) -> io::Result<()> {
+    let server = Arc::new(Server::new(dictionaries, options));
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &server) {
+                eprintln!("Connection error: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, server: &Server) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let response = match request_line
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["GET", target, _] => route(target, server),
+        _ => Response::bad_request("Only GET requests are supported".to_string()),
+    };
+    write!(
+        &stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )
+}
+
+fn route(target: &str, server: &Server) -> Response {
+    let start_time = Instant::now();
+    let (path, query) = match target.find('?') {
+        Some(i) => (&target[..i], &target[i + 1..]),
+        None => (target, ""),
+    };
+    let (label, response) = match path {
+        "/path" => ("/path", shortest_path(&parse_query(query), server)),
+        "/metrics" => (
+            "/metrics",
+            Response::ok(server.metrics.lock().unwrap().render()),
+        ),
+        _ => (
+            "other",
+            Response::not_found(format!("Unknown route {}", path)),
+        ),
+    };
+    server.metrics.lock().unwrap().record_request(
+        label,
+        &response.status[..3],
+        start_time.elapsed(),
+    );
+    response
+}
+
+fn shortest_path(params: &HashMap<String, String>, server: &Server) -> Response {
+    let (start, stop) = match (params.get("start"), params.get("end")) {
+        (Some(start), Some(stop)) => (words::normalize(start), words::normalize(stop)),
+        _ => return Response::bad_request("Missing start or end parameter".to_string()),
+    };
+    if let Err(error) = word::check_length(&start).and(word::check_length(&stop)) {
+        return Response::bad_request(error.to_string());
+    }
+    let algorithm = match params
+        .get("algorithm")
+        .map(|a| PathFindingAlgorithm::from_str(a))
+    {
+        None => PathFindingAlgorithm::Astar,
+        Some(Ok(algorithm)) => algorithm,
+        Some(Err(error)) => return Response::bad_request(error.to_string()),
+    };
+    let lang = params.get("lang").map(String::as_str);
+    let dictionary = match server.dictionaries.get(lang) {
+        Some(dictionary) => dictionary,
+        None => return Response::not_found(format!("Unknown dictionary {}", lang.unwrap_or(""))),
+    };
+    // Searches from or to a word outside of the list are refused, as on the command line
+    for endpoint in [&start, &stop] {
+        if !dictionary.words.contains(endpoint) {
+            let name = lang.unwrap_or(&server.dictionaries.default);
+            return Response::not_found(missing_endpoint(endpoint, name, &dictionary.words));
+        }
+    }
+
+    let key = CacheKey {
+        dictionary: dictionary.hash,
+        start,
+        end: stop,
+        options: algorithm.to_string(),
+    };
+    let cached = server.cache.lock().unwrap().get(&key);
+    server
+        .metrics
+        .lock()
+        .unwrap()
+        .record_cache(cached.is_some());
+    let result = match cached {
+        Some(result) => result,
+        None => match search(&key, &dictionary.words, &algorithm, server) {
+            Ok(result) => {
+                server.cache.lock().unwrap().insert(key, result.clone());
+                result
+            }
+            Err(interruption) => return Response::unavailable(interruption.to_string()),
+        },
+    };
+    match result {
+        Some(path) => Response::ok(format!(
+            "{} (achieved in {})",
+            path.words.join("->"),
+            path.cost
+        )),
+        None => Response::not_found("No path found".to_string()),
+    }
+}
+
+fn missing_endpoint(word: &str, lang: &str, words: &[String]) -> String {
+    let suggestions = words::suggestions(word, words, 3);
+    let mut message = format!("{} is not in the {} dictionary", word, lang);
+    if !suggestions.is_empty() {
+        message.push_str(&format!(", did you mean {}?", suggestions.join(", ")));
+    }
+    message
+}
+
+fn search(
+    key: &CacheKey,
+    words: &[String],
+    algorithm: &PathFindingAlgorithm,
+    server: &Server,
+) -> Result<Option<CachedPath>, Interruption> {
+    let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+    let control = match server.timeout {
+        Some(timeout) => SearchControl::new().with_timeout(timeout),
+        None => SearchControl::new(),
+    };
+    let (result, stats) = distance::find_shortest_path_with_control(
+        &key.start,
+        &key.end,
+        words.as_slice(),
+        algorithm,
+        &control,
+    );
+    server.metrics.lock().unwrap().record_search(stats.expanded);
+    Ok(result?.map(|(path, cost)| CachedPath {
+        words: path.iter().map(|w| w.to_string()).collect(),
+        cost: cost.to_string(),
+    }))
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.find('=') {
+            Some(i) => (percent_decode(&pair[..i]), percent_decode(&pair[i + 1..])),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex_value(bytes[i + 1]), hex_value(bytes[i + 2]))
+            {
+                (Some(high), Some(low)) => {
+                    decoded.push(high * 16 + low);
+                    i += 2;
+                }
+                _ => decoded.push(b'%'),
+            },
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|digit| digit as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> Server {
+        Server::new(
+            Dictionaries::new(vec![
+                (
+                    "en".to_string(),
+                    vec!["cold", "cord", "card", "ward", "warm"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                ),
+                (
+                    "fr".to_string(),
+                    vec!["banane", "banan", "banon"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                ),
+            ]),
+            Options {
+                cache_size: 16,
+                timeout: None,
+            },
+        )
+    }
+
+    #[test]
+    fn query_decoding() {
+        let params = parse_query("start=caf%C3%A9&end=a+b&lang");
+        assert_eq!(params.get("start").unwrap(), "café");
+        assert_eq!(params.get("end").unwrap(), "a b");
+        assert_eq!(params.get("lang").unwrap(), "");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn routes_to_default_dictionary() {
+        let response = route("/path?start=cold&end=warm", &server());
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.starts_with("cold->cord->card->ward->warm"));
+    }
+
+    #[test]
+    fn routes_to_requested_dictionary() {
+        let response = route("/path?start=banane&end=banon&lang=fr", &server());
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.starts_with("banane->banan->banon"));
+        let response = route("/path?start=banane&end=banon&lang=de", &server());
+        assert_eq!(response.status, "404 Not Found");
+    }
+
+    #[test]
+    fn refuses_words_outside_of_the_dictionary() {
+        let response = route("/path?start=cold&end=warm&lang=fr", &server());
+        assert_eq!(response.status, "404 Not Found");
+        assert!(response
+            .body
+            .starts_with("cold is not in the fr dictionary"));
+        let response = route("/path?start=cold&end=wart", &server());
+        assert_eq!(response.status, "404 Not Found");
+        assert_eq!(
+            response.body,
+            "wart is not in the en dictionary, did you mean ward, warm, card?"
+        );
+        // Words are looked up as the command line does, case and spaces aside
+        let response = route("/path?start=+Cold+&end=Warm", &server());
+        assert_eq!(response.status, "200 OK");
+    }
+
+    #[test]
+    fn exposes_metrics() {
+        let server = server();
+        route("/path?start=cold&end=warm", &server);
+        let response = route("/metrics", &server);
+        assert_eq!(response.status, "200 OK");
+        assert!(response
+            .body
+            .contains("typos_requests_total{route=\"/path\",status=\"200\"} 1"));
+        assert!(response
+            .body
+            .contains("typos_search_expanded_nodes_count 1"));
+        assert!(response
+            .body
+            .contains("typos_dictionary_words{lang=\"fr\"} 3"));
+    }
+
+    #[test]
+    fn caches_results_per_dictionary() {
+        let server = server();
+        let first = route("/path?start=cold&end=warm", &server);
+        let second = route("/path?start=COLD&end=warm&lang=en", &server);
+        assert_eq!(first.body, second.body);
+        route("/path?start=banane&end=banon&lang=fr", &server);
+        let response = route("/metrics", &server);
+        assert!(response
+            .body
+            .contains("typos_cache_requests_total{result=\"hit\"} 1"));
+        assert!(response
+            .body
+            .contains("typos_cache_requests_total{result=\"miss\"} 2"));
+        assert!(response
+            .body
+            .contains("typos_search_expanded_nodes_count 2"));
+    }
+
+    #[test]
+    fn aborts_searches_past_the_deadline() {
+        let server = Server::new(
+            Dictionaries::new(vec![(
+                "en".to_string(),
+                vec!["cold".to_string(), "warm".to_string()],
+            )]),
+            Options {
+                cache_size: 16,
+                timeout: Some(Duration::from_secs(0)),
+            },
+        );
+        let response = route("/path?start=cold&end=warm", &server);
+        assert_eq!(response.status, "503 Service Unavailable");
+        assert_eq!(response.body, "search deadline exceeded");
+    }
+
+    #[test]
+    fn rejects_incomplete_requests() {
+        assert_eq!(
+            route("/path?start=cold", &server()).status,
+            "400 Bad Request"
+        );
+        assert_eq!(route("/unknown", &server()).status, "404 Not Found");
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0];
+const EXPANSION_BUCKETS: [f64; 7] = [1.0, 10.0, 100.0, 1e3, 1e4, 1e5, 1e6];
+
+/// Cumulative histogram in the Prometheus exposition sense
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        self.bounds
+            .iter()
+            .zip(self.counts.iter_mut())
+            .filter(|(bound, _)| value <= **bound)
+            .for_each(|(_, count)| *count += 1);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        writeln!(out, "# HELP {} {}", name, help).unwrap();
+        writeln!(out, "# TYPE {} histogram", name).unwrap();
+        for (bound, count) in self.bounds.iter().zip(self.counts.iter()) {
+            writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count).unwrap();
+        }
+        writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count).unwrap();
+        writeln!(out, "{}_sum {}", name, self.sum).unwrap();
+        writeln!(out, "{}_count {}", name, self.count).unwrap();
+    }
+}
+
+/// Server-wide counters exposed on `/metrics`
+pub struct Metrics {
+    requests: BTreeMap<(String, &'static str), u64>,
+    latency: Histogram,
+    expanded: Histogram,
+    cache_hits: u64,
+    cache_misses: u64,
+    dictionary_sizes: BTreeMap<String, usize>,
+}
+
+impl Metrics {
+    pub fn new(dictionary_sizes: BTreeMap<String, usize>) -> Metrics {
+        Metrics {
+            requests: BTreeMap::new(),
+            latency: Histogram::new(&LATENCY_BUCKETS),
+            expanded: Histogram::new(&EXPANSION_BUCKETS),
+            cache_hits: 0,
+            cache_misses: 0,
+            dictionary_sizes,
+        }
+    }
+
+    pub fn record_request(&mut self, route: &str, status: &'static str, latency: Duration) {
+        *self
+            .requests
+            .entry((route.to_string(), status))
+            .or_insert(0) += 1;
+        self.latency.observe(latency.as_secs_f64());
+    }
+
+    pub fn record_search(&mut self, expanded: usize) {
+        self.expanded.observe(expanded as f64);
+    }
+
+    pub fn record_cache(&mut self, hit: bool) {
+        if hit {
+            self.cache_hits += 1;
+        } else {
+            self.cache_misses += 1;
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "# HELP typos_requests_total HTTP requests answered").unwrap();
+        writeln!(out, "# TYPE typos_requests_total counter").unwrap();
+        for ((route, status), count) in &self.requests {
+            writeln!(
+                out,
+                "typos_requests_total{{route=\"{}\",status=\"{}\"}} {}",
+                route, status, count
+            )
+            .unwrap();
+        }
+        self.latency.render(
+            &mut out,
+            "typos_request_duration_seconds",
+            "Time spent answering HTTP requests",
+        );
+        self.expanded.render(
+            &mut out,
+            "typos_search_expanded_nodes",
+            "Words expanded by each shortest path search",
+        );
+        writeln!(
+            out,
+            "# HELP typos_cache_requests_total Result cache lookups"
+        )
+        .unwrap();
+        writeln!(out, "# TYPE typos_cache_requests_total counter").unwrap();
+        writeln!(
+            out,
+            "typos_cache_requests_total{{result=\"hit\"}} {}",
+            self.cache_hits
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "typos_cache_requests_total{{result=\"miss\"}} {}",
+            self.cache_misses
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "# HELP typos_dictionary_words Words loaded per dictionary"
+        )
+        .unwrap();
+        writeln!(out, "# TYPE typos_dictionary_words gauge").unwrap();
+        for (lang, size) in &self.dictionary_sizes {
+            writeln!(out, "typos_dictionary_words{{lang=\"{}\"}} {}", lang, size).unwrap();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_is_cumulative() {
+        let mut histogram = Histogram::new(&EXPANSION_BUCKETS);
+        histogram.observe(5.0);
+        histogram.observe(500.0);
+        assert_eq!(histogram.counts, vec![0, 1, 1, 2, 2, 2, 2]);
+        assert_eq!(histogram.count, 2);
+        assert_eq!(histogram.sum, 505.0);
+    }
+
+    #[test]
+    fn render_exposition_format() {
+        let mut sizes = BTreeMap::new();
+        sizes.insert("en".to_string(), 42);
+        let mut metrics = Metrics::new(sizes);
+        metrics.record_request("/path", "200", Duration::from_millis(3));
+        metrics.record_search(12);
+        metrics.record_cache(false);
+        let rendered = metrics.render();
+        assert!(rendered.contains("typos_requests_total{route=\"/path\",status=\"200\"} 1"));
+        assert!(rendered.contains("typos_request_duration_seconds_bucket{le=\"0.005\"} 1"));
+        assert!(rendered.contains("typos_search_expanded_nodes_bucket{le=\"+Inf\"} 1"));
+        assert!(rendered.contains("typos_cache_requests_total{result=\"miss\"} 1"));
+        assert!(rendered.contains("typos_dictionary_words{lang=\"en\"} 42"));
+    }
+}
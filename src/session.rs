@@ -0,0 +1,102 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Words the paths of earlier games went through, kept in a file from a run to the next
+/// so that a series of puzzles may avoid them
+pub struct Session {
+    path: PathBuf,
+    used: BTreeSet<String>,
+}
+
+impl Session {
+    /// The session saved at `path`, empty until it is first saved
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Session> {
+        let path = path.into();
+        let mut used = BTreeSet::new();
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    let word = line.trim();
+                    if !word.is_empty() && !word.starts_with('#') {
+                        used.insert(word.to_string());
+                    }
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => return Err(error),
+        }
+        Ok(Session { path, used })
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.used.contains(word)
+    }
+
+    /// Number of words recorded
+    pub fn used(&self) -> usize {
+        self.used.len()
+    }
+
+    /// Record the words `path` goes through between its ends and save the session,
+    /// telling how many were not recorded yet
+    pub fn record(&mut self, path: &[&str]) -> io::Result<usize> {
+        let inner = match path {
+            [_, inner @ .., _] => inner,
+            _ => &[],
+        };
+        let before = self.used.len();
+        self.used.extend(inner.iter().map(|word| word.to_string()));
+        self.save()?;
+        Ok(self.used.len() - before)
+    }
+
+    /// Forget every word and save the empty session
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.used.clear();
+        self.save()
+    }
+
+    // A word per line in alphabetical order, the file replaced at once as checkpoints are
+    fn save(&self) -> io::Result<()> {
+        let mut written = self.path.as_os_str().to_owned();
+        written.push(".tmp");
+        let mut writer = BufWriter::new(File::create(&written)?);
+        for word in &self.used {
+            writeln!(writer, "{}", word)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&written, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_keep_inner_words_between_runs() {
+        let path = std::env::temp_dir().join(format!("typos-session-{}", std::process::id()));
+        let mut session = Session::open(&path).unwrap();
+        assert_eq!(session.used(), 0);
+        assert_eq!(
+            session
+                .record(&["cold", "cord", "card", "ward", "warm"])
+                .unwrap(),
+            3
+        );
+        assert_eq!(session.record(&["card", "ward", "warm"]).unwrap(), 0);
+        assert_eq!(session.record(&["cold"]).unwrap(), 0);
+        let mut reopened = Session::open(&path).unwrap();
+        assert!(reopened.contains("card") && !reopened.contains("cold"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "card\ncord\nward\n");
+        reopened.clear().unwrap();
+        assert_eq!(Session::open(&path).unwrap().used(), 0);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,360 @@
+use crate::pipe::split_words;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How the words to check are picked out of a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Every word
+    Prose,
+    /// Every word, camelCase and PascalCase identifiers split into their parts
+    Identifiers,
+    /// Words of the comments only, identifiers split
+    Comments,
+    /// Words outside code blocks, code spans and links
+    Markdown,
+}
+
+impl Mode {
+    /// Name accepted by `from_str`
+    pub fn name(self) -> &'static str {
+        match self {
+            Mode::Prose => "prose",
+            Mode::Identifiers => "identifiers",
+            Mode::Comments => "comments",
+            Mode::Markdown => "markdown",
+        }
+    }
+
+    /// Mode suiting files with this extension: comments of source code, markdown, or prose
+    pub fn for_extension(extension: &str) -> Mode {
+        match extension {
+            "md" | "markdown" | "mdx" => Mode::Markdown,
+            _ if syntax(extension).is_some() => Mode::Comments,
+            _ => Mode::Prose,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Mode, ()> {
+        match s {
+            "prose" => Ok(Mode::Prose),
+            "identifiers" => Ok(Mode::Identifiers),
+            "comments" => Ok(Mode::Comments),
+            "markdown" => Ok(Mode::Markdown),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Mode of every file, chosen from its extension unless overridden
+#[derive(Debug, Default)]
+pub struct Modes {
+    default: Option<Mode>,
+    extensions: HashMap<String, Mode>,
+}
+
+impl Modes {
+    /// Overrides given as `mode` for every file or `extension=mode`, the last one winning
+    pub fn parse<'a>(specs: impl IntoIterator<Item = &'a str>) -> Result<Modes, String> {
+        let mut modes = Modes::default();
+        for spec in specs {
+            let (extension, name) = match spec.split_once('=') {
+                Some((extension, name)) => (Some(extension.trim_start_matches('.')), name),
+                None => (None, spec),
+            };
+            let mode = name
+                .parse()
+                .map_err(|()| format!("unknown token mode {}", name))?;
+            match extension {
+                Some(extension) => modes.extensions.insert(extension.to_string(), mode),
+                None => modes.default.replace(mode),
+            };
+        }
+        Ok(modes)
+    }
+
+    pub fn for_path(&self, path: &str) -> Mode {
+        let extension = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match self.extensions.get(&extension) {
+            Some(&mode) => mode,
+            None => self
+                .default
+                .unwrap_or_else(|| Mode::for_extension(&extension)),
+        }
+    }
+}
+
+/// A word to check, at a line and a column in characters, both counted from 0
+#[derive(Debug, PartialEq)]
+pub struct Token {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+// Comment markers of a language
+struct Syntax {
+    line: &'static str,
+    block: Option<(&'static str, &'static str)>,
+}
+
+fn syntax(extension: &str) -> Option<Syntax> {
+    let slashes = Syntax {
+        line: "//",
+        block: Some(("/*", "*/")),
+    };
+    match extension {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "java" | "js" | "jsx" | "ts"
+        | "tsx" | "kt" | "swift" | "scala" | "php" => Some(slashes),
+        "py" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "toml" | "yaml" | "yml" => {
+            Some(Syntax {
+                line: "#",
+                block: None,
+            })
+        }
+        "lua" | "sql" | "hs" => Some(Syntax {
+            line: "--",
+            block: None,
+        }),
+        _ => None,
+    }
+}
+
+/// Parts of a camelCase or PascalCase word, acronyms kept whole: "parseHTTPResponse" gives
+/// "parse", "HTTP" and "Response", with their character offsets
+pub fn split_identifier(word: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let upper = chars[i].is_uppercase();
+        let boundary = (upper && chars[i - 1].is_lowercase())
+            || (upper
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|c| c.is_lowercase()));
+        if boundary {
+            parts.push((start, chars[start..i].iter().collect()));
+            start = i;
+        }
+    }
+    parts.push((start, chars[start..].iter().collect()));
+    parts
+}
+
+// Every line with the characters outside comments blanked, so offsets are kept
+fn comments(lines: &[&str], syntax: &Syntax) -> Vec<String> {
+    let mut in_block = false;
+    let mut texts = Vec::new();
+    for line in lines {
+        let mut text = String::new();
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut rest = *line;
+        while let Some(c) = rest.chars().next() {
+            let marker = match syntax.block {
+                Some((_, close)) if in_block && rest.starts_with(close) => Some(close),
+                Some((open, _)) if !in_block && !in_string && rest.starts_with(open) => Some(open),
+                _ => None,
+            };
+            if let Some(marker) = marker {
+                in_block = !in_block;
+                text.extend(marker.chars().map(|_| ' '));
+                rest = &rest[marker.len()..];
+                continue;
+            }
+            if in_block {
+                text.push(c);
+            } else if !in_string && rest.starts_with(syntax.line) {
+                text.push_str(rest);
+                break;
+            } else {
+                // Strings may hold comment markers, as in "http://"
+                match (in_string, escaped, c) {
+                    (true, false, '\\') => escaped = true,
+                    (_, false, '"') => in_string = !in_string,
+                    _ => escaped = false,
+                }
+                text.push(' ');
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        texts.push(text);
+    }
+    texts
+}
+
+// Every line with code blocks, code spans, link targets and URLs blanked
+fn markdown(lines: &[&str]) -> Vec<String> {
+    let mut fence = None;
+    let mut texts = Vec::new();
+    for line in lines {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) if trimmed.starts_with(marker) => fence = None,
+            Some(_) => (),
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => {
+                texts.push(markdown_line(line));
+                continue;
+            }
+        }
+        texts.push(String::new());
+    }
+    texts
+}
+
+fn markdown_line(line: &str) -> String {
+    let mut text = String::new();
+    let (mut in_code, mut in_target, mut in_url) = (false, false, false);
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if !in_code && (rest.starts_with("http://") || rest.starts_with("https://")) {
+            in_url = true;
+        }
+        let blank = in_code || in_target || in_url;
+        match c {
+            '`' => in_code = !in_code,
+            '(' if !in_code && text.ends_with(']') => in_target = true,
+            ')' if in_target => in_target = false,
+            c if c.is_whitespace() => in_url = false,
+            _ => (),
+        }
+        text.push(match blank || c == '`' {
+            true => ' ',
+            false => c,
+        });
+        rest = &rest[c.len_utf8()..];
+    }
+    text
+}
+
+/// Words of `text` to check, read from `path` as `mode` requires
+pub fn tokens(mode: Mode, path: &str, text: &str) -> Vec<Token> {
+    let lines: Vec<&str> = text.lines().collect();
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let texts = match mode {
+        Mode::Prose | Mode::Identifiers => lines.iter().map(|line| line.to_string()).collect(),
+        // Files without known comment markers most likely use the C ones
+        Mode::Comments => comments(
+            &lines,
+            &syntax(&extension).unwrap_or(Syntax {
+                line: "//",
+                block: Some(("/*", "*/")),
+            }),
+        ),
+        Mode::Markdown => markdown(&lines),
+    };
+    let mut tokens = Vec::new();
+    for (line, text) in texts.iter().enumerate() {
+        for (column, word) in split_words(text) {
+            match mode {
+                Mode::Identifiers | Mode::Comments => tokens.extend(
+                    split_identifier(&word)
+                        .into_iter()
+                        .map(|(offset, text)| Token {
+                            line,
+                            column: column + offset,
+                            text,
+                        }),
+                ),
+                Mode::Prose | Mode::Markdown => tokens.push(Token {
+                    line,
+                    column,
+                    text: word,
+                }),
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(mode: Mode, path: &str, text: &str) -> Vec<String> {
+        tokens(mode, path, text)
+            .into_iter()
+            .map(|token| format!("{}:{}:{}", token.line, token.column, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn identifiers_are_split() {
+        assert_eq!(
+            split_identifier("parseHTTPResponse"),
+            vec![
+                (0, "parse".to_string()),
+                (5, "HTTP".to_string()),
+                (9, "Response".to_string())
+            ]
+        );
+        assert_eq!(
+            words(Mode::Identifiers, "a.rs", "let max_hopCount = x;"),
+            vec!["0:0:let", "0:4:max", "0:8:hop", "0:11:Count", "0:19:x"]
+        );
+        assert_eq!(words(Mode::Prose, "a.rs", "hopCount"), vec!["0:0:hopCount"]);
+    }
+
+    #[test]
+    fn comments_only() {
+        let text = "let url = \"http://x\"; // fetchIt\n/* one\ntwo */ code /* three */\n";
+        assert_eq!(
+            words(Mode::Comments, "a.rs", text),
+            vec!["0:25:fetch", "0:30:It", "1:3:one", "2:0:two", "2:15:three"]
+        );
+        assert_eq!(
+            words(Mode::Comments, "a.py", "x = 1  # setUp\n"),
+            vec!["0:9:set", "0:12:Up"]
+        );
+    }
+
+    #[test]
+    fn markdown_skips_code_and_links() {
+        let text = "See `fooBar` [the docs](docs/x.md) at https://x.org now\n```\ncode\n```\nend\n";
+        assert_eq!(
+            words(Mode::Markdown, "a.md", text),
+            vec![
+                "0:0:See",
+                "0:14:the",
+                "0:18:docs",
+                "0:35:at",
+                "0:52:now",
+                "4:0:end"
+            ]
+        );
+    }
+
+    #[test]
+    fn modes_by_extension() {
+        let modes = Modes::parse(vec![]).unwrap();
+        assert_eq!(modes.for_path("src/main.rs"), Mode::Comments);
+        assert_eq!(modes.for_path("README.md"), Mode::Markdown);
+        assert_eq!(modes.for_path("notes.txt"), Mode::Prose);
+        assert_eq!(modes.for_path("-"), Mode::Prose);
+        let modes = Modes::parse(vec!["identifiers", ".md=prose", "txt=markdown"]).unwrap();
+        assert_eq!(modes.for_path("src/main.rs"), Mode::Identifiers);
+        assert_eq!(modes.for_path("README.MD"), Mode::Prose);
+        assert_eq!(modes.for_path("notes.txt"), Mode::Markdown);
+        assert!(Modes::parse(vec!["rs=code"]).is_err());
+    }
+}
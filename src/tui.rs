@@ -0,0 +1,256 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, Paragraph, Wrap};
+use ratatui::Frame;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use typos::distance::cost::Unit;
+use typos::distance::path::PathMultiCost;
+use typos::distance::word::EditDistance;
+use typos::distance::{
+    self, CancellationToken, Interruption, SearchControl, SearchObserver, SearchOptions,
+    SearchStats, ShortestPath,
+};
+
+type Cost = PathMultiCost<EditDistance>;
+
+// Number of recently expanded words listed
+const RECENT: usize = 30;
+const REFRESH: Duration = Duration::from_millis(50);
+
+/// What the interface shows, filled from the search thread
+#[derive(Default)]
+struct Progress {
+    closed: HashSet<String>,
+    // Cheapest known cost of every word reached and the word it was reached from
+    reached: HashMap<String, (Cost, Option<String>)>,
+    recent: VecDeque<(String, Cost)>,
+    // Expanded word closest to the end according to the heuristic
+    closest: Option<(String, Cost)>,
+}
+
+impl Progress {
+    fn open(&self) -> usize {
+        self.reached.len().saturating_sub(self.closed.len())
+    }
+
+    /// Path from the start to the closest word expanded so far
+    fn best_path(&self) -> Option<(Vec<&str>, Cost)> {
+        let (closest, _) = self.closest.as_ref()?;
+        let (cost, _) = self.reached.get(closest)?;
+        let mut path = vec![closest.as_str()];
+        while let Some((_, Some(parent))) = self.reached.get(*path.last().unwrap()) {
+            // Parents only change to strictly cheaper ones, this guards against cycles anyway
+            if path.len() > self.closed.len() {
+                break;
+            }
+            path.push(parent);
+        }
+        path.reverse();
+        Some((path, *cost))
+    }
+}
+
+/// Observer relaxing edges like the algorithm does, to follow its progress
+struct Tracker {
+    progress: Mutex<Progress>,
+}
+
+impl Tracker {
+    fn new(start: &str) -> Tracker {
+        let mut progress = Progress::default();
+        progress
+            .reached
+            .insert(start.to_string(), (Cost::new(0, 0), None));
+        Tracker {
+            progress: Mutex::new(progress),
+        }
+    }
+}
+
+impl SearchObserver for Tracker {
+    fn expanded(&self, word: &str, heuristic: Cost, successors: &[(&str, Cost)]) {
+        let mut progress = self.progress.lock().unwrap();
+        progress.closed.insert(word.to_string());
+        let base = progress
+            .reached
+            .get(word)
+            .map_or(Cost::new(0, 0), |(cost, _)| *cost);
+        for &(successor, hop) in successors {
+            let cost = base + hop;
+            match progress.reached.get_mut(successor) {
+                Some(known) if known.0 <= cost => (),
+                Some(known) => *known = (cost, Some(word.to_string())),
+                None => {
+                    progress
+                        .reached
+                        .insert(successor.to_string(), (cost, Some(word.to_string())));
+                }
+            }
+        }
+        progress.recent.push_front((word.to_string(), heuristic));
+        progress.recent.truncate(RECENT);
+        if progress
+            .closest
+            .as_ref()
+            .is_none_or(|(_, closest)| heuristic < *closest)
+        {
+            progress.closest = Some((word.to_string(), heuristic));
+        }
+    }
+}
+
+fn draw(
+    frame: &mut Frame,
+    progress: &Progress,
+    header: &str,
+    elapsed: Duration,
+    status: &str,
+    unit: Unit,
+) {
+    let [top, middle, bottom] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Min(3),
+        Constraint::Length(3),
+    ])
+    .areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(middle);
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(header.to_string()),
+            Line::from(format!(
+                "closed set {}   open set {}   elapsed {:?}",
+                progress.closed.len(),
+                progress.open(),
+                elapsed
+            )),
+        ])
+        .block(Block::bordered().title("Search")),
+        top,
+    );
+    frame.render_widget(
+        List::new(progress.recent.iter().map(|(word, heuristic)| {
+            format!("{}  h={}", word, heuristic.as_slice().last().unwrap())
+        }))
+        .block(Block::bordered().title("Last expanded words")),
+        left,
+    );
+    let best = match progress.best_path() {
+        Some((path, cost)) => format!(
+            "{} ({})",
+            path.join("->"),
+            distance::describe_cost(&cost, unit)
+        ),
+        None => String::new(),
+    };
+    frame.render_widget(
+        Paragraph::new(best)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title("Best path so far")),
+        right,
+    );
+    frame.render_widget(
+        Paragraph::new(status.to_string()).block(Block::bordered()),
+        bottom,
+    );
+}
+
+// True once q or Esc is pressed, waiting at most `timeout`
+fn quit_pressed(timeout: Duration) -> io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+    Ok(matches!(
+        event::read()?,
+        Event::Key(key) if key.kind == KeyEventKind::Press
+            && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+    ))
+}
+
+/// Run the search while drawing its progress, q cancels it then leaves
+pub fn search<'a>(
+    start: &'a str,
+    stop: &str,
+    words: &'a [&str],
+    options: SearchOptions,
+    unit: Unit,
+) -> io::Result<(Result<Option<ShortestPath<'a>>, Interruption>, SearchStats)> {
+    let tracker = Arc::new(Tracker::new(start));
+    let token = CancellationToken::new();
+    let options = options
+        .with_control(SearchControl::new().with_cancellation(token.clone()))
+        .with_observer(tracker.clone());
+    let header = format!("{} from {} to {}", options.algorithm(), start, stop);
+    // The terminal may be left in raw mode when only part of the setup went through
+    let mut terminal = ratatui::try_init().inspect_err(|_| ratatui::restore())?;
+    let start_time = Instant::now();
+    let outcome = thread::scope(|scope| {
+        let search =
+            scope.spawn(|| distance::find_shortest_path_with_options(start, stop, words, &options));
+        while !search.is_finished() {
+            let progress = tracker.progress.lock().unwrap();
+            terminal.draw(|frame| {
+                draw(
+                    frame,
+                    &progress,
+                    &header,
+                    start_time.elapsed(),
+                    "q to cancel",
+                    unit,
+                )
+            })?;
+            drop(progress);
+            if quit_pressed(REFRESH)? {
+                token.cancel();
+            }
+        }
+        let elapsed = start_time.elapsed();
+        let (result, stats) = search.join().unwrap();
+        let status = match &result {
+            Ok(Some((path, cost))) => format!(
+                "Shortest path: {} ({}), q to quit",
+                path.join("->"),
+                distance::describe_cost(cost, unit)
+            ),
+            Ok(None) => "No path found, q to quit".to_string(),
+            Err(interruption) => format!("{}, q to quit", interruption),
+        };
+        let progress = tracker.progress.lock().unwrap();
+        while !token.is_cancelled() {
+            terminal.draw(|frame| draw(frame, &progress, &header, elapsed, &status, unit))?;
+            if quit_pressed(REFRESH)? {
+                break;
+            }
+        }
+        Ok((result, stats))
+    });
+    ratatui::restore();
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_follows_the_best_path() {
+        let tracker = Tracker::new("cold");
+        let hop = |n| typos::distance::word::hop_cost(n);
+        let h = |n| typos::distance::word::hops_lower_bound(n);
+        tracker.expanded("cold", h(4), &[("cord", hop(1)), ("card", hop(2))]);
+        tracker.expanded("cord", h(3), &[("card", hop(1)), ("cold", hop(1))]);
+        tracker.expanded("card", h(2), &[("ward", hop(1))]);
+        let progress = tracker.progress.lock().unwrap();
+        assert_eq!(progress.closed.len(), 3);
+        assert_eq!(progress.open(), 1);
+        assert_eq!(progress.recent.front().unwrap().0, "card");
+        let (path, cost) = progress.best_path().unwrap();
+        assert_eq!(path, vec!["cold", "cord", "card"]);
+        assert_eq!(cost.get_cost(), vec![(2, 1)]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str;
+use typos::distance::word;
+
+const BOM: &str = "\u{feff}";
+
+/// Problem found on a line of a word list
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    Empty,
+    InvalidUtf8,
+    ByteOrderMark,
+    CarriageReturn,
+    NonAlphabetic(String),
+    Duplicate { word: String, first_line: usize },
+    CaseDuplicate { word: String, first_line: usize },
+    TooLong(word::WordTooLong),
+}
+
+impl Issue {
+    // Entries that are still usable as they are, only reported
+    fn is_warning(&self) -> bool {
+        matches!(self, Issue::NonAlphabetic(_))
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::Empty => write!(f, "empty line"),
+            Issue::InvalidUtf8 => write!(f, "not valid UTF-8"),
+            Issue::ByteOrderMark => write!(f, "byte order mark"),
+            Issue::CarriageReturn => write!(f, "carriage return at end of line"),
+            Issue::NonAlphabetic(word) => write!(f, "non-alphabetic entry {:?}", word),
+            Issue::Duplicate { word, first_line } => {
+                write!(f, "{:?} duplicates line {}", word, first_line)
+            }
+            Issue::CaseDuplicate { word, first_line } => write!(
+                f,
+                "{:?} duplicates line {} once case is folded",
+                word, first_line
+            ),
+            Issue::TooLong(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Issues of a word list by line number, and the entries worth keeping
+#[derive(Debug, Default)]
+pub struct Report {
+    pub lines: usize,
+    pub issues: Vec<(usize, Issue)>,
+    pub clean: Vec<String>,
+}
+
+impl Report {
+    pub fn errors(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|(_, issue)| !issue.is_warning())
+            .count()
+    }
+
+    pub fn write_clean(&self, mut writer: impl Write) -> io::Result<()> {
+        self.clean
+            .iter()
+            .try_for_each(|word| writeln!(writer, "{}", word))
+    }
+}
+
+// Phrases are allowed, so single spaces between letters are alphabetic too
+fn is_alphabetic(entry: &str) -> bool {
+    entry
+        .split(' ')
+        .all(|token| !token.is_empty() && token.chars().all(char::is_alphabetic))
+}
+
+/// Check every line of a word list, the cleaned copy fixes encoding problems
+/// and drops lines the search could never use
+pub fn validate(reader: impl BufRead) -> io::Result<Report> {
+    let mut report = Report::default();
+    let mut exact: HashMap<String, usize> = HashMap::new();
+    let mut folded: HashMap<String, usize> = HashMap::new();
+    for (index, line) in reader.split(b'\n').enumerate() {
+        let number = index + 1;
+        let line = line?;
+        report.lines = number;
+        let mut issue = |issue| report.issues.push((number, issue));
+        let mut entry = match str::from_utf8(&line) {
+            Ok(entry) => entry,
+            Err(_) => {
+                issue(Issue::InvalidUtf8);
+                continue;
+            }
+        };
+        if number == 1 && entry.starts_with(BOM) {
+            issue(Issue::ByteOrderMark);
+            entry = &entry[BOM.len()..];
+        }
+        if entry.ends_with('\r') {
+            issue(Issue::CarriageReturn);
+            entry = &entry[..entry.len() - 1];
+        }
+        if entry.is_empty() {
+            issue(Issue::Empty);
+            continue;
+        }
+        if let Err(error) = word::check_length(entry) {
+            issue(Issue::TooLong(error));
+            continue;
+        }
+        if let Some(&first_line) = exact.get(entry) {
+            issue(Issue::Duplicate {
+                word: entry.to_string(),
+                first_line,
+            });
+            continue;
+        }
+        exact.insert(entry.to_string(), number);
+        if let Some(&first_line) = folded.get(&entry.to_lowercase()) {
+            issue(Issue::CaseDuplicate {
+                word: entry.to_string(),
+                first_line,
+            });
+            continue;
+        }
+        folded.insert(entry.to_lowercase(), number);
+        if !is_alphabetic(entry) {
+            issue(Issue::NonAlphabetic(entry.to_string()));
+        }
+        report.clean.push(entry.to_string());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_kind_of_issue() {
+        let input = b"\xef\xbb\xbfbanana\r\n\nBanana\nbanana\r\njean-pierre\nthe cat\n\xff\n";
+        let report = validate(&input[..]).unwrap();
+        assert_eq!(report.lines, 7);
+        assert_eq!(
+            report.issues,
+            vec![
+                (1, Issue::ByteOrderMark),
+                (1, Issue::CarriageReturn),
+                (2, Issue::Empty),
+                (
+                    3,
+                    Issue::CaseDuplicate {
+                        word: "Banana".to_string(),
+                        first_line: 1
+                    }
+                ),
+                (4, Issue::CarriageReturn),
+                (
+                    4,
+                    Issue::Duplicate {
+                        word: "banana".to_string(),
+                        first_line: 1
+                    }
+                ),
+                (5, Issue::NonAlphabetic("jean-pierre".to_string())),
+                (7, Issue::InvalidUtf8),
+            ]
+        );
+        assert_eq!(report.errors(), 7);
+        assert_eq!(report.clean, vec!["banana", "jean-pierre", "the cat"]);
+    }
+
+    #[test]
+    fn clean_copy_is_one_word_per_line() {
+        let report = validate(&b"cold\ncold\nwarm"[..]).unwrap();
+        let mut output = Vec::new();
+        report.write_clean(&mut output).unwrap();
+        assert_eq!(output, b"cold\nwarm\n");
+    }
+
+    #[test]
+    fn words_too_long_are_dropped() {
+        let input = "a".repeat(word::MAX_WORD_LENGTH + 1);
+        let report = validate(input.as_bytes()).unwrap();
+        assert!(matches!(report.issues[0], (1, Issue::TooLong(_))));
+        assert!(report.clean.is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use num_traits::Zero;
+use std::fmt;
+use typos::distance::path::PathMultiCost;
+use typos::distance::word::{self, EditDistance};
+use typos::distance::{self, Dictionary, PathFindingAlgorithm, SearchOptions};
+
+/// Rule of the game a submitted path breaks
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// Fewer than a start and an end word
+    TooShort,
+    Unknown(String),
+    Repeated(String),
+    TooFar {
+        from: String,
+        to: String,
+        distance: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Issue::TooShort => write!(f, "a path needs a start and an end word"),
+            Issue::Unknown(word) => write!(f, "{} is not in the dictionary", word),
+            Issue::Repeated(word) => write!(f, "{} follows itself", word),
+            Issue::TooFar {
+                from,
+                to,
+                distance,
+                max,
+            } => write!(
+                f,
+                "{} and {} are {} letters apart, more than {}",
+                from, to, distance, max
+            ),
+        }
+    }
+}
+
+/// Score of a submitted path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub issues: Vec<Issue>,
+    /// Cost of the path as submitted, counted even when it breaks the rules
+    pub cost: PathMultiCost<EditDistance>,
+    /// Cost of a shortest path between the same words under the same rules, `None` when
+    /// there is none or the path is too short to have ends
+    pub optimal: Option<PathMultiCost<EditDistance>>,
+}
+
+impl Verdict {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn is_optimal(&self) -> bool {
+        self.is_valid() && self.optimal == Some(self.cost)
+    }
+}
+
+/// Check that every word of `path` is in `words` and every hop changes at most as many
+/// letters as `options` allow, then compare its cost with the one of a shortest path
+pub fn verify<D: Dictionary + ?Sized>(
+    path: &[&str],
+    words: &D,
+    options: &SearchOptions,
+) -> Verdict {
+    let mut issues = Vec::new();
+    if path.len() < 2 {
+        issues.push(Issue::TooShort);
+    }
+    for word in path {
+        if !words.contains(word) {
+            issues.push(Issue::Unknown(word.to_string()));
+        }
+    }
+    let mut cost = PathMultiCost::zero();
+    for hop in path.windows(2) {
+        let distance = word::levenshtein(hop[0], hop[1]);
+        match options.max_hop_distance() {
+            _ if distance == 0 => issues.push(Issue::Repeated(hop[0].to_string())),
+            Some(max) if distance > max => issues.push(Issue::TooFar {
+                from: hop[0].to_string(),
+                to: hop[1].to_string(),
+                distance,
+                max,
+            }),
+            _ => (),
+        }
+        cost = cost + word::path_cost(hop[0], hop[1]);
+    }
+    let optimal = match path {
+        [start, .., stop] => distance::find_shortest_path_with_options(start, stop, words, options)
+            .0
+            .ok()
+            .flatten()
+            .map(|(_, cost)| cost),
+        _ => None,
+    };
+    Verdict {
+        issues,
+        cost,
+        optimal,
+    }
+}
+
+/// Options of the search a submission is compared with, hops limited to `max_hop_distance`
+/// letters if given
+pub fn options(max_hop_distance: Option<usize>) -> SearchOptions {
+    let options = SearchOptions::new(PathFindingAlgorithm::Astar);
+    match max_hop_distance {
+        Some(distance) => options.with_max_hop_distance(distance),
+        None => options,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submissions_are_checked_and_scored() {
+        let words = ["cold", "cord", "card", "ward", "warm", "word"];
+        let options = options(Some(1));
+        let verdict = verify(
+            &["cold", "cord", "card", "ward", "warm"],
+            &words[..],
+            &options,
+        );
+        assert!(verdict.is_optimal(), "{:?}", verdict);
+
+        let verdict = verify(
+            &["cold", "cord", "word", "ward", "warm"],
+            &words[..],
+            &options,
+        );
+        assert!(verdict.is_optimal());
+        let verdict = verify(
+            &["cold", "cord", "word", "cord", "card", "ward", "warm"],
+            &words[..],
+            &options,
+        );
+        assert!(verdict.is_valid() && !verdict.is_optimal());
+        assert!(verdict.optimal < Some(verdict.cost));
+
+        let verdict = verify(&["cold", "cold", "warm", "worm"], &words[..], &options);
+        assert_eq!(
+            verdict.issues,
+            vec![
+                Issue::Unknown("worm".to_string()),
+                Issue::Repeated("cold".to_string()),
+                Issue::TooFar {
+                    from: "cold".to_string(),
+                    to: "warm".to_string(),
+                    distance: 4,
+                    max: 1
+                }
+            ]
+        );
+        assert_eq!(verdict.optimal, None);
+        assert_eq!(
+            verify(&["cold"], &words[..], &options).issues,
+            vec![Issue::TooShort]
+        );
+    }
+}
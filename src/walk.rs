@@ -0,0 +1,53 @@
+use crate::random::Random;
+use typos::distance::Dictionary;
+
+/// Words of a walk of up to `hops` hops from `start`, each hop going to one of the words
+/// within `k` letters of the last one picked uniformly. The walk may come back to words
+/// it went through and stops early on a word without neighbours.
+pub fn random_walk<D: Dictionary + ?Sized>(
+    start: &str,
+    words: &D,
+    hops: usize,
+    k: usize,
+    random: &mut Random,
+) -> Vec<String> {
+    let mut path = vec![start.to_string()];
+    let mut neighbors = Vec::new();
+    for _ in 0..hops {
+        neighbors.clear();
+        words.extend_neighbors(path.last().unwrap(), k, &mut neighbors);
+        if neighbors.is_empty() {
+            break;
+        }
+        // Indexes list neighbours in no particular order, a seed must pick the same one
+        neighbors.sort_unstable();
+        let next = neighbors[random.below(neighbors.len())];
+        path.push(next.to_string());
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use typos::distance::ShardedWords;
+
+    #[test]
+    fn walks_follow_hops_and_repeat_with_a_seed() {
+        let words = ["cold", "cord", "card", "ward", "warm", "bold", "xyz"];
+        let indexed = ShardedWords::new(&words);
+        let path = random_walk("cold", &indexed, 20, 1, &mut Random::new(3));
+        assert_eq!(path.len(), 21);
+        assert!(path.windows(2).all(|hop| indexed
+            .neighbors_within(&hop[0], 1)
+            .contains(&hop[1].as_str())));
+        assert_eq!(
+            random_walk("cold", &words[..], 20, 1, &mut Random::new(3)),
+            path
+        );
+        assert_eq!(
+            random_walk("xyz", &words[..], 5, 1, &mut Random::new(3)),
+            vec!["xyz"]
+        );
+    }
+}
@@ -0,0 +1,541 @@
+use crate::distance::{self, word};
+use crate::{database, remote};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const BOM: char = '\u{feff}';
+
+/// Word lists shipped with most Linux, BSD and macOS systems, in order of preference
+pub const SYSTEM_DICTIONARIES: [&str; 4] = [
+    "/usr/share/dict/words",
+    "/usr/dict/words",
+    "/usr/share/dict/web2",
+    "/usr/local/share/dict/words",
+];
+
+fn first_existing<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .find(|candidate| Path::new(candidate).is_file())
+}
+
+/// First system word list found, used when none is given
+pub fn system_dictionary() -> Option<&'static str> {
+    first_existing(&SYSTEM_DICTIONARIES)
+}
+
+// Entries may be phrases, so inner whitespace is kept but collapsed to single spaces
+pub fn normalize(entry: &str) -> String {
+    entry
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// What becomes of the hyphens and apostrophes of entries, as in `e-mail` or `don't`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Punctuation {
+    #[default]
+    Keep,
+    /// Removed, `don't` becomes `dont`
+    Strip,
+    /// Entries holding any are left out
+    Drop,
+}
+
+impl FromStr for Punctuation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Punctuation, ()> {
+        match s {
+            "keep" => Ok(Punctuation::Keep),
+            "strip" => Ok(Punctuation::Strip),
+            "drop" => Ok(Punctuation::Drop),
+            _ => Err(()),
+        }
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    matches!(c, '-' | '\'' | '\u{2019}' | '\u{2010}')
+}
+
+/// Letters an entry may be written with, such as `a-z` or `a-zàâçéèêëîïôùûü`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alphabet(Vec<(char, char)>);
+
+impl Alphabet {
+    pub fn contains(&self, c: char) -> bool {
+        self.0.iter().any(|&(first, last)| first <= c && c <= last)
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = ();
+
+    /// Letters and ranges of letters such as `a-z`, a `-` first or last standing for itself
+    fn from_str(s: &str) -> Result<Alphabet, ()> {
+        let chars: Vec<char> = s.chars().collect();
+        let mut ranges = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars.get(i + 1..i + 3) {
+                Some(&['-', last]) => {
+                    if last < chars[i] {
+                        return Err(());
+                    }
+                    ranges.push((chars[i], last));
+                    i += 3;
+                }
+                _ => {
+                    ranges.push((chars[i], chars[i]));
+                    i += 1;
+                }
+            }
+        }
+        match ranges.is_empty() {
+            true => Err(()),
+            false => Ok(Alphabet(ranges)),
+        }
+    }
+}
+
+/// Entries of word lists kept once normalized, and how they are rewritten. Spaces between
+/// the words of phrases are always allowed, everything is kept by default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    /// Letters entries may use, any when `None`
+    pub alphabet: Option<Alphabet>,
+    pub punctuation: Punctuation,
+    /// Whether entries may hold digits
+    pub digits: bool,
+    /// Query selecting the entries of word lists read as SQLite databases, text ones
+    /// are read when `None`
+    pub query: Option<String>,
+    /// Whether lines read as `word<TAB>lang` tag the entry with languages, comma
+    /// separated, rather than being a whole entry
+    pub tagged: bool,
+    /// Languages entries must be tagged with one of to be kept, any when `None`
+    pub languages: Option<Vec<String>>,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            alphabet: None,
+            punctuation: Punctuation::Keep,
+            digits: true,
+            query: None,
+            tagged: false,
+            languages: None,
+        }
+    }
+}
+
+/// Rule of a policy leaving an entry out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Filter {
+    Alphabet,
+    /// Dropping entries with punctuation, or stripping all there was of one
+    Punctuation,
+    Digits,
+    Language,
+}
+
+impl fmt::Display for Filter {
+    /// The flag setting the rule
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Filter::Alphabet => write!(f, "--alphabet"),
+            Filter::Punctuation => write!(f, "--punctuation"),
+            Filter::Digits => write!(f, "--no-digits"),
+            Filter::Language => write!(f, "--lang"),
+        }
+    }
+}
+
+impl Policy {
+    /// `entry` as the policy rewrites it, or the rule leaving it out
+    pub fn apply(&self, entry: String) -> Result<String, Filter> {
+        let entry = match self.punctuation {
+            Punctuation::Keep => entry,
+            Punctuation::Strip => entry.chars().filter(|&c| !is_punctuation(c)).collect(),
+            Punctuation::Drop if entry.chars().any(is_punctuation) => {
+                return Err(Filter::Punctuation)
+            }
+            Punctuation::Drop => entry,
+        };
+        if entry.is_empty() {
+            return Err(Filter::Punctuation);
+        }
+        // Hyphens and apostrophes still there are kept, digits have their own switch
+        for c in entry.chars() {
+            match c {
+                ' ' => (),
+                c if is_punctuation(c) => (),
+                c if c.is_numeric() && !self.digits => return Err(Filter::Digits),
+                c if c.is_numeric() => (),
+                c if !self
+                    .alphabet
+                    .as_ref()
+                    .is_none_or(|alphabet| alphabet.contains(c)) =>
+                {
+                    return Err(Filter::Alphabet)
+                }
+                _ => (),
+            }
+        }
+        Ok(entry)
+    }
+}
+
+/// What became of the entries of a word list as it was loaded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    /// Entries read, blank lines and comments aside
+    pub read: usize,
+    /// Entries the policy left out, by the rule that did
+    pub left_out: BTreeMap<Filter, usize>,
+    /// Entries equal to an earlier one once case is folded, removed by `dedup`
+    pub duplicates: usize,
+}
+
+impl Summary {
+    /// Entries left once filtered and deduplicated
+    pub fn kept(&self) -> usize {
+        self.read - self.left_out.values().sum::<usize>() - self.duplicates
+    }
+
+    /// Whether any entry read was left out or removed
+    pub fn removed_any(&self) -> bool {
+        self.kept() < self.read
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = if self.read == 1 { "entry" } else { "entries" };
+        write!(f, "{} of {} {} kept", self.kept(), self.read, entries)?;
+        let mut removed: Vec<String> = self
+            .left_out
+            .iter()
+            .map(|(filter, count)| format!("{} left out by {}", count, filter))
+            .collect();
+        if self.duplicates > 0 {
+            let plural = if self.duplicates == 1 { "" } else { "s" };
+            removed.push(format!("{} duplicate{} removed", self.duplicates, plural));
+        }
+        match removed.is_empty() {
+            true => Ok(()),
+            false => write!(f, ": {}", removed.join(", ")),
+        }
+    }
+}
+
+/// A word list as loaded: its entries, the weights and languages some of them are given,
+/// and what became of the others
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct List {
+    pub words: Vec<String>,
+    pub weights: HashMap<String, usize>,
+    pub languages: Languages,
+    pub summary: Summary,
+}
+
+impl List {
+    /// Keep the first of the entries equal once case is folded, counting the others
+    pub fn dedup(&mut self) {
+        self.summary.duplicates += dedup(&mut self.words);
+    }
+}
+
+/// Languages of the entries tagged with some, by entry
+pub type Languages = HashMap<String, Vec<String>>;
+
+// A `word<TAB>weight` line, other lines are a whole entry weighing nothing
+fn split_weight(line: &str) -> (&str, usize) {
+    line.rsplit_once('\t')
+        .and_then(|(entry, weight)| Some((entry, weight.trim().parse().ok()?)))
+        .unwrap_or((line, 0))
+}
+
+// A `word<TAB>lang<TAB>weight` line when `tagged`, the weight being optional
+fn split_languages(line: &str, tagged: bool) -> (&str, usize, Vec<String>) {
+    let (line, weight) = split_weight(line);
+    match line.rsplit_once('\t').filter(|_| tagged) {
+        Some((entry, languages)) => {
+            let languages = languages
+                .split(',')
+                .map(|language| language.trim().to_lowercase())
+                .filter(|language| !language.is_empty())
+                .collect();
+            (entry, weight, languages)
+        }
+        None => (line, weight, Vec::new()),
+    }
+}
+
+/// Entries of a word list, one per line, without `#` comments, blank lines,
+/// byte order mark or carriage returns, along with the weight of those given one as
+/// `word<TAB>weight`; the first weight of an entry listed twice is kept. Entries
+/// `policy` leaves out are skipped, and those it reads tags of come with their languages,
+/// those of an entry listed twice put together.
+pub fn from_reader(reader: impl BufRead, policy: &Policy) -> io::Result<List> {
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = match index {
+            0 => line.trim_start_matches(BOM),
+            _ => line.as_str(),
+        };
+        let line = line.trim_end_matches('\r');
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let (entry, weight, languages) = split_languages(line, policy.tagged);
+        entries.push((entry.to_string(), weight, languages));
+    }
+    from_entries(entries, policy)
+}
+
+// Entries normalized, those `policy` leaves out skipped, with their weights and languages
+fn from_entries(entries: Vec<(String, usize, Vec<String>)>, policy: &Policy) -> io::Result<List> {
+    let mut words = Vec::new();
+    let mut weights = HashMap::new();
+    let mut tags = Languages::new();
+    let mut summary = Summary::default();
+    for (entry, weight, mut languages) in entries {
+        let entry = normalize(&entry);
+        if entry.is_empty() {
+            continue;
+        }
+        summary.read += 1;
+        let mut left_out = |filter| *summary.left_out.entry(filter).or_insert(0) += 1;
+        let word = match policy.apply(entry) {
+            Ok(word) => word,
+            Err(filter) => {
+                left_out(filter);
+                continue;
+            }
+        };
+        if let Some(kept) = &policy.languages {
+            languages.retain(|language| kept.contains(language));
+            if languages.is_empty() {
+                left_out(Filter::Language);
+                continue;
+            }
+        }
+        word::check_length(&word).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if weight > 0 {
+            weights.entry(word.clone()).or_insert(weight);
+        }
+        if !languages.is_empty() {
+            let known: &mut Vec<String> = tags.entry(word.clone()).or_default();
+            for language in languages {
+                if !known.contains(&language) {
+                    known.push(language);
+                }
+            }
+        }
+        words.push(word);
+    }
+    Ok(List {
+        words,
+        weights,
+        languages: tags,
+        summary,
+    })
+}
+
+pub fn from_file(filename: impl AsRef<Path>, policy: &Policy) -> io::Result<List> {
+    from_reader(BufReader::new(File::open(filename)?), policy)
+}
+
+/// Same as `from_file`, lists given as an http or https URL being downloaded first.
+/// With a `query`, the list is a SQLite database the query selects the entries of.
+pub fn from_location(location: &str, policy: &Policy) -> io::Result<List> {
+    let path = match remote::is_url(location) {
+        true => remote::download(location)?,
+        false => PathBuf::from(location),
+    };
+    match &policy.query {
+        Some(query) => {
+            let entries = database::entries(&path, query)?
+                .into_iter()
+                .map(|(entry, weight)| (entry, weight, Vec::new()))
+                .collect();
+            from_entries(entries, policy)
+        }
+        None => from_file(path, policy),
+    }
+}
+
+/// Keep the first of the entries equal once case is folded, returns how many were dropped
+pub fn dedup(words: &mut Vec<String>) -> usize {
+    let count = words.len();
+    let mut seen = HashSet::new();
+    words.retain(|word| seen.insert(word.to_lowercase()));
+    count - words.len()
+}
+
+/// The `count` entries the closest to `word`, offered when it is not in the list
+pub fn suggestions<'a>(word: &str, words: &'a [String], count: usize) -> Vec<&'a str> {
+    distance::suggest(words, word, count)
+        .into_iter()
+        .map(|(entry, _)| entry)
+        .collect()
+}
+
+/// Number of entries of every length, in letters
+pub fn length_counts(words: &[String]) -> BTreeMap<usize, usize> {
+    let mut counts = BTreeMap::new();
+    for word in words {
+        *counts.entry(word.chars().count()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let words = from_reader(
+            &b"# names\nAdrien\n\n   \n  # indented comment\nthe  Cat\n"[..],
+            &Policy::default(),
+        );
+        assert_eq!(words.unwrap().words, vec!["adrien", "the cat"]);
+    }
+
+    #[test]
+    fn reads_weights_after_a_tab() {
+        let List { words, weights, .. } = from_reader(
+            &b"paris\t5\nCold\nwarm\t0\nthe cat\t2\r\nparis\t1\nx\ty\n"[..],
+            &Policy::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            words,
+            vec!["paris", "cold", "warm", "the cat", "paris", "x y"]
+        );
+        let expected: HashMap<String, usize> =
+            [("paris".to_string(), 5), ("the cat".to_string(), 2)].into();
+        assert_eq!(weights, expected);
+    }
+
+    #[test]
+    fn reads_languages_when_tagged() {
+        let list = &b"chat\tfr\nchat\ten\t3\ncat\tEN\nhund\tde\nbrie\tfr, en\nplain\n"[..];
+        let tagged = Policy {
+            tagged: true,
+            ..Policy::default()
+        };
+        let List {
+            words,
+            weights,
+            languages,
+            ..
+        } = from_reader(list, &tagged).unwrap();
+        assert_eq!(words, vec!["chat", "chat", "cat", "hund", "brie", "plain"]);
+        assert_eq!(weights.get("chat"), Some(&3));
+        assert_eq!(languages["chat"], vec!["fr", "en"]);
+        assert_eq!(languages["brie"], vec!["fr", "en"]);
+        assert!(!languages.contains_key("plain"));
+
+        let english = Policy {
+            languages: Some(vec!["en".to_string()]),
+            ..tagged.clone()
+        };
+        let loaded = from_reader(list, &english).unwrap();
+        assert_eq!(loaded.words, vec!["chat", "cat", "brie"]);
+        assert_eq!(loaded.languages["brie"], vec!["en"]);
+        assert_eq!(
+            loaded.summary.to_string(),
+            "3 of 6 entries kept: 3 left out by --lang"
+        );
+        let mut loaded = from_reader(list, &tagged).unwrap();
+        loaded.dedup();
+        assert_eq!(
+            loaded.summary.to_string(),
+            "5 of 6 entries kept: 1 duplicate removed"
+        );
+    }
+
+    #[test]
+    fn first_existing_skips_missing_files() {
+        let file = std::env::temp_dir().join(format!("typos-words-test-{}", std::process::id()));
+        std::fs::write(&file, "cold\n").unwrap();
+        let existing = file.to_str().unwrap();
+        assert_eq!(
+            first_existing(&["/nonexistent/words", existing]),
+            Some(existing)
+        );
+        assert_eq!(first_existing(&["/nonexistent/words"]), None);
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn dedup_keeps_first_entries() {
+        let mut words: Vec<String> = ["cold", "Cold", "warm", "cold", "café", "CAFÉ"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        assert_eq!(dedup(&mut words), 3);
+        assert_eq!(words, vec!["cold", "warm", "café"]);
+        let counts: Vec<(usize, usize)> = length_counts(&words).into_iter().collect();
+        assert_eq!(counts, vec![(4, 3)]);
+    }
+
+    #[test]
+    fn suggestions_are_the_closest_entries() {
+        let words: Vec<String> = ["warm", "cold", "bold", "colder"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        assert_eq!(suggestions("coldd", &words, 2), vec!["cold", "bold"]);
+        assert!(suggestions("cold", &[], 3).is_empty());
+    }
+
+    #[test]
+    fn strips_bom_and_crlf() {
+        let words = from_reader("\u{feff}cold\r\nwarm\r\n".as_bytes(), &Policy::default());
+        assert_eq!(words.unwrap().words, vec!["cold", "warm"]);
+    }
+
+    #[test]
+    fn policies_rewrite_or_leave_out_entries() {
+        let list = "don't\ne-mail\nCafé\nmp3\nthe cat\ncold\n'\n".as_bytes();
+        let read = |policy: &Policy| from_reader(list, policy).unwrap().words;
+        assert_eq!(read(&Policy::default()).len(), 7);
+        let strict = Policy {
+            alphabet: Some("a-z".parse().unwrap()),
+            punctuation: Punctuation::Strip,
+            digits: false,
+            ..Policy::default()
+        };
+        assert_eq!(read(&strict), vec!["dont", "email", "the cat", "cold"]);
+        let dropping = Policy {
+            punctuation: Punctuation::Drop,
+            ..Policy::default()
+        };
+        assert_eq!(read(&dropping), vec!["café", "mp3", "the cat", "cold"]);
+        let summary = from_reader(list, &strict).unwrap().summary;
+        assert_eq!(
+            summary.to_string(),
+            "4 of 7 entries kept: 1 left out by --alphabet, 1 left out by --punctuation, 1 left out by --no-digits"
+        );
+
+        let alphabet: Alphabet = "a-zé-".parse().unwrap();
+        assert!(alphabet.contains('q') && alphabet.contains('é') && alphabet.contains('-'));
+        assert!(!alphabet.contains('è'));
+        assert_eq!("z-a".parse::<Alphabet>(), Err(()));
+        assert_eq!("".parse::<Alphabet>(), Err(()));
+    }
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use common::{temp_file, typos};
+
+#[test]
+fn edited_cost_matrices_are_not_served_from_the_cache() {
+    let words = temp_file("cache-words", "cold\ncord\ncard\nward\nwarm\nword\nworm\n");
+    let matrix = temp_file("cache-matrix", "o,a,1\n");
+    let directory = std::env::temp_dir().join(format!("typos-test-cache-{}", std::process::id()));
+    let args = [
+        words.to_str().unwrap(),
+        "cold",
+        "warm",
+        "--quiet",
+        "--cost-matrix",
+        matrix.to_str().unwrap(),
+        "--cache-dir",
+        directory.to_str().unwrap(),
+    ];
+    let path = || String::from_utf8(typos(&args).stdout).unwrap();
+    assert_eq!(path(), "cold->cord->card->ward->warm\n");
+    // Turning o into a costs more than going around it now
+    std::fs::write(&matrix, "o,a,9\n").unwrap();
+    assert_eq!(path(), "cold->cord->word->worm->warm\n");
+    std::fs::remove_dir_all(directory).unwrap();
+    std::fs::remove_file(matrix).unwrap();
+    std::fs::remove_file(words).unwrap();
+}
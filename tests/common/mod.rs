@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// A file of the temporary directory named after the test, so that tests running in
+/// parallel do not share it
+pub fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("typos-test-{}-{}", name, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+pub fn typos(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_typos"))
+        .args(args)
+        .output()
+        .unwrap()
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{temp_file, typos};
+
+#[test]
+fn quiet_prints_only_the_path() {
+    let words = temp_file("quiet", "cold\ncord\ncard\nward\nwarm\nzzzz\n");
+    let words = words.to_str().unwrap();
+    let found = typos(&["-i", words, "cold", "warm", "--quiet"]);
+    assert_eq!(found.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8(found.stdout).unwrap(),
+        "cold->cord->card->ward->warm\n"
+    );
+    assert!(found.stderr.is_empty());
+    let missing = typos(&[
+        "-i",
+        words,
+        "cold",
+        "zzzz",
+        "--max-hop-distance",
+        "1",
+        "--quiet",
+    ]);
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(missing.stdout.is_empty());
+    assert!(missing.stderr.is_empty());
+    std::fs::remove_file(words).unwrap();
+}
+
+#[test]
+fn failures_have_stable_statuses() {
+    let words = temp_file("statuses", "cold\ncord\t70000\ncard\nward\nwarm\n");
+    let words = words.to_str().unwrap();
+    let status = |args: &[&str]| typos(args).status.code();
+    assert_eq!(
+        status(&["-i", words, "cold", "warm", "--max-hops", "x"]),
+        Some(2)
+    );
+    assert_eq!(
+        status(&["-i", words, "cold", "warm", "--cost-matrix", "/nonexistent"]),
+        Some(2)
+    );
+    assert_eq!(
+        status(&[
+            "-i",
+            words,
+            "cold",
+            "warm",
+            "--cost",
+            "ocr",
+            "--confusion",
+            "/nonexistent"
+        ]),
+        Some(2)
+    );
+    assert_eq!(status(&["bench", "--repeats", "0"]), Some(2));
+    assert_eq!(status(&["bench", "--repeats", "1.5"]), Some(2));
+    assert_eq!(status(&["-i", "/nonexistent", "cold", "warm"]), Some(3));
+    assert_eq!(status(&["validate", "-i", "/nonexistent"]), Some(3));
+    // Entering cord costs more than the counters hold
+    assert_eq!(
+        status(&["-i", words, "cold", "warm", "--saturation", "error"]),
+        Some(4)
+    );
+    std::fs::remove_file(words).unwrap();
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::{temp_file, typos};
+
+const WORDS: &str =
+    "cold\ncord\ncard\nward\nwarm\nworm\nword\nwore\nwire\ncore\ncare\nbare\nbore\n";
+
+#[test]
+fn same_seed_same_walks() {
+    let words = temp_file("walks", WORDS);
+    let words = words.to_str().unwrap();
+    let walks = |seed: &str| {
+        let walked = typos(&[
+            "random-walk",
+            "cold",
+            "-i",
+            words,
+            "--hops",
+            "20",
+            "--walks",
+            "5",
+            "--seed",
+            seed,
+        ]);
+        assert_eq!(walked.status.code(), Some(0));
+        String::from_utf8(walked.stdout).unwrap()
+    };
+    assert_eq!(walks("42"), walks("42"));
+    assert_ne!(walks("0"), walks("1"));
+    std::fs::remove_file(words).unwrap();
+}
+
+#[test]
+fn seeds_are_checked_by_every_command() {
+    let words = temp_file("checked", WORDS);
+    let words = words.to_str().unwrap();
+    let searched = typos(&["-i", words, "cold", "warm", "--seed", "abc"]);
+    assert_eq!(searched.status.code(), Some(2));
+    assert!(searched.stdout.is_empty());
+    std::fs::remove_file(words).unwrap();
+}